@@ -1,8 +1,8 @@
 use std::cell::{RefCell, RefMut};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
 use std::hash::Hash;
-use std::io::{self, Read, Write};
+use std::io::{self, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process;
 
@@ -18,11 +18,17 @@ use same_file::{is_same_file, Handle};
 use siphasher::sip128::{Hasher128, SipHasher};
 use termcolor::{ColorChoice, StandardStream, WriteColor};
 use typst::diag::{FileError, FileResult, SourceError, StrResult};
+use typst::doc::Attachment;
 use typst::eval::Library;
+use typst::export::{
+    Compression, Grayscale, OutputIntent, PdfCache, PdfExporter, PdfOptions, PdfStandard,
+    Rasterize,
+};
 use typst::font::{Font, FontBook, FontInfo, FontVariant};
 use typst::syntax::{Source, SourceId};
 use typst::util::{Buffer, PathExt};
 use typst::World;
+use typst_library::meta::DocumentElem;
 use walkdir::WalkDir;
 
 type CodespanResult<T> = Result<T, CodespanError>;
@@ -32,6 +38,7 @@ type CodespanError = codespan_reporting::files::Error;
 enum Command {
     Compile(CompileCommand),
     Fonts(FontsCommand),
+    FontReport(FontReportCommand),
 }
 
 /// Compile a .typ file into a PDF file.
@@ -40,6 +47,10 @@ struct CompileCommand {
     output: PathBuf,
     root: Option<PathBuf>,
     watch: bool,
+    pdf: PdfOptions,
+    html: Option<PathBuf>,
+    bundle: Option<PathBuf>,
+    deps: Option<PathBuf>,
 }
 
 const HELP: &'static str = "\
@@ -51,16 +62,48 @@ USAGE:
 
 ARGS:
   <input.typ>    Path to input Typst file
-  [output.pdf]   Path to output PDF file
+  [output.pdf]   Path to output PDF file, or to a JSON file with the
+                 laid out frames if the path ends in `.json`, which
+                 rejects the PDF options below
 
 OPTIONS:
   -h, --help     Print this help
   -V, --version  Print the CLI's version
   -w, --watch    Watch the inputs and recompile on changes
   --root <dir>   Configure the root for absolute paths
+  --jpeg-quality <1-100>
+                 Re-encode images as JPEGs with this quality
+  --grayscale <rec601|rec709|average>
+                 Convert colors and images to gray with these weights
+  --cmyk         Convert all colors to device CMYK for print
+  --pdf-standard <1.7|a-2b>
+                 Produce a PDF file that conforms to this standard
+  --icc-profile <path>
+                 Embed this ICC profile as the output intent, identified
+                 by the file's name
+  --attach <path>
+                 Attach this file to the PDF, can be given multiple times
+  --outline-text Draw text as vector paths instead of embedding fonts
+  --text-layer   Keep outlined text searchable with an invisible text layer
+  --tagged       Write a structure tree for screen readers and reflow
+  --rasterize-above <count>
+                 Draw pages with more elements than this as images
+  --raster-dpi <dpi>
+                 Resolution of rasterized pages, defaults to 150
+  --compression <none|0-10>
+                 How strongly to compress the PDF file, with none
+                 leaving page contents readable for debugging
+  --html <path>  Also write an HTML page with the document's metadata and
+                 an outline linking into the PDF file
+  --bundle <path>
+                 Also write a zip archive with the PDF file and all files
+                 it was compiled from, including fonts
+  --deps <path>  Also write the files read during compilation as a JSON
+                 list, or as a Makefile rule if the path ends in `.d`
 
 SUBCOMMANDS:
   --fonts        List all discovered system fonts
+  --font-report  List the fonts used by a document and their licenses
 ";
 
 /// List discovered system fonts.
@@ -79,6 +122,26 @@ OPTIONS:
   --variants     Also list style variants of each font family
 ";
 
+/// List the fonts used by a document alongside their licensing information.
+struct FontReportCommand {
+    input: PathBuf,
+    root: Option<PathBuf>,
+}
+
+const HELP_FONT_REPORT: &'static str = "\
+typst --font-report lists the fonts used by a document and their licenses
+
+USAGE:
+  typst --font-report [OPTIONS] <input.typ>
+
+ARGS:
+  <input.typ>    Path to input Typst file
+
+OPTIONS:
+  -h, --help     Print this help
+  --root <dir>   Configure the root for absolute paths
+";
+
 /// Entry point.
 fn main() {
     let command = parse_args();
@@ -107,6 +170,14 @@ fn parse_args() -> StrResult<Command> {
         }
 
         Command::Fonts(FontsCommand { variants: args.contains("--variants") })
+    } else if args.contains("--font-report") {
+        if help {
+            print_help(HELP_FONT_REPORT);
+        }
+
+        let root = args.opt_value_from_str("--root").map_err(|_| "missing root path")?;
+        let input = args.free_from_str().map_err(|_| "missing input file")?;
+        Command::FontReport(FontReportCommand { input, root })
     } else {
         if help {
             print_help(HELP);
@@ -114,8 +185,153 @@ fn parse_args() -> StrResult<Command> {
 
         let root = args.opt_value_from_str("--root").map_err(|_| "missing root path")?;
         let watch = args.contains(["-w", "--watch"]);
+        let jpeg_quality: Option<u8> = args
+            .opt_value_from_str("--jpeg-quality")
+            .map_err(|_| "invalid JPEG quality")?;
+        if jpeg_quality.map_or(false, |quality| !(1..=100).contains(&quality)) {
+            Err("JPEG quality must be between 1 and 100")?;
+        }
+
+        let grayscale = match args
+            .opt_value_from_str::<_, String>("--grayscale")
+            .map_err(|_| "missing grayscale weights")?
+            .as_deref()
+        {
+            None => None,
+            Some("rec601") => Some(Grayscale::Rec601),
+            Some("rec709") => Some(Grayscale::Rec709),
+            Some("average") => Some(Grayscale::Average),
+            Some(_) => Err("grayscale weights must be rec601, rec709, or average")?,
+        };
+
+        let cmyk = args.contains("--cmyk");
+        let standard_arg = args
+            .opt_value_from_str::<_, String>("--pdf-standard")
+            .map_err(|_| "missing PDF standard")?;
+        let standard = match standard_arg.as_deref() {
+            None | Some("1.7") => PdfStandard::V1_7,
+            Some("a-2b") => PdfStandard::A2b,
+            Some(_) => Err("PDF standard must be 1.7 or a-2b")?,
+        };
+
+        let output_intent = args
+            .opt_value_from_str::<_, PathBuf>("--icc-profile")
+            .map_err(|_| "missing ICC profile path")?
+            .map(|path| -> StrResult<_> {
+                let data = fs::read(&path).map_err(|_| "failed to read ICC profile")?;
+                let condition = path.file_stem().map_or("Custom".into(), |stem| {
+                    stem.to_string_lossy().as_ref().into()
+                });
+                Ok(OutputIntent::new(data.into(), condition)?)
+            })
+            .transpose()?;
+
+        let attachments = args
+            .values_from_str::<_, PathBuf>("--attach")
+            .map_err(|_| "missing attachment path")?
+            .into_iter()
+            .map(|path| -> StrResult<_> {
+                let data = fs::read(&path).map_err(|_| "failed to read attachment")?;
+                let name = path.file_name().ok_or("invalid attachment path")?;
+                Ok(Attachment {
+                    name: name.to_string_lossy().as_ref().into(),
+                    data: data.into(),
+                    description: None,
+                    mime: None,
+                })
+            })
+            .collect::<StrResult<_>>()?;
+
+        let outline_text = args.contains("--outline-text");
+        let text_layer = args.contains("--text-layer");
+        if text_layer && !outline_text {
+            Err("text layer requires outlined text")?;
+        }
+
+        let tagged = args.contains("--tagged");
+
+        let threshold: Option<usize> = args
+            .opt_value_from_str("--rasterize-above")
+            .map_err(|_| "invalid rasterization threshold")?;
+        let dpi: Option<u16> = args
+            .opt_value_from_str("--raster-dpi")
+            .map_err(|_| "invalid raster DPI")?;
+        if dpi == Some(0) {
+            Err("raster DPI must be positive")?;
+        }
+        let rasterize = match (threshold, dpi) {
+            (Some(threshold), dpi) => {
+                Some(Rasterize { threshold, dpi: dpi.unwrap_or(150) })
+            }
+            (None, Some(_)) => Err("raster DPI requires a rasterization threshold")?,
+            (None, None) => None,
+        };
+
+        let compression_arg = args
+            .opt_value_from_str::<_, String>("--compression")
+            .map_err(|_| "missing compression level")?;
+        let compression = match compression_arg.as_deref() {
+            None => Compression::default(),
+            Some("none") => Compression::None,
+            Some(level) => match level.parse() {
+                Ok(level @ 0..=10) => Compression::Level(level),
+                _ => Err("compression must be none or a level between 0 and 10")?,
+            },
+        };
+
+        let html = args.opt_value_from_str("--html").map_err(|_| "missing HTML path")?;
+        let bundle = args
+            .opt_value_from_str("--bundle")
+            .map_err(|_| "missing bundle path")?;
+        let deps = args
+            .opt_value_from_str("--deps")
+            .map_err(|_| "missing dependency manifest path")?;
         let (input, output) = parse_input_output(&mut args, "pdf")?;
-        Command::Compile(CompileCommand { input, output, watch, root })
+
+        // The JSON export only serializes the frames, so options for the PDF
+        // file would silently have no effect.
+        if output.extension() == Some("json".as_ref()) {
+            let pdf_only = [
+                ("--jpeg-quality", jpeg_quality.is_some()),
+                ("--grayscale", grayscale.is_some()),
+                ("--cmyk", cmyk),
+                ("--pdf-standard", standard_arg.is_some()),
+                ("--icc-profile", output_intent.is_some()),
+                ("--attach", !attachments.is_empty()),
+                ("--outline-text", outline_text),
+                ("--tagged", tagged),
+                ("--rasterize-above", rasterize.is_some()),
+                ("--compression", compression_arg.is_some()),
+                ("--html", html.is_some()),
+                ("--bundle", bundle.is_some()),
+            ];
+            if let Some((flag, _)) = pdf_only.iter().find(|(_, given)| *given) {
+                Err(format!("{flag} only applies to PDF output, not to JSON"))?;
+            }
+        }
+
+        Command::Compile(CompileCommand {
+            input,
+            output,
+            watch,
+            root,
+            pdf: PdfOptions {
+                jpeg_quality,
+                grayscale,
+                cmyk,
+                standard,
+                output_intent,
+                attachments,
+                outline_text,
+                text_layer,
+                tagged,
+                rasterize,
+                compression,
+            },
+            html,
+            bundle,
+            deps,
+        })
     };
 
     // Don't allow excess arguments.
@@ -127,6 +343,33 @@ fn parse_args() -> StrResult<Command> {
     Ok(command)
 }
 
+/// The path of `target` relative to the directory of the HTML file at `html`,
+/// for use in a link. Falls back to the absolute path if the two don't share
+/// a common ancestor, like on different drives.
+fn relative_href(html: &Path, target: &Path) -> Option<String> {
+    let cwd = std::env::current_dir().ok()?;
+    let html = cwd.join(html).normalize();
+    let target = cwd.join(target).normalize();
+
+    // Walk up from the HTML file's directory until the target is below it.
+    let mut path = PathBuf::new();
+    let mut dir = html.parent();
+    while let Some(base) = dir {
+        if let Ok(relative) = target.strip_prefix(base) {
+            path.push(relative);
+            break;
+        }
+        path.push("..");
+        dir = base.parent();
+    }
+
+    if dir.is_none() {
+        path = target;
+    }
+
+    Some(path.to_str()?.replace('\\', "/"))
+}
+
 /// Parse two freestanding path arguments, with the output path being optional.
 /// If it is omitted, it is determined from the input path's file stem plus the
 /// given extension.
@@ -160,6 +403,18 @@ fn print_version() -> ! {
     std::process::exit(0);
 }
 
+/// Print an application-level warning (independent from a source file).
+fn print_warning(msg: &str) -> io::Result<()> {
+    let mut w = StandardStream::stderr(ColorChoice::Always);
+    let styles = term::Styles::default();
+
+    w.set_color(&styles.header_warning)?;
+    write!(w, "warning")?;
+
+    w.reset()?;
+    writeln!(w, ": {msg}.")
+}
+
 /// Print an application-level error (independent from a source file).
 fn print_error(msg: &str) -> io::Result<()> {
     let mut w = StandardStream::stderr(ColorChoice::Always);
@@ -177,24 +432,29 @@ fn dispatch(command: Command) -> StrResult<()> {
     match command {
         Command::Compile(command) => compile(command),
         Command::Fonts(command) => fonts(command),
+        Command::FontReport(command) => font_report(command),
     }
 }
 
 /// Execute a compilation command.
 fn compile(command: CompileCommand) -> StrResult<()> {
-    let root = if let Some(root) = &command.root {
-        root.clone()
-    } else if let Some(dir) = command.input.parent() {
-        dir.into()
-    } else {
-        PathBuf::new()
-    };
-
     // Create the world that serves sources, fonts and files.
+    let root = determine_root(&command.input, command.root.as_deref());
     let mut world = SystemWorld::new(root);
 
+    // Mark up the document's structure during layout, so that the exporter
+    // has something to write into the structure tree.
+    if command.pdf.tagged {
+        let mut library = typst_library::build();
+        library.styles.set(DocumentElem::set_tagged(true));
+        world.library = Prehashed::new(library);
+    }
+
+    // Keep the content streams of unchanged pages across compilations.
+    let mut cache = PdfCache::default();
+
     // Perform initial compilation.
-    compile_once(&mut world, &command)?;
+    compile_once(&mut world, &command, &mut cache)?;
 
     if !command.watch {
         return Ok(());
@@ -232,24 +492,76 @@ fn compile(command: CompileCommand) -> StrResult<()> {
         }
 
         if recompile {
-            compile_once(&mut world, &command)?;
+            compile_once(&mut world, &command, &mut cache)?;
         }
     }
 }
 
+/// Determine the root for absolute paths, falling back to the input file's
+/// directory.
+fn determine_root(input: &Path, root: Option<&Path>) -> PathBuf {
+    if let Some(root) = root {
+        root.into()
+    } else if let Some(dir) = input.parent() {
+        dir.into()
+    } else {
+        PathBuf::new()
+    }
+}
+
 /// Compile a single time.
-fn compile_once(world: &mut SystemWorld, command: &CompileCommand) -> StrResult<()> {
+fn compile_once(
+    world: &mut SystemWorld,
+    command: &CompileCommand,
+    cache: &mut PdfCache,
+) -> StrResult<()> {
     status(command, Status::Compiling).unwrap();
 
     world.reset();
     world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
 
     match typst::compile(world) {
-        // Export the PDF.
+        // Export the PDF or the serialized frames.
         Ok(document) => {
-            let buffer = typst::export::pdf(&document);
-            fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
+            if command.output.extension() == Some("json".as_ref()) {
+                let buffer = typst::export::json(&document);
+                fs::write(&command.output, buffer)
+                    .map_err(|_| "failed to write JSON file")?;
+            } else {
+                File::create(&command.output)
+                    .map(BufWriter::new)
+                    .and_then(|file| {
+                        PdfExporter::new()
+                            .with_options(command.pdf.clone())
+                            .with_cache(cache)
+                            .write(&document, file)
+                    })
+                    .map_err(|_| "failed to write PDF file")?;
+
+                if let Some(path) = &command.html {
+                    let pdf = relative_href(path, &command.output);
+                    let buffer = typst::export::html(&document, pdf.as_deref());
+                    fs::write(path, buffer).map_err(|_| "failed to write HTML file")?;
+                }
+
+                if let Some(path) = &command.bundle {
+                    write_bundle(world, path, &command.output)
+                        .map_err(|_| "failed to write bundle")?;
+                }
+            }
+
+            if let Some(path) = &command.deps {
+                write_deps(world, path, &command.output)
+                    .map_err(|_| "failed to write dependency manifest")?;
+            }
+
             status(command, Status::Success).unwrap();
+
+            let attached =
+                !document.attachments.is_empty() || !command.pdf.attachments.is_empty();
+            if command.pdf.standard == PdfStandard::A2b && attached {
+                print_warning("attachments are left out of PDF/A files").unwrap();
+            }
         }
 
         // Print diagnostics.
@@ -263,6 +575,114 @@ fn compile_once(world: &mut SystemWorld, command: &CompileCommand) -> StrResult<
     Ok(())
 }
 
+/// Write a zip archive with the PDF file and all files that were read to
+/// compile it, so that the document can be archived and reproduced.
+///
+/// Files within the root keep their relative paths. Fonts, which live
+/// outside of it, end up in a `fonts` directory, alongside the used fonts
+/// that are embedded in the binary. Their names carry a hash of their data,
+/// so that different fonts never clash and each one is only bundled once.
+fn write_bundle(
+    world: &SystemWorld,
+    path: &Path,
+    pdf: &Path,
+) -> zip::result::ZipResult<()> {
+    let mut zip = zip::ZipWriter::new(File::create(path)?);
+    let options = zip::write::FileOptions::default();
+    let name = pdf.file_name().unwrap_or("document.pdf".as_ref()).to_string_lossy();
+    zip.start_file(name, options)?;
+    zip.write_all(&fs::read(pdf)?)?;
+
+    // Several fonts may come from the same collection file.
+    let mut written = HashSet::new();
+    let mut write_font = |zip: &mut zip::ZipWriter<File>, stem: &str, data: &[u8]| {
+        let hash = typst::util::hash128(data);
+        if !written.insert(hash) {
+            return Ok(());
+        }
+
+        let extension = match data.get(..4) {
+            Some(b"OTTO") => "otf",
+            Some(b"ttcf") => "ttc",
+            _ => "ttf",
+        };
+
+        zip.start_file(
+            format!("fonts/{stem}-{:016x}.{extension}", hash as u64),
+            options,
+        )?;
+        zip.write_all(data).map_err(zip::result::ZipError::from)
+    };
+
+    let root = world.root.canonicalize().unwrap_or_else(|_| world.root.clone());
+    for dependency in world.dependencies() {
+        let data = fs::read(&dependency)?;
+        let Ok(relative) = dependency.strip_prefix(&root) else {
+            let stem = dependency.file_stem().unwrap_or_default().to_string_lossy();
+            write_font(&mut zip, &stem, &data)?;
+            continue;
+        };
+
+        let name = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        zip.start_file(name, options)?;
+        zip.write_all(&data)?;
+    }
+
+    for font in world.embedded_fonts() {
+        write_font(&mut zip, &font.info().family, font.data())?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// Write the files that were read during compilation into a manifest for
+/// build systems.
+///
+/// If the path ends in `.d`, the manifest is a Makefile rule with the output
+/// as its target. Otherwise, it is a JSON object with the `output` path and
+/// the list of `inputs`.
+fn write_deps(world: &SystemWorld, path: &Path, output: &Path) -> io::Result<()> {
+    let inputs = world.dependencies();
+    let mut manifest = String::new();
+    if path.extension() == Some("d".as_ref()) {
+        let escape = |path: &Path| {
+            path.to_string_lossy()
+                .replace('\\', "\\\\")
+                .replace(' ', "\\ ")
+                .replace('#', "\\#")
+                .replace('$', "$$")
+        };
+
+        manifest.push_str(&escape(output));
+        manifest.push(':');
+        for input in &inputs {
+            manifest.push(' ');
+            manifest.push_str(&escape(input));
+        }
+        manifest.push('\n');
+    } else {
+        let escape = |path: &Path| {
+            serde_json::to_string(&path.to_string_lossy()).unwrap_or_default()
+        };
+        manifest.push_str("{\n  \"output\": ");
+        manifest.push_str(&escape(output));
+        manifest.push_str(",\n  \"inputs\": [");
+        for (i, input) in inputs.iter().enumerate() {
+            manifest.push_str(if i == 0 { "\n    " } else { ",\n    " });
+            manifest.push_str(&escape(input));
+        }
+        manifest.push_str("\n  ]\n}\n");
+    }
+
+    fs::write(path, manifest)
+}
+
 /// Clear the terminal and render the status message.
 fn status(command: &CompileCommand, status: Status) -> io::Result<()> {
     if !command.watch {
@@ -373,6 +793,47 @@ fn fonts(command: FontsCommand) -> StrResult<()> {
     Ok(())
 }
 
+/// Execute a font report command.
+fn font_report(command: FontReportCommand) -> StrResult<()> {
+    let root = determine_root(&command.input, command.root.as_deref());
+    let mut world = SystemWorld::new(root);
+    world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
+
+    let document = match typst::compile(&world) {
+        Ok(document) => document,
+        Err(errors) => {
+            print_diagnostics(&world, *errors)
+                .map_err(|_| "failed to print diagnostics")?;
+            return Err("failed to compile document".into());
+        }
+    };
+
+    let mut restricted = false;
+    for font in document.fonts() {
+        let info = font.info();
+        let license = font.license();
+        let FontVariant { style, weight, stretch } = info.variant;
+        println!("{}", info.family);
+        println!("- Style: {style:?}, Weight: {weight:?}, Stretch: {stretch:?}");
+        println!("- Embedding: {}", license.embedding);
+        let subsetting = if license.subsetting { "allowed" } else { "forbidden" };
+        println!("- Subsetting: {subsetting}");
+        if let Some(description) = &license.description {
+            println!("- License: {}", description.trim());
+        }
+        if let Some(url) = &license.url {
+            println!("- License URL: {url}");
+        }
+        restricted |= !license.embedding.allows_embedding();
+    }
+
+    if restricted {
+        Err("document uses fonts with restricted embedding permissions")?;
+    }
+
+    Ok(())
+}
+
 /// A world that provides access to the operating system.
 struct SystemWorld {
     root: PathBuf,
@@ -381,6 +842,10 @@ struct SystemWorld {
     fonts: Vec<FontSlot>,
     hashes: RefCell<HashMap<PathBuf, FileResult<PathHash>>>,
     paths: RefCell<HashMap<PathHash, PathSlot>>,
+    /// The ids of the fonts used during the last compilation. Font slots
+    /// keep their font across compilations, so reading their files can't
+    /// tell which ones are still in use.
+    used_fonts: RefCell<BTreeSet<usize>>,
     sources: FrozenVec<Box<Source>>,
     main: SourceId,
 }
@@ -414,6 +879,7 @@ impl SystemWorld {
             fonts: searcher.fonts,
             hashes: RefCell::default(),
             paths: RefCell::default(),
+            used_fonts: RefCell::default(),
             sources: FrozenVec::new(),
             main: SourceId::detached(),
         }
@@ -453,6 +919,7 @@ impl World for SystemWorld {
     }
 
     fn font(&self, id: usize) -> Option<Font> {
+        self.used_fonts.borrow_mut().insert(id);
         let slot = &self.fonts[id];
         slot.font
             .get_or_init(|| {
@@ -490,6 +957,50 @@ impl SystemWorld {
         }))
     }
 
+    /// The canonical paths of all files that were read during the last
+    /// compilation, including the files of all used fonts.
+    ///
+    /// Fonts embedded in the binary have no file and are thus not included.
+    /// They are available through [`embedded_fonts`](Self::embedded_fonts).
+    fn dependencies(&self) -> Vec<PathBuf> {
+        let paths = self.paths.borrow();
+        let mut seen = HashSet::new();
+        let mut dependencies = vec![];
+        for (path, hash) in self.hashes.borrow().iter() {
+            let Ok(hash) = hash else { continue };
+            let Some(slot) = paths.get(hash) else { continue };
+            let read = matches!(slot.source.get(), Some(Ok(_)))
+                || matches!(slot.buffer.get(), Some(Ok(_)));
+            if read && seen.insert(*hash) {
+                dependencies.push(path.canonicalize().unwrap_or_else(|_| path.clone()));
+            }
+        }
+
+        for &id in self.used_fonts.borrow().iter() {
+            let slot = &self.fonts[id];
+            if !slot.path.as_os_str().is_empty() {
+                dependencies
+                    .push(slot.path.canonicalize().unwrap_or_else(|_| slot.path.clone()));
+            }
+        }
+
+        dependencies.sort();
+        dependencies.dedup();
+        dependencies
+    }
+
+    /// The fonts embedded in the binary that were used during the last
+    /// compilation.
+    fn embedded_fonts(&self) -> Vec<Font> {
+        self.used_fonts
+            .borrow()
+            .iter()
+            .map(|&id| &self.fonts[id])
+            .filter(|slot| slot.path.as_os_str().is_empty())
+            .filter_map(|slot| slot.font.get().cloned().flatten())
+            .collect()
+    }
+
     fn insert(&self, path: &Path, text: String) -> SourceId {
         let id = SourceId::from_u16(self.sources.len() as u16);
         let source = Source::new(id, path, text);
@@ -526,6 +1037,7 @@ impl SystemWorld {
         self.sources.as_mut().clear();
         self.hashes.borrow_mut().clear();
         self.paths.borrow_mut().clear();
+        self.used_fonts.borrow_mut().clear();
     }
 }
 
@@ -2,6 +2,7 @@ use std::num::NonZeroI64;
 use std::str::FromStr;
 
 use typst::eval::Regex;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::prelude::*;
 
@@ -223,6 +224,190 @@ cast_from_value! {
     },
 }
 
+/// Create a gradient between two colors.
+///
+/// A gradient can be used wherever a color is expected for a fill or stroke,
+/// including the fill of text. It stretches across the bounding box of the
+/// painted shape or text run.
+///
+/// ## Example
+/// ```example
+/// #rect(fill: gradient(aqua, blue))
+/// #circle(fill: gradient(white, navy, kind: "radial"))
+/// #text(fill: gradient(red, orange, angle: 90deg))[*Hot*]
+/// ```
+///
+/// Display: Gradient
+/// Category: construct
+/// Returns: gradient
+#[func]
+pub fn gradient(
+    /// The color at the start of the gradient.
+    from: Color,
+    /// The color at the end of the gradient.
+    to: Color,
+    /// Whether the colors change along a line or from the center outwards.
+    #[named]
+    #[default(GradientShape::Linear)]
+    kind: GradientShape,
+    /// The direction of a linear gradient. At `{0deg}`, it goes from left to
+    /// right. Larger angles rotate it clockwise.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+) -> Value {
+    let kind = match kind {
+        GradientShape::Linear => GradientKind::Linear(angle),
+        GradientShape::Radial => GradientKind::Radial,
+    };
+    Gradient { kind, from, to }.into()
+}
+
+/// How the colors of a gradient are distributed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+enum GradientShape {
+    /// Along a line.
+    Linear,
+    /// From the center outwards.
+    Radial,
+}
+
+/// Create a repeating pattern of lines or dots.
+///
+/// Like a color, a pattern can be used as the fill or stroke of shapes and
+/// text. It consists of a square tile that repeats across the whole page,
+/// so patterns of adjacent shapes line up seamlessly.
+///
+/// ## Example
+/// ```example
+/// #rect(fill: pattern(blue))
+/// #circle(fill: pattern(red, kind: "grid", angle: 0deg))
+/// #square(fill: pattern(eastern, kind: "dots", thickness: 2pt))
+/// ```
+///
+/// Display: Pattern
+/// Category: construct
+/// Returns: pattern
+#[func]
+pub fn pattern(
+    /// The color of the lines or dots.
+    color: Color,
+    /// What the pattern's tile contains.
+    #[named]
+    #[default(PatternShape::Lines)]
+    kind: PatternShape,
+    /// How the pattern is rotated, clockwise.
+    #[named]
+    #[default(Angle::deg(45.0))]
+    angle: Angle,
+    /// The distance between two lines or dots. Must be an absolute length.
+    #[named]
+    #[default(Abs::pt(4.0).into())]
+    spacing: Length,
+    /// The thickness of the lines or the diameter of the dots. Must be an
+    /// absolute length.
+    #[named]
+    #[default(Abs::pt(1.0).into())]
+    thickness: Length,
+) -> Value {
+    if !spacing.em.is_zero() || !thickness.em.is_zero() {
+        bail!(args.span, "pattern lengths must be absolute");
+    }
+
+    if spacing.abs <= Abs::zero() {
+        bail!(args.span, "pattern spacing must be positive");
+    }
+
+    let kind = match kind {
+        PatternShape::Lines => PatternKind::Lines,
+        PatternShape::Grid => PatternKind::Grid,
+        PatternShape::Dots => PatternKind::Dots,
+    };
+
+    Pattern {
+        kind,
+        color,
+        angle,
+        spacing: spacing.abs,
+        thickness: thickness.abs.max(Abs::zero()),
+    }
+    .into()
+}
+
+/// What the tile of a pattern contains.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+enum PatternShape {
+    /// Parallel lines, as in hatching.
+    Lines,
+    /// Perpendicular lines, as in cross-hatching.
+    Grid,
+    /// Dots.
+    Dots,
+}
+
+/// Pick a readable text color for a background.
+///
+/// Returns the candidate color with the highest contrast to the background as
+/// defined by the Web Content Accessibility Guidelines. For a gradient, the
+/// contrast to its less favorable end counts, and for a pattern, the contrast
+/// to its color. This is useful for components like badges or striped tables
+/// whose fill is not known in advance. Use the color's
+/// [`contrast`]($type/color.contrast) method to compute the ratio itself.
+///
+/// ## Example
+/// ```example
+/// #let badge(fill, body) = box(
+///   fill: fill,
+///   inset: 3pt,
+///   radius: 2pt,
+///   text(fill: readable(fill), body),
+/// )
+///
+/// #badge(navy)[Stable]
+/// #badge(yellow)[Beta]
+/// #badge(gradient(red, purple))[Hot]
+/// ```
+///
+/// Display: Readable
+/// Category: construct
+/// Returns: color
+#[func]
+pub fn readable(
+    /// The background paint.
+    background: Paint,
+    /// The colors to choose from. Among colors with the same contrast, the
+    /// first one wins.
+    #[named]
+    #[default(vec![Color::BLACK, Color::WHITE])]
+    candidates: Vec<Color>,
+) -> Value {
+    let backgrounds = match background {
+        Paint::Solid(color) => vec![color],
+        Paint::Gradient(gradient) => vec![gradient.from, gradient.to],
+        Paint::Pattern(pattern) => vec![pattern.color],
+    };
+
+    let contrast = |color: Color| {
+        backgrounds
+            .iter()
+            .map(|background| background.contrast(color))
+            .fold(f64::INFINITY, f64::min)
+    };
+
+    if candidates.is_empty() {
+        bail!(args.span, "candidates must not be empty");
+    }
+
+    let mut best = candidates[0];
+    for &color in &candidates[1..] {
+        if contrast(color) > contrast(best) {
+            best = color;
+        }
+    }
+
+    Value::Color(best)
+}
+
 /// Create a custom symbol with modifiers.
 ///
 /// ## Example
@@ -318,6 +503,243 @@ cast_from_value! {
     v: Str => Self(v),
 }
 
+/// Format values into a string.
+///
+/// Each placeholder in curly braces is replaced by a value. An empty
+/// placeholder `{}` takes the next positional value, `{1}` takes the
+/// positional value at a specific index and `{name}` takes the named value
+/// with that name. Literal braces are written as `{{` and `}}`.
+///
+/// After a colon, a placeholder can specify how to format its value:
+///
+/// - An optional fill character followed by an alignment: `<` (left), `^`
+///   (center) or `>` (right). Numbers are right-aligned and everything else is
+///   left-aligned by default.
+/// - A `0` to pad numbers with zeros after their sign.
+/// - A minimum width in user-perceived characters.
+/// - A precision after a dot, giving the number of decimal places of a float.
+/// - A number base for integers: `b` (binary), `o` (octal), `x` or `X`
+///   (hexadecimal).
+///
+/// Strings are inserted as they are, numbers are formatted as with
+/// [`str`]($func/str) and all other values by their
+/// [representation]($func/repr).
+///
+/// ## Example
+/// ```example
+/// #format("{} has {} legs", "Spider", 8) \
+/// #format("{name}: {score:.1}", name: "Anna", score: 9.87) \
+/// #format("[{:*^9}]", "mid") \
+/// #format("#{:06X}", 48879)
+/// ```
+///
+/// Display: Format
+/// Category: construct
+/// Returns: string
+#[func]
+pub fn format(
+    /// The template with the placeholders.
+    template: Spanned<EcoString>,
+    /// The values to insert into the placeholders.
+    #[external]
+    #[variadic]
+    values: Vec<Value>,
+) -> Value {
+    let args = args.take();
+    let positional = args.to_pos();
+    let named = args.to_named();
+    let Spanned { v: template, span } = template;
+    Value::Str(format_template(&template, &positional, &named).at(span)?.into())
+}
+
+/// Replace the placeholders in a template with formatted values.
+fn format_template(
+    template: &str,
+    positional: &Array,
+    named: &Dict,
+) -> StrResult<EcoString> {
+    let mut out = EcoString::new();
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => Err("unmatched closing brace in format string")?,
+            '{' => {
+                let mut placeholder = EcoString::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => Err("unclosed placeholder in format string")?,
+                    }
+                }
+
+                let (key, spec) = match placeholder.split_once(':') {
+                    Some((key, spec)) => (key.trim(), spec),
+                    None => (placeholder.trim(), ""),
+                };
+
+                let value = if key.is_empty() || key.parse::<usize>().is_ok() {
+                    let index = key.parse().unwrap_or_else(|_| {
+                        next += 1;
+                        next - 1
+                    });
+                    positional
+                        .as_slice()
+                        .get(index)
+                        .ok_or_else(|| eco_format!("missing positional value {index}"))?
+                } else {
+                    named.at(key)?
+                };
+
+                format_value(&mut out, value, &FormatSpec::parse(spec)?)?;
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// How to format a single value.
+#[derive(Default)]
+struct FormatSpec {
+    fill: Option<char>,
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    base: Option<char>,
+}
+
+impl FormatSpec {
+    /// Parse a format specification like `*>8.2`.
+    fn parse(spec: &str) -> StrResult<Self> {
+        let invalid = || eco_format!("invalid format specification `{spec}`");
+        let mut this = Self::default();
+        let mut rest = spec;
+
+        let is_align = |c: char| matches!(c, '<' | '^' | '>');
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (Some(fill), Some(align)) if is_align(align) => {
+                this.fill = Some(fill);
+                this.align = Some(align);
+                rest = &rest[fill.len_utf8() + 1..];
+            }
+            (Some(align), _) if is_align(align) => {
+                this.align = Some(align);
+                rest = &rest[1..];
+            }
+            _ => {}
+        }
+
+        if let Some(tail) = rest.strip_prefix('0') {
+            this.zero = true;
+            rest = tail;
+        }
+
+        let digits =
+            rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 {
+            this.width = rest[..digits].parse().map_err(|_| invalid())?;
+            rest = &rest[digits..];
+        }
+
+        if let Some(tail) = rest.strip_prefix('.') {
+            let digits =
+                tail.len() - tail.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                return Err(invalid());
+            }
+            this.precision = Some(tail[..digits].parse().map_err(|_| invalid())?);
+            rest = &tail[digits..];
+        }
+
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => {}
+            (Some(base @ ('b' | 'o' | 'x' | 'X')), None) => this.base = Some(base),
+            _ => return Err(invalid()),
+        }
+
+        Ok(this)
+    }
+}
+
+/// Format a value according to a specification and append it to `out`.
+fn format_value(out: &mut EcoString, value: &Value, spec: &FormatSpec) -> StrResult<()> {
+    if spec.base.is_some() && !matches!(value, Value::Int(_)) {
+        Err(eco_format!("cannot format {} in another base", value.type_name()))?;
+    }
+
+    if spec.precision.is_some() && !matches!(value, Value::Float(_)) {
+        Err(eco_format!("cannot format {} with a precision", value.type_name()))?;
+    }
+
+    let numeric = matches!(value, Value::Int(_) | Value::Float(_));
+    let text: EcoString = match value {
+        Value::Str(v) => v.as_str().into(),
+        Value::Int(v) => match spec.base {
+            Some('b') => eco_format!("{}{:b}", sign(*v), v.unsigned_abs()),
+            Some('o') => eco_format!("{}{:o}", sign(*v), v.unsigned_abs()),
+            Some('x') => eco_format!("{}{:x}", sign(*v), v.unsigned_abs()),
+            Some('X') => eco_format!("{}{:X}", sign(*v), v.unsigned_abs()),
+            _ => eco_format!("{v}"),
+        },
+        Value::Float(v) => match spec.precision {
+            Some(precision) => eco_format!("{v:.precision$}"),
+            None => eco_format!("{v}"),
+        },
+        v => v.repr().into(),
+    };
+
+    // Count what readers perceive as characters, so that accented letters
+    // and emoji are padded like other characters.
+    let count = text.graphemes(true).count();
+    let padding = spec.width.saturating_sub(count);
+    if spec.zero && numeric && spec.align.is_none() {
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", text.as_str()),
+        };
+        out.push_str(sign);
+        out.push_str(&"0".repeat(padding));
+        out.push_str(digits);
+        return Ok(());
+    }
+
+    let fill = spec.fill.unwrap_or(' ');
+    let align = spec.align.unwrap_or(if numeric { '>' } else { '<' });
+    let (before, after) = match align {
+        '<' => (0, padding),
+        '^' => (padding / 2, padding - padding / 2),
+        _ => (padding, 0),
+    };
+
+    let fill = |n: usize| std::iter::repeat(fill).take(n).collect::<String>();
+    out.push_str(&fill(before));
+    out.push_str(&text);
+    out.push_str(&fill(after));
+    Ok(())
+}
+
+/// The sign prefix of an integer.
+fn sign(v: i64) -> &'static str {
+    if v < 0 {
+        "-"
+    } else {
+        ""
+    }
+}
+
 /// Create a label from a string.
 ///
 /// Inserting a label into content attaches it to the closest previous element
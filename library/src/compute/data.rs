@@ -23,6 +23,22 @@ use crate::prelude::*;
 pub fn read(
     /// Path to a file.
     path: Spanned<EcoString>,
+    /// The lines to read, given as an array of the first and last line
+    /// number. Line numbers start at one and both lines are included, so they
+    /// must exist in the file. This is useful to embed an excerpt of a source
+    /// file as a code listing.
+    ///
+    /// ```example
+    /// #raw(read("data.html", lines: (2, 4)), lang: "html", block: true)
+    /// ```
+    #[named]
+    #[default]
+    lines: Option<LineRange>,
+    /// If given, tabs are replaced by spaces up to the next multiple of this
+    /// many columns.
+    #[named]
+    #[default]
+    tab_size: Option<NonZeroUsize>,
 ) -> Value {
     let Spanned { v: path, span } = path;
     let path = vm.locate(&path).at(span)?;
@@ -30,9 +46,77 @@ pub fn read(
     let text = std::str::from_utf8(&data)
         .map_err(|_| "file is not valid utf-8")
         .at(span)?;
+
+    let mut text = EcoString::from(text);
+    if let Some(LineRange { start, end }) = lines {
+        let count = text.lines().count();
+        if end > count {
+            bail!(args.span, "line range out of bounds (end: {end}, len: {count})");
+        }
+
+        text = text
+            .lines()
+            .skip(start - 1)
+            .take(end - start + 1)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .into();
+    }
+
+    if let Some(size) = tab_size {
+        text = expand_tabs(&text, size.get());
+    }
+
     Value::Str(text.into())
 }
 
+/// An inclusive range of one-based line numbers.
+struct LineRange {
+    start: usize,
+    end: usize,
+}
+
+cast_from_value! {
+    LineRange,
+    v: Array => match v.as_slice() {
+        [start, end] => {
+            let start = start.clone().cast::<NonZeroUsize>()?.get();
+            let end = end.clone().cast::<NonZeroUsize>()?.get();
+            if end < start {
+                Err("last line must not come before first line")?
+            }
+            Self { start, end }
+        }
+        _ => Err("line range must contain exactly two line numbers")?,
+    },
+}
+
+/// Replace tabs with spaces up to the next multiple of `size` columns.
+fn expand_tabs(text: &str, size: usize) -> EcoString {
+    let mut out = EcoString::new();
+    let mut column = 0;
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                let n = size - column % size;
+                for _ in 0..n {
+                    out.push(' ');
+                }
+                column += n;
+            }
+            '\n' => {
+                out.push(c);
+                column = 0;
+            }
+            _ => {
+                out.push(c);
+                column += 1;
+            }
+        }
+    }
+    out
+}
+
 /// Read structured data from a CSV file.
 ///
 /// The CSV file will be read and parsed into a 2-dimensional array of strings:
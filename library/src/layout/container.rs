@@ -88,6 +88,22 @@ pub struct BoxElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// Whether to clip the content inside the box.
+    ///
+    /// Content that overflows a box with a fixed size is cut off at the box's
+    /// edges and rounded corners.
+    ///
+    /// ```example
+    /// #box(
+    ///   width: 50pt,
+    ///   height: 50pt,
+    ///   clip: true,
+    ///   image("tiger.jpg", width: 100pt),
+    /// )
+    /// ```
+    #[default(false)]
+    pub clip: bool,
+
     /// The contents of the box.
     #[positional]
     pub body: Option<Content>,
@@ -133,6 +149,11 @@ impl Layout for BoxElem {
             frame.set_baseline(frame.baseline() - shift);
         }
 
+        // Clip the contents.
+        if self.clip(styles) {
+            clip(&mut frame, self.radius(styles));
+        }
+
         // Prepare fill and stroke.
         let fill = self.fill(styles);
         let stroke = self.stroke(styles).map(|s| s.map(PartialStroke::unwrap_or_default));
@@ -256,6 +277,13 @@ pub struct BlockElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// Whether to clip the content inside the block.
+    ///
+    /// Content that overflows a block with a fixed size is cut off at the
+    /// block's edges and rounded corners.
+    #[default(false)]
+    pub clip: bool,
+
     /// The spacing around this block. This is shorthand to set `above` and
     /// `below` to the same value.
     ///
@@ -369,6 +397,14 @@ impl Layout for BlockElem {
             body.layout(vt, styles, pod)?.into_frames()
         };
 
+        // Clip the contents.
+        if self.clip(styles) {
+            let radius = self.radius(styles);
+            for frame in &mut frames {
+                clip(frame, radius);
+            }
+        }
+
         // Prepare fill and stroke.
         let fill = self.fill(styles);
         let stroke = self.stroke(styles).map(|s| s.map(PartialStroke::unwrap_or_default));
@@ -396,6 +432,17 @@ impl Layout for BlockElem {
     }
 }
 
+/// Clip the contents of a container's frame to its size and rounded corners.
+fn clip(frame: &mut Frame, radius: Corners<Rel<Abs>>) {
+    let size = frame.size();
+    let radius = radius.map(|side| side.relative_to(size.x.min(size.y) / 2.0));
+    if radius.iter().all(|side| side.is_zero()) {
+        frame.clip();
+    } else {
+        frame.clip_to(rounded_rect_path(size, radius));
+    }
+}
+
 /// Defines how to size a grid cell along an axis.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Sizing {
@@ -1,7 +1,9 @@
 use std::str::FromStr;
 
+use typst::util::hash128;
+
 use crate::layout::{BlockElem, ParElem, Sizing, Spacing};
-use crate::meta::{Numbering, NumberingPattern};
+use crate::meta::{DocumentElem, Numbering, NumberingPattern};
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -174,11 +176,12 @@ impl Layout for EnumElem {
         let mut number = self.start(styles);
         let mut parents = self.parents(styles);
         let full = self.full(styles);
+        let tagged = DocumentElem::tagged_in(styles);
 
         for item in self.children() {
             number = item.number(styles).unwrap_or(number);
 
-            let resolved = if full {
+            let mut resolved = if full {
                 parents.push(number);
                 let content = numbering.apply_vt(vt, &parents)?.display();
                 parents.pop();
@@ -192,10 +195,17 @@ impl Layout for EnumElem {
                 }
             };
 
+            let mut body = item.body().styled(Self::set_parents(Parent(number)));
+            if tagged {
+                let tag = Tag::new(Role::ListItem, vt.provider.locate(hash128(&item.0)));
+                resolved = resolved.tagged(tag.clone());
+                body = body.tagged(tag);
+            }
+
             cells.push(Content::empty());
             cells.push(resolved);
             cells.push(Content::empty());
-            cells.push(item.body().styled(Self::set_parents(Parent(number))));
+            cells.push(body);
             number = number.saturating_add(1);
         }
 
@@ -213,7 +223,15 @@ impl Layout for EnumElem {
             styles,
         );
 
-        Ok(layouter.layout()?.fragment)
+        let mut fragment = layouter.layout()?.fragment;
+        if tagged {
+            let tag = Tag::new(Role::List, vt.provider.locate(hash128(&self.0)));
+            for frame in &mut fragment {
+                frame.tag_in(tag.clone(), styles);
+            }
+        }
+
+        Ok(fragment)
     }
 }
 
@@ -169,6 +169,19 @@ pub struct GridLayouter<'a, 'v> {
     initial: Size,
     /// Frames for finished regions.
     finished: Vec<Frame>,
+    /// The number of content rows at the start that are repeated in each
+    /// region.
+    header: usize,
+    /// The number of content rows at the end that are repeated in each
+    /// region.
+    footer: usize,
+    /// The laid out header rows with their y indices.
+    header_rows: Vec<(Frame, usize)>,
+    /// The laid out footer rows with their y indices.
+    footer_rows: Vec<(Frame, usize)>,
+    /// Whether the repeated rows were laid out by an earlier layout of the
+    /// same grid.
+    reused: bool,
 }
 
 /// The resulting sizes of columns and rows in a grid.
@@ -180,6 +193,10 @@ pub struct GridLayout {
     pub cols: Vec<Abs>,
     /// The heights of the resulting rows segments, by region.
     pub rows: Vec<Vec<RowPiece>>,
+    /// The laid out header rows with their y indices.
+    pub header_rows: Vec<(Frame, usize)>,
+    /// The laid out footer rows with their y indices.
+    pub footer_rows: Vec<(Frame, usize)>,
 }
 
 /// Details about a resulting row piece.
@@ -285,18 +302,67 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             lrows,
             initial: regions.size,
             finished: vec![],
+            header: 0,
+            footer: 0,
+            header_rows: vec![],
+            footer_rows: vec![],
+            reused: false,
         }
     }
 
+    /// Repeat the given number of content rows at the start and end of the
+    /// grid in each region.
+    pub fn repeat(mut self, header: usize, footer: usize) -> Self {
+        self.header = header;
+        self.footer = footer;
+        self
+    }
+
+    /// Reuse the repeated rows of an earlier layout of the same grid instead
+    /// of laying them out again.
+    pub fn reuse(mut self, layout: &mut GridLayout) -> Self {
+        self.header_rows = std::mem::take(&mut layout.header_rows);
+        self.footer_rows = std::mem::take(&mut layout.footer_rows);
+        self.reused = true;
+        self
+    }
+
     /// Determines the columns sizes and then layouts the grid row-by-row.
     pub fn layout(mut self) -> SourceResult<GridLayout> {
         self.measure_columns()?;
 
-        for y in 0..self.rows.len() {
+        // Determine the track ranges of the repeated rows. The gutter after
+        // the header belongs to the header.
+        let len = self.rows.len();
+        let stride = if self.has_gutter { 2 } else { 1 };
+        let header_end = (self.header * stride).min(len);
+        let footer_start = if self.footer > 0 {
+            (len + stride - 1)
+                .saturating_sub(self.footer * stride)
+                .max(header_end)
+        } else {
+            len
+        };
+
+        // Lay out the repeated rows once.
+        if !self.reused {
+            for y in 0..header_end {
+                let frame = self.layout_repeated_row(y)?;
+                self.header_rows.push((frame, y));
+            }
+            for y in footer_start..len {
+                let frame = self.layout_repeated_row(y)?;
+                self.footer_rows.push((frame, y));
+            }
+        }
+
+        self.start_region();
+
+        for y in header_end..footer_start {
             // Skip to next region if current one is full, but only for content
             // rows, not for gutter rows.
             if y % 2 == 0 && self.regions.is_full() {
-                self.finish_region()?;
+                self.finish_region(false)?;
             }
 
             match self.rows[y] {
@@ -306,12 +372,14 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             }
         }
 
-        self.finish_region()?;
+        self.finish_region(true)?;
 
         Ok(GridLayout {
             fragment: Fragment::frames(self.finished),
             cols: self.rcols,
             rows: self.rrows,
+            header_rows: self.header_rows,
+            footer_rows: self.footer_rows,
         })
     }
 
@@ -490,7 +558,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
         // Skip the first region if it's empty for some cell.
         if skip && !self.regions.in_last() {
-            self.finish_region()?;
+            self.finish_region(false)?;
             resolved.remove(0);
         }
 
@@ -512,7 +580,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         for (i, frame) in fragment.into_iter().enumerate() {
             self.push_row(frame, y);
             if i + 1 < len {
-                self.finish_region()?;
+                self.finish_region(false)?;
             }
         }
 
@@ -528,7 +596,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         // Skip to fitting region.
         let height = frame.height();
         while !self.regions.size.y.fits(height) && !self.regions.in_last() {
-            self.finish_region()?;
+            self.finish_region(false)?;
 
             // Don't skip multiple regions for gutter and don't push a row.
             if y % 2 == 1 {
@@ -541,6 +609,29 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         Ok(())
     }
 
+    /// Layout a row that is repeated in each region. Such a row cannot break
+    /// across multiple regions. Fractional rows are sized like auto rows here
+    /// as repeated rows don't take part in distributing the remaining space.
+    fn layout_repeated_row(&mut self, y: usize) -> SourceResult<Frame> {
+        let height = match self.rows[y] {
+            Sizing::Rel(v) => v.resolve(self.styles).relative_to(self.regions.base().y),
+            Sizing::Auto | Sizing::Fr(_) => {
+                let mut height = Abs::zero();
+                for (x, &rcol) in self.rcols.iter().enumerate() {
+                    if let Some(cell) = self.cell(x, y) {
+                        let size = Size::new(rcol, Abs::inf());
+                        let pod = Regions::one(size, Axes::new(true, false));
+                        let frame = cell.measure(self.vt, self.styles, pod)?.into_frame();
+                        height.set_max(frame.height());
+                    }
+                }
+                height
+            }
+        };
+
+        self.layout_single_row(height, y)
+    }
+
     /// Layout a row with fixed height and return its frame.
     fn layout_single_row(&mut self, height: Abs, y: usize) -> SourceResult<Frame> {
         let mut output = Frame::new(Size::new(self.width, height));
@@ -602,8 +693,26 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         self.lrows.push(Row::Frame(frame, y));
     }
 
-    /// Finish rows for one region.
-    fn finish_region(&mut self) -> SourceResult<()> {
+    /// Start a new region by placing the header rows and reserving space for
+    /// the footer rows.
+    fn start_region(&mut self) {
+        for (frame, y) in self.header_rows.clone() {
+            self.push_row(frame, y);
+        }
+
+        for (frame, _) in &self.footer_rows {
+            self.regions.size.y -= frame.height();
+        }
+    }
+
+    /// Finish rows for one region. Unless it's the last one, the next region
+    /// is started right away.
+    fn finish_region(&mut self, last: bool) -> SourceResult<()> {
+        // Place the footer rows in the space reserved for them.
+        for (frame, y) in self.footer_rows.clone() {
+            self.lrows.push(Row::Frame(frame, y));
+        }
+
         // Determine the height of existing rows in the region.
         let mut used = Abs::zero();
         let mut fr = Fr::zero();
@@ -648,6 +757,10 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         self.regions.next();
         self.initial = self.regions.size;
 
+        if !last {
+            self.start_region();
+        }
+
         Ok(())
     }
 
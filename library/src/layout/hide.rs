@@ -1,3 +1,4 @@
+use super::{BoxElem, PlaceElem};
 use crate::prelude::*;
 
 /// Hide content without affecting layout.
@@ -7,6 +8,10 @@ use crate::prelude::*;
 /// content. It may also be useful to redact content because its arguments are
 /// not included in the output.
 ///
+/// Hidden content remains visible to introspection: Labels and counters
+/// within it can still be queried and referenced. Hidden headings don't appear
+/// in the [outline]($func/outline) or among the PDF bookmarks, though.
+///
 /// ## Example
 /// ```example
 /// Hello Jane \
@@ -20,10 +25,33 @@ pub struct HideElem {
     /// The content to hide.
     #[required]
     pub body: Content,
+
+    /// Whether the hidden content should take up no space at all.
+    ///
+    /// Collapsed content only serves as metadata. This is useful for
+    /// invisible anchors, like a label or a bookmark.
+    ///
+    /// ```example
+    /// #hide(collapse: true)[
+    ///   #figure(rect(), caption: [Hidden]) <hidden>
+    /// ]
+    /// Text right at the top refers
+    /// to @hidden.
+    /// ```
+    #[default(false)]
+    pub collapse: bool,
 }
 
 impl Show for HideElem {
-    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
-        Ok(self.body().styled(MetaElem::set_data(vec![Meta::Hide])))
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let hidden = self.body().styled(MetaElem::set_data(vec![Meta::Hide]));
+        if !self.collapse(styles) {
+            return Ok(hidden);
+        }
+
+        // A placed element doesn't take up space in the zero-sized box, but
+        // is still laid out with the full available width.
+        let placed = PlaceElem::new(hidden).pack();
+        Ok(BoxElem::new().with_body(Some(placed)).pack())
     }
 }
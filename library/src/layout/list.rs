@@ -1,4 +1,7 @@
+use typst::util::hash128;
+
 use crate::layout::{BlockElem, ParElem, Sizing, Spacing};
+use crate::meta::DocumentElem;
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -130,12 +133,21 @@ impl Layout for ListElem {
         let depth = self.depth(styles);
         let marker = self.marker(styles).resolve(vt, depth)?;
 
+        let tagged = DocumentElem::tagged_in(styles);
         let mut cells = vec![];
         for item in self.children() {
+            let mut marker = marker.clone();
+            let mut body = item.body().styled(Self::set_depth(Depth));
+            if tagged {
+                let tag = Tag::new(Role::ListItem, vt.provider.locate(hash128(&item.0)));
+                marker = marker.tagged(tag.clone());
+                body = body.tagged(tag);
+            }
+
             cells.push(Content::empty());
-            cells.push(marker.clone());
+            cells.push(marker);
             cells.push(Content::empty());
-            cells.push(item.body().styled(Self::set_depth(Depth)));
+            cells.push(body);
         }
 
         let layouter = GridLayouter::new(
@@ -152,7 +164,15 @@ impl Layout for ListElem {
             styles,
         );
 
-        Ok(layouter.layout()?.fragment)
+        let mut fragment = layouter.layout()?.fragment;
+        if tagged {
+            let tag = Tag::new(Role::List, vt.provider.locate(hash128(&self.0)));
+            for frame in &mut fragment {
+                frame.tag_in(tag.clone(), styles);
+            }
+        }
+
+        Ok(fragment)
     }
 }
 
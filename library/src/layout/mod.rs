@@ -11,14 +11,17 @@ mod grid;
 mod hide;
 mod list;
 mod measure;
+mod note;
 mod pad;
 mod page;
 mod par;
 mod place;
 mod regions;
 mod repeat;
+mod responsive;
 mod spacing;
 mod stack;
+mod tab;
 mod table;
 mod terms;
 mod transform;
@@ -33,14 +36,17 @@ pub use self::grid::*;
 pub use self::hide::*;
 pub use self::list::*;
 pub use self::measure::*;
+pub use self::note::*;
 pub use self::pad::*;
 pub use self::page::*;
 pub use self::par::*;
 pub use self::place::*;
 pub use self::regions::*;
 pub use self::repeat::*;
+pub use self::responsive::*;
 pub use self::spacing::*;
 pub use self::stack::*;
+pub use self::tab::*;
 pub use self::table::*;
 pub use self::terms::*;
 pub use self::transform::*;
@@ -56,7 +62,7 @@ use crate::math::{EquationElem, LayoutMath};
 use crate::meta::DocumentElem;
 use crate::prelude::*;
 use crate::shared::BehavedBuilder;
-use crate::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
+use crate::text::{KernElem, LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
 use crate::visualize::{CircleElem, EllipseElem, ImageElem, RectElem, SquareElem};
 
 /// Root-level layout.
@@ -492,6 +498,8 @@ impl<'a> ParBuilder<'a> {
         } else if content.is::<SpaceElem>()
             || content.is::<TextElem>()
             || content.is::<HElem>()
+            || content.is::<TabElem>()
+            || content.is::<KernElem>()
             || content.is::<LinebreakElem>()
             || content.is::<SmartQuoteElem>()
             || content.to::<EquationElem>().map_or(false, |elem| !elem.block(styles))
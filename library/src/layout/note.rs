@@ -0,0 +1,194 @@
+use crate::prelude::*;
+
+/// A note in the page margin.
+///
+/// The note is anchored to the point in the text where it appears and placed
+/// in the margin next to it. Notes that would overlap are shifted down. The
+/// notes are laid out after the text of the page, but with the styles in effect
+/// where they appear.
+///
+/// ## Example
+/// ```example
+/// #set page(margin: (right: 3.5cm))
+/// #let side = marginnote.with(connector: 0.5pt + gray)
+///
+/// The mitochondria#side[Organelles found in most
+/// eukaryotic cells.] is the powerhouse of the
+/// cell.#marginnote[A popular phrase.] It produces
+/// energy through respiration.
+/// ```
+///
+/// Display: Margin Note
+/// Category: layout
+#[element(Locatable, Synthesize, Show)]
+pub struct MarginNoteElem {
+    /// The content of the note.
+    #[required]
+    pub body: Content,
+
+    /// In which margin to place the note. Can be `{left}` or `{right}`.
+    #[default(MarginSide::Right)]
+    pub side: MarginSide,
+
+    /// The distance between the note and the text as well as between
+    /// consecutive notes.
+    #[resolve]
+    #[default(Em::new(1.0).into())]
+    pub gap: Length,
+
+    /// How to stroke a line connecting the anchor in the text with the note.
+    ///
+    /// ```example
+    /// #set page(margin: (left: 3cm))
+    /// A#marginnote(
+    ///   side: left,
+    ///   connector: red,
+    /// )[Connected.] line.
+    /// ```
+    #[resolve]
+    #[fold]
+    pub connector: Option<PartialStroke>,
+
+    /// The styles in effect where the note appears.
+    #[internal]
+    pub styles: Styles,
+}
+
+impl Synthesize for MarginNoteElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        // The note is laid out by the page, so it keeps the styles to resolve
+        // its properties and lay out its body with later.
+        self.push_styles(styles.to_map());
+    }
+}
+
+impl Show for MarginNoteElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        // The note itself is laid out by the page, which finds it through the
+        // metadata left at its anchor.
+        Ok(Content::empty())
+    }
+}
+
+/// In which margin to place a note.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MarginSide {
+    /// The left margin.
+    Left,
+    /// The right margin.
+    Right,
+}
+
+cast_from_value! {
+    MarginSide,
+    align: GenAlign => match align {
+        GenAlign::Specific(Align::Left) => Self::Left,
+        GenAlign::Specific(Align::Right) => Self::Right,
+        _ => Err("expected either `left` or `right`")?,
+    },
+}
+
+cast_to_value! {
+    v: MarginSide => Value::from(match v {
+        MarginSide::Left => GenAlign::Specific(Align::Left),
+        MarginSide::Right => GenAlign::Specific(Align::Right),
+    })
+}
+
+/// Lay out the margin notes anchored in a page's frame into its margins.
+pub(super) fn layout_margin_notes(
+    vt: &mut Vt,
+    styles: StyleChain,
+    frame: &mut Frame,
+    margins: Sides<Abs>,
+) -> SourceResult<()> {
+    let mut notes = vec![];
+    collect(frame, Transform::identity(), &mut notes);
+    notes.sort_by_key(|(anchor, _)| anchor.y);
+
+    for side in [MarginSide::Left, MarginSide::Right] {
+        let mut cursor = Abs::zero();
+        for (anchor, note) in &notes {
+            let local = note.styles(StyleChain::default());
+            let styles = styles.chain(&local);
+            if note.side(styles) != side {
+                continue;
+            }
+
+            // Lay out the note in the margin, with its first baseline at the
+            // height of the anchor if possible.
+            let gap = note.gap(styles);
+            let width = match side {
+                MarginSide::Left => margins.left,
+                MarginSide::Right => margins.right,
+            };
+            let size = Size::new((width - 2.0 * gap).max(Abs::zero()), Abs::inf());
+            let pod = Regions::one(size, Axes::new(true, false));
+            let sub = note.body().layout(vt, styles, pod)?.into_frame();
+            let baseline = first_baseline(&sub).unwrap_or_default();
+            let y = (anchor.y - baseline).max(cursor);
+            cursor = y + sub.height() + gap;
+
+            let (x, edge) = match side {
+                MarginSide::Left => (gap, margins.left),
+                MarginSide::Right => {
+                    let edge = frame.width() - margins.right;
+                    (edge + gap, edge)
+                }
+            };
+
+            if let Some(stroke) = note.connector(styles) {
+                let stroke = stroke.unwrap_or_default();
+                let end = match side {
+                    MarginSide::Left => Point::new(x + sub.width(), y + baseline),
+                    MarginSide::Right => Point::new(x, y + baseline),
+                };
+                let mut connector = Frame::new(Size::zero());
+                for (from, to) in [
+                    (*anchor, Point::new(edge, anchor.y)),
+                    (Point::new(edge, anchor.y), end),
+                ] {
+                    let line = Geometry::Line(to - from).stroked(stroke);
+                    connector.push(from, FrameItem::Shape(line, note.span()));
+                }
+                connector.mark_artifact();
+                frame.push_frame(Point::zero(), connector);
+            }
+
+            frame.push_frame(Point::new(x, y), sub);
+        }
+    }
+
+    Ok(())
+}
+
+/// Collect the margin notes anchored in a frame with their positions.
+fn collect(frame: &Frame, ts: Transform, notes: &mut Vec<(Point, MarginNoteElem)>) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                collect(&group.frame, ts, notes);
+            }
+            FrameItem::Meta(Meta::Elem(content), _) => {
+                let Some(note) = content.to::<MarginNoteElem>() else { continue };
+                if !notes.iter().any(|(_, prev)| prev.0.location() == content.location())
+                {
+                    notes.push((pos.transform(ts), note.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The position of the first baseline of text in a frame.
+fn first_baseline(frame: &Frame) -> Option<Abs> {
+    frame.items().find_map(|(pos, item)| match item {
+        FrameItem::Text(_) => Some(pos.y),
+        FrameItem::Group(group) => first_baseline(&group.frame).map(|y| pos.y + y),
+        _ => None,
+    })
+}
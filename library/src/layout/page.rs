@@ -1,8 +1,10 @@
 use std::ptr;
 use std::str::FromStr;
 
+use super::note::layout_margin_notes;
+use super::place::{layout_bleed_placed, layout_page_placed};
 use super::{AlignElem, ColumnsElem};
-use crate::meta::{Counter, CounterKey, Numbering};
+use crate::meta::{Counter, CounterKey, HeadingElem, Numbering};
 use crate::prelude::*;
 
 /// Layouts its child onto one or multiple pages.
@@ -135,6 +137,80 @@ pub struct PageElem {
     #[default(NonZeroUsize::ONE)]
     pub columns: NonZeroUsize,
 
+    /// How far the page extends beyond its trimmed edges on each side.
+    ///
+    /// Printed pages are trimmed to their final size, which can't be done
+    /// perfectly precisely. To ensure that backgrounds reach up to the trimmed
+    /// edges anyway, they should extend into the bleed. The page's `fill` and
+    /// content [placed]($func/place.scope) with `{scope: "bleed"}` cover the
+    /// bleed, while everything else is laid out relative to the trimmed page.
+    /// In PDF export, the trimmed page is marked with a trim box.
+    ///
+    /// ```example
+    /// #set page(height: 80pt, bleed: 6pt, fill: aqua)
+    /// #place(
+    ///   top,
+    ///   scope: "bleed",
+    ///   rect(width: 100%, height: 30pt, fill: navy),
+    /// )
+    /// Trimmed later.
+    /// ```
+    #[resolve]
+    #[default]
+    pub bleed: Bleed,
+
+    /// How far viewers rotate the page clockwise when displaying it.
+    ///
+    /// The rotation must be a multiple of `{90deg}`. It doesn't affect the
+    /// layout, so a landscape table can be laid out on a portrait page that
+    /// readers then see the right way up. Only PDF export takes the rotation
+    /// into account.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// Portrait.
+    ///
+    /// #page(rotation: 90deg)[
+    ///   Turned in the viewer.
+    /// ]
+    /// ```
+    #[default]
+    pub rotation: Rotation,
+
+    /// Thumb tabs at the outer page edge that show which section a page
+    /// belongs to.
+    ///
+    /// Each top-level section gets a colored tab one step further down the
+    /// page edge than the previous one. The tabs are placed on the right edge
+    /// of odd pages and on the left edge of even pages and extend into the
+    /// bleed, so that they remain visible on the trimmed edge of the printed
+    /// book.
+    ///
+    /// Accepts a dictionary with the following optional keys:
+    /// - `level`: The heading level that starts a new section. Defaults to
+    ///   `{1}`.
+    /// - `width`: How far the tabs reach into the page. Defaults to `{6mm}`.
+    /// - `height`: The height of each tab. Defaults to `{12mm}`.
+    /// - `fill`: The tabs' color. Defaults to `{luma(40%)}`.
+    ///
+    /// Tabs wrap around to the top once they would reach into the bottom
+    /// margin.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 100pt,
+    ///   bleed: 4pt,
+    ///   tabs: (height: 16pt, fill: eastern),
+    /// )
+    ///
+    /// = Setup
+    /// #lorem(10)
+    /// #pagebreak()
+    /// = Usage
+    /// #lorem(10)
+    /// ```
+    pub tabs: Option<Tabs>,
+
     /// The page's background color.
     ///
     /// This instructs the printer to color the complete page with the given
@@ -151,7 +227,10 @@ pub struct PageElem {
 
     /// How to [number]($func/numbering) the pages.
     ///
-    /// If an explicit `footer` is given, the numbering is ignored.
+    /// If an explicit `footer` is given, the numbering is not displayed. It
+    /// still determines the page labels that PDF viewers show, so that a
+    /// preface can be labelled with roman numerals and the body can restart
+    /// at arabic `1` after resetting the page counter.
     ///
     /// ```example
     /// #set page(
@@ -180,6 +259,11 @@ pub struct PageElem {
 
     /// The page's header. Fills the top margin of each page.
     ///
+    /// Instead of content, the header (like the footer, background, and
+    /// foreground) can also be a function that receives the physical page
+    /// number, starting at one for the first page of the document, and returns
+    /// content. This way, the header can differ between odd and even pages.
+    ///
     /// ```example
     /// #set par(justify: true)
     /// #set page(
@@ -193,7 +277,7 @@ pub struct PageElem {
     ///
     /// #lorem(19)
     /// ```
-    pub header: Option<Content>,
+    pub header: Option<Marginal>,
 
     /// The amount the header is raised into the top margin.
     #[resolve]
@@ -223,7 +307,23 @@ pub struct PageElem {
     ///
     /// #lorem(48)
     /// ```
-    pub footer: Option<Content>,
+    ///
+    /// To alternate the footer's alignment between recto and verso pages of a
+    /// book, you can use a function:
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 100pt,
+    ///   margin: 20pt,
+    ///   footer: n => align(
+    ///     if calc.odd(n) { right } else { left },
+    ///     text(8pt)[Page #n],
+    ///   ),
+    /// )
+    ///
+    /// #lorem(48)
+    /// ```
+    pub footer: Option<Marginal>,
 
     /// The amount the footer is lowered into the bottom margin.
     #[resolve]
@@ -246,7 +346,7 @@ pub struct PageElem {
     /// In the year 2023, we plan to take
     /// over the world (of typesetting).
     /// ```
-    pub background: Option<Content>,
+    pub background: Option<Marginal>,
 
     /// Content in the page's foreground.
     ///
@@ -259,7 +359,7 @@ pub struct PageElem {
     /// "Weak Reject" because they did
     /// not understand our approach...
     /// ```
-    pub foreground: Option<Content>,
+    pub foreground: Option<Marginal>,
 
     /// The contents of the page(s).
     ///
@@ -272,7 +372,14 @@ pub struct PageElem {
 
 impl PageElem {
     /// Layout the page run into a sequence of frames, one per page.
-    pub fn layout(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Fragment> {
+    ///
+    /// The `number` is the physical page number of the run's first page.
+    pub fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        mut number: NonZeroUsize,
+    ) -> SourceResult<Fragment> {
         // When one of the lengths is infinite the page fits its content along
         // that axis.
         let width = self.width(styles).unwrap_or(Abs::inf());
@@ -306,6 +413,9 @@ impl PageElem {
         let regions = Regions::repeat(size, size.map(Abs::is_finite));
         let mut fragment = child.layout(vt, styles, regions)?;
 
+        let bleed = self.bleed(styles);
+        let rotation = self.rotation(styles);
+        let tabs = self.tabs(styles);
         let fill = self.fill(styles);
         let foreground = self.foreground(styles);
         let background = self.background(styles);
@@ -317,24 +427,31 @@ impl PageElem {
                     Numbering::Pattern(pattern) => pattern.pieces() >= 2,
                     Numbering::Func(_) => true,
                 };
-                Counter::new(CounterKey::Page)
-                    .display(Some(numbering), both)
-                    .aligned(self.number_align(styles))
+                Marginal::Content(
+                    Counter::new(CounterKey::Page)
+                        .display(Some(numbering), both)
+                        .aligned(self.number_align(styles)),
+                )
             })
         });
         let footer_descent = self.footer_descent(styles);
 
-        // Realize overlays.
-        for frame in &mut fragment {
-            if let Some(fill) = fill {
-                frame.fill(fill);
-            }
+        // The pages on which the tabs' sections start, which are walked
+        // alongside the pages.
+        let starts = tabs.as_ref().map(|tabs| tabs.starts(vt)).unwrap_or_default();
+        let mut started = 0;
 
+        // Realize placed content, margin notes and overlays.
+        for frame in &mut fragment {
             let size = frame.size();
             let pad = padding.resolve(styles).relative_to(size);
+            layout_page_placed(vt, styles, frame, pad)?;
+            layout_margin_notes(vt, styles, frame, pad)?;
+
             let pw = size.x - pad.left - pad.right;
             for marginal in [&header, &footer, &background, &foreground] {
                 let Some(content) = marginal else { continue };
+                let content = content.resolve(vt, number.get())?;
 
                 let (pos, area, align);
                 if ptr::eq(marginal, &header) {
@@ -354,23 +471,99 @@ impl PageElem {
                 };
 
                 let pod = Regions::one(area, Axes::splat(true));
-                let sub = content
-                    .clone()
+                let mut sub = content
                     .styled(AlignElem::set_alignment(align))
                     .layout(vt, styles, pod)?
                     .into_frame();
+
+                // Marginals are decoration that assistive technologies should
+                // skip.
+                sub.mark_artifact();
                 if ptr::eq(marginal, &header) || ptr::eq(marginal, &background) {
                     frame.prepend_frame(pos, sub);
                 } else {
                     frame.push_frame(pos, sub);
                 }
             }
+
+            // Extend the page into the bleed.
+            if bleed > Abs::zero() {
+                let trimmed = std::mem::take(frame);
+                *frame = Frame::new(trimmed.size() + Size::splat(2.0 * bleed));
+                frame.push_frame(Point::splat(bleed), trimmed);
+                frame.push(
+                    Point::zero(),
+                    FrameItem::Meta(Meta::Bleed(bleed), Size::zero()),
+                );
+            }
+
+            if rotation.0 != 0 {
+                frame.push(
+                    Point::zero(),
+                    FrameItem::Meta(Meta::Rotation(rotation.0), Size::zero()),
+                );
+            }
+
+            // The current section is the last one that starts on or before
+            // this page.
+            while starts.get(started).map_or(false, |&start| start <= number) {
+                started += 1;
+            }
+
+            if let (Some(tabs), Some(index)) = (&tabs, started.checked_sub(1)) {
+                tabs.layout(styles, frame, number, index, pad, bleed);
+            }
+
+            layout_bleed_placed(vt, styles, frame)?;
+
+            if let Some(fill) = fill {
+                let mut background = Frame::new(frame.size());
+                background.fill(fill);
+                background.mark_artifact();
+                frame.prepend_frame(Point::zero(), background);
+            }
+
+            // Attach the label that viewers show for this page.
+            let pod = Regions::one(Size::zero(), Axes::splat(false));
+            let label = PageLabelElem::new(self.numbering(styles))
+                .pack()
+                .layout(vt, styles, pod)?
+                .into_frame();
+            frame.push_frame(Point::zero(), label);
+
+            number = number.saturating_add(1);
         }
 
         Ok(fragment)
     }
 }
 
+/// Determines the label of the page it is laid out on from the page counter.
+///
+/// Display: Page Label
+/// Category: special
+#[element(Locatable, Show)]
+struct PageLabelElem {
+    /// How the pages are numbered.
+    #[required]
+    numbering: Option<Numbering>,
+}
+
+impl Show for PageLabelElem {
+    fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        let location = self.0.location().unwrap();
+        let number = Counter::new(CounterKey::Page).at(vt, location)?.first();
+        let label = match self.numbering() {
+            Some(Numbering::Pattern(pattern)) => pattern.page_label(number),
+            _ => PageLabel::arabic(number),
+        };
+
+        Ok(MetaElem::new()
+            .pack()
+            .styled(MetaElem::set_data(vec![Meta::PageLabel(label)])))
+    }
+}
+
 /// A manual page break.
 ///
 /// Must not be used inside any containers.
@@ -395,6 +588,135 @@ pub struct PagebreakElem {
     pub weak: bool,
 }
 
+/// A clockwise page rotation in whole quarter turns.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rotation(pub u16);
+
+cast_from_value! {
+    Rotation,
+    v: Angle => {
+        let quarters = v.to_deg() / 90.0;
+        if (quarters - quarters.round()).abs() > 1e-6 {
+            Err("rotation must be a multiple of 90deg")?;
+        }
+        Self((quarters.round() as i64).rem_euclid(4) as u16 * 90)
+    },
+}
+
+cast_to_value! {
+    v: Rotation => Value::Angle(Angle::deg(v.0.into()))
+}
+
+/// How far a page extends beyond its trimmed edges.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bleed(pub Length);
+
+cast_from_value! {
+    Bleed,
+    v: Length => {
+        if v.abs < Abs::zero() || v.em < Em::zero() {
+            Err("bleed must not be negative")?;
+        }
+        Self(v)
+    },
+}
+
+cast_to_value! {
+    v: Bleed => v.0.into()
+}
+
+impl Resolve for Bleed {
+    type Output = Abs;
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        self.0.resolve(styles)
+    }
+}
+
+/// Thumb tabs for section navigation.
+#[derive(Debug, Clone, Hash)]
+pub struct Tabs {
+    /// The heading level that starts a new section.
+    pub level: NonZeroUsize,
+    /// How far the tabs reach into the page.
+    pub width: Length,
+    /// The height of each tab.
+    pub height: Length,
+    /// The tabs' color.
+    pub fill: Paint,
+}
+
+impl Tabs {
+    /// The pages on which the sections start, in document order.
+    fn starts(&self, vt: &Vt) -> Vec<NonZeroUsize> {
+        let selector =
+            Selector::Elem(HeadingElem::func(), Some(dict! { "level" => self.level }));
+        vt.introspector
+            .query(selector)
+            .iter()
+            .map(|elem| vt.introspector.page(elem.location().unwrap()))
+            .collect()
+    }
+
+    /// Add the tab of the section with the given index at the page's outer
+    /// edge.
+    ///
+    /// The frame must already include the bleed.
+    fn layout(
+        &self,
+        styles: StyleChain,
+        frame: &mut Frame,
+        number: NonZeroUsize,
+        index: usize,
+        pad: Sides<Abs>,
+        bleed: Abs,
+    ) {
+        let size = frame.size() - Size::splat(2.0 * bleed);
+        let width = self.width.resolve(styles);
+        let height = self.height.resolve(styles);
+        let room = size.y - pad.top - pad.bottom;
+        let slots = if height > Abs::zero() {
+            ((room / height).floor() as usize).max(1)
+        } else {
+            1
+        };
+
+        let y = bleed + pad.top + height * (index % slots) as f64;
+        let x = if number.get() % 2 == 1 { bleed + size.x - width } else { Abs::zero() };
+
+        let mut tab = Frame::new(Size::new(width + bleed, height));
+        tab.fill(self.fill);
+        tab.mark_artifact();
+        frame.push_frame(Point::new(x, y), tab);
+    }
+}
+
+cast_from_value! {
+    Tabs,
+    mut dict: Dict => {
+        let level = dict.take("level").ok().map(Value::cast).transpose()?;
+        let width = dict.take("width").ok().map(Value::cast).transpose()?;
+        let height = dict.take("height").ok().map(Value::cast).transpose()?;
+        let fill = dict.take("fill").ok().map(Value::cast).transpose()?;
+        dict.finish(&["level", "width", "height", "fill"])?;
+        Self {
+            level: level.unwrap_or(NonZeroUsize::ONE),
+            width: width.unwrap_or(Abs::mm(6.0).into()),
+            height: height.unwrap_or(Abs::mm(12.0).into()),
+            fill: fill.unwrap_or(Color::Luma(LumaColor(102)).into()),
+        }
+    },
+}
+
+cast_to_value! {
+    v: Tabs => Value::Dict(dict! {
+        "level" => v.level,
+        "width" => v.width,
+        "height" => v.height,
+        "fill" => v.fill,
+    })
+}
+
 /// A header, footer, foreground or background definition.
 #[derive(Debug, Clone, Hash)]
 pub enum Marginal {
@@ -1,14 +1,19 @@
+use std::borrow::Cow;
+
 use typst::eval::Tracer;
+use typst::util::hash128;
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
 use xi_unicode::LineBreakIterator;
 
-use super::{BoxElem, HElem, Sizing, Spacing};
+use super::{BoxElem, HElem, RepeatElem, Sizing, Spacing, TabAlign, TabElem, TabStop};
 use crate::layout::AlignElem;
 use crate::math::EquationElem;
+use crate::meta::DocumentElem;
 use crate::prelude::*;
 use crate::text::{
-    shape, LinebreakElem, Quoter, Quotes, ShapedText, SmartQuoteElem, SpaceElem, TextElem,
+    shape, KernElem, LinebreakElem, Quoter, Quotes, ShapedText, SmartQuoteElem,
+    SpaceElem, TextElem,
 };
 
 /// Arrange text, spacing and inline-level elements into a paragraph.
@@ -57,6 +62,24 @@ pub struct ParElem {
     #[default(false)]
     pub justify: bool,
 
+    /// How much glyphs may be widened or narrowed to improve the spacing of
+    /// justified lines.
+    ///
+    /// With a non-zero expansion, part of the space that justification would
+    /// add to or remove from the spaces between words is instead absorbed by
+    /// scaling the line's text horizontally, by at most the given fraction.
+    /// This results in more even word spacing and is taken into account when
+    /// choosing where to break lines. Small values like `{2%}` are usually
+    /// imperceptible. Lines that aren't justified are never expanded.
+    ///
+    /// ```example
+    /// #set page(width: 200pt)
+    /// #set par(justify: true, expansion: 2%)
+    /// #lorem(20)
+    /// ```
+    #[default(Ratio::zero())]
+    pub expansion: Ratio,
+
     /// How to determine line breaks.
     ///
     /// When this property is set to `{auto}`, its default value, optimized line
@@ -80,6 +103,14 @@ pub struct ParElem {
     /// very aesthetic example is one
     /// of them.
     /// ```
+    ///
+    /// For headings and captions, balanced line breaks typically look best:
+    ///
+    /// ```example
+    /// #set page(width: 190pt)
+    /// #show heading: set par(linebreaks: "balanced")
+    /// = A heading that is long enough to wrap
+    /// ```
     #[default]
     pub linebreaks: Smart<Linebreaks>,
 
@@ -98,6 +129,32 @@ pub struct ParElem {
     #[resolve]
     pub hanging_indent: Length,
 
+    /// The tab stops for [tabs]($func/tab) in the paragraph.
+    ///
+    /// Each stop is either a length, which defines a left-aligned stop at
+    /// that distance from the start of the line, or a dictionary with the
+    /// following keys:
+    ///
+    /// - `pos`: The position of the stop.
+    /// - `align`: How the text following the tab is aligned with the stop. One
+    ///   of `{"left"}`, `{"right"}`, `{"center"}`, or `{"decimal"}`, which
+    ///   places the text's first decimal point at the stop. Defaults to
+    ///   `{"left"}`.
+    /// - `leader`: Content to repeat in the space before the stop, for
+    ///   example `{[.]}` for a dotted leader. Defaults to `{none}`.
+    ///
+    /// Lines containing tabs are never justified.
+    ///
+    /// ```example
+    /// #set par(tabs: (
+    ///   (pos: 4cm, align: "right", leader: [.]),
+    /// ))
+    ///
+    /// Introduction #tab() 1 \
+    /// Methods #tab() 4
+    /// ```
+    pub tabs: Vec<TabStop>,
+
     /// The contents of the paragraph.
     #[external]
     pub body: Content,
@@ -160,7 +217,17 @@ impl ParElem {
             let lines = linebreak(&vt, &p, region.x - p.hang);
 
             // Stack the lines into one frame per region.
-            finalize(&mut vt, &p, &lines, region, expand)
+            let mut fragment = finalize(&mut vt, &p, &lines, region, expand)?;
+
+            // Mark the lines as belonging to the paragraph.
+            if DocumentElem::tagged_in(styles) {
+                let tag = Tag::new(Role::Paragraph, vt.provider.locate(hash128(par)));
+                for frame in &mut fragment {
+                    frame.tag_in(tag.clone(), styles);
+                }
+            }
+
+            Ok(fragment)
         }
 
         cached(
@@ -187,6 +254,13 @@ pub enum Linebreaks {
     /// Typst will try to produce more evenly filled lines of text by
     /// considering the whole paragraph when calculating line breaks.
     Optimized,
+    /// Balance the lengths of all lines.
+    ///
+    /// Typst will break the text into as many lines as the simple style
+    /// would, but make them about equally long. This avoids a lone word on the
+    /// last line and is best suited for short texts like headings and
+    /// captions.
+    Balanced,
 }
 
 /// A paragraph break.
@@ -248,6 +322,10 @@ struct Preparation<'a> {
     justify: bool,
     /// The paragraph's hanging indent.
     hang: Abs,
+    /// The paragraph's resolved tab stops, sorted by position.
+    tabs: Vec<(Abs, TabAlign, Option<Content>)>,
+    /// Manual kerns, given by the text offset they follow and their amount.
+    kerns: Vec<(usize, Abs)>,
 }
 
 impl<'a> Preparation<'a> {
@@ -302,10 +380,14 @@ enum Segment<'a> {
     Text(usize),
     /// Horizontal spacing between other segments.
     Spacing(Spacing),
+    /// An adjustment of the preceding glyph's advance.
+    Kern(Length),
     /// A mathematical equation.
     Equation(&'a EquationElem),
     /// A box with arbitrary content.
     Box(&'a BoxElem, bool),
+    /// A tab.
+    Tab,
     /// Metadata.
     Meta,
 }
@@ -317,6 +399,8 @@ impl Segment<'_> {
             Self::Text(len) => len,
             Self::Spacing(_) => SPACING_REPLACE.len_utf8(),
             Self::Box(_, true) => SPACING_REPLACE.len_utf8(),
+            Self::Tab => '\t'.len_utf8(),
+            Self::Kern(_) => 0,
             Self::Equation(_) | Self::Box(_, _) | Self::Meta => OBJ_REPLACE.len_utf8(),
         }
     }
@@ -333,6 +417,8 @@ enum Item<'a> {
     Fractional(Fr, Option<(&'a BoxElem, StyleChain<'a>)>),
     /// Layouted inline-level content.
     Frame(Frame),
+    /// A tab, whose width depends on its position in the line.
+    Tab(StyleChain<'a>),
 }
 
 impl<'a> Item<'a> {
@@ -350,6 +436,7 @@ impl<'a> Item<'a> {
             Self::Text(shaped) => shaped.text.len(),
             Self::Absolute(_) | Self::Fractional(_, _) => SPACING_REPLACE.len_utf8(),
             Self::Frame(_) => OBJ_REPLACE.len_utf8(),
+            Self::Tab(_) => '\t'.len_utf8(),
         }
     }
 
@@ -359,7 +446,7 @@ impl<'a> Item<'a> {
             Self::Text(shaped) => shaped.width,
             Self::Absolute(v) => *v,
             Self::Frame(frame) => frame.width(),
-            Self::Fractional(_, _) => Abs::zero(),
+            Self::Fractional(_, _) | Self::Tab(_) => Abs::zero(),
         }
     }
 }
@@ -423,6 +510,8 @@ struct Line<'a> {
     /// Whether the line ends with a hyphen or dash, either naturally or through
     /// hyphenation.
     dash: bool,
+    /// The widths and leaders of the line's tabs.
+    tabs: Vec<(Abs, Option<Content>)>,
 }
 
 impl<'a> Line<'a> {
@@ -464,6 +553,15 @@ impl<'a> Line<'a> {
         count
     }
 
+    /// How much of the line is text that can be expanded.
+    fn expandable(&self) -> Abs {
+        let mut expandable = Abs::zero();
+        for shaped in self.items().filter_map(Item::text) {
+            expandable += shaped.width;
+        }
+        expandable
+    }
+
     /// How much of the line is stretchable spaces.
     fn stretch(&self) -> Abs {
         let mut stretch = Abs::zero();
@@ -548,6 +646,11 @@ fn collect<'a>(
         } else if let Some(elem) = child.to::<HElem>() {
             full.push(SPACING_REPLACE);
             Segment::Spacing(elem.amount())
+        } else if child.is::<TabElem>() {
+            full.push('\t');
+            Segment::Tab
+        } else if let Some(elem) = child.to::<KernElem>() {
+            Segment::Kern(elem.amount())
         } else if let Some(elem) = child.to::<LinebreakElem>() {
             let c = if elem.justify(styles) { '\u{2028}' } else { '\n' };
             full.push(c);
@@ -563,7 +666,10 @@ fn collect<'a>(
                         elem.text().chars().next()
                     } else if child.is::<SmartQuoteElem>() {
                         Some('"')
-                    } else if child.is::<SpaceElem>() || child.is::<HElem>() {
+                    } else if child.is::<SpaceElem>()
+                        || child.is::<HElem>()
+                        || child.is::<TabElem>()
+                    {
                         Some(SPACING_REPLACE)
                     } else {
                         Some(OBJ_REPLACE)
@@ -632,6 +738,7 @@ fn prepare<'a>(
 
     let mut cursor = 0;
     let mut items = vec![];
+    let mut kerns = vec![];
 
     // Shape / layout the children and collect them into items.
     for (segment, styles) in segments {
@@ -665,6 +772,16 @@ fn prepare<'a>(
                     items.push(Item::Frame(frame));
                 }
             }
+            Segment::Tab => {
+                items.push(Item::Tab(styles));
+            }
+            Segment::Kern(amount) => {
+                if let Some(Item::Text(shaped)) = items.last_mut() {
+                    let kern = (cursor, amount.resolve(styles));
+                    apply_kerns(shaped, &[kern]);
+                    kerns.push(kern);
+                }
+            }
             Segment::Meta => {
                 let mut frame = Frame::new(Size::zero());
                 frame.meta(styles, true);
@@ -675,6 +792,12 @@ fn prepare<'a>(
         cursor = end;
     }
 
+    let mut tabs: Vec<_> = ParElem::tabs_in(styles)
+        .into_iter()
+        .map(|stop| (stop.pos.resolve(styles), stop.align, stop.leader))
+        .collect();
+    tabs.sort_by(|a, b| a.0.cmp(&b.0));
+
     Ok(Preparation {
         bidi,
         items,
@@ -685,9 +808,67 @@ fn prepare<'a>(
         align: AlignElem::alignment_in(styles).x.resolve(styles),
         justify: ParElem::justify_in(styles),
         hang: ParElem::hanging_indent_in(styles),
+        tabs,
+        kerns,
     })
 }
 
+/// Widen the advance of the glyphs that the kerns follow, if they are part of
+/// the shaped run.
+fn apply_kerns(shaped: &mut ShapedText, kerns: &[(usize, Abs)]) {
+    let end = shaped.base + shaped.text.len();
+    for &(offset, amount) in kerns {
+        if offset <= shaped.base || offset > end {
+            continue;
+        }
+
+        // Find the last glyph of the cluster before the kern. Right-to-left
+        // runs are stored in visual order, so that is the leftmost one.
+        let glyphs = shaped.glyphs.to_mut();
+        let Some(cluster) =
+            glyphs.iter().map(|g| g.cluster).filter(|&c| c < offset).max()
+        else {
+            continue;
+        };
+
+        let rtl = shaped.dir == Dir::RTL;
+        let index = if rtl {
+            glyphs.iter().position(|g| g.cluster == cluster)
+        } else {
+            glyphs.iter().rposition(|g| g.cluster == cluster)
+        };
+
+        // In right-to-left text, the space belongs to the left of the glyph.
+        let em = Em::from_length(amount, shaped.size);
+        let Some(glyph) = index.map(|i| &mut glyphs[i]) else { continue };
+        glyph.x_advance += em;
+        if rtl {
+            glyph.x_offset += em;
+        }
+
+        shaped.width += amount;
+    }
+}
+
+/// Apply the kerns to a reshaped run again, unless it reuses the glyphs of
+/// the run it was sliced from, which already have them.
+fn reapply_kerns(shaped: &mut ShapedText, kerns: &[(usize, Abs)]) {
+    if let Cow::Owned(_) = shaped.glyphs {
+        apply_kerns(shaped, kerns);
+    }
+}
+
+/// Add the CJK-Latin spacing before the next item to a reshaped run again,
+/// unless it reuses the glyphs of the run it was sliced from, which already
+/// have it.
+fn reapply_cjk_latin_spacing(shaped: &mut ShapedText, next: Option<&Item>) {
+    if let Cow::Owned(_) = shaped.glyphs {
+        if let Some(Item::Text(next)) = next {
+            add_cjk_latin_spacing(shaped, next);
+        }
+    }
+}
+
 /// Group a range of text by BiDi level and script, shape the runs and generate
 /// items for them.
 fn shape_range<'a>(
@@ -701,6 +882,9 @@ fn shape_range<'a>(
     let mut process = |range: Range, level: BidiLevel| {
         let dir = if level.is_ltr() { Dir::LTR } else { Dir::RTL };
         let shaped = shape(vt, range.start, &bidi.text[range], spans, styles, dir);
+        if let Some(Item::Text(prev)) = items.last_mut() {
+            add_cjk_latin_spacing(prev, &shaped);
+        }
         items.push(Item::Text(shaped));
     };
 
@@ -733,6 +917,45 @@ fn shape_range<'a>(
     process(cursor..range.end, prev_level);
 }
 
+/// Add a quarter em of space between adjacent CJK and Latin text runs.
+fn add_cjk_latin_spacing(prev: &mut ShapedText, next: &ShapedText) {
+    if prev.dir != Dir::LTR
+        || next.dir != Dir::LTR
+        || !TextElem::cjk_latin_spacing_in(prev.styles)
+        || !TextElem::cjk_latin_spacing_in(next.styles)
+    {
+        return;
+    }
+
+    let (Some(a), Some(b)) = (prev.text.chars().last(), next.text.chars().next()) else {
+        return;
+    };
+
+    if !(is_cjk(a) && is_latin(b) || is_latin(a) && is_cjk(b)) {
+        return;
+    }
+
+    if let Some(glyph) = prev.glyphs.to_mut().last_mut() {
+        let amount = Em::new(0.25);
+        glyph.x_advance += amount;
+        prev.width += amount.at(prev.size);
+    }
+}
+
+/// Whether a character is a CJK ideograph or kana.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c.script(),
+        Script::Han | Script::Hiragana | Script::Katakana | Script::Bopomofo
+    )
+}
+
+/// Whether a character is a Latin-like letter or a digit.
+fn is_latin(c: char) -> bool {
+    c.is_ascii_digit()
+        || matches!(c.script(), Script::Latin | Script::Greek | Script::Cyrillic)
+}
+
 /// Whether this is not a specific script.
 fn is_generic_script(script: Script) -> bool {
     matches!(script, Script::Unknown | Script::Common | Script::Inherited)
@@ -771,6 +994,7 @@ fn linebreak<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line<'a>> {
     match linebreaks {
         Linebreaks::Simple => linebreak_simple(vt, p, width),
         Linebreaks::Optimized => linebreak_optimized(vt, p, width),
+        Linebreaks::Balanced => linebreak_balanced(vt, p, width),
     }
 }
 
@@ -860,6 +1084,7 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
     }];
 
     let em = TextElem::size_in(p.styles);
+    let expansion = ParElem::expansion_in(p.styles).get();
 
     for (end, mandatory, hyphen) in breakpoints(p) {
         let k = table.len();
@@ -873,8 +1098,14 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
             let attempt = line(vt, p, start..end, mandatory, hyphen);
 
             // Determine how much the line's spaces would need to be stretched
-            // to make it the desired width.
-            let delta = width - attempt.width;
+            // to make it the desired width. In justified lines, expanding the
+            // text absorbs part of the difference first.
+            let mut delta = width - attempt.width;
+            if attempt.justify {
+                let max = attempt.expandable() * expansion;
+                delta -= delta.max(-max).min(max);
+            }
+
             let mut ratio = delta / attempt.stretch();
             if ratio.is_infinite() {
                 ratio = delta / (em / 2.0);
@@ -940,6 +1171,38 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
     lines
 }
 
+/// Perform line breaking such that all lines are about equally long. To this
+/// end, we first determine how many lines first-fit breaking would produce.
+/// Then, we search for the narrowest width at which first-fit breaking still
+/// needs no more lines than that.
+fn linebreak_balanced<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line<'a>> {
+    // Balancing is only worthwhile for short texts.
+    const MAX_LINES: usize = 10;
+
+    let mut best = linebreak_simple(vt, p, width);
+    let count = best.len();
+    if count < 2 || count > MAX_LINES || !width.is_finite() {
+        return best;
+    }
+
+    // Binary search for the narrowest width.
+    let precision = Abs::pt(0.5);
+    let mut lo = width / count as f64;
+    let mut hi = width;
+    while hi - lo > precision {
+        let mid = (lo + hi) / 2.0;
+        let attempt = linebreak_simple(vt, p, mid);
+        if attempt.len() <= count && attempt.iter().all(|l| mid.fits(l.width)) {
+            best = attempt;
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    best
+}
+
 /// Determine all possible points in the text where lines can broken.
 ///
 /// Returns for each breakpoint the text index, whether the break is mandatory
@@ -950,6 +1213,8 @@ fn breakpoints<'a>(p: &'a Preparation<'a>) -> Breakpoints<'a> {
         p,
         linebreaks: LineBreakIterator::new(p.bidi.text),
         syllables: None,
+        urls: urls(p.bidi.text),
+        url: None,
         offset: 0,
         suffix: 0,
         end: 0,
@@ -965,6 +1230,12 @@ struct Breakpoints<'a> {
     linebreaks: LineBreakIterator<'a>,
     /// Iterator over syllables of the current word.
     syllables: Option<hypher::Syllables<'a>>,
+    /// The whitespace-delimited URLs in the text with the offsets at which
+    /// their bodies start.
+    urls: Vec<(Range, usize)>,
+    /// Iterator over the break opportunities in the current word if it is
+    /// part of a URL, ending with the word's end.
+    url: Option<std::vec::IntoIter<usize>>,
     /// The current text offset.
     offset: usize,
     /// The trimmed end of the current word.
@@ -979,45 +1250,109 @@ impl Iterator for Breakpoints<'_> {
     type Item = (usize, bool, bool);
 
     fn next(&mut self) -> Option<Self::Item> {
-        // If we're currently in a hyphenated "word", process the next syllable.
-        if let Some(syllable) = self.syllables.as_mut().and_then(Iterator::next) {
-            self.offset += syllable.len();
-            if self.offset == self.suffix {
-                self.offset = self.end;
+        // Skipped opportunities continue the loop instead of recursing, since
+        // there may be arbitrarily many of them in a row.
+        loop {
+            // If we're currently in a hyphenated "word", process the next syllable.
+            if let Some(syllable) = self.syllables.as_mut().and_then(Iterator::next) {
+                self.offset += syllable.len();
+                if self.offset == self.suffix {
+                    self.offset = self.end;
+                }
+
+                // Filter out hyphenation opportunities where hyphenation was
+                // actually disabled.
+                let hyphen = self.offset < self.end;
+                if hyphen && !self.hyphenate(self.offset) {
+                    continue;
+                }
+
+                return Some((self.offset, self.mandatory && !hyphen, hyphen));
             }
 
-            // Filter out hyphenation opportunities where hyphenation was
-            // actually disabled.
-            let hyphen = self.offset < self.end;
-            if hyphen && !self.hyphenate(self.offset) {
-                return self.next();
+            // If we're currently in a URL, process its next segment.
+            if let Some(offset) = self.url.as_mut().and_then(Iterator::next) {
+                self.offset = offset;
+                return Some((offset, self.mandatory && offset == self.end, false));
             }
 
-            return Some((self.offset, self.mandatory && !hyphen, hyphen));
-        }
+            // Get the next "word".
+            (self.end, self.mandatory) = self.linebreaks.next()?;
+
+            // Split URLs at slashes and dots instead of hyphenating them.
+            if let Some(breaks) = self.url_breaks() {
+                self.url = Some(breaks.into_iter());
+                continue;
+            }
 
-        // Get the next "word".
-        (self.end, self.mandatory) = self.linebreaks.next()?;
-
-        // Hyphenate the next word.
-        if self.p.hyphenate != Some(false) {
-            if let Some(lang) = self.lang(self.offset) {
-                let word = &self.p.bidi.text[self.offset..self.end];
-                let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
-                if !trimmed.is_empty() {
-                    self.suffix = self.offset + trimmed.len();
-                    self.syllables = Some(hypher::hyphenate(trimmed, lang));
-                    return self.next();
+            // Hyphenate the next word.
+            if self.p.hyphenate != Some(false) {
+                if let Some(lang) = self.lang(self.offset) {
+                    let word = &self.p.bidi.text[self.offset..self.end];
+                    let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
+                    if !trimmed.is_empty() {
+                        self.suffix = self.offset + trimmed.len();
+                        self.syllables = Some(hypher::hyphenate(trimmed, lang));
+                        continue;
+                    }
                 }
             }
-        }
 
-        self.offset = self.end;
-        Some((self.end, self.mandatory, false))
+            self.offset = self.end;
+
+            // Filter out opportunities prohibited by East Asian line breaking
+            // rules.
+            if !self.mandatory && self.prohibited(self.end) {
+                continue;
+            }
+
+            return Some((self.end, self.mandatory, false));
+        }
     }
 }
 
 impl Breakpoints<'_> {
+    /// Whether breaking at the given offset would start a line with a
+    /// character that must not start a line or end one with a character that
+    /// must not end a line (kinsoku shori).
+    fn prohibited(&self, offset: usize) -> bool {
+        let text = self.p.bidi.text;
+        let before = text[..offset].chars().next_back();
+        let after = text[offset..].chars().next();
+        after.map_or(false, is_no_start) || before.map_or(false, is_no_end)
+    }
+
+    /// The break opportunities within the current word if it is part of a
+    /// URL, followed by the word's end.
+    ///
+    /// A URL may be broken after slashes and before dots, query separators
+    /// and fragments, but not within its scheme.
+    fn url_breaks(&self) -> Option<Vec<usize>> {
+        let i = self.urls.partition_point(|(range, _)| range.end <= self.offset);
+        let (range, body) = self.urls.get(i)?;
+        if range.start > self.offset {
+            return None;
+        }
+
+        let text = self.p.bidi.text;
+        let mut breaks = vec![];
+        let mut prev = None;
+        for (i, c) in text[self.offset..self.end].char_indices() {
+            let offset = self.offset + i;
+            if offset > self.offset && offset > *body {
+                let after_slash = prev == Some('/') && c != '/';
+                let before_sep = matches!(c, '.' | '?' | '#' | '&') && prev != Some(c);
+                if after_slash || before_sep {
+                    breaks.push(offset);
+                }
+            }
+            prev = Some(c);
+        }
+
+        breaks.push(self.end);
+        Some(breaks)
+    }
+
     /// Whether hyphenation is enabled at the given offset.
     fn hyphenate(&self, offset: usize) -> bool {
         self.p
@@ -1041,6 +1376,49 @@ impl Breakpoints<'_> {
     }
 }
 
+/// Find the whitespace-delimited tokens in a text that are URLs.
+///
+/// Returns for each URL its range and the offset at which its body starts,
+/// i.e. after its scheme or at its `www.` prefix.
+fn urls(text: &str) -> Vec<(Range, usize)> {
+    let mut urls = vec![];
+    let mut start = 0;
+    let mut scheme = None;
+    let mut www = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if let Some(body) = scheme.take().or(www.then_some(start)) {
+                urls.push((start..i, body));
+            }
+            start = i + c.len_utf8();
+            www = false;
+        } else if c == ':' && scheme.is_none() && text[i..].starts_with("://") {
+            scheme = Some(i + 3);
+        } else if i == start && text[i..].starts_with("www.") {
+            www = true;
+        }
+    }
+
+    if let Some(body) = scheme.or(www.then_some(start)) {
+        urls.push((start..text.len(), body));
+    }
+
+    urls
+}
+
+/// Whether a character must not start a line.
+fn is_no_start(c: char) -> bool {
+    "）］｝〕〉》」』】〙〗〟’”｠、。，．・：；？！‼⁇⁈⁉゠〜ーヽヾゝゞ々〻\
+     ぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶ"
+        .contains(c)
+        || ('ㇰ'..='ㇿ').contains(&c)
+}
+
+/// Whether a character must not end a line.
+fn is_no_end(c: char) -> bool {
+    "（［｛〔〈《「『【〘〖〝‘“｟".contains(c)
+}
+
 /// Create a line which spans the given range.
 fn line<'a>(
     vt: &Vt,
@@ -1063,6 +1441,7 @@ fn line<'a>(
             width: Abs::zero(),
             justify,
             dash: false,
+            tabs: vec![],
         };
     }
 
@@ -1100,6 +1479,7 @@ fn line<'a>(
             if hyphen || start < range.end || before.is_empty() {
                 let shifted = start - base..range.end - base;
                 let mut reshaped = shaped.reshape(vt, &p.spans, shifted);
+                reapply_kerns(&mut reshaped, &p.kerns);
                 if hyphen || shy {
                     reshaped.push_hyphen(vt);
                 }
@@ -1122,7 +1502,9 @@ fn line<'a>(
         if range.start + shaped.text.len() > end {
             if range.start < end {
                 let shifted = range.start - base..end - base;
-                let reshaped = shaped.reshape(vt, &p.spans, shifted);
+                let mut reshaped = shaped.reshape(vt, &p.spans, shifted);
+                reapply_kerns(&mut reshaped, &p.kerns);
+                reapply_cjk_latin_spacing(&mut reshaped, after.first().or(last.as_ref()));
                 width += reshaped.width;
                 first = Some(Item::Text(reshaped));
             }
@@ -1136,7 +1518,7 @@ fn line<'a>(
         width += item.width();
     }
 
-    Line {
+    let mut line = Line {
         bidi: &p.bidi,
         trimmed: range,
         end,
@@ -1146,7 +1528,88 @@ fn line<'a>(
         width,
         justify,
         dash,
+        tabs: vec![],
+    };
+
+    // Resolve the tabs now that the line's items are known. They are
+    // resolved in visual order, in which they are also committed.
+    line.tabs = {
+        let (reordered, starts_rtl) = reorder(&line);
+        let start = if starts_rtl { Abs::zero() } else { p.hang };
+        resolve_tabs(p, &reordered, start)
+    };
+    if !line.tabs.is_empty() {
+        line.width += line.tabs.iter().map(|(amount, _)| *amount).sum::<Abs>();
+        line.justify = false;
+    }
+
+    line
+}
+
+/// Determine how far each of the line's tabs advances and with which leader.
+///
+/// The items must be in visual order and start at `start` from the left edge
+/// of the line.
+fn resolve_tabs(
+    p: &Preparation,
+    items: &[&Item],
+    start: Abs,
+) -> Vec<(Abs, Option<Content>)> {
+    let is_tab = |item: &&Item| matches!(item, Item::Tab(_));
+
+    let mut tabs = vec![];
+    let mut x = start;
+    for (i, item) in items.iter().enumerate() {
+        if !is_tab(item) {
+            x += item.width();
+            continue;
+        }
+
+        // The items up to the next tab are aligned with the stop.
+        let rest = &items[i + 1..];
+        let segment = &rest[..rest.iter().position(is_tab).unwrap_or(rest.len())];
+        let width: Abs = segment.iter().map(|item| item.width()).sum();
+
+        let (amount, leader) = match p.tabs.iter().find(|(pos, ..)| *pos > x) {
+            Some((pos, align, leader)) => {
+                let before = match align {
+                    TabAlign::Left => Abs::zero(),
+                    TabAlign::Right => width,
+                    TabAlign::Center => width / 2.0,
+                    TabAlign::Decimal => decimal_offset(segment).unwrap_or(width),
+                };
+                ((*pos - x - before).max(Abs::zero()), leader.clone())
+            }
+            None => {
+                // Fall back to stops at every half inch.
+                let interval = Abs::inches(0.5);
+                (interval * ((x / interval).floor() + 1.0) - x, None)
+            }
+        };
+
+        x += amount;
+        tabs.push((amount, leader));
     }
+
+    tabs
+}
+
+/// The width of the items before the first decimal point.
+fn decimal_offset(items: &[&Item]) -> Option<Abs> {
+    let mut offset = Abs::zero();
+    for item in items {
+        if let Item::Text(shaped) = item {
+            let mut advance = Em::zero();
+            for glyph in shaped.glyphs.iter() {
+                if glyph.c == '.' {
+                    return Some(offset + advance.at(shaped.size));
+                }
+                advance += glyph.x_advance;
+            }
+        }
+        offset += item.width();
+    }
+    None
 }
 
 /// Combine layouted lines into one frame per region.
@@ -1217,28 +1680,42 @@ fn commit(
         offset += p.hang;
     }
 
-    // Handle hanging punctuation to the left.
+    // Handle hanging punctuation to the left. In left-to-right text, this is
+    // the start of the line, where punctuation only hangs if enabled.
     if let Some(Item::Text(text)) = reordered.first() {
         if let Some(glyph) = text.glyphs.first() {
-            if !text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
+            if TextElem::overhang_in(text.styles)
                 && (reordered.len() > 1 || text.glyphs.len() > 1)
             {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+                let ratio = if !text.dir.is_positive() {
+                    overhang(glyph.c)
+                } else if TextElem::overhang_start_in(text.styles) {
+                    overhang_start(glyph.c)
+                } else {
+                    0.0
+                };
+                let amount = ratio * glyph.x_advance.at(text.size);
                 offset -= amount;
                 remaining += amount;
             }
         }
     }
 
-    // Handle hanging punctuation to the right.
+    // Handle hanging punctuation to the right. In right-to-left text, this is
+    // the start of the line.
     if let Some(Item::Text(text)) = reordered.last() {
         if let Some(glyph) = text.glyphs.last() {
-            if text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
+            if TextElem::overhang_in(text.styles)
                 && (reordered.len() > 1 || text.glyphs.len() > 1)
             {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+                let ratio = if text.dir.is_positive() {
+                    overhang(glyph.c)
+                } else if TextElem::overhang_start_in(text.styles) {
+                    overhang_start(glyph.c)
+                } else {
+                    0.0
+                };
+                let amount = ratio * glyph.x_advance.at(text.size);
                 remaining += amount;
             }
         }
@@ -1247,10 +1724,22 @@ fn commit(
     // Determine how much to justify each space.
     let fr = line.fr();
     let mut justification = Abs::zero();
+    let mut scale = 1.0;
     if remaining < Abs::zero() || (line.justify && fr.is_zero()) {
+        // Let justified lines absorb part of the remaining space by scaling
+        // their text first.
+        let expandable = line.expandable();
+        let max = expandable * ParElem::expansion_in(p.styles).get();
+        if line.justify && fr.is_zero() && max > Abs::zero() {
+            let amount = remaining.max(-max).min(max);
+            scale += amount / expandable;
+            remaining -= amount;
+        }
+
+        // The spaces are justified before the text is scaled.
         let justifiables = line.justifiables();
         if justifiables > 0 {
-            justification = remaining / justifiables as f64;
+            justification = remaining / (justifiables as f64 * scale);
             remaining = Abs::zero();
         }
     }
@@ -1260,6 +1749,7 @@ fn commit(
 
     // Build the frames and determine the height and baseline.
     let mut frames = vec![];
+    let mut tabs = line.tabs.iter();
     for item in reordered {
         let mut push = |offset: &mut Abs, frame: Frame| {
             let width = frame.width();
@@ -1286,12 +1776,31 @@ fn commit(
                 }
             }
             Item::Text(shaped) => {
-                let frame = shaped.build(vt, justification);
+                let mut frame = shaped.build(vt, justification);
+                if scale != 1.0 {
+                    let width = frame.width() * scale;
+                    frame.transform(Transform::scale(Ratio::new(scale), Ratio::one()));
+                    frame.size_mut().x = width;
+                }
                 push(&mut offset, frame);
             }
             Item::Frame(frame) => {
                 push(&mut offset, frame.clone());
             }
+            Item::Tab(styles) => {
+                let Some((amount, leader)) = tabs.next() else { continue };
+                match leader {
+                    Some(leader) if *amount > Abs::zero() => {
+                        let region = Size::new(*amount, full);
+                        let pod = Regions::one(region, Axes::new(true, false));
+                        let repeat = RepeatElem::new(leader.clone());
+                        let mut frame = repeat.layout(vt, *styles, pod)?.into_frame();
+                        frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
+                        push(&mut offset, frame);
+                    }
+                    _ => offset += *amount,
+                }
+            }
         }
     }
 
@@ -1354,6 +1863,20 @@ fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
     (reordered, starts_rtl)
 }
 
+/// How much a character should hang into the start margin.
+fn overhang_start(c: char) -> f64 {
+    match c {
+        // Quotes.
+        '“' | '‘' | '„' | '‚' | '"' | '\'' => 0.6,
+        '«' | '‹' | '»' | '›' => 0.3,
+
+        // Brackets.
+        '(' | '[' | '{' => 0.1,
+
+        _ => 0.0,
+    }
+}
+
 /// How much a character should hang into the end margin.
 ///
 /// For more discussion, see:
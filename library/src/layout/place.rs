@@ -2,10 +2,11 @@ use crate::prelude::*;
 
 /// Place content at an absolute position.
 ///
-/// Placed content will not affect the position of other content. Place is
-/// always relative to its parent container and will be in the foreground of all
-/// other content in the container. Page margins will be respected.
-///
+/// Placed content will not affect the position of other content. By default,
+/// place is relative to its parent container and will be in the foreground of
+/// all other content in the container. Page margins will be respected. With
+/// the `scope` argument, content can also be placed relative to the page,
+/// wherever the `place` call appears.
 ///
 /// ## Example
 /// ```example
@@ -48,6 +49,34 @@ pub struct PlaceElem {
     /// The vertical displacement of the placed content.
     pub dy: Rel<Length>,
 
+    /// Relative to which area to place the content.
+    ///
+    /// With `{"body"}` and `{"page"}`, the content is placed on the current
+    /// page, independently of the containers the `place` call is nested in.
+    /// This is useful for cover pages, stamps and letterheads. The content is
+    /// then laid out with the page's styles and in front of the page's body.
+    ///
+    /// With `{"bleed"}`, the content is placed relative to the page extended
+    /// by its [bleed]($func/page.bleed), behind all other content of the page.
+    /// This is useful for backgrounds and images that should reach up to the
+    /// page's edges after trimming.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #box(width: 50%, inset: 5pt, stroke: 1pt)[
+    ///   Nested
+    ///   #place(
+    ///     bottom + right,
+    ///     scope: "page",
+    ///     dx: -5pt,
+    ///     dy: -5pt,
+    ///     text(red)[*DRAFT*],
+    ///   )
+    /// ]
+    /// ```
+    #[default(PlaceScope::Parent)]
+    pub scope: PlaceScope,
+
     /// The content to place.
     #[required]
     pub body: Content,
@@ -60,6 +89,21 @@ impl Layout for PlaceElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        // Content placed relative to the page only leaves a marker here. The
+        // page lays it out once its own size is known.
+        if self.scope(styles) != PlaceScope::Parent {
+            let mut elem = self.clone();
+            elem.push_alignment(self.alignment(styles));
+            elem.push_dx(self.dx(styles));
+            elem.push_dy(self.dy(styles));
+            elem.push_scope(self.scope(styles));
+
+            let mut frame = Frame::new(Size::zero());
+            let meta = Meta::Placed(elem.pack());
+            frame.push(Point::zero(), FrameItem::Meta(meta, Size::zero()));
+            return Ok(Fragment::frame(frame));
+        }
+
         let out_of_flow = self.out_of_flow(styles);
 
         // The pod is the base area of the region because for absolute
@@ -91,7 +135,105 @@ impl PlaceElem {
     /// base origin. Instead of relative to the parent's current flow/cursor
     /// position.
     pub fn out_of_flow(&self, styles: StyleChain) -> bool {
-        self.alignment(styles).y.is_some()
+        self.alignment(styles).y.is_some() || self.scope(styles) != PlaceScope::Parent
+    }
+}
+
+/// Relative to which area to place content.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PlaceScope {
+    /// The parent container.
+    Parent,
+    /// The area of the current page inside of its margins.
+    Body,
+    /// The full current page, ignoring its margins.
+    Page,
+    /// The full current page extended by its [bleed]($func/page.bleed).
+    Bleed,
+}
+
+/// Lay out the content that was placed relative to a page.
+pub(super) fn layout_page_placed(
+    vt: &mut Vt,
+    styles: StyleChain,
+    frame: &mut Frame,
+    margins: Sides<Abs>,
+) -> SourceResult<()> {
+    let mut placed = vec![];
+    collect(frame, &mut placed);
+
+    // The fields were resolved where the content was placed.
+    let fields = StyleChain::default();
+
+    for elem in placed {
+        let (pos, size) = match elem.scope(fields) {
+            PlaceScope::Bleed => continue,
+            PlaceScope::Page => (Point::zero(), frame.size()),
+            PlaceScope::Parent | PlaceScope::Body => (
+                Point::new(margins.left, margins.top),
+                frame.size() - margins.sum_by_axis(),
+            ),
+        };
+
+        let child = elem
+            .body()
+            .moved(Axes::new(elem.dx(fields), elem.dy(fields)))
+            .aligned(elem.alignment(fields));
+
+        let pod = Regions::one(size, Axes::splat(true));
+        let sub = child.layout(vt, styles, pod)?.into_frame();
+        frame.push_frame(pos, sub);
+    }
+
+    Ok(())
+}
+
+/// Lay out the content that was placed relative to a page's bleed area into
+/// the page's frame, which already includes the bleed.
+///
+/// The content ends up behind all other content of the page.
+pub(super) fn layout_bleed_placed(
+    vt: &mut Vt,
+    styles: StyleChain,
+    frame: &mut Frame,
+) -> SourceResult<()> {
+    let mut placed = vec![];
+    collect(frame, &mut placed);
+
+    // The fields were resolved where the content was placed.
+    let fields = StyleChain::default();
+
+    // Prepend in reverse so that later content ends up in front.
+    for elem in placed.iter().rev() {
+        if elem.scope(fields) != PlaceScope::Bleed {
+            continue;
+        }
+
+        let child = elem
+            .body()
+            .moved(Axes::new(elem.dx(fields), elem.dy(fields)))
+            .aligned(elem.alignment(fields));
+
+        let pod = Regions::one(frame.size(), Axes::splat(true));
+        let sub = child.layout(vt, styles, pod)?.into_frame();
+        frame.prepend_frame(Point::zero(), sub);
+    }
+
+    Ok(())
+}
+
+/// Collect the content placed relative to the page from a frame.
+fn collect(frame: &Frame, placed: &mut Vec<PlaceElem>) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => collect(&group.frame, placed),
+            FrameItem::Meta(Meta::Placed(content), _) => {
+                if let Some(elem) = content.to::<PlaceElem>() {
+                    placed.push(elem.clone());
+                }
+            }
+            _ => {}
+        }
     }
 }
 
@@ -7,7 +7,8 @@ use super::AlignElem;
 /// This can be useful when implementing a custom index, reference, or outline.
 ///
 /// Space may be inserted between the instances of the body parameter, so be
-/// sure to include negative space if you need the instances to overlap.
+/// sure to include negative space if you need the instances to overlap or
+/// disable `justify`.
 ///
 /// ## Example
 /// ```example
@@ -28,6 +29,29 @@ pub struct RepeatElem {
     /// The content to repeat.
     #[required]
     pub body: Content,
+
+    /// The gap between each instance of the body.
+    ///
+    /// ```example
+    /// Name: #box(width: 1fr, repeat(gap: 2pt)[-])
+    /// ```
+    #[resolve]
+    #[default]
+    pub gap: Length,
+
+    /// Whether to increase the gaps between the instances to completely fill
+    /// the available space.
+    ///
+    /// If this is `{false}`, the instances are placed with exactly the
+    /// configured gap and the run as a whole is positioned according to the
+    /// [alignment]($func/align.alignment).
+    ///
+    /// ```example
+    /// #set align(right)
+    /// Total #box(width: 1fr, repeat(justify: false)[.]) 10
+    /// ```
+    #[default(true)]
+    pub justify: bool,
 }
 
 impl Layout for RepeatElem {
@@ -41,11 +65,14 @@ impl Layout for RepeatElem {
         let piece = self.body().layout(vt, styles, pod)?.into_frame();
         let align = AlignElem::alignment_in(styles).x.resolve(styles);
 
+        let justify = self.justify(styles);
+        let gap = self.gap(styles);
         let fill = regions.size.x;
         let width = piece.width();
-        let count = (fill / width).floor();
-        let remaining = fill % width;
-        let apart = remaining / (count - 1.0);
+        let count = ((fill + gap) / (width + gap)).floor().max(0.0);
+        let remaining = fill - count * width - (count - 1.0).max(0.0) * gap;
+        let apart =
+            if justify && count > 1.0 { gap + remaining / (count - 1.0) } else { gap };
 
         let size = Size::new(regions.size.x, piece.height());
         let mut frame = Frame::new(size);
@@ -54,7 +81,7 @@ impl Layout for RepeatElem {
         }
 
         let mut offset = Abs::zero();
-        if count == 1.0 {
+        if !justify || count == 1.0 {
             offset += align.position(remaining);
         }
 
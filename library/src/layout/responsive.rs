@@ -0,0 +1,60 @@
+use crate::prelude::*;
+
+/// Provides access to the space available to content.
+///
+/// The function is called with a dictionary with the `width` and `height` of
+/// the available space, which are `{none}` if the space is unbounded in that
+/// direction. If the function takes two parameters, it additionally receives
+/// the active styles for use with [`measure`]($func/measure). The content it
+/// returns is then laid out in the same space. This makes it possible to build
+/// components that adapt to their surroundings.
+///
+/// The size is that of the whole region the content starts in, which is also
+/// what relative lengths like `{50%}` resolve against. It doesn't shrink by
+/// what preceding content already used up, and content that doesn't fit still
+/// breaks into the following regions, whose sizes aren't passed on.
+///
+/// ```example
+/// #let bar(progress) = layout(size => {
+///   let width = size.width * progress
+///   box(width: width, height: 6pt, fill: eastern)
+/// })
+///
+/// #bar(0.25) \
+/// #bar(0.8)
+/// ```
+///
+/// Display: Layout
+/// Category: layout
+#[element(Layout)]
+pub struct LayoutElem {
+    /// A function that receives the available space and returns the content
+    /// to lay out in it.
+    ///
+    /// This function is called once for each time the content returned by
+    /// `layout` is laid out.
+    #[required]
+    pub func: Func,
+}
+
+impl Layout for LayoutElem {
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        // Only the base size is passed, so that the content doesn't depend on
+        // how much of the region preceding content happens to use up.
+        let func = self.func();
+        let length = |v: Abs| if v.is_finite() { v.into() } else { Value::None };
+        let Size { x, y } = regions.base();
+        let mut args = vec![dict! { "width" => length(x), "height" => length(y) }.into()];
+        if func.argc() == Some(2) {
+            args.push(styles.to_map().into());
+        }
+
+        let content = func.call_vt(vt, args)?.display();
+        content.layout(vt, styles, regions)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::prelude::*;
+
+/// Advances to the next tab stop.
+///
+/// The tab stops of a paragraph are configured through its
+/// [`tabs`]($func/par.tabs) property. Each stop defines a position measured
+/// from the start of the line and how the text following the tab is aligned
+/// with it. Optionally, the space up to the stop can be filled with a leader,
+/// like the dots in a table of contents.
+///
+/// If there is no further tab stop in the line, the tab advances to the next
+/// multiple of half an inch.
+///
+/// ## Example
+/// ```example
+/// #set par(tabs: (
+///   (pos: 4cm, align: "right", leader: [.]),
+///   (pos: 5.5cm, align: "decimal"),
+/// ))
+///
+/// Pasta #tab() Primi #tab() 12.50 \
+/// Tiramisu #tab() Dolci #tab() 7.00 \
+/// Espresso #tab() Caffè #tab() 2.5
+/// ```
+///
+/// Display: Tab
+/// Category: layout
+#[element]
+pub struct TabElem {}
+
+/// A tab stop within a paragraph.
+#[derive(Debug, Clone, Hash)]
+pub struct TabStop {
+    /// The position of the stop, measured from the start of the line.
+    pub pos: Length,
+    /// How text following a tab is aligned with the stop.
+    pub align: TabAlign,
+    /// Content to repeat in the space up to the stop.
+    pub leader: Option<Content>,
+}
+
+cast_from_value! {
+    TabStop,
+    pos: Length => Self { pos, align: TabAlign::Left, leader: None },
+    mut dict: Dict => {
+        let pos = dict.take("pos")?.cast()?;
+        let align = dict.take("align").ok().map(Value::cast).transpose()?;
+        let leader = dict.take("leader").ok().map(Value::cast).transpose()?;
+        dict.finish(&["pos", "align", "leader"])?;
+        Self {
+            pos,
+            align: align.unwrap_or(TabAlign::Left),
+            leader: leader.flatten(),
+        }
+    },
+}
+
+cast_to_value! {
+    v: TabStop => Value::Dict(dict! {
+        "pos" => Value::Length(v.pos),
+        "align" => Value::from(v.align),
+        "leader" => Value::from(v.leader),
+    })
+}
+
+/// How text following a tab is aligned with the tab stop.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum TabAlign {
+    /// The text starts at the stop.
+    Left,
+    /// The text ends at the stop.
+    Right,
+    /// The text is centered at the stop.
+    Center,
+    /// The text's first decimal point is placed at the stop.
+    Decimal,
+}
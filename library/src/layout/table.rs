@@ -1,4 +1,4 @@
-use crate::layout::{AlignElem, GridLayouter, TrackSizings};
+use crate::layout::{AlignElem, GridLayouter, RotateElem, TrackSizings};
 use crate::meta::LocalName;
 use crate::prelude::*;
 
@@ -64,7 +64,8 @@ pub struct TableElem {
     ///
     /// This can be a color or a function that returns a color. The function is
     /// passed the cell's column and row index, starting at zero. This can be
-    /// used to implement striped tables.
+    /// used to implement striped tables. If the function declares a named
+    /// `body` parameter, it is also passed the cell's content.
     ///
     /// ```example
     /// #table(
@@ -80,23 +81,115 @@ pub struct TableElem {
     ///   [Profit:], [500 €], [1000 €], [1500 €],
     /// )
     /// ```
+    ///
+    /// Taking the content into account allows to highlight cells based on
+    /// their values:
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   fill: (_, _, body: none) => if body == [0] { red.lighten(60%) },
+    ///   [3], [0], [5],
+    ///   [0], [2], [1],
+    /// )
+    /// ```
     pub fill: Celled<Option<Paint>>,
 
     /// How to align the cell's content.
     ///
     /// This can either be a single alignment or a function that returns an
-    /// alignment. The function is passed the cell's column and row index,
-    /// starting at zero. If set to `{auto}`, the outer alignment is used.
+    /// alignment. The function is passed the same arguments as for the `fill`.
+    /// If set to `{auto}`, the outer alignment is used.
+    ///
+    /// Vertical alignments place the content at the `{top}`, in the middle
+    /// (`{horizon}`), or at the `{bottom}` of the cell.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   rows: 40pt,
+    ///   align: (col, _) => (top, horizon, bottom).at(col),
+    ///   [Top], [Middle], [Bottom],
+    /// )
+    /// ```
     pub align: Celled<Smart<Axes<Option<GenAlign>>>>,
 
+    /// How to rotate the cell's content.
+    ///
+    /// This can either be a single angle or a function that returns an angle.
+    /// The function is passed the same arguments as for the `fill`. Rotated
+    /// content takes up the space of its rotated bounding box, so a quarter
+    /// turn is useful for narrow columns with long headers.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 4,
+    ///   align: bottom,
+    ///   rotate: (_, row) => if row == 0 { -90deg } else { 0deg },
+    ///   [], [*Monday*], [*Tuesday*], [*Wednesday*],
+    ///   [Alice], [2h], [3h], [1h],
+    ///   [Bob], [1h], [4h], [2h],
+    /// )
+    /// ```
+    pub rotate: Celled<Angle>,
+
     /// How to stroke the cells.
     ///
     /// This can be a color, a stroke width, both, or `{none}` to disable
-    /// the stroke.
+    /// the stroke. With a dictionary, the stroke for each side of the cells
+    /// can be set individually. It accepts the same keys as the [rectangle's
+    /// stroke]($func/rect.stroke). Sides that are not specified use the
+    /// default stroke.
+    ///
+    /// This can also be a function that returns a stroke or a dictionary. The
+    /// function is passed the same arguments as for the `fill`.
+    ///
+    /// When neighbouring cells disagree about the stroke of the edge between
+    /// them, the thicker stroke wins. For strokes of the same thickness, the
+    /// stroke of the cell further right or further down wins. An edge is only
+    /// left blank if both cells specify `{none}` for it. Where lines meet,
+    /// they are extended to close the joint.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (x: none),
+    ///   [*A*], [*B*], [*C*],
+    ///   [1], [2], [3],
+    /// )
+    ///
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (_, row) => if row == 0 {
+    ///     (bottom: 1.5pt)
+    ///   } else {
+    ///     0.5pt + luma(180)
+    ///   },
+    ///   [*A*], [*B*], [*C*],
+    ///   [1], [2], [3],
+    /// )
+    /// ```
+    #[fold]
+    #[default(Celled::Value(Sides::splat(Some(PartialStroke::default()))))]
+    pub stroke: Celled<Sides<Option<Option<PartialStroke>>>>,
+
+    /// A stroke around the whole table.
+    ///
+    /// On the outer edges of the table, this takes precedence over the
+    /// strokes of the cells.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: 0.5pt + luma(180),
+    ///   border: 1.5pt,
+    ///   [A], [B], [C],
+    ///   [1], [2], [3],
+    /// )
+    /// ```
     #[resolve]
     #[fold]
-    #[default(Some(PartialStroke::default()))]
-    pub stroke: Option<PartialStroke>,
+    pub border: Option<PartialStroke>,
 
     /// How much to pad the cells's content.
     ///
@@ -104,6 +197,51 @@ pub struct TableElem {
     #[default(Abs::pt(5.0).into())]
     pub inset: Rel<Length>,
 
+    /// The number of rows at the start of the table that are repeated on
+    /// every page the table spans.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #table(
+    ///   columns: 2,
+    ///   header-rows: 1,
+    ///   [*Name*], [*Value*],
+    ///   ..range(12).map(n => ([Item #n], str(n * n))).flatten(),
+    /// )
+    /// ```
+    pub header_rows: usize,
+
+    /// The number of rows at the end of the table that are repeated on every
+    /// page the table spans.
+    pub footer_rows: usize,
+
+    /// Content to show above each part of the table that continues from a
+    /// previous page.
+    ///
+    /// This can be content or a function that returns content. The function
+    /// is passed the number of the part, starting at one, and the total number
+    /// of parts the table was broken into. The marker is only shown if the
+    /// table actually breaks.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #table(
+    ///   columns: 2,
+    ///   continued-top: (part, parts) => emph[
+    ///     (Part #part of #parts)
+    ///   ],
+    ///   continued-bottom: align(right, emph[Continued on next page]),
+    ///   ..range(16).map(str),
+    /// )
+    /// ```
+    pub continued_top: Option<Continuation>,
+
+    /// Content to show below each part of the table that continues on the
+    /// next page.
+    ///
+    /// Accepts the same values as `continued-top`.
+    pub continued_bottom: Option<Continuation>,
+
     /// The contents of the table cells.
     #[variadic]
     pub children: Vec<Content>,
@@ -118,20 +256,29 @@ impl Layout for TableElem {
     ) -> SourceResult<Fragment> {
         let inset = self.inset(styles);
         let align = self.align(styles);
+        let rotate = self.rotate(styles);
 
         let tracks = Axes::new(self.columns(styles).0, self.rows(styles).0);
         let gutter = Axes::new(self.column_gutter(styles).0, self.row_gutter(styles).0);
         let cols = tracks.x.len().max(1);
-        let cells: Vec<_> = self
-            .children()
-            .into_iter()
+        let children = self.children();
+        let cells: Vec<_> = children
+            .iter()
+            .cloned()
             .enumerate()
-            .map(|(i, child)| {
-                let mut child = child.padded(Sides::splat(inset));
-
+            .map(|(i, body)| {
                 let x = i % cols;
                 let y = i / cols;
-                if let Smart::Custom(alignment) = align.resolve(vt, x, y)? {
+
+                let mut child = body.clone();
+                let angle = rotate.resolve(vt, x, y, &body)?;
+                if !angle.is_zero() {
+                    child =
+                        RotateElem::new(child).with_angle(angle).with_reflow(true).pack();
+                }
+
+                child = child.padded(Sides::splat(inset));
+                if let Smart::Custom(alignment) = align.resolve(vt, x, y, &body)? {
                     child = child.styled(AlignElem::set_alignment(alignment));
                 }
 
@@ -139,8 +286,25 @@ impl Layout for TableElem {
             })
             .collect::<SourceResult<_>>()?;
 
+        // The content of the cell at the given track indices, if any.
+        let has_gutter = gutter.as_ref().any(|tracks| !tracks.is_empty());
+        let body_at = |x: usize, y: usize| {
+            let (x, y) = match has_gutter {
+                true if x % 2 == 1 || y % 2 == 1 => return Content::empty(),
+                true => (x / 2, y / 2),
+                false => (x, y),
+            };
+            children.get(y * cols + x).cloned().unwrap_or_default()
+        };
+
         let fill = self.fill(styles);
-        let stroke = self.stroke(styles).map(PartialStroke::unwrap_or_default);
+        let stroke = self.stroke(styles);
+        let border = self.border(styles).map(PartialStroke::unwrap_or_default);
+
+        let header = self.header_rows(styles);
+        let footer = self.footer_rows(styles);
+        let top = self.continued_top(styles);
+        let bottom = self.continued_bottom(styles);
 
         // Prepare grid layout by unifying content and gutter tracks.
         let layouter = GridLayouter::new(
@@ -150,60 +314,256 @@ impl Layout for TableElem {
             &cells,
             regions,
             styles,
-        );
+        )
+        .repeat(header, footer);
 
         // Measure the columns and layout the grid row-by-row.
         let mut layout = layouter.layout()?;
 
-        // Add lines and backgrounds.
-        for (frame, rows) in layout.fragment.iter_mut().zip(&layout.rows) {
-            // Render table lines.
-            if let Some(stroke) = stroke {
-                let thickness = stroke.thickness;
-                let half = thickness / 2.0;
-
-                // Render horizontal lines.
-                for offset in points(rows.iter().map(|piece| piece.height)) {
-                    let target = Point::with_x(frame.width() + thickness);
-                    let hline = Geometry::Line(target).stroked(stroke);
-                    frame.prepend(
-                        Point::new(-half, offset),
-                        FrameItem::Shape(hline, self.span()),
-                    );
-                }
-
-                // Render vertical lines.
-                for offset in points(layout.cols.iter().copied()) {
-                    let target = Point::with_y(frame.height() + thickness);
-                    let vline = Geometry::Line(target).stroked(stroke);
-                    frame.prepend(
-                        Point::new(offset, -half),
-                        FrameItem::Shape(vline, self.span()),
-                    );
+        // If the table breaks, lay it out once more, leaving room for the
+        // continuation markers in each region. The markers are measured for
+        // the number of parts of the first layout and the repeated rows are
+        // reused from it. Should leaving room change the number of parts, the
+        // markers are shown for the final parts nonetheless.
+        let mut parts = layout.fragment.len();
+        if parts > 1 && (top.is_some() || bottom.is_some()) {
+            let mut reserved = Abs::zero();
+            for marker in [&top, &bottom].into_iter().flatten() {
+                let mut height = Abs::zero();
+                for part in 1..=parts {
+                    let pod = Regions::one(regions.base(), Axes::splat(false));
+                    let content = marker.resolve(vt, part, parts)?;
+                    let frame = content.measure(vt, styles, pod)?.into_frame();
+                    height.set_max(frame.height());
                 }
+                reserved += height;
             }
 
-            // Render cell backgrounds.
+            let mut backlog = vec![];
+            let pod = regions.map(&mut backlog, |size| {
+                Size::new(size.x, (size.y - reserved).max(Abs::zero()))
+            });
+
+            layout = GridLayouter::new(
+                vt,
+                tracks.as_deref(),
+                gutter.as_deref(),
+                &cells,
+                pod,
+                styles,
+            )
+            .repeat(header, footer)
+            .reuse(&mut layout)
+            .layout()?;
+            parts = layout.fragment.len();
+        }
+
+        // Add lines and backgrounds.
+        for (frame, rows) in layout.fragment.iter_mut().zip(&layout.rows) {
+            // Resolve the strokes of the cells and prepare their backgrounds.
+            let mut strokes = vec![];
+            let mut fills = vec![];
             let mut dx = Abs::zero();
             for (x, &col) in layout.cols.iter().enumerate() {
                 let mut dy = Abs::zero();
                 for row in rows {
-                    if let Some(fill) = fill.resolve(vt, x, row.y)? {
+                    let body = body_at(x, row.y);
+                    let sides = match stroke {
+                        Celled::Value(sides) => sides,
+                        Celled::Func(ref func) => call_cell(func, vt, x, row.y, &body)?
+                            .cast::<Sides<Option<Option<PartialStroke>>>>()
+                            .at(func.span())?
+                            .fold(Sides::splat(Some(PartialStroke::default()))),
+                    };
+                    strokes.push(sides.map(|side| {
+                        side.map(|stroke| stroke.resolve(styles).unwrap_or_default())
+                    }));
+
+                    if let Some(fill) = fill.resolve(vt, x, row.y, &body)? {
                         let pos = Point::new(dx, dy);
                         let size = Size::new(col, row.height);
-                        let rect = Geometry::Rect(size).filled(fill);
-                        frame.prepend(pos, FrameItem::Shape(rect, self.span()));
+                        fills.push((pos, Geometry::Rect(size).filled(fill)));
                     }
                     dy += row.height;
                 }
                 dx += col;
             }
+
+            // Render table lines.
+            let heights: Vec<_> = rows.iter().map(|piece| piece.height).collect();
+            let rules = Rules {
+                cols: &layout.cols,
+                rows: &heights,
+                strokes,
+                border,
+            };
+            rules.render(frame, self.span());
+
+            // Render cell backgrounds.
+            for (pos, rect) in fills {
+                frame.prepend(pos, FrameItem::Shape(rect, self.span()));
+            }
+        }
+
+        // Add the continuation markers.
+        if parts > 1 {
+            for (i, frame) in layout.fragment.iter_mut().enumerate() {
+                let size = Size::new(frame.width(), Abs::inf());
+                let pod = Regions::one(size, Axes::new(true, false));
+
+                if let Some(marker) = top.as_ref().filter(|_| i > 0) {
+                    let mut marker = marker
+                        .resolve(vt, i + 1, parts)?
+                        .layout(vt, styles, pod)?
+                        .into_frame();
+                    marker.mark_artifact();
+                    let target = frame.size() + Size::with_y(marker.height());
+                    frame.resize(target, Axes::new(Align::Left, Align::Bottom));
+                    frame.push_frame(Point::zero(), marker);
+                }
+
+                if let Some(marker) = bottom.as_ref().filter(|_| i + 1 < parts) {
+                    let mut marker = marker
+                        .resolve(vt, i + 1, parts)?
+                        .layout(vt, styles, pod)?
+                        .into_frame();
+                    marker.mark_artifact();
+                    let y = frame.height();
+                    let target = frame.size() + Size::with_y(marker.height());
+                    frame.resize(target, Axes::new(Align::Left, Align::Top));
+                    frame.push_frame(Point::with_y(y), marker);
+                }
+            }
         }
 
         Ok(layout.fragment)
     }
 }
 
+/// The lines of one region of a table.
+struct Rules<'a> {
+    /// The widths of the columns.
+    cols: &'a [Abs],
+    /// The heights of the rows.
+    rows: &'a [Abs],
+    /// The strokes of each cell's sides, in column-major order.
+    strokes: Vec<Sides<Option<Stroke>>>,
+    /// The stroke around the whole table.
+    border: Option<Stroke>,
+}
+
+impl Rules<'_> {
+    /// Render the lines into the frame.
+    fn render(&self, frame: &mut Frame, span: Span) {
+        let xs: Vec<_> = points(self.cols.iter().copied()).collect();
+        let ys: Vec<_> = points(self.rows.iter().copied()).collect();
+
+        // Horizontal lines, merging segments with the same stroke.
+        for (j, &y) in ys.iter().enumerate() {
+            let mut i = 0;
+            while i < self.cols.len() {
+                let Some(stroke) = self.hline(i, j) else {
+                    i += 1;
+                    continue;
+                };
+
+                let start = i;
+                while i < self.cols.len() && self.hline(i, j) == Some(stroke) {
+                    i += 1;
+                }
+
+                let before = self.vjoint(start, j) / 2.0;
+                let after = self.vjoint(i, j) / 2.0;
+                let target = Point::with_x(xs[i] - xs[start] + before + after);
+                let hline = Geometry::Line(target).stroked(stroke);
+                let pos = Point::new(xs[start] - before, y);
+                frame.prepend(pos, FrameItem::Shape(hline, span));
+            }
+        }
+
+        // Vertical lines, merging segments with the same stroke.
+        for (i, &x) in xs.iter().enumerate() {
+            let mut j = 0;
+            while j < self.rows.len() {
+                let Some(stroke) = self.vline(i, j) else {
+                    j += 1;
+                    continue;
+                };
+
+                let start = j;
+                while j < self.rows.len() && self.vline(i, j) == Some(stroke) {
+                    j += 1;
+                }
+
+                let before = self.hjoint(i, start) / 2.0;
+                let after = self.hjoint(i, j) / 2.0;
+                let target = Point::with_y(ys[j] - ys[start] + before + after);
+                let vline = Geometry::Line(target).stroked(stroke);
+                let pos = Point::new(x, ys[start] - before);
+                frame.prepend(pos, FrameItem::Shape(vline, span));
+            }
+        }
+    }
+
+    /// The stroke of the cell in column `i` and row `j`.
+    fn cell(&self, i: usize, j: usize) -> &Sides<Option<Stroke>> {
+        &self.strokes[i * self.rows.len() + j]
+    }
+
+    /// The stroke of the horizontal line `j` in column `i`.
+    fn hline(&self, i: usize, j: usize) -> Option<Stroke> {
+        if self.border.is_some() && (j == 0 || j == self.rows.len()) {
+            return self.border;
+        }
+
+        let above = j.checked_sub(1).and_then(|j| self.cell(i, j).bottom);
+        let below = (j < self.rows.len()).then(|| self.cell(i, j).top).flatten();
+        prefer(above, below)
+    }
+
+    /// The stroke of the vertical line `i` in row `j`.
+    fn vline(&self, i: usize, j: usize) -> Option<Stroke> {
+        if self.border.is_some() && (i == 0 || i == self.cols.len()) {
+            return self.border;
+        }
+
+        let left = i.checked_sub(1).and_then(|i| self.cell(i, j).right);
+        let right = (i < self.cols.len()).then(|| self.cell(i, j).left).flatten();
+        prefer(left, right)
+    }
+
+    /// The thickest vertical line meeting horizontal line `j` at column
+    /// boundary `i`.
+    fn vjoint(&self, i: usize, j: usize) -> Abs {
+        let above = j.checked_sub(1).and_then(|j| self.vline(i, j));
+        let below = (j < self.rows.len()).then(|| self.vline(i, j)).flatten();
+        thickest(above, below)
+    }
+
+    /// The thickest horizontal line meeting vertical line `i` at row boundary
+    /// `j`.
+    fn hjoint(&self, i: usize, j: usize) -> Abs {
+        let left = i.checked_sub(1).and_then(|i| self.hline(i, j));
+        let right = (i < self.cols.len()).then(|| self.hline(i, j)).flatten();
+        thickest(left, right)
+    }
+}
+
+/// Decide between the strokes two neighbouring cells want for their shared
+/// edge. The thicker stroke wins, ties go to the later cell.
+fn prefer(earlier: Option<Stroke>, later: Option<Stroke>) -> Option<Stroke> {
+    match (earlier, later) {
+        (Some(a), Some(b)) if a.thickness > b.thickness => Some(a),
+        (a, b) => b.or(a),
+    }
+}
+
+/// The thickness of the thicker of two strokes.
+fn thickest(a: Option<Stroke>, b: Option<Stroke>) -> Abs {
+    let thickness = |stroke: Option<Stroke>| stroke.map_or(Abs::zero(), |s| s.thickness);
+    thickness(a).max(thickness(b))
+}
+
 /// Turn an iterator extents into an iterator of offsets before, in between, and
 /// after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
 fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
@@ -226,18 +586,61 @@ pub enum Celled<T> {
 }
 
 impl<T: Cast + Clone> Celled<T> {
-    /// Resolve the value based on the cell position.
-    pub fn resolve(&self, vt: &mut Vt, x: usize, y: usize) -> SourceResult<T> {
+    /// Resolve the value based on the cell position and content.
+    ///
+    /// The content is only passed to functions that declare a named `body`
+    /// parameter.
+    pub fn resolve(
+        &self,
+        vt: &mut Vt,
+        x: usize,
+        y: usize,
+        body: &Content,
+    ) -> SourceResult<T> {
         Ok(match self {
             Self::Value(value) => value.clone(),
-            Self::Func(func) => func
-                .call_vt(vt, [Value::Int(x as i64), Value::Int(y as i64)])?
-                .cast()
-                .at(func.span())?,
+            Self::Func(func) => call_cell(func, vt, x, y, body)?.cast().at(func.span())?,
         })
     }
 }
 
+/// Call a per-cell function with the cell position and, if it declares a named
+/// `body` parameter, the cell's content.
+fn call_cell(
+    func: &Func,
+    vt: &mut Vt,
+    x: usize,
+    y: usize,
+    body: &Content,
+) -> SourceResult<Value> {
+    let span = func.span();
+    let mut args = Args::new(span, [Value::Int(x as i64), Value::Int(y as i64)]);
+    if func.takes_named("body") {
+        args.push_named(span, "body".into(), Value::Content(body.clone()));
+    }
+    func.call_vt_args(vt, args)
+}
+
+impl<T> Fold for Celled<T>
+where
+    T: Fold,
+    T::Output: Default,
+{
+    type Output = Celled<T::Output>;
+
+    fn fold(self, outer: Self::Output) -> Self::Output {
+        match (self, outer) {
+            (Self::Value(inner), Celled::Value(outer)) => {
+                Celled::Value(inner.fold(outer))
+            }
+            (Self::Value(inner), Celled::Func(_)) => {
+                Celled::Value(inner.fold(Default::default()))
+            }
+            (Self::Func(func), _) => Celled::Func(func),
+        }
+    }
+}
+
 impl<T: Default> Default for Celled<T> {
     fn default() -> Self {
         Self::Value(T::default())
@@ -271,6 +674,46 @@ impl<T: Into<Value>> From<Celled<T>> for Value {
     }
 }
 
+/// Content marking where a table continues across pages.
+#[derive(Debug, Clone, Hash)]
+pub enum Continuation {
+    /// The same content for all parts.
+    Content(Content),
+    /// A closure mapping from the part number and the number of parts to
+    /// content.
+    Func(Func),
+}
+
+impl Continuation {
+    /// Resolve the marker for the given part.
+    pub fn resolve(
+        &self,
+        vt: &mut Vt,
+        part: usize,
+        parts: usize,
+    ) -> SourceResult<Content> {
+        Ok(match self {
+            Self::Content(content) => content.clone(),
+            Self::Func(func) => func
+                .call_vt(vt, [Value::Int(part as i64), Value::Int(parts as i64)])?
+                .display(),
+        })
+    }
+}
+
+cast_from_value! {
+    Continuation,
+    v: Content => Self::Content(v),
+    v: Func => Self::Func(v),
+}
+
+cast_to_value! {
+    v: Continuation => match v {
+        Continuation::Content(v) => v.into(),
+        Continuation::Func(v) => v.into(),
+    }
+}
+
 impl LocalName for TableElem {
     fn local_name(&self, lang: Lang) -> &'static str {
         match lang {
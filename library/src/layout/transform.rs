@@ -99,6 +99,21 @@ pub struct RotateElem {
     #[resolve]
     pub origin: Axes<Option<GenAlign>>,
 
+    /// Whether the rotation impacts the layout.
+    ///
+    /// If set to `{false}`, the rotated content will retain the bounding box of
+    /// the original content. If set to `{true}`, the bounding box will take
+    /// the rotation of the content into account and adjust the layout
+    /// accordingly. For quarter turns, the content is laid out with the width
+    /// and height of the available space swapped. The `origin` is ignored in
+    /// this case.
+    ///
+    /// ```example
+    /// Hello #rotate(90deg, reflow: true)[World]!
+    /// ```
+    #[default(false)]
+    pub reflow: bool,
+
     /// The content to rotate.
     #[required]
     pub body: Content,
@@ -111,18 +126,55 @@ impl Layout for RotateElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        let angle = self.angle(styles);
+        if self.reflow(styles) {
+            return self.layout_reflowed(vt, styles, regions, angle);
+        }
+
         let pod = Regions::one(regions.base(), Axes::splat(false));
         let mut frame = self.body().layout(vt, styles, pod)?.into_frame();
         let origin = self.origin(styles).unwrap_or(Align::CENTER_HORIZON);
         let Axes { x, y } = origin.zip(frame.size()).map(|(o, s)| o.position(s));
         let ts = Transform::translate(x, y)
-            .pre_concat(Transform::rotate(self.angle(styles)))
+            .pre_concat(Transform::rotate(angle))
             .pre_concat(Transform::translate(-x, -y));
         frame.transform(ts);
         Ok(Fragment::frame(frame))
     }
 }
 
+impl RotateElem {
+    /// Layout the rotated content such that its bounding box encloses it.
+    fn layout_reflowed(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+        angle: Angle,
+    ) -> SourceResult<Fragment> {
+        let (sin, cos) = (angle.sin().abs(), angle.cos().abs());
+
+        // Mostly upright content is laid out in the available space as is,
+        // mostly sideways content with the axes swapped.
+        let base = regions.base();
+        let size = if sin > cos { Size::new(base.y, base.x) } else { base };
+        let pod = Regions::one(size, Axes::splat(false));
+        let mut frame = self.body().layout(vt, styles, pod)?.into_frame();
+
+        // Rotate around the center and move the center to the center of the
+        // enclosing box.
+        let Axes { x: w, y: h } = frame.size();
+        let bbox = Size::new(w * cos + h * sin, w * sin + h * cos);
+        let ts = Transform::translate(bbox.x / 2.0, bbox.y / 2.0)
+            .pre_concat(Transform::rotate(angle))
+            .pre_concat(Transform::translate(-w / 2.0, -h / 2.0));
+        frame.transform(ts);
+        frame.set_size(bbox);
+        frame.set_baseline(bbox.y);
+        Ok(Fragment::frame(frame))
+    }
+}
+
 /// Scale content without affecting layout.
 ///
 /// The `scale` function allows you to scale and mirror content without
@@ -190,3 +242,65 @@ impl Layout for ScaleElem {
         Ok(Fragment::frame(frame))
     }
 }
+
+/// Skew content without affecting layout.
+///
+/// The `skew` function shears content along the horizontal and vertical axes.
+/// Like with `rotate` and `scale`, the layout will act as if the element was
+/// not skewed.
+///
+/// ## Example
+/// ```example
+/// #skew(ax: -12deg)[
+///   This is some fake italic text.
+/// ]
+/// ```
+///
+/// Display: Skew
+/// Category: layout
+#[element(Layout)]
+pub struct SkewElem {
+    /// The horizontal skewing angle.
+    ///
+    /// Positive angles shear the bottom of the content to the right.
+    pub ax: Angle,
+
+    /// The vertical skewing angle.
+    ///
+    /// Positive angles shear the right side of the content downwards.
+    pub ay: Angle,
+
+    /// The origin of the skew transformation.
+    ///
+    /// By default, the origin is the center of the skewed element.
+    ///
+    /// ```example
+    /// #box(skew(ax: 20deg, origin: top + left, rect[A]))
+    /// #box(skew(ax: 20deg, origin: bottom + left, rect[B]))
+    /// ```
+    #[resolve]
+    pub origin: Axes<Option<GenAlign>>,
+
+    /// The content to skew.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for SkewElem {
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let mut frame = self.body().layout(vt, styles, pod)?.into_frame();
+        let origin = self.origin(styles).unwrap_or(Align::CENTER_HORIZON);
+        let Axes { x, y } = origin.zip(frame.size()).map(|(o, s)| o.position(s));
+        let transform = Transform::translate(x, y)
+            .pre_concat(Transform::skew(self.ax(styles), self.ay(styles)))
+            .pre_concat(Transform::translate(-x, -y));
+        frame.transform(transform);
+        Ok(Fragment::frame(frame))
+    }
+}
@@ -33,6 +33,7 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("text", text::TextElem::func());
     global.define("linebreak", text::LinebreakElem::func());
     global.define("smartquote", text::SmartQuoteElem::func());
+    global.define("kern", text::KernElem::func());
     global.define("strong", text::StrongElem::func());
     global.define("emph", text::EmphElem::func());
     global.define("lower", text::lower);
@@ -43,7 +44,14 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("underline", text::UnderlineElem::func());
     global.define("strike", text::StrikeElem::func());
     global.define("overline", text::OverlineElem::func());
+    global.define("highlight", text::HighlightElem::func());
+    global.define("emphmark", text::EmphmarkElem::func());
+    global.define("ruby", text::RubyElem::func());
     global.define("raw", text::RawElem::func());
+    global.define("unit", text::unit);
+    global.define("qty", text::qty);
+    global.define("ce", text::ce);
+    global.define("isotope", text::isotope);
     global.define("lorem", text::lorem);
 
     // Math.
@@ -56,6 +64,7 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("par", layout::ParElem::func());
     global.define("parbreak", layout::ParbreakElem::func());
     global.define("h", layout::HElem::func());
+    global.define("tab", layout::TabElem::func());
     global.define("box", layout::BoxElem::func());
     global.define("block", layout::BlockElem::func());
     global.define("list", layout::ListElem::func());
@@ -67,14 +76,17 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("columns", layout::ColumnsElem::func());
     global.define("colbreak", layout::ColbreakElem::func());
     global.define("place", layout::PlaceElem::func());
+    global.define("marginnote", layout::MarginNoteElem::func());
     global.define("align", layout::AlignElem::func());
     global.define("pad", layout::PadElem::func());
     global.define("repeat", layout::RepeatElem::func());
     global.define("move", layout::MoveElem::func());
     global.define("scale", layout::ScaleElem::func());
     global.define("rotate", layout::RotateElem::func());
+    global.define("skew", layout::SkewElem::func());
     global.define("hide", layout::HideElem::func());
     global.define("measure", layout::measure);
+    global.define("layout", layout::LayoutElem::func());
 
     // Visualize.
     global.define("image", visualize::ImageElem::func());
@@ -88,11 +100,22 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("document", meta::DocumentElem::func());
     global.define("ref", meta::RefElem::func());
     global.define("link", meta::LinkElem::func());
+    global.define("artifact", meta::ArtifactElem::func());
+    global.define("reading", meta::ReadingElem::func());
     global.define("outline", meta::OutlineElem::func());
+    global.define("bookmark", meta::BookmarkElem::func());
+    global.define("attachment", meta::AttachmentElem::func());
+    global.define("field", meta::FieldElem::func());
     global.define("heading", meta::HeadingElem::func());
     global.define("figure", meta::FigureElem::func());
+    global.define("figures", meta::FiguresElem::func());
+    global.define("theorem", meta::TheoremElem::func());
     global.define("cite", meta::CiteElem::func());
     global.define("bibliography", meta::BibliographyElem::func());
+    global.define("glossary", meta::GlossaryElem::func());
+    global.define("endnote", meta::EndnoteElem::func());
+    global.define("endnotes", meta::EndnotesElem::func());
+    global.define("term", meta::TermElem::func());
     global.define("locate", meta::locate);
     global.define("style", meta::style);
     global.define("counter", meta::counter);
@@ -115,8 +138,12 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("luma", compute::luma);
     global.define("rgb", compute::rgb);
     global.define("cmyk", compute::cmyk);
+    global.define("gradient", compute::gradient);
+    global.define("pattern", compute::pattern);
+    global.define("readable", compute::readable);
     global.define("symbol", compute::symbol);
     global.define("str", compute::str);
+    global.define("format", compute::format);
     global.define("label", compute::label);
     global.define("regex", compute::regex);
     global.define("range", compute::range);
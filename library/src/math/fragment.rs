@@ -220,9 +220,11 @@ impl GlyphFragment {
             size: self.font_size,
             fill: self.fill,
             lang: self.lang,
+            text: self.c.into(),
             glyphs: vec![Glyph {
                 id: self.id.0,
                 c: self.c,
+                range: 0..self.c.len_utf8() as u16,
                 x_advance: Em::from_length(self.width, self.font_size),
                 x_offset: Em::zero(),
                 span: self.span,
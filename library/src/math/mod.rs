@@ -152,6 +152,24 @@ pub struct EquationElem {
     /// ```
     pub numbering: Option<Numbering>,
 
+    /// Whether to number each line of a multi-line block equation separately
+    /// instead of the equation as a whole.
+    ///
+    /// Combined with [alignment points]($category/math/align-point), this
+    /// yields an environment of aligned, individually numbered equations. A
+    /// reference to such an equation shows the number of its last line.
+    ///
+    /// ```example
+    /// #set math.equation(numbering: "(1)", number-lines: true)
+    ///
+    /// $ a &= (x + y)^2 \
+    ///     &= x^2 + 2 x y + y^2 $ <binomial>
+    ///
+    /// By @binomial, we are done.
+    /// ```
+    #[default(false)]
+    pub number_lines: bool,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
@@ -161,6 +179,7 @@ impl Synthesize for EquationElem {
     fn synthesize(&mut self, styles: StyleChain) {
         self.push_block(self.block(styles));
         self.push_numbering(self.numbering(styles));
+        self.push_number_lines(self.number_lines(styles));
     }
 }
 
@@ -210,10 +229,59 @@ impl Layout for EquationElem {
         };
 
         let mut ctx = MathContext::new(vt, styles, regions, &font, block);
-        let mut frame = ctx.layout_frame(self)?;
+        let numbering = self.numbering(styles);
+        let mut baselines = None;
+        let mut frame = if block && numbering.is_some() && self.number_lines(styles) {
+            let (frame, lines) = ctx.layout_row(self)?.to_frame_with_baselines(&ctx);
+            baselines = Some(lines);
+            frame
+        } else {
+            ctx.layout_frame(self)?
+        };
 
         if block {
-            if let Some(numbering) = self.numbering(styles) {
+            if let (Some(numbering), Some(baselines)) = (&numbering, baselines) {
+                let pod = Regions::one(regions.base(), Axes::splat(false));
+                let location = self.0.location().unwrap();
+                let last = Counter::of(Self::func()).at(vt, location)?.first().get();
+                let lines = self.lines();
+
+                // Lay out the number of each line.
+                let mut numbers = vec![];
+                for (i, baseline) in baselines.into_iter().enumerate().take(lines) {
+                    let Some(n) = NonZeroUsize::new((last + 1 + i).saturating_sub(lines))
+                    else {
+                        continue;
+                    };
+                    let number = numbering
+                        .apply_vt(vt, &[n])?
+                        .display()
+                        .layout(vt, styles, pod)?
+                        .into_frame();
+                    numbers.push((baseline, number));
+                }
+
+                let max = numbers.iter().map(|(_, number)| number.width()).max();
+                let width = if regions.size.x.is_finite() {
+                    regions.size.x
+                } else {
+                    frame.width()
+                        + 2.0 * (max.unwrap_or_default() + NUMBER_GUTTER.resolve(styles))
+                };
+
+                let height = frame.height();
+                frame.resize(Size::new(width, height), Align::CENTER_HORIZON);
+
+                for (baseline, number) in numbers {
+                    let x = if TextElem::dir_in(styles).is_positive() {
+                        frame.width() - number.width()
+                    } else {
+                        Abs::zero()
+                    };
+                    let y = baseline - number.baseline();
+                    frame.push_frame(Point::new(x, y), number);
+                }
+            } else if let Some(numbering) = numbering {
                 let pod = Regions::one(regions.base(), Axes::splat(false));
                 let counter = Counter::of(Self::func())
                     .display(Some(numbering), false)
@@ -255,11 +323,37 @@ impl Layout for EquationElem {
     }
 }
 
+impl EquationElem {
+    /// The number of lines in the equation.
+    fn lines(&self) -> usize {
+        fn count(content: &Content) -> usize {
+            if content.is::<LinebreakElem>() {
+                1
+            } else if let Some(children) = content.to_sequence() {
+                children.map(count).sum()
+            } else if let Some((elem, _)) = content.to_styled() {
+                count(elem)
+            } else {
+                0
+            }
+        }
+
+        1 + count(&self.body())
+    }
+}
+
 impl Count for EquationElem {
     fn update(&self) -> Option<CounterUpdate> {
-        (self.block(StyleChain::default())
-            && self.numbering(StyleChain::default()).is_some())
-        .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        let styles = StyleChain::default();
+        if !self.block(styles) || self.numbering(styles).is_none() {
+            return None;
+        }
+
+        Some(if self.number_lines(styles) {
+            CounterUpdate::Advance(NonZeroUsize::ONE, self.lines())
+        } else {
+            CounterUpdate::Step(NonZeroUsize::ONE)
+        })
     }
 }
 
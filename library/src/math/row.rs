@@ -107,6 +107,14 @@ impl MathRow {
         self.to_aligned_frame(ctx, &[], align)
     }
 
+    /// Like [`to_frame`](Self::to_frame), but additionally returns the
+    /// baseline of each line.
+    pub fn to_frame_with_baselines(self, ctx: &MathContext) -> (Frame, Vec<Abs>) {
+        let styles = ctx.styles();
+        let align = AlignElem::alignment_in(styles).x.resolve(styles);
+        self.layout_lines(ctx, &[], align)
+    }
+
     pub fn to_fragment(self, ctx: &MathContext) -> MathFragment {
         if self.0.len() == 1 {
             self.0.into_iter().next().unwrap()
@@ -116,11 +124,20 @@ impl MathRow {
     }
 
     pub fn to_aligned_frame(
-        mut self,
+        self,
         ctx: &MathContext,
         points: &[Abs],
         align: Align,
     ) -> Frame {
+        self.layout_lines(ctx, points, align).0
+    }
+
+    fn layout_lines(
+        mut self,
+        ctx: &MathContext,
+        points: &[Abs],
+        align: Align,
+    ) -> (Frame, Vec<Abs>) {
         if self.iter().any(|frag| matches!(frag, MathFragment::Linebreak)) {
             let fragments: Vec<_> = std::mem::take(&mut self.0);
             let leading = if ctx.style.size >= MathSize::Text {
@@ -137,6 +154,7 @@ impl MathRow {
             let width = rows.iter().map(|row| row.width()).max().unwrap_or_default();
             let points = alignments(&rows);
             let mut frame = Frame::new(Size::zero());
+            let mut baselines = vec![];
 
             for (i, row) in rows.into_iter().enumerate() {
                 let sub = row.to_line_frame(&points, align);
@@ -149,13 +167,16 @@ impl MathRow {
                 if points.is_empty() {
                     pos.x = align.position(width - sub.width());
                 }
+                baselines.push(pos.y + sub.baseline());
                 size.y += sub.height();
                 size.x.set_max(sub.width());
                 frame.push_frame(pos, sub);
             }
-            frame
+            (frame, baselines)
         } else {
-            self.to_line_frame(points, align)
+            let frame = self.to_line_frame(points, align);
+            let baseline = frame.baseline();
+            (frame, vec![baseline])
         }
     }
 
@@ -0,0 +1,78 @@
+use typst::util::hash128;
+
+use super::DocumentElem;
+use crate::prelude::*;
+
+/// Mark content as a purely decorative artifact.
+///
+/// Artifacts are still visible, but assistive technologies like screen
+/// readers skip them. Use this for content that does not carry meaning, like
+/// ornaments, rules, or repeated running material. Page backgrounds, headers
+/// and footers are marked as artifacts automatically. Artifacts are only
+/// marked in PDFs exported with a structure tree.
+///
+/// ## Example
+/// ```example
+/// #artifact(line(length: 100%))
+/// The line above is decoration.
+/// ```
+///
+/// Display: Artifact
+/// Category: meta
+#[element(Show)]
+pub struct ArtifactElem {
+    /// The decorative content.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for ArtifactElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body().styled(MetaElem::set_data(vec![Meta::Artifact])))
+    }
+}
+
+/// Changes where content is read by assistive technologies.
+///
+/// By default, screen readers and other assistive technologies read content
+/// in the order in which it appears in the document. This function groups its
+/// body into a section that is read at another position instead: Among the
+/// content next to it, sections are read in ascending `order`. Everything
+/// else has an order of zero and content with the same order is read in
+/// document order.
+///
+/// The layout of the content is not affected. Like artifacts, the reading
+/// order only matters for PDFs exported with a structure tree.
+///
+/// ## Example
+/// ```example
+/// #reading(order: 1)[
+///   _Note:_ This aside is read last.
+/// ]
+/// The main text is read first.
+/// ```
+///
+/// Display: Reading
+/// Category: meta
+#[element(Show)]
+pub struct ReadingElem {
+    /// Where the content is read among the content next to it.
+    #[default(0)]
+    pub order: i64,
+
+    /// The content to read at the given position.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for ReadingElem {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        if !DocumentElem::tagged_in(styles) {
+            return Ok(self.body());
+        }
+
+        let location = vt.provider.locate(hash128(&self.0));
+        let tag = Tag::new(Role::Section, location).with_order(self.order(styles));
+        Ok(self.body().tagged(tag))
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use typst::doc::Attachment;
+
+use crate::prelude::*;
+
+/// Attaches a file to the exported PDF.
+///
+/// Attachments are not rendered within the document. Instead, PDF viewers list
+/// them in their attachments panel, from where readers can open or save them.
+/// This is useful to ship the data behind a chart, a machine-readable invoice,
+/// or the document's own source alongside it.
+///
+/// Attachments are left out of PDF/A files, as that standard only allows
+/// attaching other PDF/A files. The command line warns when that happens.
+///
+/// ## Example
+/// ```example
+/// #attachment(
+///   "data.csv",
+///   description: "Measurements shown in the chart",
+///   mime: "text/csv",
+/// )
+/// ```
+///
+/// Display: Attachment
+/// Category: meta
+#[element(Locatable, Synthesize, Show)]
+pub struct AttachmentElem {
+    /// Path to the file to attach.
+    #[required]
+    #[parse(
+        let Spanned { v: path, span } =
+            args.expect::<Spanned<EcoString>>("path to file")?;
+        let path: EcoString = vm.locate(&path).at(span)?.to_string_lossy().into();
+        let _ = vm.world().file(Path::new(path.as_str())).at(span)?;
+        path
+    )]
+    pub path: EcoString,
+
+    /// The file name under which the file is attached. Defaults to the name of
+    /// the attached file.
+    pub name: Option<EcoString>,
+
+    /// A description of the file's contents.
+    pub description: Option<EcoString>,
+
+    /// The file's MIME type, like `{"text/csv"}` or `{"application/xml"}`.
+    pub mime: Option<EcoString>,
+}
+
+impl Synthesize for AttachmentElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        self.push_name(self.name(styles));
+        self.push_description(self.description(styles));
+        self.push_mime(self.mime(styles));
+    }
+}
+
+impl Show for AttachmentElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Resolve the files attached throughout the document.
+pub(super) fn resolve_attachments(vt: &Vt) -> SourceResult<Vec<Attachment>> {
+    let mut attachments = vec![];
+    for elem in vt.introspector.query(Selector::Elem(AttachmentElem::func(), None)) {
+        let attachment = elem.to::<AttachmentElem>().unwrap();
+        let styles = StyleChain::default();
+        let path = attachment.path();
+        let data = vt.world.file(Path::new(path.as_str())).at(elem.span())?;
+        let name = attachment.name(styles).unwrap_or_else(|| {
+            Path::new(path.as_str())
+                .file_name()
+                .map_or(path.clone(), |name| name.to_string_lossy().as_ref().into())
+        });
+
+        attachments.push(Attachment {
+            name,
+            data,
+            description: attachment.description(styles),
+            mime: attachment.mime(styles),
+        });
+    }
+
+    Ok(attachments)
+}
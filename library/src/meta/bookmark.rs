@@ -0,0 +1,156 @@
+use ecow::eco_vec;
+
+use super::HeadingElem;
+use crate::prelude::*;
+use crate::text::{LinebreakElem, SpaceElem, TextElem};
+
+/// Adds an entry to the document's outline and, optionally, a named
+/// destination.
+///
+/// Bookmarks are not rendered within the document. Instead, they appear in the
+/// outline panel (also known as the bookmarks panel) of PDF viewers and let
+/// readers jump to any place in the document. A bookmark points to the place
+/// where it is located in the document unless a `target` label is given.
+///
+/// [Headings]($func/heading) appear in the outline panel automatically. Custom
+/// bookmarks are placed among them in document order and nested by level.
+///
+/// ## Example
+/// ```example
+/// #bookmark("Overview")
+/// #bookmark("Details", level: 2, closed: true)
+/// #bookmark("Results", target: <results>, name: "results")
+///
+/// #lorem(10)
+///
+/// *Results* <results>
+/// ```
+///
+/// Display: Bookmark
+/// Category: meta
+#[element(Locatable, Synthesize, Show)]
+pub struct BookmarkElem {
+    /// The title shown in the outline panel.
+    #[required]
+    pub title: EcoString,
+
+    /// A label pointing to the place the bookmark should jump to. When this is
+    /// `{none}`, the bookmark jumps to its own position.
+    pub target: Option<Label>,
+
+    /// The nesting level of the bookmark in the outline, starting at one.
+    #[default(NonZeroUsize::ONE)]
+    pub level: NonZeroUsize,
+
+    /// Whether the bookmark's children are initially collapsed.
+    #[default(false)]
+    pub closed: bool,
+
+    /// If given, additionally exports the bookmark's target as a named
+    /// destination under this name. Other documents can then link to it with
+    /// a URL like `document.pdf#name`.
+    pub name: Option<EcoString>,
+
+    /// Whether the bookmark should appear in the outline. Disable this to only
+    /// export a named destination.
+    #[default(true)]
+    pub outlined: bool,
+}
+
+impl Synthesize for BookmarkElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        self.push_target(self.target(styles));
+        self.push_level(self.level(styles));
+        self.push_closed(self.closed(styles));
+        self.push_name(self.name(styles));
+        self.push_outlined(self.outlined(styles));
+    }
+}
+
+impl Show for BookmarkElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Resolve the bookmarks and named destinations defined in the document.
+///
+/// Headings and bookmark elements both become bookmarks, in document order.
+pub(super) fn resolve_bookmarks(
+    introspector: Tracked<Introspector>,
+) -> (Vec<Bookmark>, Vec<(EcoString, Location)>) {
+    let mut bookmarks = vec![];
+    let mut destinations = vec![];
+
+    let selector = Selector::Any(eco_vec![
+        BookmarkElem::func().select(),
+        HeadingElem::func().select()
+    ]);
+
+    for elem in introspector.query(selector) {
+        let styles = StyleChain::default();
+        if let Some(heading) = elem.to::<HeadingElem>() {
+            let outlined = heading.outlined(styles);
+            if heading.bookmarked(styles).unwrap_or(outlined) {
+                let mut title = EcoString::new();
+                plain_text(&heading.body(), &mut title);
+                bookmarks.push(Bookmark {
+                    title: title.trim().into(),
+                    level: heading.level(styles),
+                    closed: false,
+                    location: heading.0.location().unwrap(),
+                });
+            }
+            continue;
+        }
+
+        let bookmark = elem.to::<BookmarkElem>().unwrap();
+        let mut location = bookmark.0.location().unwrap();
+        if let Some(label) = bookmark.target(styles) {
+            match introspector.query(Selector::Label(label)).first() {
+                Some(target) => match target.location() {
+                    Some(target) => location = target,
+                    None => continue,
+                },
+                None => continue,
+            }
+        }
+
+        if let Some(name) = bookmark.name(styles) {
+            destinations.push((name, location));
+        }
+
+        if bookmark.outlined(styles) {
+            bookmarks.push(Bookmark {
+                title: bookmark.title(),
+                level: bookmark.level(styles),
+                closed: bookmark.closed(styles),
+                location,
+            });
+        }
+    }
+
+    (bookmarks, destinations)
+}
+
+/// Collect the plain text of content, for use as a bookmark title or a
+/// figure's description.
+pub(super) fn plain_text(content: &Content, text: &mut EcoString) {
+    if let Some(elem) = content.to::<TextElem>() {
+        text.push_str(&elem.text());
+    } else if content.is::<SpaceElem>() || content.is::<LinebreakElem>() {
+        text.push(' ');
+    } else if let Some(children) = content.to_sequence() {
+        for child in children {
+            plain_text(child, text);
+        }
+    } else if let Some((child, _)) = content.to_styled() {
+        plain_text(child, text);
+    } else {
+        for (_, value) in content.fields() {
+            if let Value::Content(child) = value {
+                plain_text(&child, text);
+            }
+        }
+    }
+}
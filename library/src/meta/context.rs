@@ -1,4 +1,6 @@
+use crate::layout::PageElem;
 use crate::prelude::*;
+use crate::text::TextElem;
 
 /// Provides access to the location of content.
 ///
@@ -51,6 +53,28 @@ impl Show for LocateElem {
 /// In the future, the provided styles might also be directly accessed to look
 /// up styles defined by [set rules]($styling/#set-rules).
 ///
+/// If the function takes a second parameter, it also receives a dictionary
+/// with the effective values of commonly needed properties at that point:
+///
+/// - `text-size`: The resolved font size as a length.
+/// - `font`: The font family fallback list as an array of strings.
+/// - `lang`: The language code.
+/// - `dir`: The resolved text direction.
+/// - `page-width`, `page-height`: The page dimensions, taking `flipped` into
+///   account, or `{none}` if the page grows with its content.
+///
+/// To adapt to the space available in the current container (like a column)
+/// rather than the page, use [`layout`]($func/layout) instead.
+///
+/// ```example
+/// #let note(body) = style((_, ctx) => {
+///   text(ctx.text-size * 0.8, body)
+/// })
+///
+/// #set text(14pt)
+/// Main text #note[and a note]
+/// ```
+///
 /// Display: Style
 /// Category: meta
 /// Returns: content
@@ -80,6 +104,31 @@ struct StyleElem {
 
 impl Show for StyleElem {
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
-        Ok(self.func().call_vt(vt, [styles.to_map().into()])?.display())
+        let func = self.func();
+        let mut args = vec![styles.to_map().into()];
+        if func.argc() == Some(2) {
+            args.push(context(styles).into());
+        }
+
+        Ok(func.call_vt(vt, args)?.display())
+    }
+}
+
+/// Collect the effective values of commonly queried properties.
+fn context(styles: StyleChain) -> Dict {
+    let length = |v: Smart<Abs>| v.map_or(Value::None, |v| v.into());
+    let mut width = PageElem::width_in(styles);
+    let mut height = PageElem::height_in(styles);
+    if PageElem::flipped_in(styles) {
+        std::mem::swap(&mut width, &mut height);
+    }
+
+    dict! {
+        "text-size" => TextElem::size_in(styles),
+        "font" => TextElem::font_in(styles),
+        "lang" => Value::Str(TextElem::lang_in(styles).as_str().into()),
+        "dir" => TextElem::dir_in(styles),
+        "page-width" => length(width),
+        "page-height" => length(height),
     }
 }
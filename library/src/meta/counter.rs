@@ -408,8 +408,7 @@ impl Counter {
 
     /// The selector relevant for this counter's updates.
     fn selector(&self) -> Selector {
-        let mut selector =
-            Selector::Elem(UpdateElem::func(), Some(dict! { "counter" => self.clone() }));
+        let mut selector = self.update_selector();
 
         if let CounterKey::Selector(key) = &self.0 {
             selector = Selector::Any(eco_vec![selector, key.clone()]);
@@ -418,6 +417,11 @@ impl Counter {
         selector
     }
 
+    /// The selector for explicit updates of this counter.
+    pub(super) fn update_selector(&self) -> Selector {
+        Selector::Elem(UpdateElem::func(), Some(dict! { "counter" => self.clone() }))
+    }
+
     /// Whether this is the page counter.
     fn is_page(&self) -> bool {
         self.0 == CounterKey::Page
@@ -482,6 +486,8 @@ pub enum CounterUpdate {
     Set(CounterState),
     /// Increase the number for the given level by one.
     Step(NonZeroUsize),
+    /// Increase the number for the given level by the given amount.
+    Advance(NonZeroUsize, usize),
     /// Apply the given function to the counter's state.
     Func(Func),
 }
@@ -514,6 +520,14 @@ impl CounterState {
         match update {
             CounterUpdate::Set(state) => *self = state,
             CounterUpdate::Step(level) => self.step(level, 1),
+            CounterUpdate::Advance(level, by) => {
+                // Stepping by one first ensures that missing levels are
+                // created.
+                if by > 0 {
+                    self.step(level, 1);
+                    self.step(level, by - 1);
+                }
+            }
             CounterUpdate::Func(func) => {
                 *self = func
                     .call_vt(vt, self.0.iter().copied().map(Into::into))?
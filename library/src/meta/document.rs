@@ -1,3 +1,4 @@
+use super::{resolve_attachments, resolve_bookmarks};
 use crate::layout::{LayoutRoot, PageElem};
 use crate::prelude::*;
 
@@ -21,6 +22,61 @@ pub struct DocumentElem {
     /// The document's authors.
     pub author: Author,
 
+    /// A short description of the document's subject.
+    pub subject: Option<EcoString>,
+
+    /// The document's abstract, summarizing its content in a few sentences.
+    ///
+    /// The abstract is not embedded into the PDF file, but included in the
+    /// HTML outline that can be exported alongside it.
+    pub description: Option<EcoString>,
+
+    /// The document's keywords.
+    pub keywords: Keywords,
+
+    /// The document's creation date.
+    ///
+    /// The date is a dictionary with a `year` and optionally a `month`, a
+    /// `day`, and an `hour`, `minute`, and `second`. If no date is given, none
+    /// is embedded into the PDF.
+    ///
+    /// ```example
+    /// #set document(date: (year: 2023, month: 4, day: 18))
+    /// ```
+    pub date: Option<Date>,
+
+    /// Custom properties to embed into the document's XMP metadata.
+    ///
+    /// Each property is a dictionary with a `namespace` URI, a `prefix` for
+    /// that namespace, the property's `name`, and its string `value`. This
+    /// allows to attach metadata from schemas beyond the standard ones, for
+    /// example Dublin Core extensions or company-specific schemas.
+    ///
+    /// ```example
+    /// #set document(xmp: (
+    ///   (
+    ///     namespace: "http://ns.example.com/project/1.0/",
+    ///     prefix: "project",
+    ///     name: "codename",
+    ///     value: "Apollo",
+    ///   ),
+    /// ))
+    /// ```
+    pub xmp: Vec<XmpProperty>,
+
+    /// Whether to mark up paragraphs, headings, lists, figures, and links
+    /// while laying out the document, so that it can be exported as a tagged
+    /// PDF with a structure tree for screen readers and reflow.
+    ///
+    /// This is off by default because marking up every paragraph slows down
+    /// layout. Exporting with `--tagged` turns it on.
+    ///
+    /// ```example
+    /// #set document(tagged: true)
+    /// ```
+    #[default(false)]
+    pub tagged: bool,
+
     /// The page runs.
     #[internal]
     #[variadic]
@@ -41,17 +97,29 @@ impl LayoutRoot for DocumentElem {
             }
 
             if let Some(page) = child.to::<PageElem>() {
-                let fragment = page.layout(vt, styles)?;
+                let number = NonZeroUsize::ONE.saturating_add(pages.len());
+                let fragment = page.layout(vt, styles, number)?;
                 pages.extend(fragment);
             } else {
                 bail!(child.span(), "unexpected document child");
             }
         }
 
+        let (bookmarks, destinations) = resolve_bookmarks(vt.introspector);
+        let attachments = resolve_attachments(vt)?;
+
         Ok(Document {
             pages,
             title: self.title(styles),
             author: self.author(styles).0,
+            subject: self.subject(styles),
+            description: self.description(styles),
+            keywords: self.keywords(styles).0,
+            date: self.date(styles),
+            bookmarks,
+            destinations,
+            xmp: self.xmp(styles),
+            attachments,
         })
     }
 }
@@ -69,3 +137,17 @@ cast_from_value! {
 cast_to_value! {
     v: Author => v.0.into()
 }
+
+/// A list of keywords.
+#[derive(Debug, Default, Clone, Hash)]
+pub struct Keywords(Vec<EcoString>);
+
+cast_from_value! {
+    Keywords,
+    v: EcoString => Self(vec![v]),
+    v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
+}
+
+cast_to_value! {
+    v: Keywords => v.0.into()
+}
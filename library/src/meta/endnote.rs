@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use ecow::eco_vec;
+
+use super::{Counter, HeadingElem, LocalName, Numbering, NumberingPattern};
+use crate::layout::ParbreakElem;
+use crate::prelude::*;
+use crate::text::{SpaceElem, SuperElem, TextElem};
+
+/// A note whose body is collected and shown at the end of a chapter or the
+/// document.
+///
+/// In the text, the note is marked with its number. The note's body appears
+/// in the next [list of endnotes]($func/endnotes). The mark links to the body
+/// and the body's number links back to the mark.
+///
+/// ## Example
+/// ```example
+/// #set page(height: auto)
+///
+/// Typst was started in
+/// 2019#endnote[In Berlin.] and
+/// released in 2023.#endnote[As open
+/// source software.]
+///
+/// #endnotes()
+/// ```
+///
+/// Display: Endnote
+/// Category: meta
+#[element(Locatable, Synthesize, Show)]
+pub struct EndnoteElem {
+    /// The content of the note.
+    #[required]
+    pub body: Content,
+
+    /// How to number the note. Accepts a
+    /// [numbering pattern or function]($func/numbering).
+    ///
+    /// ```example
+    /// #set page(height: auto)
+    /// #set endnote(numbering: "i")
+    ///
+    /// Marked.#endnote[A note.]
+    /// #endnotes(title: none)
+    /// ```
+    #[default(NumberingPattern::from_str("1").unwrap().into())]
+    pub numbering: Numbering,
+}
+
+impl EndnoteElem {
+    /// The location of the note's body in the list of endnotes.
+    fn body_location(&self) -> Location {
+        self.0.location().unwrap().variant(1)
+    }
+
+    /// The note's number.
+    fn number(&self, vt: &mut Vt) -> SourceResult<Content> {
+        let location = self.0.location().unwrap();
+        Counter::of(Self::func())
+            .at(vt, location)?
+            .display(vt, &self.numbering(StyleChain::default()))
+    }
+}
+
+impl Synthesize for EndnoteElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        self.push_numbering(self.numbering(styles));
+    }
+}
+
+impl Show for EndnoteElem {
+    fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        if !vt.introspector.init() {
+            return Ok(Content::empty());
+        }
+
+        let number = self.number(vt)?;
+        Ok(SuperElem::new(number)
+            .pack()
+            .linked(Destination::Location(self.body_location())))
+    }
+}
+
+/// The bodies of endnotes.
+///
+/// Lists the bodies of all [endnotes]($func/endnote) since the previous list
+/// of endnotes. To show the notes at the end of each chapter, place a list at
+/// the end of each chapter. To show all notes at the end of the document,
+/// place a single list there. Notes after the last list are not shown.
+///
+/// ## Example
+/// ```example
+/// #set page(height: auto)
+///
+/// = Introduction
+/// Some text.#endnote[Introductory.]
+/// #endnotes(title: [Notes to the introduction])
+///
+/// = Conclusion
+/// More text.#endnote[Concluding.]
+/// #endnotes(title: [Notes to the conclusion])
+/// ```
+///
+/// Display: Endnotes
+/// Category: meta
+#[element(Locatable, Show, LocalName)]
+pub struct EndnotesElem {
+    /// The title of the list.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the [text
+    ///   language]($func/text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the list will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+}
+
+impl Show for EndnotesElem {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles) {
+            let title = title.clone().unwrap_or_else(|| {
+                TextElem::packed(self.local_name(TextElem::lang_in(styles)))
+                    .spanned(self.span())
+            });
+
+            seq.push(
+                HeadingElem::new(title)
+                    .with_level(NonZeroUsize::ONE)
+                    .with_numbering(None)
+                    .pack(),
+            );
+        }
+
+        if !vt.introspector.init() {
+            return Ok(Content::sequence(seq));
+        }
+
+        // Find the notes since the previous list of endnotes.
+        let location = self.0.location().unwrap();
+        let selector =
+            Selector::Any(eco_vec![EndnoteElem::func().select(), Self::func().select()]);
+
+        let mut notes = vec![];
+        for elem in vt.introspector.query_before(selector, location) {
+            if elem.location() == Some(location) {
+                break;
+            } else if elem.func() == Self::func() {
+                notes.clear();
+            } else {
+                notes.push(elem.to::<EndnoteElem>().unwrap().clone());
+            }
+        }
+
+        for note in notes {
+            // Make the link from the mark to here work.
+            let backlink = {
+                let mut content = Content::empty();
+                content.set_location(note.body_location());
+                MetaElem::set_data(vec![Meta::Elem(content)])
+            };
+
+            let mark = note.0.location().unwrap();
+            let number = note.number(vt)?.linked(Destination::Location(mark));
+            seq.push((number + SpaceElem::new().pack() + note.body()).styled(backlink));
+            seq.push(ParbreakElem::new().pack());
+        }
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl LocalName for EndnotesElem {
+    fn local_name(&self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::GERMAN => "Anmerkungen",
+            Lang::ENGLISH | _ => "Notes",
+        }
+    }
+}
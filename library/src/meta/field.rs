@@ -0,0 +1,106 @@
+use typst::doc::{FormField, FormFieldKind};
+
+use crate::layout::{BoxElem, Sizing};
+use crate::prelude::*;
+
+/// An interactive form field that readers can fill in.
+///
+/// In the exported PDF, each field becomes part of an interactive form. PDF
+/// viewers let readers type into text fields, toggle checkboxes, and sign
+/// signature fields, and can save or submit the values under the fields'
+/// names. In other export formats, only the field's outline is visible.
+///
+/// PDF/A files can't contain fields whose appearance is left to the viewer, so
+/// fields are exported as plain outlines in that mode.
+///
+/// ## Example
+/// ```example
+/// Name: #field("name") \
+/// Address: #field("address", multiline: true) \
+/// #field("newsletter", kind: "checkbox", checked: true)
+/// Subscribe to the newsletter
+///
+/// #field("signature", kind: "signature")
+/// ```
+///
+/// Display: Field
+/// Category: meta
+#[element(Show)]
+pub struct FieldElem {
+    /// The name that identifies the field's value when the form is saved or
+    /// submitted. Names should be unique within the document.
+    #[required]
+    pub name: EcoString,
+
+    /// What kind of field to create.
+    #[default(FieldKind::Text)]
+    pub kind: FieldKind,
+
+    /// The initial value of a text field.
+    pub value: EcoString,
+
+    /// Whether a checkbox is initially checked.
+    #[default(false)]
+    pub checked: bool,
+
+    /// Whether a text field accepts multiple lines of text.
+    #[default(false)]
+    pub multiline: bool,
+
+    /// A description shown when hovering over the field.
+    pub tooltip: Option<EcoString>,
+
+    /// The width of the field. Defaults to a size that suits the field's kind.
+    pub width: Smart<Rel<Length>>,
+
+    /// The height of the field. Defaults to a size that suits the field's kind.
+    pub height: Smart<Rel<Length>>,
+
+    /// How to stroke the field's outline.
+    #[default(Some(PartialStroke::default()))]
+    pub stroke: Option<PartialStroke>,
+}
+
+impl Show for FieldElem {
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let multiline = self.multiline(styles);
+        let (kind, width, height) = match self.kind(styles) {
+            FieldKind::Text => (
+                FormFieldKind::Text { value: self.value(styles), multiline },
+                10.0,
+                if multiline { 4.0 } else { 1.4 },
+            ),
+            FieldKind::Checkbox => {
+                (FormFieldKind::Checkbox { checked: self.checked(styles) }, 0.8, 0.8)
+            }
+            FieldKind::Signature => (FormFieldKind::Signature, 12.0, 3.0),
+        };
+
+        let default = |v: f64| Rel::from(Length::from(Em::new(v)));
+        let width = self.width(styles).unwrap_or(default(width));
+        let height = self.height(styles).unwrap_or(default(height));
+        let field = FormField {
+            name: self.name(),
+            kind,
+            tooltip: self.tooltip(styles),
+        };
+
+        Ok(BoxElem::new()
+            .with_width(Sizing::Rel(width))
+            .with_height(Smart::Custom(height))
+            .with_stroke(Sides::splat(Some(self.stroke(styles))))
+            .pack()
+            .styled(MetaElem::set_data(vec![Meta::FormField(field)])))
+    }
+}
+
+/// The kind of a form field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FieldKind {
+    /// A text input.
+    Text,
+    /// A checkbox that is either checked or not.
+    Checkbox,
+    /// A placeholder to sign the document digitally.
+    Signature,
+}
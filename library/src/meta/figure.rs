@@ -1,12 +1,25 @@
 use std::str::FromStr;
 
-use super::{Count, Counter, CounterUpdate, LocalName, Numbering, NumberingPattern};
+use ecow::eco_vec;
+
+use super::bookmark::plain_text;
+use super::{
+    Count, Counter, CounterKey, CounterUpdate, DocumentElem, LocalName, Numbering,
+    NumberingPattern,
+};
 use crate::layout::{BlockElem, VElem};
 use crate::prelude::*;
 use crate::text::TextElem;
 
 /// A figure with an optional caption.
 ///
+/// By default, all figures share one counter. Tables and code listings can be
+/// numbered separately by setting the figure's kind.
+///
+/// Figures nested in the body of another figure become its sub-figures. They
+/// are labelled with letters and referencing them yields the parent's number
+/// followed by the letter.
+///
 /// ## Example
 /// ```example
 /// = Pipeline
@@ -32,62 +45,280 @@ pub struct FigureElem {
     /// The figure's caption.
     pub caption: Option<Content>,
 
+    /// A description of the figure for readers who can't see it.
+    ///
+    /// When the document is exported as a tagged PDF, screen readers announce
+    /// this instead of the figure's body. Defaults to the text of the caption.
+    ///
+    /// ```example
+    /// #figure(
+    ///   image("molecular.jpg", width: 80%),
+    ///   alt: "A flow chart with four steps.",
+    /// )
+    /// ```
+    pub alt: Option<EcoString>,
+
+    /// Whether to place the caption above or below the body.
+    ///
+    /// ```example
+    /// #figure(
+    ///   table(columns: 2)[A][B][C][D],
+    ///   caption: [A small table.],
+    ///   caption-pos: top,
+    /// )
+    /// ```
+    #[default(CaptionPos::Bottom)]
+    pub caption_pos: CaptionPos,
+
+    /// The kind of the figure. Figures of the same kind share a counter.
+    ///
+    /// When set to `{auto}`, the figure is a plain figure.
+    ///
+    /// ```example
+    /// #figure(
+    ///   table(columns: 2)[A][B],
+    ///   caption: [A small table.],
+    ///   kind: "table",
+    /// )
+    /// ```
+    pub kind: Smart<FigureKind>,
+
+    /// The name of the figure's kind, like "Figure" or "Table".
+    ///
+    /// When set to `{auto}`, a name suitable for the figure's kind in the
+    /// text's language is used.
+    pub supplement: Smart<Content>,
+
     /// How to number the figure. Accepts a
     /// [numbering pattern or function]($func/numbering).
     #[default(Some(NumberingPattern::from_str("1").unwrap().into()))]
     pub numbering: Option<Numbering>,
 
+    /// How to label sub-figures. Accepts a
+    /// [numbering pattern or function]($func/numbering).
+    ///
+    /// ```example
+    /// #figure(
+    ///   grid(
+    ///     columns: 2,
+    ///     gutter: 1em,
+    ///     figure(rect(), caption: [Left]),
+    ///     figure(circle(), caption: [Right]) <right>,
+    ///   ),
+    ///   caption: [Two shapes],
+    /// )
+    ///
+    /// The circle is in @right.
+    /// ```
+    #[default(Some(NumberingPattern::from_str("(a)").unwrap().into()))]
+    pub sub_numbering: Option<Numbering>,
+
     /// The vertical gap between the body and caption.
     #[default(Em::new(0.65).into())]
     pub gap: Length,
+
+    /// The kind of the figure this one is nested in, if any.
+    #[internal]
+    pub parent: Option<FigureKind>,
+}
+
+impl FigureElem {
+    /// The counter shared by all figures of this figure's kind and their
+    /// sub-figures. Updates of `{counter(figure)}` apply to plain figures.
+    pub fn counter(&self) -> Counter {
+        let kind = self.counted_kind();
+        let mut selectors = eco_vec![
+            Selector::Elem(
+                Self::func(),
+                Some(dict! { "kind" => kind, "parent" => Value::None }),
+            ),
+            Selector::Elem(Self::func(), Some(dict! { "parent" => kind })),
+        ];
+
+        if kind == FigureKind::Figure {
+            selectors.push(Counter::of(Self::func()).update_selector());
+        }
+
+        Counter::new(CounterKey::Selector(Selector::Any(selectors)))
+    }
+
+    /// The resolved supplement of the figure.
+    pub fn resolved_supplement(&self, styles: StyleChain) -> Content {
+        match self.supplement(styles) {
+            Smart::Auto => TextElem::packed(self.local_name(TextElem::lang_in(styles))),
+            Smart::Custom(supplement) => supplement,
+        }
+    }
+
+    /// The figure's number as it appears in references. For sub-figures, the
+    /// parent's number is followed by the sub-figure's label.
+    pub fn ref_number(&self, vt: &mut Vt) -> SourceResult<Content> {
+        let styles = StyleChain::default();
+        let state = self.counter().at(vt, self.0.location().unwrap())?;
+        let Some(numbering) = self.numbering(styles) else {
+            return Ok(Content::empty());
+        };
+
+        if self.parent(styles).is_none() {
+            return state.display(vt, &numbering.trimmed());
+        }
+
+        let [parent, sub] = state.0.as_slice() else {
+            return Ok(Content::empty());
+        };
+
+        let mut number = numbering.trimmed().apply_vt(vt, &[*parent])?.display();
+        if let Some(sub_numbering) = self.sub_numbering(styles) {
+            number += sub_numbering.trimmed().apply_vt(vt, &[*sub])?.display();
+        }
+
+        Ok(number)
+    }
+
+    /// The kind whose counter the figure is counted with.
+    fn counted_kind(&self) -> FigureKind {
+        let styles = StyleChain::default();
+        self.parent(styles)
+            .unwrap_or_else(|| self.kind(styles).unwrap_or(FigureKind::Figure))
+    }
+
+    /// The letter of a sub-figure, displayed in front of its caption.
+    fn sub_label(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+    ) -> SourceResult<Option<Content>> {
+        if !vt.introspector.init() || self.numbering(styles).is_none() {
+            return Ok(None);
+        }
+
+        let Some(sub_numbering) = self.sub_numbering(styles) else {
+            return Ok(None);
+        };
+
+        let state = self.counter().at(vt, self.0.location().unwrap())?;
+        let Some(&sub) = state.0.get(1) else { return Ok(None) };
+        Ok(Some(sub_numbering.apply_vt(vt, &[sub])?.display()))
+    }
+
+    /// Wrap realized content into an unbreakable, centered block.
+    fn pack_block(&self, realized: Content, styles: StyleChain) -> Content {
+        let mut realized = BlockElem::new()
+            .with_body(Some(realized))
+            .with_breakable(false)
+            .pack();
+
+        if DocumentElem::tagged_in(styles) {
+            let alt = self.alt(styles).or_else(|| {
+                let mut text = EcoString::new();
+                plain_text(&self.caption(styles)?, &mut text);
+                let text: EcoString = text.trim().into();
+                (!text.is_empty()).then_some(text)
+            });
+
+            let tag = Tag::new(Role::Figure, self.0.location().unwrap()).with_alt(alt);
+            realized = realized.tagged(tag);
+        }
+
+        realized.aligned(Axes::with_x(Some(Align::Center.into())))
+    }
 }
 
 impl Synthesize for FigureElem {
     fn synthesize(&mut self, styles: StyleChain) {
+        let kind = self.kind(styles).unwrap_or(FigureKind::Figure);
+        self.push_kind(Smart::Custom(kind));
+        self.push_supplement(Smart::Custom(self.resolved_supplement(styles)));
         self.push_numbering(self.numbering(styles));
+        self.push_sub_numbering(self.sub_numbering(styles));
+        self.push_parent(self.parent(styles));
     }
 }
 
 impl Show for FigureElem {
-    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
-        let mut realized = self.body();
-
-        if let Some(mut caption) = self.caption(styles) {
-            if let Some(numbering) = self.numbering(styles) {
-                let name = self.local_name(TextElem::lang_in(styles));
-                caption = TextElem::packed(eco_format!("{name}\u{a0}"))
-                    + Counter::of(Self::func())
-                        .display(Some(numbering), false)
-                        .spanned(self.span())
-                    + TextElem::packed(": ")
-                    + caption;
-            }
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let body = self.body().styled(Self::set_parent(Some(self.counted_kind())));
+
+        let Some(mut caption) = self.caption(styles) else {
+            return Ok(self.pack_block(body, styles));
+        };
 
-            realized += VElem::weak(self.gap(styles).into()).pack();
-            realized += caption;
+        if self.parent(styles).is_some() {
+            if let Some(label) = self.sub_label(vt, styles)? {
+                caption = label + TextElem::packed(' ') + caption;
+            }
+        } else if let Some(numbering) = self.numbering(styles) {
+            caption = self.resolved_supplement(styles)
+                + TextElem::packed('\u{a0}')
+                + self.counter().display(Some(numbering), false).spanned(self.span())
+                + TextElem::packed(": ")
+                + caption;
         }
 
-        Ok(BlockElem::new()
-            .with_body(Some(realized))
-            .with_breakable(false)
-            .pack()
-            .aligned(Axes::with_x(Some(Align::Center.into()))))
+        let gap = VElem::weak(self.gap(styles).into()).pack();
+        let realized = match self.caption_pos(styles) {
+            CaptionPos::Top => caption + gap + body,
+            CaptionPos::Bottom => body + gap + caption,
+        };
+
+        Ok(self.pack_block(realized, styles))
     }
 }
 
 impl Count for FigureElem {
     fn update(&self) -> Option<CounterUpdate> {
-        self.numbering(StyleChain::default())
-            .is_some()
-            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        let styles = StyleChain::default();
+        self.numbering(styles).is_some().then(|| {
+            let level = if self.parent(styles).is_some() { 2 } else { 1 };
+            CounterUpdate::Step(NonZeroUsize::new(level).unwrap())
+        })
     }
 }
 
 impl LocalName for FigureElem {
     fn local_name(&self, lang: Lang) -> &'static str {
-        match lang {
-            Lang::GERMAN => "Abbildung",
-            Lang::ENGLISH | _ => "Figure",
+        match (self.kind(StyleChain::default()), lang) {
+            (Smart::Custom(FigureKind::Table), Lang::GERMAN) => "Tabelle",
+            (Smart::Custom(FigureKind::Table), Lang::ENGLISH | _) => "Table",
+            (Smart::Custom(FigureKind::Listing), _) => "Listing",
+            (_, Lang::GERMAN) => "Abbildung",
+            (_, Lang::ENGLISH | _) => "Figure",
         }
     }
 }
+
+/// The kind of a figure.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FigureKind {
+    /// An image or other illustration.
+    Figure,
+    /// A table.
+    Table,
+    /// A code listing.
+    Listing,
+}
+
+/// Where a figure's caption is placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CaptionPos {
+    /// Above the figure's body.
+    Top,
+    /// Below the figure's body.
+    Bottom,
+}
+
+cast_from_value! {
+    CaptionPos,
+    align: GenAlign => match align {
+        GenAlign::Specific(Align::Top) => Self::Top,
+        GenAlign::Specific(Align::Bottom) => Self::Bottom,
+        _ => Err("expected either `top` or `bottom`")?,
+    },
+}
+
+cast_to_value! {
+    v: CaptionPos => GenAlign::Specific(match v {
+        CaptionPos::Top => Align::Top,
+        CaptionPos::Bottom => Align::Bottom,
+    }).into()
+}
@@ -0,0 +1,120 @@
+use super::outline::push_entry;
+use super::{FigureElem, FigureKind, HeadingElem, LocalName};
+use crate::layout::{ParbreakElem, RepeatElem};
+use crate::prelude::*;
+use crate::text::{SpaceElem, TextElem};
+
+/// A list of figures, tables, or listings.
+///
+/// This function lists all captioned figures of one kind in the document with
+/// their numbers, captions, and page numbers, like the
+/// [outline]($func/outline) does for headings. Sub-figures are not listed.
+///
+/// ## Example
+/// ```example
+/// #figures()
+/// #figures(kind: "table")
+///
+/// #figure(
+///   rect(),
+///   caption: [A rectangle.],
+/// )
+///
+/// #figure(
+///   table(columns: 2)[A][B],
+///   caption: [A tiny table.],
+///   kind: "table",
+/// )
+/// ```
+///
+/// Display: List of Figures
+/// Category: meta
+#[element(Synthesize, Show, LocalName)]
+pub struct FiguresElem {
+    /// The title of the list.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the kind of figures
+    ///   and the [text language]($func/text.lang) will be used. This is the
+    ///   default.
+    /// - When set to `{none}`, the list will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+
+    /// The kind of figures to list. Either `{"figure"}`, `{"table"}`, or
+    /// `{"listing"}`.
+    #[default(FigureKind::Figure)]
+    pub kind: FigureKind,
+
+    /// Content to fill the space between the caption and the page number. Can
+    /// be set to `none` to disable filling. The default is `{repeat[.]}`.
+    #[default(Some(RepeatElem::new(TextElem::packed(".")).pack()))]
+    pub fill: Option<Content>,
+}
+
+impl Synthesize for FiguresElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        self.push_kind(self.kind(styles));
+    }
+}
+
+impl Show for FiguresElem {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles) {
+            let title = title.clone().unwrap_or_else(|| {
+                TextElem::packed(self.local_name(TextElem::lang_in(styles)))
+                    .spanned(self.span())
+            });
+
+            seq.push(
+                HeadingElem::new(title)
+                    .with_level(NonZeroUsize::ONE)
+                    .with_numbering(None)
+                    .with_outlined(false)
+                    .pack(),
+            );
+        }
+
+        let elems = vt.introspector.query(Selector::Elem(
+            FigureElem::func(),
+            Some(dict! { "kind" => self.kind(styles), "parent" => Value::None }),
+        ));
+
+        for elem in &elems {
+            let figure = elem.to::<FigureElem>().unwrap();
+            let location = figure.0.location().unwrap();
+            let Some(caption) = figure.caption(StyleChain::default()) else {
+                continue;
+            };
+
+            // Format the numbering.
+            let mut start = caption;
+            if let Some(numbering) = figure.numbering(StyleChain::default()) {
+                let numbers = figure
+                    .counter()
+                    .at(vt, location)?
+                    .display(vt, &numbering.trimmed())?;
+                start = numbers + SpaceElem::new().pack() + start;
+            }
+
+            push_entry(vt, &mut seq, start, location, self.fill(styles));
+        }
+
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl LocalName for FiguresElem {
+    fn local_name(&self, lang: Lang) -> &'static str {
+        match (self.kind(StyleChain::default()), lang) {
+            (FigureKind::Figure, Lang::GERMAN) => "Abbildungsverzeichnis",
+            (FigureKind::Figure, Lang::ENGLISH | _) => "List of Figures",
+            (FigureKind::Table, Lang::GERMAN) => "Tabellenverzeichnis",
+            (FigureKind::Table, Lang::ENGLISH | _) => "List of Tables",
+            (FigureKind::Listing, _) => "List of Listings",
+        }
+    }
+}
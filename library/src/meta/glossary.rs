@@ -0,0 +1,324 @@
+use super::{HeadingElem, LocalName};
+use crate::layout::{BlockElem, GridElem, Sizing, TrackSizings, VElem};
+use crate::prelude::*;
+use crate::text::{StrongElem, TextElem};
+
+/// A glossary of terms and acronyms.
+///
+/// Each entry defines a term through a unique key, a short form and a long
+/// form. Once you have added a glossary somewhere in your document, you can
+/// use its terms with the [term]($func/term) function. The first use of a term
+/// spells it out in full, later uses only show the short form.
+///
+/// The glossary lists all its entries sorted by their short forms, together
+/// with the pages on which they are used.
+///
+/// ## Example
+/// ```example
+/// #set page(height: auto)
+///
+/// Typst exports to #term("pdf").
+/// Every #term("pdf") file can be
+/// printed.
+///
+/// #glossary(
+///   (
+///     key: "pdf",
+///     short: "PDF",
+///     long: [Portable Document Format],
+///   ),
+///   (
+///     key: "svg",
+///     short: "SVG",
+///     long: [Scalable Vector Graphics],
+///     description: [A format for vector images.],
+///   ),
+/// )
+/// ```
+///
+/// Display: Glossary
+/// Category: meta
+#[element(Locatable, Show, LocalName)]
+pub struct GlossaryElem {
+    /// The glossary's entries.
+    ///
+    /// Each entry is a dictionary with the keys `key`, `short`, and `long`,
+    /// and optionally a `description`.
+    #[variadic]
+    pub entries: Vec<GlossaryEntry>,
+
+    /// The title of the glossary.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the [text
+    ///   language]($func/text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the glossary will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+}
+
+impl GlossaryElem {
+    /// Find the document's glossary.
+    pub fn find(introspector: Tracked<Introspector>) -> StrResult<Self> {
+        let mut iter = introspector.query(Self::func().select()).into_iter();
+        let Some(elem) = iter.next() else {
+            return Err("the document does not contain a glossary".into());
+        };
+
+        if iter.next().is_some() {
+            Err("multiple glossaries are not supported")?;
+        }
+
+        Ok(elem.to::<Self>().unwrap().clone())
+    }
+
+    /// Find the entry with the given key.
+    pub fn entry(&self, key: &str) -> Option<GlossaryEntry> {
+        self.entries().into_iter().find(|entry| entry.key == key)
+    }
+}
+
+impl Show for GlossaryElem {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        const COLUMN_GUTTER: Em = Em::new(0.65);
+
+        let mut seq = vec![];
+        if let Some(title) = self.title(styles) {
+            let title = title.clone().unwrap_or_else(|| {
+                TextElem::packed(self.local_name(TextElem::lang_in(styles)))
+                    .spanned(self.span())
+            });
+
+            seq.push(
+                HeadingElem::new(title)
+                    .with_level(NonZeroUsize::ONE)
+                    .with_numbering(None)
+                    .pack(),
+            );
+        }
+
+        let mut entries = self.entries();
+        for (i, entry) in entries.iter().enumerate() {
+            if entries[..i].iter().any(|prev| prev.key == entry.key) {
+                bail!(self.span(), "duplicate glossary key: {}", entry.key);
+            }
+        }
+
+        if !vt.introspector.init() {
+            return Ok(Content::sequence(seq));
+        }
+
+        entries.sort_by_cached_key(|entry| entry.short.to_lowercase());
+
+        let mut cells = vec![];
+        for entry in entries {
+            let mut body = entry.long;
+            if let Some(description) = entry.description {
+                body += TextElem::packed(": ") + description;
+            }
+
+            cells.push(StrongElem::new(TextElem::packed(entry.short)).pack());
+            cells.push(body);
+            cells.push(back_references(vt, &entry.key));
+        }
+
+        let row_gutter = BlockElem::below_in(styles).amount();
+        seq.push(VElem::new(row_gutter).with_weakness(3).pack());
+        seq.push(
+            GridElem::new(cells)
+                .with_columns(TrackSizings(vec![
+                    Sizing::Auto,
+                    Sizing::Fr(Fr::one()),
+                    Sizing::Auto,
+                ]))
+                .with_column_gutter(TrackSizings(vec![COLUMN_GUTTER.into()]))
+                .with_row_gutter(TrackSizings(vec![row_gutter.into()]))
+                .pack(),
+        );
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl LocalName for GlossaryElem {
+    fn local_name(&self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::GERMAN => "Glossar",
+            Lang::ENGLISH | _ => "Glossary",
+        }
+    }
+}
+
+/// The page numbers on which a term is used, each linking to the term's first
+/// use on that page.
+fn back_references(vt: &Vt, key: &str) -> Content {
+    let mut pages: Vec<(NonZeroUsize, Location)> = vec![];
+    for elem in vt.introspector.query(TermElem::select_key(key)) {
+        let location = elem.location().unwrap();
+        let page = vt.introspector.page(location);
+        if !pages.iter().any(|&(prev, _)| prev == page) {
+            pages.push((page, location));
+        }
+    }
+
+    let mut seq = vec![];
+    for (i, (page, location)) in pages.into_iter().enumerate() {
+        if i > 0 {
+            seq.push(TextElem::packed(", "));
+        }
+        seq.push(
+            TextElem::packed(eco_format!("{page}"))
+                .linked(Destination::Location(location)),
+        );
+    }
+
+    Content::sequence(seq)
+}
+
+/// An entry of a glossary.
+#[derive(Debug, Clone, Hash)]
+pub struct GlossaryEntry {
+    /// The key with which the term is used.
+    pub key: EcoString,
+    /// The short form of the term, like an acronym.
+    pub short: EcoString,
+    /// The long form of the term.
+    pub long: Content,
+    /// An optional explanation of the term.
+    pub description: Option<Content>,
+}
+
+cast_from_value! {
+    GlossaryEntry,
+    mut dict: Dict => {
+        let key = dict.take("key")?.cast()?;
+        let short = dict.take("short")?.cast()?;
+        let long = dict.take("long")?.cast()?;
+        let description = dict.take("description").ok().map(Value::cast).transpose()?;
+        dict.finish(&["key", "short", "long", "description"])?;
+        Self { key, short, long, description }
+    },
+}
+
+cast_to_value! {
+    v: GlossaryEntry => Value::Dict(dict! {
+        "key" => v.key,
+        "short" => v.short,
+        "long" => v.long,
+        "description" => v.description,
+    })
+}
+
+/// Use a term from the glossary.
+///
+/// Before you start using terms, you need to add a
+/// [glossary]($func/glossary) somewhere in your document. Each term links to
+/// its entry in the glossary.
+///
+/// ## Example
+/// ```example
+/// #set page(height: auto)
+///
+/// The #term("cpu") executes
+/// instructions. Modern #term("cpu")s
+/// have many cores.
+///
+/// #glossary(
+///   title: none,
+///   (
+///     key: "cpu",
+///     short: "CPU",
+///     long: [central processing unit],
+///   ),
+/// )
+/// ```
+///
+/// Display: Term
+/// Category: meta
+#[element(Locatable, Show)]
+pub struct TermElem {
+    /// The key of the term in the glossary.
+    #[required]
+    pub key: EcoString,
+
+    /// Which form of the term to display.
+    ///
+    /// When set to `{auto}`, the first use of a term in the document shows
+    /// the full form and all further uses show the short form.
+    ///
+    /// ```example
+    /// #set page(height: auto)
+    ///
+    /// #term("gpu", form: "short") and
+    /// #term("gpu", form: "long").
+    ///
+    /// #glossary(
+    ///   title: none,
+    ///   (
+    ///     key: "gpu",
+    ///     short: "GPU",
+    ///     long: [graphics processing unit],
+    ///   ),
+    /// )
+    /// ```
+    pub form: Smart<TermForm>,
+}
+
+impl TermElem {
+    /// A selector for all uses of the term with the given key.
+    fn select_key(key: &str) -> Selector {
+        Selector::Elem(Self::func(), Some(dict! { "key" => key }))
+    }
+
+    /// Whether this is the first use of the term in the document.
+    fn is_first(&self, vt: &Vt) -> bool {
+        let location = self.0.location().unwrap();
+        let uses = vt.introspector.query_before(Self::select_key(&self.key()), location);
+        uses.first().and_then(Content::location) == Some(location)
+    }
+}
+
+impl Show for TermElem {
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        if !vt.introspector.init() {
+            return Ok(Content::empty());
+        }
+
+        let glossary = GlossaryElem::find(vt.introspector).at(self.span())?;
+        let entry = glossary
+            .entry(&self.key())
+            .ok_or("glossary does not contain this key")
+            .at(self.span())?;
+
+        let form = self.form(styles).unwrap_or_else(|| {
+            if self.is_first(vt) {
+                TermForm::Full
+            } else {
+                TermForm::Short
+            }
+        });
+
+        let short = TextElem::packed(entry.short);
+        let realized = match form {
+            TermForm::Short => short,
+            TermForm::Long => entry.long,
+            TermForm::Full => {
+                entry.long + TextElem::packed(" (") + short + TextElem::packed(')')
+            }
+        };
+
+        let location = glossary.0.location().unwrap();
+        Ok(realized.linked(Destination::Location(location)))
+    }
+}
+
+/// In which form a term is displayed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum TermForm {
+    /// Only the short form, like "PDF".
+    Short,
+    /// Only the long form, like "Portable Document Format".
+    Long,
+    /// The long form followed by the short form in parentheses.
+    Full,
+}
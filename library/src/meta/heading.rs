@@ -1,6 +1,6 @@
 use typst::font::FontWeight;
 
-use super::{Counter, CounterUpdate, LocalName, Numbering};
+use super::{Counter, CounterUpdate, DocumentElem, LocalName, Numbering};
 use crate::layout::{BlockElem, HElem, VElem};
 use crate::meta::Count;
 use crate::prelude::*;
@@ -71,9 +71,24 @@ pub struct HeadingElem {
     /// This heading does not appear
     /// in the outline.
     /// ```
+    ///
+    /// [Hidden]($func/hide) headings are never outlined.
     #[default(true)]
     pub outlined: bool,
 
+    /// Whether the heading should appear as a bookmark in the outline panel
+    /// of PDF viewers.
+    ///
+    /// When set to `{auto}`, the heading appears there if it appears in the
+    /// [outline]($func/outline) as determined by `outlined`.
+    ///
+    /// ```example
+    /// #heading(bookmarked: false)[Not bookmarked]
+    /// #heading(outlined: false, bookmarked: true)[Only bookmarked]
+    /// ```
+    #[default(Smart::Auto)]
+    pub bookmarked: Smart<bool>,
+
     /// The heading's title.
     #[required]
     pub body: Content,
@@ -83,7 +98,16 @@ impl Synthesize for HeadingElem {
     fn synthesize(&mut self, styles: StyleChain) {
         self.push_level(self.level(styles));
         self.push_numbering(self.numbering(styles));
-        self.push_outlined(self.outlined(styles));
+
+        // Hidden headings can still be counted and referenced, but they don't
+        // show up in the outline or the bookmarks.
+        if MetaElem::data_in(styles).contains(&Meta::Hide) {
+            self.push_outlined(false);
+            self.push_bookmarked(Smart::Custom(false));
+        } else {
+            self.push_outlined(self.outlined(styles));
+            self.push_bookmarked(self.bookmarked(styles));
+        }
     }
 }
 
@@ -97,7 +121,12 @@ impl Show for HeadingElem {
                 + HElem::new(Em::new(0.3).into()).with_weak(true).pack()
                 + realized;
         }
-        Ok(BlockElem::new().with_body(Some(realized)).pack())
+        let mut realized = BlockElem::new().with_body(Some(realized)).pack();
+        if DocumentElem::tagged_in(styles) {
+            let role = Role::Heading(self.level(styles));
+            realized = realized.tagged(Tag::new(role, self.0.location().unwrap()));
+        }
+        Ok(realized)
     }
 }
 
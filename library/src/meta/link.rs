@@ -1,3 +1,6 @@
+use typst::util::hash128;
+
+use super::DocumentElem;
 use crate::prelude::*;
 use crate::text::{Hyphenate, TextElem};
 
@@ -78,8 +81,13 @@ impl LinkElem {
 }
 
 impl Show for LinkElem {
-    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
-        Ok(self.body())
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        if !DocumentElem::tagged_in(styles) {
+            return Ok(self.body());
+        }
+
+        let tag = Tag::new(Role::Link, vt.provider.locate(hash128(&self.0)));
+        Ok(self.body().tagged(tag))
     }
 }
 
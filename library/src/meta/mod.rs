@@ -1,10 +1,17 @@
 //! Interaction between document parts.
 
+mod artifact;
+mod attachment;
 mod bibliography;
+mod bookmark;
 mod context;
 mod counter;
 mod document;
+mod endnote;
+mod field;
 mod figure;
+mod figures;
+mod glossary;
 mod heading;
 mod link;
 mod numbering;
@@ -12,12 +19,20 @@ mod outline;
 mod query;
 mod reference;
 mod state;
+mod theorem;
 
+pub use self::artifact::*;
+pub use self::attachment::*;
 pub use self::bibliography::*;
+pub use self::bookmark::*;
 pub use self::context::*;
 pub use self::counter::*;
 pub use self::document::*;
+pub use self::endnote::*;
+pub use self::field::*;
 pub use self::figure::*;
+pub use self::figures::*;
+pub use self::glossary::*;
 pub use self::heading::*;
 pub use self::link::*;
 pub use self::numbering::*;
@@ -25,6 +40,7 @@ pub use self::outline::*;
 pub use self::query::*;
 pub use self::reference::*;
 pub use self::state::*;
+pub use self::theorem::*;
 
 use typst::doc::Lang;
 
@@ -200,6 +200,33 @@ impl NumberingPattern {
     pub fn pieces(&self) -> usize {
         self.pieces.len()
     }
+
+    /// The page label for a page with this numbering.
+    ///
+    /// Only the first counting symbol and its prefix are considered as page
+    /// labels consist of a prefix and a single number. Symbols are written
+    /// out into the prefix.
+    pub fn page_label(&self, number: NonZeroUsize) -> PageLabel {
+        let (prefix, kind, case) = self.pieces.first().unwrap();
+        let mut prefix = if self.trimmed { EcoString::new() } else { prefix.clone() };
+        let style = match (kind, case) {
+            (NumberingKind::Arabic, _) => Some(PageLabelStyle::Arabic),
+            (NumberingKind::Roman, Case::Lower) => Some(PageLabelStyle::LowerRoman),
+            (NumberingKind::Roman, Case::Upper) => Some(PageLabelStyle::UpperRoman),
+            (NumberingKind::Letter, Case::Lower) => Some(PageLabelStyle::LowerAlpha),
+            (NumberingKind::Letter, Case::Upper) => Some(PageLabelStyle::UpperAlpha),
+            (NumberingKind::Symbol, _) => {
+                prefix.push_str(&kind.apply(number, *case));
+                None
+            }
+        };
+
+        PageLabel {
+            style,
+            prefix: (!prefix.is_empty()).then_some(prefix),
+            number,
+        }
+    }
 }
 
 impl FromStr for NumberingPattern {
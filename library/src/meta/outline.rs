@@ -142,28 +142,7 @@ impl Show for OutlineElem {
                 start = numbers + SpaceElem::new().pack() + start;
             };
 
-            // Add the numbering and section name.
-            seq.push(start.linked(Destination::Location(location)));
-
-            // Add filler symbols between the section name and page number.
-            if let Some(filler) = self.fill(styles) {
-                seq.push(SpaceElem::new().pack());
-                seq.push(
-                    BoxElem::new()
-                        .with_body(Some(filler.clone()))
-                        .with_width(Fr::one().into())
-                        .pack(),
-                );
-                seq.push(SpaceElem::new().pack());
-            } else {
-                seq.push(HElem::new(Fr::one().into()).pack());
-            }
-
-            // Add the page number and linebreak.
-            let page = vt.introspector.page(location);
-            let end = TextElem::packed(eco_format!("{page}"));
-            seq.push(end.linked(Destination::Location(location)));
-            seq.push(LinebreakElem::new().pack());
+            push_entry(vt, &mut seq, start, location, self.fill(styles));
             ancestors.push(heading);
         }
 
@@ -173,6 +152,39 @@ impl Show for OutlineElem {
     }
 }
 
+/// Add an entry that links to the given location to an outline: The start of
+/// the entry, filler symbols, and the page number, followed by a linebreak.
+pub(super) fn push_entry(
+    vt: &Vt,
+    seq: &mut Vec<Content>,
+    start: Content,
+    location: Location,
+    fill: Option<Content>,
+) {
+    // Add the numbering and section name.
+    seq.push(start.linked(Destination::Location(location)));
+
+    // Add filler symbols between the section name and page number.
+    if let Some(filler) = fill {
+        seq.push(SpaceElem::new().pack());
+        seq.push(
+            BoxElem::new()
+                .with_body(Some(filler))
+                .with_width(Fr::one().into())
+                .pack(),
+        );
+        seq.push(SpaceElem::new().pack());
+    } else {
+        seq.push(HElem::new(Fr::one().into()).pack());
+    }
+
+    // Add the page number and linebreak.
+    let page = vt.introspector.page(location);
+    let end = TextElem::packed(eco_format!("{page}"));
+    seq.push(end.linked(Destination::Location(location)));
+    seq.push(LinebreakElem::new().pack());
+}
+
 impl LocalName for OutlineElem {
     fn local_name(&self, lang: Lang) -> &'static str {
         match lang {
@@ -1,4 +1,6 @@
-use super::{BibliographyElem, CiteElem, Counter, LocalName, Numbering};
+use super::{
+    BibliographyElem, CiteElem, Counter, FigureElem, LocalName, Numbering, TheoremElem,
+};
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -117,11 +119,15 @@ impl Show for RefElem {
 
         let supplement = self.supplement(styles);
         let mut supplement = match supplement {
-            Smart::Auto => elem
-                .with::<dyn LocalName>()
-                .map(|elem| elem.local_name(TextElem::lang_in(styles)))
-                .map(TextElem::packed)
-                .unwrap_or_default(),
+            Smart::Auto => match (elem.to::<TheoremElem>(), elem.to::<FigureElem>()) {
+                (Some(theorem), _) => theorem.resolved_supplement(StyleChain::default()),
+                (_, Some(figure)) => figure.resolved_supplement(StyleChain::default()),
+                _ => elem
+                    .with::<dyn LocalName>()
+                    .map(|elem| elem.local_name(TextElem::lang_in(styles)))
+                    .map(TextElem::packed)
+                    .unwrap_or_default(),
+            },
             Smart::Custom(None) => Content::empty(),
             Smart::Custom(Some(Supplement::Content(content))) => content.clone(),
             Smart::Custom(Some(Supplement::Func(func))) => {
@@ -137,9 +143,18 @@ impl Show for RefElem {
             bail!(self.span(), "only numbered elements can be referenced");
         };
 
-        let numbers = Counter::of(elem.func())
-            .at(vt, elem.location().unwrap())?
-            .display(vt, &numbering.trimmed())?;
+        let numbers = if let Some(figure) = elem.to::<FigureElem>() {
+            figure.ref_number(vt)?
+        } else {
+            let counter = match elem.to::<TheoremElem>() {
+                Some(theorem) => theorem.counter(),
+                None => Counter::of(elem.func()),
+            };
+
+            counter
+                .at(vt, elem.location().unwrap())?
+                .display(vt, &numbering.trimmed())?
+        };
 
         Ok((supplement + numbers).linked(Destination::Location(elem.location().unwrap())))
     }
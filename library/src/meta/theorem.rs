@@ -0,0 +1,172 @@
+use std::str::FromStr;
+
+use ecow::eco_vec;
+
+use super::{
+    Count, Counter, CounterKey, CounterUpdate, HeadingElem, LocalName, Numbering,
+    NumberingPattern,
+};
+use crate::layout::BlockElem;
+use crate::prelude::*;
+use crate::text::{StrongElem, TextElem};
+
+/// A numbered theorem-like environment.
+///
+/// Theorems, lemmas, definitions, examples and similar environments are all
+/// created with this function. To define a reusable environment, preconfigure
+/// the function with [`with`]($type/function.with). Environments of the same
+/// `kind` share a counter, so that lemmas and theorems can be numbered in one
+/// sequence while examples are counted separately.
+///
+/// Theorems can be referenced by attaching a label to them. The reference
+/// then uses the environment's supplement.
+///
+/// ## Example
+/// ```example
+/// #set heading(numbering: "1.")
+/// #set theorem(reset: 1)
+/// #let lemma = theorem.with(supplement: [Lemma])
+/// #let example = theorem.with(
+///   kind: "example",
+///   supplement: [Example],
+/// )
+///
+/// = Primes
+/// #theorem(title: [Euclid])[
+///   There are infinitely many primes.
+/// ] <euclid>
+///
+/// #lemma[Every integer greater than
+/// one has a prime factor.]
+///
+/// #example[The number 7 is prime.]
+///
+/// By @euclid, the list never ends.
+/// ```
+///
+/// Display: Theorem
+/// Category: meta
+#[element(Locatable, Synthesize, Count, Show, LocalName)]
+pub struct TheoremElem {
+    /// The statement of the theorem.
+    #[required]
+    pub body: Content,
+
+    /// An optional title, displayed in parentheses after the number.
+    pub title: Option<Content>,
+
+    /// The name of the environment, like "Lemma" or "Definition".
+    ///
+    /// When set to `{auto}`, a name suitable for theorems in the text's
+    /// language is used.
+    pub supplement: Smart<Content>,
+
+    /// The kind of the environment. Environments of the same kind share a
+    /// counter.
+    #[default("theorem".into())]
+    pub kind: EcoString,
+
+    /// How to number the environment. Accepts a
+    /// [numbering pattern or function]($func/numbering).
+    #[default(Some(NumberingPattern::from_str("1.1").unwrap().into()))]
+    pub numbering: Option<Numbering>,
+
+    /// The heading level at which the counter is reset.
+    ///
+    /// When set, the numbers of the enclosing numbered headings up to this
+    /// level are prefixed to the environment's number and the counter starts
+    /// over with each of these headings.
+    ///
+    /// ```example
+    /// #set heading(numbering: "1.")
+    /// #set theorem(reset: 1)
+    ///
+    /// = Groups
+    /// #theorem[Subgroups of cyclic
+    /// groups are cyclic.]
+    ///
+    /// = Rings
+    /// #theorem[Every field is an
+    /// integral domain.]
+    /// ```
+    pub reset: Option<NonZeroUsize>,
+}
+
+impl TheoremElem {
+    /// The counter shared by all environments of this kind.
+    pub fn counter(&self) -> Counter {
+        let styles = StyleChain::default();
+        let mut selectors = eco_vec![Selector::Elem(
+            Self::func(),
+            Some(dict! { "kind" => self.kind(styles) }),
+        )];
+
+        if let Some(reset) = self.reset(styles) {
+            for level in (1..=reset.get()).filter_map(NonZeroUsize::new) {
+                selectors.push(Selector::Elem(
+                    HeadingElem::func(),
+                    Some(dict! { "level" => level }),
+                ));
+            }
+        }
+
+        Counter::new(CounterKey::Selector(Selector::Any(selectors)))
+    }
+
+    /// The resolved supplement of the environment.
+    pub fn resolved_supplement(&self, styles: StyleChain) -> Content {
+        match self.supplement(styles) {
+            Smart::Auto => TextElem::packed(self.local_name(TextElem::lang_in(styles))),
+            Smart::Custom(supplement) => supplement,
+        }
+    }
+}
+
+impl Synthesize for TheoremElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        self.push_supplement(Smart::Custom(self.resolved_supplement(styles)));
+        self.push_kind(self.kind(styles));
+        self.push_numbering(self.numbering(styles));
+        self.push_reset(self.reset(styles));
+    }
+}
+
+impl Show for TheoremElem {
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let mut head = self.resolved_supplement(styles);
+
+        if let Some(numbering) = self.numbering(styles) {
+            head += TextElem::packed('\u{a0}')
+                + self.counter().display(Some(numbering), false).spanned(self.span());
+        }
+
+        if let Some(title) = self.title(styles) {
+            head += TextElem::packed(" (") + title + TextElem::packed(')');
+        }
+
+        let realized = StrongElem::new(head + TextElem::packed('.')).pack()
+            + TextElem::packed(' ')
+            + self.body();
+
+        Ok(BlockElem::new().with_body(Some(realized)).pack())
+    }
+}
+
+impl Count for TheoremElem {
+    fn update(&self) -> Option<CounterUpdate> {
+        let styles = StyleChain::default();
+        self.numbering(styles).is_some().then(|| {
+            let level = self.reset(styles).map_or(1, |reset| reset.get() + 1);
+            CounterUpdate::Step(NonZeroUsize::new(level).unwrap())
+        })
+    }
+}
+
+impl LocalName for TheoremElem {
+    fn local_name(&self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::GERMAN => "Satz",
+            Lang::ENGLISH | _ => "Theorem",
+        }
+    }
+}
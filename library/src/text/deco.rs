@@ -1,7 +1,8 @@
 use kurbo::{BezPath, Line, ParamCurve};
 use ttf_parser::{GlyphId, OutlineBuilder};
+use typst::font::VerticalFontMetric;
 
-use super::TextElem;
+use super::{TextEdge, TextElem};
 use crate::prelude::*;
 
 /// Underline text.
@@ -50,6 +51,15 @@ pub struct UnderlineElem {
     #[resolve]
     pub extent: Length,
 
+    /// The style of the line.
+    ///
+    /// ```example
+    /// #underline(style: "double")[Double],
+    /// #underline(style: "wavy")[wavy]
+    /// ```
+    #[default(LineStyle::Solid)]
+    pub style: LineStyle,
+
     /// Whether the line skips sections in which it would collide with the
     /// glyphs.
     ///
@@ -73,6 +83,7 @@ impl Show for UnderlineElem {
             offset: self.offset(styles),
             extent: self.extent(styles),
             evade: self.evade(styles),
+            style: self.style(styles),
         })))
     }
 }
@@ -124,6 +135,15 @@ pub struct OverlineElem {
     #[resolve]
     pub extent: Length,
 
+    /// The style of the line.
+    ///
+    /// ```example
+    /// #overline(style: "double")[Double],
+    /// #overline(style: "wavy")[wavy]
+    /// ```
+    #[default(LineStyle::Solid)]
+    pub style: LineStyle,
+
     /// Whether the line skips sections in which it would collide with the
     /// glyphs.
     ///
@@ -152,6 +172,7 @@ impl Show for OverlineElem {
             offset: self.offset(styles),
             extent: self.extent(styles),
             evade: self.evade(styles),
+            style: self.style(styles),
         })))
     }
 }
@@ -216,6 +237,133 @@ impl Show for StrikeElem {
             offset: self.offset(styles),
             extent: self.extent(styles),
             evade: false,
+            style: LineStyle::Solid,
+        })))
+    }
+}
+
+/// Highlight text with a background color.
+///
+/// The highlight follows the text across line breaks: Each line gets its own
+/// rectangle, which is painted beneath the text.
+///
+/// ## Example
+/// ```example
+/// This is #highlight[important].
+/// This is #highlight(
+///   fill: aqua,
+///   extent: 2pt,
+///   radius: 2pt,
+/// )[even more important].
+/// ```
+///
+/// Display: Highlight
+/// Category: text
+#[element(Show)]
+pub struct HighlightElem {
+    /// The color to highlight the text with.
+    #[default(Color::YELLOW.into())]
+    pub fill: Paint,
+
+    /// The top end of the highlight.
+    ///
+    /// ```example
+    /// #set highlight(top-edge: "x-height")
+    /// #highlight[Low]
+    /// ```
+    #[default(TextEdge::Metric(VerticalFontMetric::Ascender))]
+    pub top_edge: TextEdge,
+
+    /// The bottom end of the highlight.
+    #[default(TextEdge::Metric(VerticalFontMetric::Descender))]
+    pub bottom_edge: TextEdge,
+
+    /// Amount that the highlight will be wider or narrower than its
+    /// associated text.
+    #[resolve]
+    pub extent: Length,
+
+    /// How much to round the highlight's corners.
+    #[resolve]
+    pub radius: Length,
+
+    /// The content to highlight.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for HighlightElem {
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        Ok(self.body().styled(TextElem::set_deco(Decoration {
+            line: DecoLine::Highlight {
+                top_edge: self.top_edge(styles),
+                bottom_edge: self.bottom_edge(styles),
+                radius: self.radius(styles),
+            },
+            stroke: PartialStroke {
+                paint: Smart::Custom(self.fill(styles)),
+                thickness: Smart::Auto,
+                ..Default::default()
+            },
+            offset: Smart::Auto,
+            extent: self.extent(styles),
+            evade: false,
+            style: LineStyle::Solid,
+        })))
+    }
+}
+
+/// Add emphasis marks to text.
+///
+/// Emphasis marks (also known as boten or 着重号) are small dots placed over
+/// or under each character. They are the customary way to emphasize text in
+/// Chinese and Japanese.
+///
+/// ## Example
+/// ```example
+/// これは#emphmark[大切]です。
+/// 这是#emphmark(position: "under")[重点]。
+/// ```
+///
+/// Display: Emphasis Marks
+/// Category: text
+#[element(Show)]
+pub struct EmphmarkElem {
+    /// Where to place the marks.
+    #[default(MarkPosition::Over)]
+    pub position: MarkPosition,
+
+    /// The color of the marks. Uses the text color if `{auto}`.
+    pub fill: Smart<Paint>,
+
+    /// The diameter of the marks. Defaults to `{0.15em}` if `{auto}`.
+    #[resolve]
+    pub size: Smart<Length>,
+
+    /// Position of the marks' near edge relative to the baseline. Placed just
+    /// beyond the font's ascender or descender if `{auto}`.
+    #[resolve]
+    pub offset: Smart<Length>,
+
+    /// The content to add marks to.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for EmphmarkElem {
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let stroke = PartialStroke {
+            paint: self.fill(styles),
+            thickness: self.size(styles),
+            ..Default::default()
+        };
+        Ok(self.body().styled(TextElem::set_deco(Decoration {
+            line: DecoLine::Marks(self.position(styles)),
+            stroke,
+            offset: self.offset(styles),
+            extent: Abs::zero(),
+            evade: false,
+            style: LineStyle::Solid,
         })))
     }
 }
@@ -228,6 +376,7 @@ pub struct Decoration {
     pub offset: Smart<Abs>,
     pub extent: Abs,
     pub evade: bool,
+    pub style: LineStyle,
 }
 
 impl Fold for Decoration {
@@ -249,6 +398,34 @@ pub enum DecoLine {
     Underline,
     Strikethrough,
     Overline,
+    /// Emphasis marks placed over or under each character.
+    Marks(MarkPosition),
+    /// A background behind the text, filled with the stroke's paint.
+    Highlight {
+        top_edge: TextEdge,
+        bottom_edge: TextEdge,
+        radius: Abs,
+    },
+}
+
+/// How a decorative line is drawn.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LineStyle {
+    /// A single straight line.
+    Solid,
+    /// Two parallel straight lines.
+    Double,
+    /// A single wavy line.
+    Wavy,
+}
+
+/// Where emphasis marks are placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum MarkPosition {
+    /// Above the text, as is customary in Japanese and vertical Chinese.
+    Over,
+    /// Below the text, as is customary in horizontal Chinese.
+    Under,
 }
 
 /// Add line decorations to a single run of shaped text.
@@ -265,12 +442,42 @@ pub(super) fn decorate(
         DecoLine::Strikethrough => font_metrics.strikethrough,
         DecoLine::Overline => font_metrics.overline,
         DecoLine::Underline => font_metrics.underline,
+        DecoLine::Marks(position) => {
+            decorate_marks(frame, deco, position, text, shift, pos);
+            return;
+        }
+        DecoLine::Highlight { top_edge, bottom_edge, radius } => {
+            let edge = |edge| match edge {
+                TextEdge::Metric(metric) => font_metrics.vertical(metric).at(text.size),
+                TextEdge::Length(length) => length.abs + length.em.at(text.size),
+            };
+
+            let top = edge(top_edge);
+            let size = Size::new(width + 2.0 * deco.extent, top - edge(bottom_edge));
+            let origin = Point::new(pos.x - deco.extent, pos.y - shift - top);
+            let fill = deco.stroke.paint.unwrap_or(text.fill);
+
+            // Highlights go beneath the text.
+            let shapes = rounded_rect(
+                size,
+                Corners::splat(radius),
+                Some(fill),
+                Sides::splat(None),
+            );
+            frame.prepend_multiple(
+                shapes
+                    .into_iter()
+                    .map(|shape| (origin, FrameItem::Shape(shape, Span::detached()))),
+            );
+            return;
+        }
     };
 
     let offset = deco.offset.unwrap_or(-metrics.position.at(text.size)) - shift;
     let stroke = deco.stroke.unwrap_or(Stroke {
         paint: text.fill,
         thickness: metrics.thickness.at(text.size),
+        ..Stroke::default()
     });
 
     let gap_padding = 0.08 * text.size;
@@ -284,8 +491,7 @@ pub(super) fn decorate(
         let target = Point::new(to - from, Abs::zero());
 
         if target.x >= min_width || !deco.evade {
-            let shape = Geometry::Line(target).stroked(stroke);
-            frame.push(origin, FrameItem::Shape(shape, Span::detached()));
+            push_line(frame, deco.style, origin, target.x, stroke);
         }
     };
 
@@ -354,6 +560,96 @@ pub(super) fn decorate(
     }
 }
 
+/// Push a decorative line of the given style starting at `origin`.
+fn push_line(
+    frame: &mut Frame,
+    style: LineStyle,
+    origin: Point,
+    width: Abs,
+    stroke: Stroke,
+) {
+    let t = stroke.thickness;
+    match style {
+        LineStyle::Solid => {
+            let shape = Geometry::Line(Point::with_x(width)).stroked(stroke);
+            frame.push(origin, FrameItem::Shape(shape, Span::detached()));
+        }
+        LineStyle::Double => {
+            for dy in [-t, t] {
+                let shape = Geometry::Line(Point::with_x(width)).stroked(stroke);
+                let pos = origin + Point::with_y(dy);
+                frame.push(pos, FrameItem::Shape(shape, Span::detached()));
+            }
+        }
+        LineStyle::Wavy => {
+            // Each wave consists of one crest and one trough.
+            let amplitude = 1.5 * t;
+            let half = 2.5 * t;
+            let mut path = Path::new();
+            let mut x = Abs::zero();
+            let mut sign = -1.0;
+            path.move_to(Point::zero());
+            while half > Abs::zero() && x < width {
+                let next = (x + half).min(width);
+                let y = sign * amplitude;
+                let third = (next - x) / 3.0;
+                path.cubic_to(
+                    Point::new(x + third, y),
+                    Point::new(next - third, y),
+                    Point::with_x(next),
+                );
+                x = next;
+                sign = -sign;
+            }
+            let shape = Geometry::Path(path).stroked(stroke);
+            frame.push(origin, FrameItem::Shape(shape, Span::detached()));
+        }
+    }
+}
+
+/// Add emphasis marks to a single run of shaped text.
+fn decorate_marks(
+    frame: &mut Frame,
+    deco: &Decoration,
+    position: MarkPosition,
+    text: &TextItem,
+    shift: Abs,
+    pos: Point,
+) {
+    let metrics = text.font.metrics();
+    let paint = deco.stroke.paint.unwrap_or(text.fill);
+    let diameter = deco.stroke.thickness.unwrap_or(0.15 * text.size);
+    let offset = deco.offset.unwrap_or(match position {
+        MarkPosition::Over => -metrics.ascender.at(text.size) - 0.1 * text.size,
+        MarkPosition::Under => -metrics.descender.at(text.size) + 0.1 * text.size,
+    }) - shift;
+
+    let mut x = pos.x;
+    for glyph in &text.glyphs {
+        let advance = glyph.x_advance.at(text.size);
+        if !glyph.c.is_whitespace() && !is_cjk_punctuation(glyph.c) {
+            let center = x + glyph.x_offset.at(text.size) + advance / 2.0;
+            let y = match position {
+                MarkPosition::Over => pos.y + offset - diameter,
+                MarkPosition::Under => pos.y + offset,
+            };
+            let origin = Point::new(center - diameter / 2.0, y);
+            let shape = ellipse(Size::splat(diameter), Some(paint), None);
+            frame.push(origin, FrameItem::Shape(shape, Span::detached()));
+        }
+        x += advance;
+    }
+}
+
+/// Whether a character is punctuation that shouldn't receive emphasis marks.
+fn is_cjk_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '\u{3000}'..='\u{303F}' | '\u{FF00}'..='\u{FF0F}' | '\u{FF1A}'..='\u{FF20}'
+        )
+}
+
 /// Builds a kurbo [`BezPath`] for a glyph.
 struct BezPathBuilder {
     path: BezPath,
@@ -61,6 +61,33 @@ impl Behave for LinebreakElem {
     }
 }
 
+/// Adjusts the space after the preceding glyph.
+///
+/// Unlike [horizontal spacing]($func/h), a kern does not create a separate
+/// piece of spacing in the line. Instead, it widens or narrows the advance of
+/// the glyph right before it, just like the kerning built into the font. In
+/// the exported PDF, it thus ends up as part of the glyph's positioning
+/// adjustment. A kern that does not follow any text in the same paragraph has
+/// no effect.
+///
+/// ## Example
+/// ```example
+/// #set text(size: 20pt)
+/// AV \
+/// A#kern(-0.1em)V \
+/// T#kern(0.2em)o
+/// ```
+///
+/// Display: Kern
+/// Category: text
+#[element]
+pub struct KernElem {
+    /// How much to widen the preceding glyph's advance. Negative values pull
+    /// the following text closer.
+    #[required]
+    pub amount: Length,
+}
+
 /// Strongly emphasizes content by increasing the font weight.
 ///
 /// Increases the current font weight by a given `delta`.
@@ -4,15 +4,19 @@ mod deco;
 mod misc;
 mod quotes;
 mod raw;
+mod ruby;
 mod shaping;
 mod shift;
+mod units;
 
 pub use self::deco::*;
 pub use self::misc::*;
 pub use self::quotes::*;
 pub use self::raw::*;
+pub use self::ruby::*;
 pub use self::shaping::*;
 pub use self::shift::*;
+pub use self::units::*;
 
 use std::borrow::Cow;
 
@@ -177,6 +181,33 @@ pub struct TextElem {
     #[default(Rel::one())]
     pub spacing: Rel<Length>,
 
+    /// Whether to automatically insert a quarter em of space between CJK and
+    /// Latin characters, as is customary in East Asian typesetting.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// Typstは組版システムです。
+    ///
+    /// #set text(cjk-latin-spacing: true)
+    /// Typstは組版システムです。
+    /// ```
+    #[default(false)]
+    pub cjk_latin_spacing: bool,
+
+    /// Whether to remove the blank halves between adjacent full-width
+    /// punctuation, so that in `」「` or `。」` only half an em of space remains
+    /// between the visible marks.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// 「括弧」「括弧」。
+    ///
+    /// #set text(cjk-punctuation-compression: true)
+    /// 「括弧」「括弧」。
+    /// ```
+    #[default(false)]
+    pub cjk_punctuation_compression: bool,
+
     /// An amount to shift the text baseline by.
     ///
     /// ```example
@@ -189,6 +220,10 @@ pub struct TextElem {
     /// Whether certain glyphs can hang over into the margin in justified text.
     /// This can make justification visually more pleasing.
     ///
+    /// Whether a glyph hangs is decided by the text it belongs to, so a set
+    /// rule toggles hanging for whole paragraphs while a text call toggles it
+    /// for individual pieces of text.
+    ///
     /// ```example
     /// #set par(justify: true)
     /// In this particular text, the
@@ -209,6 +244,20 @@ pub struct TextElem {
     #[default(true)]
     pub overhang: bool,
 
+    /// Whether opening quotes and brackets at the start of a line also hang
+    /// into the margin. Has no effect unless `overhang` is enabled.
+    ///
+    /// ```example
+    /// #set par(justify: true)
+    /// #set text(overhang-start: true)
+    /// "Quotes at the start of
+    /// a line" hang into the
+    /// margin, just like those
+    /// at the end of a "line."
+    /// ```
+    #[default(false)]
+    pub overhang_start: bool,
+
     /// The top end of the conceptual frame around the text used for layout and
     /// positioning. This affects the size of containers that hold text.
     ///
@@ -0,0 +1,134 @@
+use super::{TextElem, TextSize};
+use crate::layout::BoxElem;
+use crate::prelude::*;
+
+/// Annotate text with ruby, small text typically giving its reading.
+///
+/// Ruby annotations (also known as furigana in Japanese) are laid out above
+/// (or below) their base text and centered on it. An annotation that is wider
+/// than its base may hang over the neighbouring text by a limited amount.
+/// Lines containing ruby grow to make room for the annotation.
+///
+/// ## Example
+/// ```example
+/// #ruby[漢字][かんじ]を
+/// #ruby[勉強][べんきょう]します。
+/// ```
+///
+/// Display: Ruby
+/// Category: text
+#[element(Show)]
+pub struct RubyElem {
+    /// Where to place the annotation relative to its base text.
+    ///
+    /// ```example
+    /// #ruby(position: "under")[東京][とうきょう]
+    /// ```
+    #[default(RubyPosition::Over)]
+    pub position: RubyPosition,
+
+    /// The font size of the annotation.
+    #[default(TextSize(Em::new(0.5).into()))]
+    pub size: TextSize,
+
+    /// The gap between the base text and its annotation.
+    #[resolve]
+    pub gap: Length,
+
+    /// How far an annotation that is wider than its base text may hang over
+    /// the adjacent text on each side.
+    #[resolve]
+    #[default(Em::new(0.5).into())]
+    pub overhang: Length,
+
+    /// The base text.
+    #[required]
+    pub body: Content,
+
+    /// The annotation.
+    #[required]
+    pub annotation: Content,
+}
+
+impl Show for RubyElem {
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let annotation = self.annotation().styled(TextElem::set_size(self.size(styles)));
+        let layout = RubyLayoutElem::new(
+            self.body(),
+            annotation,
+            self.position(styles),
+            self.gap(styles).into(),
+            self.overhang(styles).into(),
+        );
+        Ok(BoxElem::new().with_body(Some(layout.pack())).pack())
+    }
+}
+
+/// Where a ruby annotation is placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RubyPosition {
+    /// Above the base text.
+    Over,
+    /// Below the base text.
+    Under,
+}
+
+/// Lays out a base text and its ruby annotation.
+///
+/// Display: Ruby Layout
+/// Category: special
+#[element(Layout)]
+struct RubyLayoutElem {
+    /// The base text.
+    #[required]
+    body: Content,
+
+    /// The already styled annotation.
+    #[required]
+    annotation: Content,
+
+    /// Where to place the annotation.
+    #[required]
+    position: RubyPosition,
+
+    /// The gap between the base and the annotation.
+    #[required]
+    gap: Length,
+
+    /// How far the annotation may overhang its base on each side.
+    #[required]
+    overhang: Length,
+}
+
+impl Layout for RubyLayoutElem {
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let base = self.body().layout(vt, styles, pod)?.into_frame();
+        let annotation = self.annotation().layout(vt, styles, pod)?.into_frame();
+
+        // The annotation may hang over the adjacent text, so it only widens
+        // the ruby if it's much wider than the base.
+        let gap = self.gap().resolve(styles);
+        let overhang = self.overhang().resolve(styles).max(Abs::zero());
+        let width = base.width().max(annotation.width() - 2.0 * overhang);
+        let height = base.height() + gap + annotation.height();
+
+        let base_x = (width - base.width()) / 2.0;
+        let annotation_x = (width - annotation.width()) / 2.0;
+        let (base_y, annotation_y) = match self.position() {
+            RubyPosition::Over => (annotation.height() + gap, Abs::zero()),
+            RubyPosition::Under => (Abs::zero(), base.height() + gap),
+        };
+
+        let mut frame = Frame::new(Size::new(width, height));
+        frame.set_baseline(base_y + base.baseline());
+        frame.push_frame(Point::new(base_x, base_y), base);
+        frame.push_frame(Point::new(annotation_x, annotation_y), annotation);
+        Ok(Fragment::frame(frame))
+    }
+}
@@ -1,7 +1,6 @@
 use std::ops::Range;
-use std::str::FromStr;
 
-use rustybuzz::{Feature, Tag, UnicodeBuffer};
+use rustybuzz::{Feature, Tag};
 use typst::font::{Font, FontVariant};
 use typst::util::SliceExt;
 
@@ -72,6 +71,23 @@ impl ShapedGlyph {
     pub fn is_justifiable(&self) -> bool {
         self.is_space() || matches!(self.c, '，' | '。' | '、')
     }
+
+    /// Whether the glyph is a full-width opening bracket or quote, whose
+    /// blank half is on its leading side.
+    pub fn is_cjk_opening(&self) -> bool {
+        self.is_full_width() && "（［｛〔〈《「『【〘〖〝‘“".contains(self.c)
+    }
+
+    /// Whether the glyph is full-width closing punctuation, whose blank half
+    /// is on its trailing side.
+    pub fn is_cjk_closing(&self) -> bool {
+        self.is_full_width() && "）］｝〕〉》」』】〙〗〟’”、。，．".contains(self.c)
+    }
+
+    /// Whether the glyph occupies a full em.
+    fn is_full_width(&self) -> bool {
+        self.x_advance >= Em::new(0.9)
+    }
 }
 
 /// A side you can go toward.
@@ -99,14 +115,25 @@ impl<'a> ShapedText<'a> {
         let lang = TextElem::lang_in(self.styles);
         let decos = TextElem::deco_in(self.styles);
         let fill = TextElem::fill_in(self.styles);
+        let clusters = self.clusters();
 
+        let mut index = 0;
         for ((font, y_offset), group) in
             self.glyphs.as_ref().group_by_key(|g| (g.font.clone(), g.y_offset))
         {
             let pos = Point::new(offset, top + shift - y_offset.at(self.size));
+            let ranges = &clusters[index..index + group.len()];
+            index += group.len();
+
+            // The text covered by the group's clusters.
+            let start = ranges.iter().map(|r| r.start).min().unwrap_or_default();
+            let end = ranges.iter().map(|r| r.end).max().unwrap_or_default();
+            let text = self.text.get(start..end).unwrap_or_default();
+
             let glyphs = group
                 .iter()
-                .map(|glyph| Glyph {
+                .zip(ranges)
+                .map(|(glyph, range)| Glyph {
                     id: glyph.glyph_id,
                     x_advance: glyph.x_advance
                         + if glyph.is_justifiable() {
@@ -117,12 +144,20 @@ impl<'a> ShapedText<'a> {
                         },
                     x_offset: glyph.x_offset,
                     c: glyph.c,
+                    range: (range.start - start) as u16..(range.end - start) as u16,
                     span: glyph.span,
                     offset: glyph.offset,
                 })
                 .collect();
 
-            let item = TextItem { font, size: self.size, lang, fill, glyphs };
+            let item = TextItem {
+                font,
+                size: self.size,
+                lang,
+                fill,
+                text: text.into(),
+                glyphs,
+            };
             let layer = frame.layer();
             let width = item.width();
 
@@ -141,6 +176,29 @@ impl<'a> ShapedText<'a> {
         frame
     }
 
+    /// The byte range of each glyph's cluster in the text.
+    ///
+    /// A cluster extends up to the start of the next cluster in logical order.
+    /// The glyphs of reshaped text keep the clusters of the text they were
+    /// first shaped from, so they are shifted to start at zero.
+    fn clusters(&self) -> Vec<Range<usize>> {
+        let shift = self.glyphs.iter().map(|g| g.cluster).min().unwrap_or_default();
+        let mut bounds: Vec<usize> =
+            self.glyphs.iter().map(|g| g.cluster - shift).collect();
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        self.glyphs
+            .iter()
+            .map(|g| {
+                let start = (g.cluster - shift).min(self.text.len());
+                let next = bounds.partition_point(|&b| b <= start);
+                let end = bounds.get(next).copied().unwrap_or(self.text.len());
+                start..end.clamp(start, self.text.len())
+            })
+            .collect()
+    }
+
     /// Measure the top and bottom extent of this text.
     fn measure(&self, vt: &Vt) -> (Abs, Abs) {
         let mut top = Abs::zero();
@@ -235,7 +293,7 @@ impl<'a> ShapedText<'a> {
                 .and_then(|id| world.font(id))?;
             let ttf = font.ttf();
             let glyph_id = ttf.glyph_index('-')?;
-            let x_advance = font.to_em(ttf.glyph_hor_advance(glyph_id)?);
+            let x_advance = font.advance(glyph_id.0)?;
             let cluster = self.glyphs.last().map(|g| g.cluster).unwrap_or_default();
             self.width += x_advance.at(self.size);
             self.glyphs.to_mut().push(ShapedGlyph {
@@ -362,6 +420,7 @@ pub fn shape<'a>(
         shape_segment(&mut ctx, 0, text, families(styles));
     }
 
+    compress_punctuation(&mut ctx);
     track_and_space(&mut ctx);
 
     ShapedText {
@@ -416,20 +475,8 @@ fn shape_segment<'a>(
 
     ctx.used.push(font.clone());
 
-    // Fill the buffer with our text.
-    let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
-    buffer.set_language(language(ctx.styles));
-    buffer.set_direction(match ctx.dir {
-        Dir::LTR => rustybuzz::Direction::LeftToRight,
-        Dir::RTL => rustybuzz::Direction::RightToLeft,
-        _ => unimplemented!("vertical text layout"),
-    });
-
     // Shape!
-    let buffer = rustybuzz::shape(font.rusty(), &ctx.tags, buffer);
-    let infos = buffer.glyph_infos();
-    let pos = buffer.glyph_positions();
+    let infos = font.shape(text, ctx.dir, &language(ctx.styles), &ctx.tags);
 
     // Collect the shaped glyphs, doing fallback and shaping parts again with
     // the next font if necessary.
@@ -445,11 +492,29 @@ fn shape_segment<'a>(
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
                 glyph_id: info.glyph_id as u16,
-                x_advance: font.to_em(pos[i].x_advance),
-                x_offset: font.to_em(pos[i].x_offset),
-                y_offset: font.to_em(pos[i].y_offset),
+                x_advance: font.to_em(info.x_advance),
+                x_offset: font.to_em(info.x_offset),
+                y_offset: font.to_em(info.y_offset),
+                cluster: base + cluster,
+                safe_to_break: !info.unsafe_to_break,
+                c: text[cluster..].chars().next().unwrap(),
+                span,
+                offset,
+            });
+        } else if let Some((glyph_id, x_advance)) =
+            synthesize_space(&font, text[cluster..].chars().next().unwrap())
+        {
+            // Give typographic spaces the font lacks their intended width
+            // instead of falling back to another font.
+            let (span, offset) = ctx.spans.span_at(ctx.base + cluster);
+            ctx.glyphs.push(ShapedGlyph {
+                font: font.clone(),
+                glyph_id,
+                x_advance,
+                x_offset: Em::zero(),
+                y_offset: Em::zero(),
                 cluster: base + cluster,
-                safe_to_break: !info.unsafe_to_break(),
+                safe_to_break: !info.unsafe_to_break,
                 c: text[cluster..].chars().next().unwrap(),
                 span,
                 offset,
@@ -552,6 +617,38 @@ fn track_and_space(ctx: &mut ShapingContext) {
     }
 }
 
+/// Remove the blank halves between adjacent full-width punctuation.
+///
+/// For example, in `」「` or `。」` only half an em of blank space remains
+/// between the visible marks instead of a full em.
+fn compress_punctuation(ctx: &mut ShapingContext) {
+    if ctx.dir != Dir::LTR || !TextElem::cjk_punctuation_compression_in(ctx.styles) {
+        return;
+    }
+
+    let kinds: Vec<_> = ctx
+        .glyphs
+        .iter()
+        .map(|glyph| (glyph.is_cjk_opening(), glyph.is_cjk_closing()))
+        .collect();
+
+    let half = Em::new(0.5);
+    for i in 1..ctx.glyphs.len() {
+        if ctx.glyphs[i - 1].cluster == ctx.glyphs[i].cluster {
+            continue;
+        }
+
+        let (prev_opening, prev_closing) = kinds[i - 1];
+        let (opening, closing) = kinds[i];
+        if prev_closing && (opening || closing) {
+            ctx.glyphs[i - 1].x_advance -= half;
+        } else if prev_opening && opening {
+            ctx.glyphs[i].x_advance -= half;
+            ctx.glyphs[i].x_offset -= half;
+        }
+    }
+}
+
 /// Difference between non-breaking and normal space.
 fn nbsp_delta(font: &Font) -> Option<Em> {
     let space = font.ttf().glyph_index(' ')?.0;
@@ -559,6 +656,29 @@ fn nbsp_delta(font: &Font) -> Option<Em> {
     Some(font.advance(nbsp)? - font.advance(space)?)
 }
 
+/// The space glyph and width to use for a typographic space or invisible
+/// formatting character that the font has no glyph for.
+fn synthesize_space(font: &Font, c: char) -> Option<(u16, Em)> {
+    let advance = |c| font.ttf().glyph_index(c).and_then(|id| font.advance(id.0));
+    let width = match c {
+        '\u{2000}' | '\u{2002}' => Em::new(0.5),
+        '\u{2001}' | '\u{2003}' => Em::one(),
+        '\u{2004}' => Em::new(1.0 / 3.0),
+        '\u{2005}' => Em::new(0.25),
+        '\u{2006}' => Em::new(1.0 / 6.0),
+        '\u{2007}' => advance('0')?,
+        '\u{2008}' => advance('.')?,
+        '\u{2009}' | '\u{202F}' => Em::new(0.2),
+        '\u{200A}' => Em::new(0.1),
+        '\u{205F}' => Em::new(4.0 / 18.0),
+        '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => Em::zero(),
+        _ => return None,
+    };
+
+    let space = font.ttf().glyph_index(' ')?.0;
+    Some((space, width))
+}
+
 /// Resolve the font variant.
 pub fn variant(styles: StyleChain) -> FontVariant {
     let mut variant = FontVariant::new(
@@ -666,13 +786,13 @@ fn tags(styles: StyleChain) -> Vec<Feature> {
     tags
 }
 
-/// Process the language and and region of a style chain into a
-/// rustybuzz-compatible BCP 47 language.
-fn language(styles: StyleChain) -> rustybuzz::Language {
+/// Process the language and and region of a style chain into a BCP 47
+/// language tag.
+fn language(styles: StyleChain) -> EcoString {
     let mut bcp: EcoString = TextElem::lang_in(styles).as_str().into();
     if let Some(region) = TextElem::region_in(styles) {
         bcp.push('-');
         bcp.push_str(region.as_str());
     }
-    rustybuzz::Language::from_str(&bcp).unwrap()
+    bcp
 }
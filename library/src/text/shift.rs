@@ -1,4 +1,6 @@
-use super::{variant, SpaceElem, TextElem, TextSize};
+use rustybuzz::{Feature, Tag, UnicodeBuffer};
+
+use super::{variant, FontFeatures, SpaceElem, TextElem, TextSize};
 use crate::prelude::*;
 
 /// Set text in subscript.
@@ -14,11 +16,12 @@ use crate::prelude::*;
 /// Category: text
 #[element(Show)]
 pub struct SubElem {
-    /// Whether to prefer the dedicated subscript characters of the font.
+    /// Whether to prefer the dedicated subscript glyphs of the font.
     ///
-    /// If this is enabled, Typst first tries to transform the text to subscript
-    /// codepoints. If that fails, it falls back to rendering lowered and shrunk
-    /// normal letters.
+    /// If this is enabled, Typst first tries to use the font's OpenType `subs`
+    /// glyphs. If the font lacks them, it tries to transform the text to
+    /// subscript codepoints. If that fails, it falls back to rendering lowered
+    /// and shrunk normal letters.
     ///
     /// ```example
     /// N#sub(typographic: true)[1]
@@ -28,14 +31,14 @@ pub struct SubElem {
     pub typographic: bool,
 
     /// The baseline shift for synthetic subscripts. Does not apply if
-    /// `typographic` is true and the font has subscript codepoints for the
-    /// given `body`.
+    /// `typographic` is true and the font has subscript glyphs for the given
+    /// `body`.
     #[default(Em::new(0.2).into())]
     pub baseline: Length,
 
     /// The font size for synthetic subscripts. Does not apply if
-    /// `typographic` is true and the font has subscript codepoints for the
-    /// given `body`.
+    /// `typographic` is true and the font has subscript glyphs for the given
+    /// `body`.
     #[default(TextSize(Em::new(0.6).into()))]
     pub size: TextSize,
 
@@ -49,12 +52,8 @@ impl Show for SubElem {
         let body = self.body();
         let mut transformed = None;
         if self.typographic(styles) {
-            if let Some(text) = search_text(&body, true) {
-                if is_shapable(vt, &text, styles) {
-                    transformed = Some(TextElem::packed(text));
-                }
-            }
-        };
+            transformed = typographic(vt, &body, styles, true);
+        }
 
         Ok(transformed.unwrap_or_else(|| {
             body.styled(TextElem::set_baseline(self.baseline(styles)))
@@ -76,9 +75,10 @@ impl Show for SubElem {
 /// Category: text
 #[element(Show)]
 pub struct SuperElem {
-    /// Whether to prefer the dedicated superscript characters of the font.
+    /// Whether to prefer the dedicated superscript glyphs of the font.
     ///
-    /// If this is enabled, Typst first tries to transform the text to
+    /// If this is enabled, Typst first tries to use the font's OpenType `sups`
+    /// glyphs. If the font lacks them, it tries to transform the text to
     /// superscript codepoints. If that fails, it falls back to rendering
     /// raised and shrunk normal letters.
     ///
@@ -90,13 +90,13 @@ pub struct SuperElem {
     pub typographic: bool,
 
     /// The baseline shift for synthetic superscripts. Does not apply if
-    /// `typographic` is true and the font has superscript codepoints for the
+    /// `typographic` is true and the font has superscript glyphs for the
     /// given `body`.
     #[default(Em::new(-0.5).into())]
     pub baseline: Length,
 
     /// The font size for synthetic superscripts. Does not apply if
-    /// `typographic` is true and the font has superscript codepoints for the
+    /// `typographic` is true and the font has superscript glyphs for the
     /// given `body`.
     #[default(TextSize(Em::new(0.6).into()))]
     pub size: TextSize,
@@ -111,12 +111,8 @@ impl Show for SuperElem {
         let body = self.body();
         let mut transformed = None;
         if self.typographic(styles) {
-            if let Some(text) = search_text(&body, false) {
-                if is_shapable(vt, &text, styles) {
-                    transformed = Some(TextElem::packed(text));
-                }
-            }
-        };
+            transformed = typographic(vt, &body, styles, false);
+        }
 
         Ok(transformed.unwrap_or_else(|| {
             body.styled(TextElem::set_baseline(self.baseline(styles)))
@@ -125,17 +121,38 @@ impl Show for SuperElem {
     }
 }
 
-/// Find and transform the text contained in `content` to the given script kind
-/// if and only if it only consists of `Text`, `Space`, and `Empty` leafs.
-fn search_text(content: &Content, sub: bool) -> Option<EcoString> {
+/// Try to display `body` with the font's dedicated sub- or superscript glyphs.
+///
+/// Prefers the OpenType `subs` / `sups` feature and falls back to the
+/// respective Unicode codepoints.
+fn typographic(
+    vt: &Vt,
+    body: &Content,
+    styles: StyleChain,
+    sub: bool,
+) -> Option<Content> {
+    let text = search_text(body)?;
+    let tag = Tag::from_bytes(if sub { b"subs" } else { b"sups" });
+    if has_feature_glyphs(vt, &text, styles, tag) {
+        let features = FontFeatures(vec![(tag, 1)]);
+        return Some(body.clone().styled(TextElem::set_features(features)));
+    }
+
+    let text = convert_script(&text, sub)?;
+    is_shapable(vt, &text, styles).then(|| TextElem::packed(text))
+}
+
+/// Find the text contained in `content` if and only if it only consists of
+/// `Text`, `Space`, and `Empty` leafs.
+fn search_text(content: &Content) -> Option<EcoString> {
     if content.is::<SpaceElem>() {
         Some(' '.into())
     } else if let Some(elem) = content.to::<TextElem>() {
-        convert_script(&elem.text(), sub)
+        Some(elem.text())
     } else if let Some(children) = content.to_sequence() {
         let mut full = EcoString::new();
         for item in children {
-            match search_text(&item, sub) {
+            match search_text(&item) {
                 Some(text) => full.push_str(&text),
                 None => return None,
             }
@@ -146,6 +163,40 @@ fn search_text(content: &Content, sub: bool) -> Option<EcoString> {
     }
 }
 
+/// Checks whether the OpenType feature with the given tag substitutes every
+/// non-space glyph of the text in the first retrievable family.
+fn has_feature_glyphs(vt: &Vt, text: &str, styles: StyleChain, tag: Tag) -> bool {
+    let world = vt.world;
+    for family in TextElem::font_in(styles) {
+        if let Some(font) = world
+            .book()
+            .select(family.as_str(), variant(styles))
+            .and_then(|id| world.font(id))
+        {
+            let shape = |features: &[Feature]| {
+                let mut buffer = UnicodeBuffer::new();
+                buffer.push_str(text);
+                let output = rustybuzz::shape(font.rusty(), features, buffer);
+                output
+                    .glyph_infos()
+                    .iter()
+                    .map(|info| (info.glyph_id, info.cluster as usize))
+                    .collect::<Vec<_>>()
+            };
+
+            let plain = shape(&[]);
+            let shifted = shape(&[Feature::new(tag, 1, ..)]);
+            return plain.len() == shifted.len()
+                && plain.iter().zip(&shifted).all(|(&(a, cluster), &(b, _))| {
+                    let space = text[cluster..].starts_with(char::is_whitespace);
+                    a != 0 && (a != b || space)
+                });
+        }
+    }
+
+    false
+}
+
 /// Checks whether the first retrievable family contains all code points of the
 /// given string.
 fn is_shapable(vt: &Vt, text: &str, styles: StyleChain) -> bool {
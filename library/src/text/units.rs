@@ -0,0 +1,385 @@
+use crate::math::{AttachElem, EquationElem, MathStyleElem};
+use crate::prelude::*;
+
+use super::{SubElem, SuperElem, TextElem};
+
+/// Typesets a physical unit.
+///
+/// Units are written as a space-separated list of unit symbols, each
+/// optionally raised to a power with `^`. Units after a slash are placed in
+/// the denominator. A few names are replaced by their symbols: `ohm` (Ω),
+/// `deg` (°), `degC` (°C), `arcmin` (′), `arcsec` (″), `angstrom` (Å),
+/// `percent` (%), and the prefix `micro` (µ).
+///
+/// ## Example
+/// ```example
+/// #unit("kg m/s^2") \
+/// #unit("kg m/s^2", per: "slash") \
+/// #unit("microm kohm")
+/// ```
+///
+/// Display: Unit
+/// Category: text
+/// Returns: content
+#[func]
+pub fn unit(
+    /// The unit to typeset.
+    unit: EcoString,
+    /// How to typeset units in the denominator.
+    #[named]
+    #[default(UnitPer::Power)]
+    per: UnitPer,
+) -> Value {
+    format_unit(&unit, per).into()
+}
+
+/// Typesets a quantity: A number followed by a unit.
+///
+/// The number is separated from the unit by a narrow, unbreakable space.
+/// Minus signs are typeset properly, long numbers are grouped into blocks of
+/// three digits, and exponents like in `{"6.022e23"}` are converted into
+/// scientific notation. For the syntax of units, see the
+/// [`unit`]($func/unit) function.
+///
+/// ## Example
+/// ```example
+/// #qty(9.81, "m/s^2") \
+/// #qty("6.02214076e23", "mol^-1") \
+/// #qty(-40, "degC") \
+/// #qty(90, "deg")
+/// ```
+///
+/// Display: Quantity
+/// Category: text
+/// Returns: content
+#[func]
+pub fn qty(
+    /// The numeric value. Pass a string to keep its exact digits.
+    value: QtyValue,
+    /// The unit of the quantity.
+    unit: EcoString,
+    /// How to typeset units in the denominator.
+    #[named]
+    #[default(UnitPer::Power)]
+    per: UnitPer,
+) -> Value {
+    let mut seq = vec![format_number(&value.0)];
+    let symbol = unit.trim();
+    if !matches!(symbol, "deg" | "arcmin" | "arcsec") {
+        seq.push(TextElem::packed('\u{202F}'));
+    }
+    seq.push(format_unit(&unit, per));
+    Value::Content(Content::sequence(seq))
+}
+
+/// Typesets a chemical formula.
+///
+/// Digits directly following an element or a closing bracket become
+/// subscripts, while a charge is written after a `^` or as a trailing `+` or
+/// `-`. Reaction arrows `->`, `<-`, and `<=>` as well as `*` (for adducts
+/// like hydrates) are replaced by the proper symbols.
+///
+/// ## Example
+/// ```example
+/// #ce("H2O") \
+/// #ce("SO4^2-") \
+/// #ce("CuSO4*5H2O") \
+/// #ce("2H2 + O2 -> 2H2O") \
+/// #ce("Na+ + Cl- <=> NaCl")
+/// ```
+///
+/// Display: Chemical Formula
+/// Category: text
+/// Returns: content
+#[func]
+pub fn ce(
+    /// The formula to typeset.
+    formula: EcoString,
+) -> Value {
+    format_formula(&formula).into()
+}
+
+/// Typesets a nuclide with its mass and atomic number.
+///
+/// ## Example
+/// ```example
+/// #isotope("U", mass: 235, atomic: 92) \
+/// #isotope("C", mass: 14)
+/// ```
+///
+/// Display: Isotope
+/// Category: text
+/// Returns: content
+#[func]
+pub fn isotope(
+    /// The symbol of the element.
+    symbol: EcoString,
+    /// The mass number, displayed at the top left.
+    #[named]
+    #[default]
+    mass: Option<i64>,
+    /// The atomic number, displayed at the bottom left.
+    #[named]
+    #[default]
+    atomic: Option<i64>,
+) -> Value {
+    let number = |n: Option<i64>| n.map(|n| TextElem::packed(eco_format!("{n}")));
+    let scripts = AttachElem::new(Content::empty())
+        .with_top(number(mass))
+        .with_bottom(number(atomic))
+        .pack();
+    let symbol = MathStyleElem::new(TextElem::packed(symbol))
+        .with_italic(Some(false))
+        .pack();
+    EquationElem::new(scripts + symbol).with_block(false).pack().into()
+}
+
+/// How to typeset units in the denominator.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum UnitPer {
+    /// Raise the units to negative powers, e.g. m s⁻¹.
+    Power,
+    /// Separate the denominator with a slash, e.g. m/s.
+    Slash,
+}
+
+/// The numeric value of a quantity.
+pub struct QtyValue(EcoString);
+
+cast_from_value! {
+    QtyValue,
+    v: i64 => Self(eco_format!("{v}")),
+    v: f64 => Self(eco_format!("{v}")),
+    v: EcoString => Self(v),
+}
+
+/// Typeset a number, converting an exponent into scientific notation.
+fn format_number(number: &str) -> Content {
+    let number = number.trim();
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(i) => (&number[..i], Some(&number[i + 1..])),
+        None => (number, None),
+    };
+
+    let mut seq = vec![TextElem::packed(group_digits(&minus(mantissa)))];
+    if let Some(exponent) = exponent {
+        let exponent = minus(exponent.trim_start_matches('+'));
+        seq.push(TextElem::packed("\u{202F}×\u{202F}10"));
+        seq.push(SuperElem::new(TextElem::packed(exponent)).pack());
+    }
+
+    Content::sequence(seq)
+}
+
+/// Group the digits of a long number into blocks of three.
+fn group_digits(number: &str) -> EcoString {
+    let (int, frac) = match number.find(['.', ',']) {
+        Some(i) => (&number[..i], Some(&number[i..])),
+        None => (number, None),
+    };
+
+    let digits = int.chars().filter(char::is_ascii_digit).count();
+    let mut out = EcoString::new();
+    let mut remaining = digits;
+    for c in int.chars() {
+        out.push(c);
+        if c.is_ascii_digit() {
+            remaining -= 1;
+            if digits > 4 && remaining > 0 && remaining % 3 == 0 {
+                out.push('\u{202F}');
+            }
+        }
+    }
+
+    if let Some(frac) = frac {
+        let mut chars = frac.chars();
+        if let Some(separator) = chars.next() {
+            out.push(separator);
+        }
+        let rest: Vec<char> = chars.collect();
+        for (i, c) in rest.iter().enumerate() {
+            if rest.len() > 4 && i > 0 && i % 3 == 0 {
+                out.push('\u{202F}');
+            }
+            out.push(*c);
+        }
+    }
+
+    out
+}
+
+/// Replace hyphens with proper minus signs.
+fn minus(text: &str) -> EcoString {
+    text.replace('-', "\u{2212}").into()
+}
+
+/// Typeset a unit.
+fn format_unit(unit: &str, per: UnitPer) -> Content {
+    let mut parts = unit.split('/');
+    let numerator = parts.next().unwrap_or_default();
+    let denominators: Vec<&str> = parts.collect();
+
+    let mut seq = vec![];
+    let push = |seq: &mut Vec<Content>, token: &str, invert: bool| {
+        let (name, exponent) = match token.split_once('^') {
+            Some((name, exponent)) => (name, Some(exponent)),
+            None => (token, None),
+        };
+
+        if !seq.is_empty() {
+            seq.push(TextElem::packed('\u{202F}'));
+        }
+
+        seq.push(TextElem::packed(unit_symbol(name)));
+
+        let exponent = match (exponent, invert) {
+            (Some(exp), true) => match exp.strip_prefix('-') {
+                Some(positive) if positive != "1" => Some(positive.into()),
+                Some(_) => None,
+                None => Some(eco_format!("-{exp}")),
+            },
+            (None, true) => Some("-1".into()),
+            (exp, false) => exp.map(Into::into),
+        };
+
+        if let Some(exponent) = exponent {
+            seq.push(SuperElem::new(TextElem::packed(minus(&exponent))).pack());
+        }
+    };
+
+    for token in numerator.split_whitespace() {
+        push(&mut seq, token, false);
+    }
+
+    for denominator in denominators {
+        match per {
+            UnitPer::Power => {
+                for token in denominator.split_whitespace() {
+                    push(&mut seq, token, true);
+                }
+            }
+            UnitPer::Slash => {
+                seq.push(TextElem::packed('/'));
+                let mut inner = vec![];
+                for token in denominator.split_whitespace() {
+                    push(&mut inner, token, false);
+                }
+                seq.extend(inner);
+            }
+        }
+    }
+
+    Content::sequence(seq)
+}
+
+/// Map a unit name to its symbol.
+fn unit_symbol(name: &str) -> EcoString {
+    match name {
+        "ohm" => "Ω".into(),
+        "deg" => "°".into(),
+        "degC" => "°C".into(),
+        "arcmin" => "′".into(),
+        "arcsec" => "″".into(),
+        "angstrom" => "Å".into(),
+        "percent" => "%".into(),
+        _ => match name.strip_prefix("micro") {
+            Some(rest) => eco_format!("µ{}", unit_symbol(rest)),
+            None => match name.strip_suffix("ohm") {
+                Some(prefix) => eco_format!("{prefix}Ω"),
+                None => name.into(),
+            },
+        },
+    }
+}
+
+/// Typeset a chemical formula.
+fn format_formula(formula: &str) -> Content {
+    let mut seq = vec![];
+    let mut text = EcoString::new();
+    let mut chars = formula.chars();
+    let mut prev: Option<char> = None;
+
+    // Flush the accumulated text and push a sub- or superscript.
+    let script = |seq: &mut Vec<Content>, text: &mut EcoString, body: &str, sup: bool| {
+        if !text.is_empty() {
+            seq.push(TextElem::packed(std::mem::take(text)));
+        }
+        let body = TextElem::packed(minus(body));
+        seq.push(if sup {
+            SuperElem::new(body).pack()
+        } else {
+            SubElem::new(body).pack()
+        });
+    };
+
+    while let Some(c) = chars.next() {
+        let rest = chars.as_str();
+        match c {
+            // Reaction arrows.
+            '-' if rest.starts_with('>') => {
+                chars.next();
+                text.push('→');
+            }
+            '<' if rest.starts_with("=>") => {
+                chars.next();
+                chars.next();
+                text.push('⇌');
+            }
+            '<' if rest.starts_with('-') => {
+                chars.next();
+                text.push('←');
+            }
+
+            // Adducts.
+            '*' => text.push('·'),
+
+            // Explicit charges.
+            '^' => {
+                let mut charge = String::new();
+                while let Some(next) = chars.clone().next() {
+                    if next.is_whitespace() {
+                        break;
+                    }
+                    charge.push(next);
+                    chars.next();
+                }
+                script(&mut seq, &mut text, &charge, true);
+            }
+
+            // Stoichiometric indices.
+            '0'..='9'
+                if prev
+                    .map_or(false, |p| p.is_alphabetic() || matches!(p, ')' | ']')) =>
+            {
+                let mut digits = String::from(c);
+                while let Some(next) = chars.clone().next() {
+                    if !next.is_ascii_digit() {
+                        break;
+                    }
+                    digits.push(next);
+                    chars.next();
+                }
+                script(&mut seq, &mut text, &digits, false);
+                prev = digits.chars().last();
+                continue;
+            }
+
+            // Trailing charges like in `Na+` or `Cl-`.
+            '+' | '-'
+                if prev.map_or(false, |p| !p.is_whitespace())
+                    && chars.clone().next().map_or(true, char::is_whitespace) =>
+            {
+                script(&mut seq, &mut text, &c.to_string(), true);
+            }
+
+            _ => text.push(c),
+        }
+        prev = Some(c);
+    }
+
+    if !text.is_empty() {
+        seq.push(TextElem::packed(text));
+    }
+
+    Content::sequence(seq)
+}
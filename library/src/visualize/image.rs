@@ -1,7 +1,7 @@
 use std::ffi::OsStr;
 use std::path::Path;
 
-use typst::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+use typst::image::{Image, ImageEffects, ImageFormat, RasterFormat, VectorFormat};
 
 use crate::prelude::*;
 
@@ -35,6 +35,29 @@ pub struct ImageElem {
     )]
     pub path: EcoString,
 
+    /// Paths to other representations of the same image, like a PNG version
+    /// of an SVG graphic.
+    ///
+    /// Each export format uses the representation that suits it best: PDF
+    /// export prefers vector graphics and JPEGs, while exporting for GPU
+    /// rendering prefers raster images. A representation that can't be
+    /// decoded is skipped. The image is sized according to the primary path.
+    #[parse(match args.named::<Spanned<Vec<EcoString>>>("alternates")? {
+        Some(Spanned { v: paths, span }) => Some(
+            paths
+                .into_iter()
+                .map(|path| {
+                    let path: EcoString =
+                        vm.locate(&path).at(span)?.to_string_lossy().into();
+                    let _ = load(vm.world(), &path).at(span)?;
+                    Ok(path)
+                })
+                .collect::<SourceResult<_>>()?,
+        ),
+        None => None,
+    })]
+    pub alternates: Vec<EcoString>,
+
     /// The width of the image.
     pub width: Smart<Rel<Length>>,
 
@@ -44,6 +67,57 @@ pub struct ImageElem {
     /// How the image should adjust itself to a given area.
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
+
+    /// A shape to clip the visible part of the image to.
+    ///
+    /// This can be `{"ellipse"}` to clip the image to the ellipse inscribed in
+    /// it or a corner radius to round the image's corners. The radius can be
+    /// given in the same ways as for [rectangles]($func/rect.radius).
+    ///
+    /// ```example
+    /// #set image(width: 3cm, height: 3cm)
+    /// #image("tiger.jpg", clip: "ellipse")
+    /// #image("tiger.jpg", clip: 25%)
+    /// ```
+    pub clip: Option<ImageClip>,
+
+    /// How much to brighten or darken the image, from `{-100%}` to `{100%}`.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", width: 40%, brightness: 20%)
+    /// ```
+    #[parse(match args.named::<Spanned<Ratio>>("brightness")? {
+        Some(Spanned { v, span }) if v.get().abs() > 1.0 => {
+            bail!(span, "brightness must be between -100% and 100%")
+        }
+        brightness => brightness.map(|Spanned { v, .. }| v),
+    })]
+    pub brightness: Ratio,
+
+    /// How much to scale the image's contrast. Values below `{100%}` flatten
+    /// the image while values above it make it more striking.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", width: 40%, contrast: 150%)
+    /// ```
+    #[default(Ratio::one())]
+    pub contrast: Ratio,
+
+    /// Whether to convert the image to gray.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", width: 40%, grayscale: true)
+    /// ```
+    #[default(false)]
+    pub grayscale: bool,
+
+    /// Two colors to tint the image with. The image's dark tones are mapped to
+    /// the first color and its light tones to the second one.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", width: 40%, duotone: (navy, yellow))
+    /// ```
+    pub duotone: Option<Duotone>,
 }
 
 impl Layout for ImageElem {
@@ -53,7 +127,22 @@ impl Layout for ImageElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let image = load(vt.world, &self.path()).unwrap();
+        let alternates = self
+            .alternates(styles)
+            .into_iter()
+            .map(|path| load(vt.world, &path).at(self.span()))
+            .collect::<SourceResult<Vec<_>>>()?;
+        let image = load(vt.world, &self.path())
+            .at(self.span())?
+            .with_alternates(alternates)
+            .with_effects(ImageEffects {
+                brightness: self.brightness(styles),
+                contrast: self.contrast(styles),
+                grayscale: self.grayscale(styles),
+                duotone: self
+                    .duotone(styles)
+                    .map(|Duotone(dark, light)| (dark.to_rgba(), light.to_rgba())),
+            });
         let sizing = Axes::new(self.width(styles), self.height(styles));
         let region = sizing
             .zip(regions.base())
@@ -98,11 +187,23 @@ impl Layout for ImageElem {
         // process.
         let mut frame = Frame::new(fitted);
         frame.push(Point::zero(), FrameItem::Image(image, fitted, self.span()));
+
+        // Clip the image to the requested shape if it is fully visible.
+        let clip = self.clip(styles);
+        if let Some(clip) = clip {
+            if target.fits(fitted) {
+                frame.clip_to(clip.path(fitted, styles));
+            }
+        }
+
         frame.resize(target, Align::CENTER_HORIZON);
 
         // Create a clipping group if only part of the image should be visible.
         if fit == ImageFit::Cover && !target.fits(fitted) {
-            frame.clip();
+            match clip {
+                Some(clip) => frame.clip_to(clip.path(target, styles)),
+                None => frame.clip(),
+            }
         }
 
         // Apply metadata.
@@ -124,6 +225,65 @@ pub enum ImageFit {
     Stretch,
 }
 
+/// A shape to clip an image to.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub enum ImageClip {
+    /// The ellipse inscribed in the image.
+    Ellipse,
+    /// A rectangle with rounded corners.
+    Rounded(Corners<Option<Rel<Length>>>),
+}
+
+impl ImageClip {
+    /// The clipping path for an image of the given size.
+    fn path(self, size: Size, styles: StyleChain) -> Path {
+        match self {
+            Self::Ellipse => ellipse_path(size),
+            Self::Rounded(radius) => {
+                let radius = radius.map(|r| {
+                    r.unwrap_or_default()
+                        .resolve(styles)
+                        .relative_to(size.x.min(size.y) / 2.0)
+                });
+                rounded_rect_path(size, radius)
+            }
+        }
+    }
+}
+
+cast_from_value! {
+    ImageClip,
+    /// Clip the image to the ellipse inscribed in it.
+    "ellipse" => Self::Ellipse,
+    v: Corners<Option<Rel<Length>>> => Self::Rounded(v),
+}
+
+cast_to_value! {
+    v: ImageClip => match v {
+        ImageClip::Ellipse => "ellipse".into(),
+        ImageClip::Rounded(radius) => radius.into(),
+    }
+}
+
+/// The two colors of a duotone effect.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct Duotone(Color, Color);
+
+cast_from_value! {
+    Duotone,
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self(a.cast()?, b.cast()?),
+            _ => Err("duotone array must contain exactly two colors")?,
+        }
+    },
+}
+
+cast_to_value! {
+    v: Duotone => Value::Array(array![v.0, v.1])
+}
+
 /// Load an image from a path.
 #[comemo::memoize]
 fn load(world: Tracked<dyn World>, full: &str) -> StrResult<Image> {
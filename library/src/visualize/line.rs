@@ -40,9 +40,26 @@ pub struct LineElem {
     ///   to `{1pt}`.
     /// - A stroke combined from color and thickness using the `+` operator as
     ///   in `{2pt + red}`.
+    /// - A dictionary with any of the following keys:
+    ///   - `paint`: The color of the stroke.
+    ///   - `thickness`: The thickness of the stroke.
+    ///   - `cap`: How the ends of the line look. One of `{"butt"}`,
+    ///     `{"round"}`, or `{"square"}`.
+    ///   - `join`: How the corners of a path look. One of `{"miter"}`,
+    ///     `{"round"}`, or `{"bevel"}`.
+    ///   - `miter-limit`: How long miter joins may become relative to the
+    ///     thickness before they are beveled instead. Must be at least one.
+    ///   - `dash`: The dash pattern, whose lengths scale with the thickness.
+    ///     One of `{"solid"}`, `{"dotted"}`, `{"densely-dotted"}`,
+    ///     `{"loosely-dotted"}`, `{"dashed"}`, `{"densely-dashed"}`,
+    ///     `{"loosely-dashed"}`, or `{"dash-dotted"}`.
+    ///   - `dash-phase`: How far into the dash pattern the stroke starts.
     ///
     /// ```example
     /// #line(length: 100%, stroke: 2pt + red)
+    /// #line(length: 100%, stroke: (thickness: 4pt, cap: "round"))
+    /// #line(length: 100%, stroke: (thickness: 2pt, dash: "dash-dotted"))
+    /// #line(length: 100%, stroke: (dash: "dashed", dash-phase: 1.5pt))
     /// ```
     #[resolve]
     #[fold]
@@ -47,6 +47,9 @@ pub struct RectElem {
     ///   to `{1pt}`.
     /// - A stroke combined from color and thickness using the `+` operator as
     ///   in `{2pt + red}`.
+    /// - A dictionary with the keys `paint`, `thickness`, `cap`, `join`,
+    ///   `miter-limit`, and `dash`, as described for the
+    ///   [line]($func/line.stroke).
     /// - A dictionary: With a dictionary, the stroke for each side can be set
     ///   individually. The dictionary can contain the following keys in order
     ///   of precedence:
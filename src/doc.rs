@@ -2,11 +2,13 @@
 
 use std::fmt::{self, Debug, Formatter, Write};
 use std::num::NonZeroUsize;
+use std::ops::Range;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 
+use crate::diag::StrResult;
 use crate::eval::{cast_from_value, cast_to_value, dict, Dict, Value};
 use crate::font::Font;
 use crate::geom::{
@@ -16,6 +18,7 @@ use crate::geom::{
 use crate::image::Image;
 use crate::model::{Content, Location, MetaElem, StyleChain};
 use crate::syntax::Span;
+use crate::util::Buffer;
 
 /// A finished document with metadata and page frames.
 #[derive(Debug, Default, Clone, Hash)]
@@ -26,6 +29,186 @@ pub struct Document {
     pub title: Option<EcoString>,
     /// The document's author.
     pub author: Vec<EcoString>,
+    /// The document's subject.
+    pub subject: Option<EcoString>,
+    /// The document's abstract.
+    pub description: Option<EcoString>,
+    /// The document's keywords.
+    pub keywords: Vec<EcoString>,
+    /// The document's creation date.
+    pub date: Option<Date>,
+    /// Entries for the document outline, in document order.
+    pub bookmarks: Vec<Bookmark>,
+    /// Named destinations that can be targeted from outside the document.
+    pub destinations: Vec<(EcoString, Location)>,
+    /// Custom properties for the document's XMP metadata.
+    pub xmp: Vec<XmpProperty>,
+    /// Files to attach to the exported document.
+    pub attachments: Vec<Attachment>,
+}
+
+impl Document {
+    /// The distinct fonts used by the text in the document, in order of first
+    /// use.
+    pub fn fonts(&self) -> Vec<Font> {
+        let mut fonts = vec![];
+        for page in &self.pages {
+            page.collect_fonts(&mut fonts);
+        }
+        fonts
+    }
+}
+
+/// An entry in a document's outline.
+#[derive(Debug, Clone, Hash)]
+pub struct Bookmark {
+    /// The title of the entry.
+    pub title: EcoString,
+    /// The nesting level of the entry, starting at one.
+    pub level: NonZeroUsize,
+    /// Whether the entry's children are initially collapsed.
+    pub closed: bool,
+    /// The location the entry points to.
+    pub location: Location,
+}
+
+/// A custom, namespaced property in a document's XMP metadata.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct XmpProperty {
+    /// The URI identifying the property's schema.
+    pub namespace: EcoString,
+    /// The prefix used for the namespace in the serialized metadata.
+    pub prefix: EcoString,
+    /// The name of the property within its namespace.
+    pub name: EcoString,
+    /// The property's value.
+    pub value: EcoString,
+}
+
+cast_from_value! {
+    XmpProperty,
+    mut dict: Dict => {
+        let namespace: EcoString = dict.take("namespace")?.cast()?;
+        let prefix: EcoString = dict.take("prefix")?.cast()?;
+        let name: EcoString = dict.take("name")?.cast()?;
+        let value: EcoString = dict.take("value")?.cast()?;
+        dict.finish(&["namespace", "prefix", "name", "value"])?;
+        Self { namespace, prefix, name, value }
+    },
+}
+
+cast_to_value! {
+    v: XmpProperty => Value::Dict(dict! {
+        "namespace" => Value::Str(v.namespace.into()),
+        "prefix" => Value::Str(v.prefix.into()),
+        "name" => Value::Str(v.name.into()),
+        "value" => Value::Str(v.value.into()),
+    })
+}
+
+/// A file attached to a document.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Attachment {
+    /// The file name under which the file is attached.
+    pub name: EcoString,
+    /// The raw file contents.
+    pub data: Buffer,
+    /// A description of the file's contents.
+    pub description: Option<EcoString>,
+    /// The file's MIME type, like `text/csv`.
+    pub mime: Option<EcoString>,
+}
+
+/// A calendar date with an optional time of day.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Date {
+    /// The year.
+    pub year: u16,
+    /// The month, from 1 to 12.
+    pub month: u8,
+    /// The day of the month, from 1 to 31.
+    pub day: u8,
+    /// The hour, minute, and second of the day, if any.
+    pub time: Option<(u8, u8, u8)>,
+}
+
+impl Date {
+    /// Format the date as an ISO 8601 string.
+    pub fn to_iso(&self) -> EcoString {
+        let mut iso = eco_format!("{:04}-{:02}-{:02}", self.year, self.month, self.day);
+        if let Some((hour, minute, second)) = self.time {
+            iso.push_str(&eco_format!("T{hour:02}:{minute:02}:{second:02}"));
+        }
+        iso
+    }
+}
+
+cast_from_value! {
+    Date,
+    mut dict: Dict => {
+        let year = take_component(&mut dict, "year", 0, 9999)?
+            .ok_or("missing key: \"year\"")?;
+        let month = take_component(&mut dict, "month", 1, 12)?.unwrap_or(1);
+        let day = take_component(&mut dict, "day", 1, days_in_month(year, month))?
+            .unwrap_or(1);
+        let hour = take_component(&mut dict, "hour", 0, 23)?;
+        let minute = take_component(&mut dict, "minute", 0, 59)?;
+        let second = take_component(&mut dict, "second", 0, 59)?;
+        dict.finish(&["year", "month", "day", "hour", "minute", "second"])?;
+        if hour.is_none() && (minute.is_some() || second.is_some()) {
+            Err("minute and second require an hour")?;
+        }
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            time: hour.map(|hour| {
+                (hour as u8, minute.unwrap_or(0) as u8, second.unwrap_or(0) as u8)
+            }),
+        }
+    },
+}
+
+cast_to_value! {
+    v: Date => {
+        let mut dict = dict! {
+            "year" => Value::Int(v.year.into()),
+            "month" => Value::Int(v.month.into()),
+            "day" => Value::Int(v.day.into()),
+        };
+        if let Some((hour, minute, second)) = v.time {
+            dict.insert("hour".into(), Value::Int(hour.into()));
+            dict.insert("minute".into(), Value::Int(minute.into()));
+            dict.insert("second".into(), Value::Int(second.into()));
+        }
+        Value::Dict(dict)
+    }
+}
+
+/// Take an optional component of a date from a dictionary and check that it
+/// lies within the given range.
+fn take_component(
+    dict: &mut Dict,
+    key: &str,
+    min: i64,
+    max: i64,
+) -> StrResult<Option<i64>> {
+    let Ok(value) = dict.take(key) else { return Ok(None) };
+    let value: i64 = value.cast()?;
+    if !(min..=max).contains(&value) {
+        return Err(eco_format!("{key} must be between {min} and {max}"));
+    }
+    Ok(Some(value))
+}
+
+/// The number of days in a month of a year in the Gregorian calendar.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
 }
 
 /// A finished layout with items at fixed positions.
@@ -115,6 +298,19 @@ impl Frame {
         self.items.iter()
     }
 
+    /// Collect the distinct fonts used in the frame and its children.
+    fn collect_fonts(&self, fonts: &mut Vec<Font>) {
+        for (_, item) in self.items() {
+            match item {
+                FrameItem::Text(text) if !fonts.contains(&text.font) => {
+                    fonts.push(text.font.clone());
+                }
+                FrameItem::Group(group) => group.frame.collect_fonts(fonts),
+                _ => {}
+            }
+        }
+    }
+
     /// Approximately recover the text inside of the frame and its children.
     pub fn text(&self) -> EcoString {
         let mut text = EcoString::new();
@@ -245,6 +441,21 @@ impl Frame {
         }
     }
 
+    /// Remove all visible content from the frame, but keep the metadata of
+    /// identifiable elements so that they can still be introspected.
+    pub fn hide(&mut self) {
+        if !self.is_empty() {
+            Arc::make_mut(&mut self.items).retain_mut(|(_, item)| match item {
+                FrameItem::Group(group) => {
+                    group.frame.hide();
+                    !group.frame.is_empty()
+                }
+                FrameItem::Meta(Meta::Elem(_), _) => true,
+                _ => false,
+            });
+        }
+    }
+
     /// Resize the frame to a new size, distributing new space according to the
     /// given alignments.
     pub fn resize(&mut self, target: Size, aligns: Axes<Align>) {
@@ -273,12 +484,26 @@ impl Frame {
     /// Attach the metadata from this style chain to the frame.
     pub fn meta(&mut self, styles: StyleChain, force: bool) {
         if force || !self.is_empty() {
+            let mut hide = false;
+            let mut artifact = false;
+            let mut tags = vec![];
             for meta in MetaElem::data_in(styles) {
-                if matches!(meta, Meta::Hide) {
-                    self.clear();
-                    break;
+                match meta {
+                    Meta::Hide => hide = true,
+                    Meta::Artifact => artifact = true,
+                    Meta::Tag(tag) => tags.push(tag),
+                    _ => self.prepend(Point::zero(), FrameItem::Meta(meta, self.size)),
+                }
+            }
+            if hide {
+                self.hide();
+            } else {
+                for tag in tags {
+                    self.tag(tag);
+                }
+                if artifact {
+                    self.mark_artifact();
                 }
-                self.prepend(Point::zero(), FrameItem::Meta(meta, self.size));
             }
         }
     }
@@ -325,6 +550,41 @@ impl Frame {
         }
     }
 
+    /// Clip the contents of a frame to a path.
+    pub fn clip_to(&mut self, path: geom::Path) {
+        if !self.is_empty() {
+            self.group(|g| {
+                g.clips = true;
+                g.clip_path = Some(path);
+            });
+        }
+    }
+
+    /// Mark the contents of the frame as part of a semantic element that is
+    /// nested in the semantic elements of the style chain.
+    pub fn tag_in(&mut self, tag: Tag, styles: StyleChain) {
+        self.tag(tag);
+        for meta in MetaElem::data_in(styles) {
+            if let Meta::Tag(tag) = meta {
+                self.tag(tag);
+            }
+        }
+    }
+
+    /// Mark the contents of the frame as part of a semantic element.
+    pub fn tag(&mut self, tag: Tag) {
+        if !self.is_empty() {
+            self.group(|g| g.tag = Some(tag));
+        }
+    }
+
+    /// Mark the contents of the frame as a decorative artifact.
+    pub fn mark_artifact(&mut self) {
+        if !self.is_empty() {
+            self.group(|g| g.artifact = true);
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -359,6 +619,7 @@ impl Frame {
                 Geometry::Line(Point::with_x(self.size.x)).stroked(Stroke {
                     paint: Color::RED.into(),
                     thickness: Abs::pt(1.0),
+                    ..Stroke::default()
                 }),
                 Span::detached(),
             ),
@@ -386,6 +647,7 @@ impl Frame {
                 Geometry::Line(Point::with_x(self.size.x)).stroked(Stroke {
                     paint: Color::GREEN.into(),
                     thickness: Abs::pt(1.0),
+                    ..Stroke::default()
                 }),
                 Span::detached(),
             ),
@@ -438,6 +700,14 @@ pub struct GroupItem {
     pub transform: Transform,
     /// Whether the frame should be a clipping boundary.
     pub clips: bool,
+    /// A path to clip the contents to instead of the frame's bounds. Only
+    /// takes effect if the group `clips`.
+    pub clip_path: Option<geom::Path>,
+    /// Whether the group's contents are purely decorative and should be
+    /// skipped by assistive technologies.
+    pub artifact: bool,
+    /// The semantic element the group's contents belong to.
+    pub tag: Option<Tag>,
 }
 
 impl GroupItem {
@@ -447,6 +717,9 @@ impl GroupItem {
             frame,
             transform: Transform::identity(),
             clips: false,
+            clip_path: None,
+            artifact: false,
+            tag: None,
         }
     }
 }
@@ -469,6 +742,8 @@ pub struct TextItem {
     pub fill: Paint,
     /// The natural language of the text.
     pub lang: Lang,
+    /// The text the glyphs were shaped from.
+    pub text: EcoString,
     /// The glyphs.
     pub glyphs: Vec<Glyph>,
 }
@@ -494,7 +769,7 @@ impl Debug for TextItem {
 }
 
 /// A glyph in a run of shaped text.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Glyph {
     /// The glyph's index in the font.
     pub id: u16,
@@ -504,12 +779,21 @@ pub struct Glyph {
     pub x_offset: Em,
     /// The first character of the glyph's cluster.
     pub c: char,
+    /// The byte range of the glyph's cluster in the item's text.
+    pub range: Range<u16>,
     /// The source code location of the text.
     pub span: Span,
     /// The offset within the spanned text.
     pub offset: u16,
 }
 
+impl Glyph {
+    /// The byte range of the glyph's cluster in the item's text.
+    pub fn range(&self) -> Range<usize> {
+        usize::from(self.range.start)..usize::from(self.range.end)
+    }
+}
+
 /// An identifier for a natural language.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Lang([u8; 3], u8);
@@ -604,14 +888,151 @@ pub enum Meta {
     Elem(Content),
     /// Indicates that content should be hidden. This variant doesn't appear
     /// in the final frames as it is removed alongside the content that should
-    /// be hidden.
+    /// be hidden. The metadata of identifiable elements within the hidden
+    /// content is kept.
     Hide,
+    /// Indicates that content is purely decorative. This variant doesn't
+    /// appear in the final frames as the content is wrapped in an artifact
+    /// group instead.
+    Artifact,
+    /// Content that is placed relative to the page instead of the area this
+    /// metadata is attached to. The page lays out such content itself.
+    Placed(Content),
+    /// Indicates that a page extends this far beyond its trimmed edges on
+    /// each side. Only appears at the top level of page frames.
+    Bleed(Abs),
+    /// The clockwise rotation in degrees with which viewers display a page.
+    /// Always a multiple of 90. Only appears at the top level of page frames.
+    Rotation(u16),
+    /// Marks content as part of a semantic element. This variant doesn't
+    /// appear in the final frames as the content is wrapped in a tagged group
+    /// instead.
+    Tag(Tag),
+    /// The label under which viewers display the page this metadata is on.
+    PageLabel(PageLabel),
+    /// An interactive form field covering the area this metadata is attached
+    /// to.
+    FormField(FormField),
 }
 
 cast_from_value! {
     Meta: "meta",
 }
 
+/// How a page is labelled in viewers, e.g. `iv` for a page in the preface.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PageLabel {
+    /// The style of the number. If `None`, the label consists only of the
+    /// prefix.
+    pub style: Option<PageLabelStyle>,
+    /// Text displayed in front of the number.
+    pub prefix: Option<EcoString>,
+    /// The number of the page.
+    pub number: NonZeroUsize,
+}
+
+impl PageLabel {
+    /// A label with just an arabic number.
+    pub fn arabic(number: NonZeroUsize) -> Self {
+        Self {
+            style: Some(PageLabelStyle::Arabic),
+            prefix: None,
+            number,
+        }
+    }
+}
+
+/// The numbering style of a page label.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PageLabelStyle {
+    /// Arabic numerals: 1, 2, 3.
+    Arabic,
+    /// Lowercase roman numerals: i, ii, iii.
+    LowerRoman,
+    /// Uppercase roman numerals: I, II, III.
+    UpperRoman,
+    /// Lowercase letters: a, b, c.
+    LowerAlpha,
+    /// Uppercase letters: A, B, C.
+    UpperAlpha,
+}
+
+/// An interactive form field that readers can fill in.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FormField {
+    /// The name that identifies the field's value when the form is submitted
+    /// or exported.
+    pub name: EcoString,
+    /// What kind of field this is.
+    pub kind: FormFieldKind,
+    /// A description shown when hovering over the field.
+    pub tooltip: Option<EcoString>,
+}
+
+/// The kind of a form field.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum FormFieldKind {
+    /// A text input with an initial value.
+    Text { value: EcoString, multiline: bool },
+    /// A checkbox that is either checked or not.
+    Checkbox { checked: bool },
+    /// A placeholder for a digital signature.
+    Signature,
+}
+
+/// Identifies the semantic element that content belongs to, for accessible
+/// export formats.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Tag {
+    /// What kind of element the content belongs to.
+    pub role: Role,
+    /// Identifies the element. Parts of the same element, like the lines of
+    /// a paragraph split across pages, share a location.
+    pub location: Location,
+    /// Where the element is read among its siblings. Siblings are read in
+    /// ascending order, keeping the document order for equal ones.
+    pub order: i64,
+    /// A textual alternative for the element, like a description of a
+    /// figure.
+    pub alt: Option<EcoString>,
+}
+
+impl Tag {
+    /// Create a new tag that is read in document order.
+    pub fn new(role: Role, location: Location) -> Self {
+        Self { role, location, order: 0, alt: None }
+    }
+
+    /// Read the element at the given position among its siblings.
+    pub fn with_order(self, order: i64) -> Self {
+        Self { order, ..self }
+    }
+
+    /// Describe the element with a textual alternative.
+    pub fn with_alt(self, alt: Option<EcoString>) -> Self {
+        Self { alt, ..self }
+    }
+}
+
+/// The kind of a semantic element.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Role {
+    /// A paragraph.
+    Paragraph,
+    /// A heading of the given level.
+    Heading(NonZeroUsize),
+    /// A bullet or numbered list.
+    List,
+    /// An item of a list, including its marker.
+    ListItem,
+    /// A figure with its caption.
+    Figure,
+    /// A link.
+    Link,
+    /// A group of content with its own place in the reading order.
+    Section,
+}
+
 /// A link destination.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Destination {
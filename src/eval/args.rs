@@ -46,6 +46,15 @@ impl Args {
         })
     }
 
+    /// Push a named argument.
+    pub fn push_named(&mut self, span: Span, name: Str, value: Value) {
+        self.items.push(Arg {
+            span: self.span,
+            name: Some(name),
+            value: Spanned::new(value, span),
+        })
+    }
+
     /// Consume and cast the first positional argument if there is one.
     pub fn eat<T>(&mut self) -> SourceResult<Option<T>>
     where
@@ -4,8 +4,9 @@ use std::ops::{Add, AddAssign};
 
 use ecow::{eco_format, EcoString, EcoVec};
 
-use super::{ops, Args, Func, Value, Vm};
+use super::{ops, Args, Dict, Func, Str, Value, Vm};
 use crate::diag::{At, SourceResult, StrResult};
+use crate::syntax::Span;
 use crate::util::pretty_array_like;
 
 /// Create a new [`Array`] from values.
@@ -271,13 +272,33 @@ impl Array {
         Ok(result)
     }
 
-    /// Return a sorted version of this array.
+    /// Return a sorted version of this array, optionally ordered by the keys
+    /// a function returns for the items.
     ///
     /// Returns an error if two values could not be compared.
-    pub fn sorted(&self) -> StrResult<Self> {
+    pub fn sorted(
+        &self,
+        vm: &mut Vm,
+        span: Span,
+        key: Option<Func>,
+    ) -> SourceResult<Self> {
+        // Compute the keys up front so that the key function is called only
+        // once per item.
+        let keys = match &key {
+            Some(func) => self
+                .iter()
+                .map(|item| {
+                    let args = Args::new(func.span(), [item.clone()]);
+                    func.call_vm(vm, args)
+                })
+                .collect::<SourceResult<Vec<_>>>()?,
+            None => self.0.to_vec(),
+        };
+
         let mut result = Ok(());
-        let mut vec = self.0.clone();
-        vec.make_mut().sort_by(|a, b| {
+        let mut indices: Vec<usize> = (0..keys.len()).collect();
+        indices.sort_by(|&a, &b| {
+            let (a, b) = (&keys[a], &keys[b]);
             a.partial_cmp(b).unwrap_or_else(|| {
                 if result.is_ok() {
                     result = Err(eco_format!(
@@ -289,7 +310,67 @@ impl Array {
                 Ordering::Equal
             })
         });
-        result.map(|_| Self::from_vec(vec))
+        result.at(span)?;
+        Ok(indices.into_iter().map(|i| self.0[i].clone()).collect())
+    }
+
+    /// Combine this array with another one into an array of pairs. The result
+    /// is as long as the shorter of the two arrays.
+    pub fn zip(&self, other: Array) -> Self {
+        self.iter()
+            .zip(other)
+            .map(|(a, b)| Value::Array(array![a.clone(), b]))
+            .collect()
+    }
+
+    /// All overlapping windows of `size` consecutive items.
+    pub fn windows(&self, size: i64) -> StrResult<Self> {
+        let size = Self::positive(size, "window size")?;
+        Ok(self
+            .0
+            .windows(size)
+            .map(|window| Value::Array(window.iter().cloned().collect()))
+            .collect())
+    }
+
+    /// Split the array into chunks of `size` items. The last chunk may be
+    /// shorter.
+    pub fn chunks(&self, size: i64) -> StrResult<Self> {
+        let size = Self::positive(size, "chunk size")?;
+        Ok(self
+            .0
+            .chunks(size)
+            .map(|chunk| Value::Array(chunk.iter().cloned().collect()))
+            .collect())
+    }
+
+    /// Remove all but the first of consecutive equal items.
+    pub fn dedup(&self) -> Self {
+        let mut vec = self.0.clone();
+        vec.make_mut().dedup();
+        Self::from_vec(vec)
+    }
+
+    /// Group the items by the string key the function returns for them.
+    pub fn group_by(&self, vm: &mut Vm, func: Func) -> SourceResult<Dict> {
+        let mut groups = Dict::new();
+        for item in self.iter() {
+            let args = Args::new(func.span(), [item.clone()]);
+            let key = func.call_vm(vm, args)?.cast::<Str>().at(func.span())?;
+            match groups.at_mut(&key) {
+                Ok(Value::Array(group)) => group.push(item.clone()),
+                _ => groups.insert(key, Value::Array(array![item.clone()])),
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Check that a size is positive.
+    fn positive(size: i64, what: &str) -> StrResult<usize> {
+        usize::try_from(size)
+            .ok()
+            .filter(|&size| size > 0)
+            .ok_or_else(|| eco_format!("{what} must be positive"))
     }
 
     /// Repeat this array `n` times.
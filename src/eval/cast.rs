@@ -267,7 +267,13 @@ impl CastInfo {
                         *matching_type = true;
                     }
                 }
-                CastInfo::Type(ty) => parts.push((*ty).into()),
+                CastInfo::Type(ty) => {
+                    // A type that is accepted in several ways, like a
+                    // dictionary for both a stroke and the sides it is set
+                    // for, is only mentioned at its last position.
+                    parts.retain(|part| part != ty);
+                    parts.push((*ty).into());
+                }
                 CastInfo::Union(options) => {
                     for option in options {
                         accumulate(option, found, parts, matching_type);
@@ -5,8 +5,8 @@ use std::sync::Arc;
 
 use ecow::{eco_format, EcoString};
 
-use super::{array, Array, Str, Value};
-use crate::diag::StrResult;
+use super::{array, Args, Array, Func, Str, Value, Vm};
+use crate::diag::{At, SourceResult, StrResult};
 use crate::syntax::is_ident;
 use crate::util::{pretty_array_like, separated_list, ArcExt};
 
@@ -115,6 +115,31 @@ impl Dict {
             .collect()
     }
 
+    /// Produce a new dictionary with only the pairs for which the function
+    /// returns `true` when called with the key and value.
+    pub fn filter(&self, vm: &mut Vm, func: Func) -> SourceResult<Self> {
+        let mut kept = BTreeMap::new();
+        for (key, value) in self.iter() {
+            let args = Args::new(func.span(), [Value::Str(key.clone()), value.clone()]);
+            if func.call_vm(vm, args)?.cast::<bool>().at(func.span())? {
+                kept.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(Self::from_map(kept))
+    }
+
+    /// Transform each pair with a function that receives the key and value
+    /// and collect the results into an array.
+    pub fn map(&self, vm: &mut Vm, func: Func) -> SourceResult<Array> {
+        self.iter()
+            .map(|(key, value)| {
+                let args =
+                    Args::new(func.span(), [Value::Str(key.clone()), value.clone()]);
+                func.call_vm(vm, args)
+            })
+            .collect()
+    }
+
     /// Iterate over pairs of references to the contained keys and values.
     pub fn iter(&self) -> std::collections::btree_map::Iter<Str, Value> {
         self.0.iter()
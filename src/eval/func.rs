@@ -83,6 +83,15 @@ impl Func {
         }
     }
 
+    /// Whether the function declares a named parameter with the given name.
+    pub fn takes_named(&self, name: &str) -> bool {
+        match &self.repr {
+            Repr::Closure(closure) => closure.takes_named(name),
+            Repr::With(arc) => arc.0.takes_named(name),
+            _ => false,
+        }
+    }
+
     /// Call the function with the given arguments.
     pub fn call_vm(&self, vm: &mut Vm, mut args: Args) -> SourceResult<Value> {
         match &self.repr {
@@ -126,11 +135,15 @@ impl Func {
         vt: &mut Vt,
         args: impl IntoIterator<Item = Value>,
     ) -> SourceResult<Value> {
+        self.call_vt_args(vt, Args::new(self.span(), args))
+    }
+
+    /// Call the function with a Vt and prepared arguments.
+    pub fn call_vt_args(&self, vt: &mut Vt, args: Args) -> SourceResult<Value> {
         let route = Route::default();
         let id = SourceId::detached();
         let scopes = Scopes::new(None);
         let mut vm = Vm::new(vt.reborrow_mut(), route.track(), id, scopes);
-        let args = Args::new(self.span(), args);
         self.call_vm(&mut vm, args)
     }
 
@@ -353,6 +366,13 @@ impl Closure {
 
         Some(self.params.iter().filter(|(_, default)| default.is_none()).count())
     }
+
+    /// Whether the closure declares a named parameter with the given name.
+    fn takes_named(&self, name: &str) -> bool {
+        self.params
+            .iter()
+            .any(|(param, default)| default.is_some() && param.as_str() == name)
+    }
 }
 
 impl From<Closure> for Func {
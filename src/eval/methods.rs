@@ -2,7 +2,7 @@
 
 use ecow::EcoString;
 
-use super::{Args, Str, Value, Vm};
+use super::{Args, Str, StrUnit, Value, Vm};
 use crate::diag::{At, SourceResult};
 use crate::model::Location;
 use crate::syntax::Span;
@@ -23,11 +23,15 @@ pub fn call(
             "lighten" => Value::Color(color.lighten(args.expect("amount")?)),
             "darken" => Value::Color(color.darken(args.expect("amount")?)),
             "negate" => Value::Color(color.negate()),
+            "contrast" => Value::Float(color.contrast(args.expect("other")?)),
             _ => return missing(),
         },
 
         Value::Str(string) => match method {
-            "len" => Value::Int(string.len() as i64),
+            "len" => {
+                let unit = args.named("unit")?.unwrap_or(StrUnit::Bytes);
+                Value::Int(string.len_in(unit))
+            }
             "first" => Value::Str(string.first().at(span)?),
             "last" => Value::Str(string.last().at(span)?),
             "at" => Value::Str(string.at(args.expect("index")?).at(span)?),
@@ -37,10 +41,14 @@ pub fn call(
                 if end.is_none() {
                     end = args.named("count")?.map(|c: i64| start + c);
                 }
-                Value::Str(string.slice(start, end).at(span)?)
+                let unit = args.named("unit")?.unwrap_or(StrUnit::Bytes);
+                Value::Str(string.slice_in(start, end, unit).at(span)?)
             }
             "clusters" => Value::Array(string.clusters()),
             "codepoints" => Value::Array(string.codepoints()),
+            "words" => Value::Array(string.words()),
+            "upper" => Value::Str(string.upper(args.named("lang")?)),
+            "lower" => Value::Str(string.lower(args.named("lang")?)),
             "contains" => Value::Bool(string.contains(args.expect("pattern")?)),
             "starts-with" => Value::Bool(string.starts_with(args.expect("pattern")?)),
             "ends-with" => Value::Bool(string.ends_with(args.expect("pattern")?)),
@@ -115,7 +123,12 @@ pub fn call(
                 let last = args.named("last")?;
                 array.join(sep, last).at(span)?
             }
-            "sorted" => Value::Array(array.sorted().at(span)?),
+            "sorted" => Value::Array(array.sorted(vm, span, args.named("key")?)?),
+            "zip" => Value::Array(array.zip(args.expect("other")?)),
+            "windows" => Value::Array(array.windows(args.expect("size")?).at(span)?),
+            "chunks" => Value::Array(array.chunks(args.expect("size")?).at(span)?),
+            "dedup" => Value::Array(array.dedup()),
+            "group-by" => Value::Dict(array.group_by(vm, args.expect("function")?)?),
             _ => return missing(),
         },
 
@@ -125,6 +138,8 @@ pub fn call(
             "keys" => Value::Array(dict.keys()),
             "values" => Value::Array(dict.values()),
             "pairs" => Value::Array(dict.pairs()),
+            "filter" => Value::Dict(dict.filter(vm, args.expect("function")?)?),
+            "map" => Value::Array(dict.map(vm, args.expect("function")?)?),
             _ => return missing(),
         },
 
@@ -252,7 +267,9 @@ fn missing_method(type_name: &str, method: &str) -> String {
 /// List the available methods for a type and whether they take arguments.
 pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
     match type_name {
-        "color" => &[("lighten", true), ("darken", true), ("negate", false)],
+        "color" => {
+            &[("lighten", true), ("darken", true), ("negate", false), ("contrast", true)]
+        }
         "string" => &[
             ("len", false),
             ("at", true),
@@ -263,6 +280,7 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("find", true),
             ("first", false),
             ("last", false),
+            ("lower", false),
             ("match", true),
             ("matches", true),
             ("position", true),
@@ -271,18 +289,23 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("split", true),
             ("starts-with", true),
             ("trim", true),
+            ("upper", false),
+            ("words", false),
         ],
         "content" => &[("func", false), ("has", true), ("at", true), ("location", false)],
         "array" => &[
             ("all", true),
             ("any", true),
             ("at", true),
+            ("chunks", true),
             ("contains", true),
+            ("dedup", false),
             ("filter", true),
             ("find", true),
             ("first", false),
             ("flatten", false),
             ("fold", true),
+            ("group-by", true),
             ("insert", true),
             ("split", true),
             ("join", true),
@@ -295,13 +318,17 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("remove", true),
             ("rev", false),
             ("slice", true),
-            ("sorted", false),
+            ("sorted", true),
+            ("windows", true),
+            ("zip", true),
         ],
         "dictionary" => &[
             ("at", true),
+            ("filter", true),
             ("insert", true),
             ("keys", false),
             ("len", false),
+            ("map", true),
             ("pairs", false),
             ("remove", true),
             ("values", false),
@@ -6,7 +6,10 @@ use ecow::eco_format;
 
 use super::{format_str, Regex, Value};
 use crate::diag::StrResult;
-use crate::geom::{Axes, Axis, GenAlign, Length, Numeric, PartialStroke, Rel, Smart};
+use crate::geom::{
+    Axes, Axis, GenAlign, Gradient, Length, Numeric, Paint, PartialStroke, Pattern, Rel,
+    Smart,
+};
 use Value::*;
 
 /// Bail with a type mismatch error.
@@ -108,6 +111,17 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
             Value::dynamic(PartialStroke {
                 paint: Smart::Custom(color.into()),
                 thickness: Smart::Custom(thickness),
+                ..Default::default()
+            })
+        }
+
+        (Dyn(paint), Length(thickness)) | (Length(thickness), Dyn(paint))
+            if paint.is::<Gradient>() || paint.is::<Pattern>() =>
+        {
+            Value::dynamic(PartialStroke {
+                paint: Smart::Custom(Dyn(paint).cast::<Paint>()?),
+                thickness: Smart::Custom(thickness),
+                ..Default::default()
             })
         }
 
@@ -6,8 +6,9 @@ use std::ops::{Add, AddAssign, Deref};
 use ecow::EcoString;
 use unicode_segmentation::UnicodeSegmentation;
 
-use super::{cast_from_value, dict, Array, Dict, Value};
+use super::{cast_from_value, dict, Array, Cast, Dict, Value};
 use crate::diag::StrResult;
+use crate::doc::Lang;
 use crate::geom::GenAlign;
 
 /// Create a new [`Str`] from a format string.
@@ -39,6 +40,15 @@ impl Str {
         self.0.len() as i64
     }
 
+    /// The length of the string in the given unit.
+    pub fn len_in(&self, unit: StrUnit) -> i64 {
+        match unit {
+            StrUnit::Bytes => self.len(),
+            StrUnit::Codepoints => self.0.chars().count() as i64,
+            StrUnit::Clusters => self.0.graphemes(true).count() as i64,
+        }
+    }
+
     /// A string slice containing the entire string.
     pub fn as_str(&self) -> &str {
         self
@@ -79,6 +89,19 @@ impl Str {
         Ok(self.0[start..end].into())
     }
 
+    /// Extract a contiguous substring with indices measured in the given unit.
+    pub fn slice_in(
+        &self,
+        start: i64,
+        end: Option<i64>,
+        unit: StrUnit,
+    ) -> StrResult<Self> {
+        let len = self.len_in(unit);
+        let start = self.locate_in(start, len, unit)?;
+        let end = self.locate_in(end.unwrap_or(len), len, unit)?.max(start);
+        Ok(self.0[start..end].into())
+    }
+
     /// The grapheme clusters the string consists of.
     pub fn clusters(&self) -> Array {
         self.as_str().graphemes(true).map(|s| Value::Str(s.into())).collect()
@@ -89,6 +112,32 @@ impl Str {
         self.chars().map(|c| Value::Str(c.into())).collect()
     }
 
+    /// The words the string consists of, as determined by Unicode word
+    /// boundaries. Punctuation and whitespace are not included.
+    pub fn words(&self) -> Array {
+        self.as_str().unicode_words().map(|s| Value::Str(s.into())).collect()
+    }
+
+    /// Convert the string to uppercase, respecting the rules of the given
+    /// language.
+    pub fn upper(&self, lang: Option<Lang>) -> Self {
+        if is_turkic(lang) {
+            self.0.replace('i', "İ").to_uppercase().into()
+        } else {
+            self.0.to_uppercase().into()
+        }
+    }
+
+    /// Convert the string to lowercase, respecting the rules of the given
+    /// language.
+    pub fn lower(&self, lang: Option<Lang>) -> Self {
+        if is_turkic(lang) {
+            self.0.replace('I', "ı").replace('İ', "i").to_lowercase().into()
+        } else {
+            self.0.to_lowercase().into()
+        }
+    }
+
     /// Whether the given pattern exists in this string.
     pub fn contains(&self, pattern: StrPattern) -> bool {
         match pattern {
@@ -292,6 +341,43 @@ impl Str {
 
         Ok(resolved)
     }
+
+    /// Resolve an index in the given unit to a byte offset.
+    fn locate_in(&self, index: i64, len: i64, unit: StrUnit) -> StrResult<usize> {
+        let resolved = match unit {
+            StrUnit::Bytes => return self.locate(index),
+            _ if index >= 0 => Some(index),
+            _ => len.checked_add(index),
+        }
+        .filter(|&v| v >= 0 && v <= len)
+        .ok_or_else(|| out_of_bounds(index, len))? as usize;
+
+        let offset = match unit {
+            StrUnit::Bytes => unreachable!(),
+            StrUnit::Codepoints => self.0.char_indices().map(|(i, _)| i).nth(resolved),
+            StrUnit::Clusters => {
+                self.0.grapheme_indices(true).map(|(i, _)| i).nth(resolved)
+            }
+        };
+
+        Ok(offset.unwrap_or(self.0.len()))
+    }
+}
+
+/// The unit in which the length of and indices into a string are measured.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum StrUnit {
+    /// UTF-8 encoded bytes.
+    Bytes,
+    /// Unicode codepoints.
+    Codepoints,
+    /// Grapheme clusters, i.e. user-perceived characters.
+    Clusters,
+}
+
+/// Whether the language uses the Turkic dotted and dotless i.
+fn is_turkic(lang: Option<Lang>) -> bool {
+    lang.map_or(false, |lang| matches!(lang.as_str(), "tr" | "az"))
 }
 
 /// The out of bounds access error message.
@@ -0,0 +1,199 @@
+//! Export of a document's metadata and outline into HTML.
+
+use std::fmt::Write;
+
+use crate::doc::Document;
+use crate::model::Introspector;
+
+/// Export a document's metadata and heading outline into an HTML page.
+///
+/// This is meant to accompany the exported PDF file in publication pipelines
+/// that also need indexable web metadata. The page contains:
+/// - The document's title, authors, keywords, creation date, and abstract,
+///   both as `meta` tags and as visible text.
+/// - A nested list of the document's bookmarks, which include its headings
+///   unless they opt out of being bookmarked.
+///
+/// If `pdf` is given, it is used as the path of the PDF file relative to the
+/// HTML page and each outline entry links to its page in that file.
+pub fn html(document: &Document, pdf: Option<&str>) -> String {
+    let introspector = Introspector::new(&document.pages);
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+
+    if let Some(title) = &document.title {
+        writeln!(out, "<title>{}</title>", escape(title)).unwrap();
+    }
+
+    let meta = |out: &mut String, name: &str, content: &str| {
+        writeln!(out, "<meta name=\"{name}\" content=\"{}\">", escape(content)).unwrap();
+    };
+
+    if !document.author.is_empty() {
+        meta(&mut out, "author", &document.author.join(", "));
+    }
+
+    if let Some(description) = document.description.as_ref().or(document.subject.as_ref())
+    {
+        meta(&mut out, "description", description);
+    }
+
+    if !document.keywords.is_empty() {
+        meta(&mut out, "keywords", &document.keywords.join(", "));
+    }
+
+    if let Some(date) = document.date {
+        meta(&mut out, "dcterms.created", &date.to_iso());
+    }
+
+    out.push_str("</head>\n<body>\n");
+
+    if let Some(title) = &document.title {
+        writeln!(out, "<h1>{}</h1>", escape(title)).unwrap();
+    }
+
+    if !document.author.is_empty() {
+        let authors = document.author.join(", ");
+        writeln!(out, "<p class=\"author\">{}</p>", escape(&authors)).unwrap();
+    }
+
+    if let Some(description) = &document.description {
+        writeln!(
+            out,
+            "<section class=\"abstract\">\n<p>{}</p>\n</section>",
+            escape(description)
+        )
+        .unwrap();
+    }
+
+    if !document.bookmarks.is_empty() {
+        out.push_str("<nav>\n");
+
+        // The number of currently open lists. Entries can be at most one
+        // level deeper than their predecessor so that lists always nest
+        // within an item.
+        let mut open = 0;
+        for bookmark in &document.bookmarks {
+            let level = bookmark.level.get().min(open + 1);
+            if level > open {
+                out.push_str("<ol>");
+                open += 1;
+            } else {
+                out.push_str("</li>");
+                while open > level {
+                    out.push_str("</ol></li>");
+                    open -= 1;
+                }
+            }
+
+            let title = escape(&bookmark.title);
+            match pdf {
+                Some(pdf) => {
+                    let page = introspector.page(bookmark.location);
+                    write!(
+                        out,
+                        "\n<li><a href=\"{}#page={page}\">{title}</a>",
+                        escape(pdf)
+                    )
+                    .unwrap();
+                }
+                None => write!(out, "\n<li>{title}").unwrap(),
+            }
+        }
+
+        out.push_str("</li>");
+        while open > 1 {
+            out.push_str("</ol></li>");
+            open -= 1;
+        }
+        out.push_str("</ol>\n</nav>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Escape text for use in HTML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::doc::{Bookmark, Frame, FrameItem, Meta};
+    use crate::geom::{Abs, Size};
+    use crate::model::{Content, StabilityProvider};
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(escape("a < b & \"c\""), "a &lt; b &amp; &quot;c&quot;");
+    }
+
+    #[test]
+    fn test_metadata() {
+        let document = Document {
+            title: Some("Q&A".into()),
+            author: vec!["Ada".into(), "Grace".into()],
+            description: Some("Questions and answers.".into()),
+            ..Default::default()
+        };
+
+        let out = html(&document, None);
+        assert!(out.contains("<title>Q&amp;A</title>"));
+        assert!(out.contains("<meta name=\"author\" content=\"Ada, Grace\">"));
+        assert!(out
+            .contains("<meta name=\"description\" content=\"Questions and answers.\">"));
+        assert!(!out.contains("<nav>"));
+    }
+
+    #[test]
+    fn test_outline() {
+        let mut provider = StabilityProvider::new();
+        let mut pages = vec![];
+        let mut bookmarks = vec![];
+        for (i, (title, level)) in
+            [("Intro", 1), ("Details", 2), ("End", 1)].into_iter().enumerate()
+        {
+            let location = provider.locate(i as u128);
+            let mut content = Content::empty();
+            content.set_location(location);
+            let mut frame = Frame::new(Size::splat(Abs::pt(100.0)));
+            frame.push(
+                Default::default(),
+                FrameItem::Meta(Meta::Elem(content), Size::zero()),
+            );
+            pages.push(frame);
+            bookmarks.push(Bookmark {
+                title: title.into(),
+                level: NonZeroUsize::new(level).unwrap(),
+                closed: false,
+                location,
+            });
+        }
+
+        let document = Document { pages, bookmarks, ..Default::default() };
+        let out = html(&document, Some("a&b.pdf"));
+        assert!(out.contains(concat!(
+            "<nav>\n<ol>\n<li><a href=\"a&amp;b.pdf#page=1\">Intro</a>",
+            "<ol>\n<li><a href=\"a&amp;b.pdf#page=2\">Details</a></li></ol></li>",
+            "\n<li><a href=\"a&amp;b.pdf#page=3\">End</a></li></ol>\n</nav>",
+        )));
+
+        let out = html(&document, None);
+        assert!(out.contains("\n<li>Details</li></ol></li>\n<li>End</li></ol>"));
+    }
+}
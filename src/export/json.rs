@@ -0,0 +1,494 @@
+//! Serialization of laid out frames into JSON.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::num::NonZeroUsize;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ecow::EcoString;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::doc::{Destination, Document, Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::font::Font;
+use crate::geom::{
+    Abs, Color, Geometry, GradientKind, LineCap, LineJoin, Paint, Path, PathItem,
+    PatternKind, Size, Stroke, Transform,
+};
+use crate::image::{DecodedImage, Image, ImageFormat, RasterFormat, VectorFormat};
+use crate::model::Introspector;
+
+/// The version of the JSON format. Incremented whenever the format changes in
+/// a backwards-incompatible way.
+pub const JSON_VERSION: u32 = 1;
+
+/// Serialize a document's pages into JSON.
+///
+/// This allows separate processes, like preview renderers, to consume layout
+/// results without linking the compiler. The result is an object with the
+/// following fields:
+/// - `version`: The format version, see [`JSON_VERSION`].
+/// - `pages`: The page frames. A frame has a `width`, a `height`, an optional
+///   `baseline`, and a list of `items`. Each item has an `x` and `y` position
+///   and a `kind`, which is one of `group`, `text`, `shape`, `image`, or
+///   `link`. The remaining fields depend on the kind.
+/// - `fonts`: The fonts referenced by text items, with their base64-encoded
+///   data and their index within the data.
+/// - `images`: The images referenced by image items, with their format, pixel
+///   size, and base64-encoded data.
+///
+/// All lengths are in points. Glyph advances and offsets are in em units of the
+/// text's font size. Numbers that aren't finite are written as `null`.
+pub fn json(document: &Document) -> String {
+    let mut ctx = JsonContext {
+        introspector: Introspector::new(&document.pages),
+        fonts: vec![],
+        font_map: HashMap::new(),
+        images: vec![],
+        image_map: HashMap::new(),
+    };
+
+    let pages = document.pages.iter().map(|page| ctx.frame(page)).collect();
+    let fonts = ctx
+        .fonts
+        .iter()
+        .map(|font| JsonFont {
+            family: font.info().family.clone(),
+            index: font.index(),
+            data: STANDARD.encode(font.data()),
+        })
+        .collect();
+
+    let images = ctx
+        .images
+        .iter()
+        .map(|image| {
+            let mut format = match image.format() {
+                ImageFormat::Raster(RasterFormat::Png) => "png",
+                ImageFormat::Raster(RasterFormat::Jpg) => "jpg",
+                ImageFormat::Raster(RasterFormat::Gif) => "gif",
+                ImageFormat::Vector(VectorFormat::Svg) => "svg",
+            };
+
+            // Images with color effects are embedded with the effects applied.
+            let mut data = image.data().as_slice();
+            let encoded = encode_effects(image);
+            if let Some(encoded) = &encoded {
+                format = "png";
+                data = encoded;
+            }
+
+            JsonImage {
+                format,
+                width: image.width(),
+                height: image.height(),
+                data: STANDARD.encode(data),
+            }
+        })
+        .collect();
+
+    let output = JsonDocument { version: JSON_VERSION, pages, fonts, images };
+    serde_json::to_string(&output).unwrap()
+}
+
+/// Context for JSON serialization.
+struct JsonContext {
+    introspector: Introspector,
+    fonts: Vec<Font>,
+    font_map: HashMap<Font, usize>,
+    images: Vec<Image>,
+    image_map: HashMap<Image, usize>,
+}
+
+impl JsonContext {
+    fn frame(&mut self, frame: &Frame) -> JsonFrame {
+        let items = frame
+            .items()
+            .filter_map(|(pos, item)| {
+                let kind = match item {
+                    FrameItem::Group(group) => self.group(group),
+                    FrameItem::Text(text) => self.text(text),
+                    FrameItem::Shape(shape, _) => JsonItemKind::Shape {
+                        geometry: geometry(&shape.geometry),
+                        fill: shape.fill.map(paint),
+                        stroke: shape.stroke.map(stroke),
+                    },
+                    FrameItem::Image(image, size, _) => {
+                        let image = image.select(&PREFERRED_FORMATS);
+                        let index =
+                            *self.image_map.entry(image.clone()).or_insert_with(|| {
+                                self.images.push(image.clone());
+                                self.images.len() - 1
+                            });
+                        JsonItemKind::Image {
+                            image: index,
+                            width: size.x.to_pt(),
+                            height: size.y.to_pt(),
+                        }
+                    }
+                    FrameItem::Meta(Meta::Link(dest), size) => self.link(dest, *size),
+                    // Metadata other than links doesn't affect rendering.
+                    FrameItem::Meta(..) => return None,
+                };
+                Some(JsonItem { x: pos.x.to_pt(), y: pos.y.to_pt(), kind })
+            })
+            .collect();
+
+        JsonFrame {
+            width: frame.width().to_pt(),
+            height: frame.height().to_pt(),
+            baseline: frame.has_baseline().then(|| frame.baseline().to_pt()),
+            items,
+        }
+    }
+
+    fn group(&mut self, group: &GroupItem) -> JsonItemKind {
+        let Transform { sx, ky, kx, sy, tx, ty } = group.transform;
+        JsonItemKind::Group {
+            transform: [sx.get(), ky.get(), kx.get(), sy.get(), tx.to_pt(), ty.to_pt()],
+            clips: group.clips,
+            artifact: group.artifact,
+            clip_path: group.clip_path.as_ref().map(path),
+            frame: Box::new(self.frame(&group.frame)),
+        }
+    }
+
+    fn text(&mut self, text: &TextItem) -> JsonItemKind {
+        let index = *self.font_map.entry(text.font.clone()).or_insert_with(|| {
+            self.fonts.push(text.font.clone());
+            self.fonts.len() - 1
+        });
+
+        JsonItemKind::Text {
+            font: index,
+            size: text.size.to_pt(),
+            fill: paint(text.fill),
+            lang: text.lang.as_str().into(),
+            glyphs: text
+                .glyphs
+                .iter()
+                .map(|glyph| JsonGlyph {
+                    id: glyph.id,
+                    x_advance: glyph.x_advance.get(),
+                    x_offset: glyph.x_offset.get(),
+                    c: glyph.c,
+                })
+                .collect(),
+        }
+    }
+
+    fn link(&self, dest: &Destination, size: Size) -> JsonItemKind {
+        let (url, pos) = match dest {
+            Destination::Url(url) => (Some(url.clone()), None),
+            Destination::Position(pos) => (None, Some(*pos)),
+            Destination::Location(loc) => (None, Some(self.introspector.position(*loc))),
+        };
+
+        JsonItemKind::Link {
+            width: size.x.to_pt(),
+            height: size.y.to_pt(),
+            url,
+            page: pos.map(|pos| pos.page),
+            target: pos.map(|pos| [pos.point.x.to_pt(), pos.point.y.to_pt()]),
+        }
+    }
+}
+
+/// The serialized document.
+#[derive(Serialize)]
+struct JsonDocument {
+    version: u32,
+    pages: Vec<JsonFrame>,
+    fonts: Vec<JsonFont>,
+    images: Vec<JsonImage>,
+}
+
+/// A font with its base64-encoded data.
+#[derive(Serialize)]
+struct JsonFont {
+    family: String,
+    index: u32,
+    data: String,
+}
+
+/// An image with its base64-encoded data.
+#[derive(Serialize)]
+struct JsonImage {
+    format: &'static str,
+    width: u32,
+    height: u32,
+    data: String,
+}
+
+/// A frame. Lengths are in points.
+#[derive(Serialize)]
+struct JsonFrame {
+    width: f64,
+    height: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    baseline: Option<f64>,
+    items: Vec<JsonItem>,
+}
+
+/// A positioned item in a frame.
+#[derive(Serialize)]
+struct JsonItem {
+    x: f64,
+    y: f64,
+    #[serde(flatten)]
+    kind: JsonItemKind,
+}
+
+/// The kind-specific fields of an item.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum JsonItemKind {
+    Group {
+        transform: [f64; 6],
+        clips: bool,
+        artifact: bool,
+        clip_path: Option<Vec<Vec<Value>>>,
+        frame: Box<JsonFrame>,
+    },
+    Text {
+        font: usize,
+        size: f64,
+        fill: JsonPaint,
+        lang: EcoString,
+        glyphs: Vec<JsonGlyph>,
+    },
+    Shape {
+        geometry: JsonGeometry,
+        fill: Option<JsonPaint>,
+        stroke: Option<JsonStroke>,
+    },
+    Image {
+        image: usize,
+        width: f64,
+        height: f64,
+    },
+    Link {
+        width: f64,
+        height: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        url: Option<EcoString>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        page: Option<NonZeroUsize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        target: Option<[f64; 2]>,
+    },
+}
+
+/// A glyph. Advances and offsets are in em units of the text's font size.
+#[derive(Serialize)]
+struct JsonGlyph {
+    id: u16,
+    x_advance: f64,
+    x_offset: f64,
+    c: char,
+}
+
+/// The geometry of a shape.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum JsonGeometry {
+    Line { x: f64, y: f64 },
+    Rect { width: f64, height: f64 },
+    Path { items: Vec<Vec<Value>> },
+}
+
+/// A stroke.
+#[derive(Serialize)]
+struct JsonStroke {
+    paint: JsonPaint,
+    thickness: f64,
+    cap: &'static str,
+    join: &'static str,
+    miter_limit: f64,
+    dash: Vec<f64>,
+    dash_phase: f64,
+}
+
+/// A paint. Solid paints are written as colors, gradients and patterns as
+/// objects with their kind, their parameters, and their colors.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum JsonPaint {
+    Color(JsonColor),
+    Gradient {
+        gradient: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        angle: Option<f64>,
+        from: JsonColor,
+        to: JsonColor,
+    },
+    Pattern {
+        pattern: &'static str,
+        angle: f64,
+        spacing: f64,
+        thickness: f64,
+        color: JsonColor,
+    },
+}
+
+/// A color with a color space and components in the range from zero to one.
+#[derive(Serialize)]
+struct JsonColor {
+    space: &'static str,
+    components: Vec<f64>,
+}
+
+/// Convert a shape's geometry.
+fn geometry(geometry: &Geometry) -> JsonGeometry {
+    match geometry {
+        Geometry::Line(target) => {
+            JsonGeometry::Line { x: target.x.to_pt(), y: target.y.to_pt() }
+        }
+        Geometry::Rect(size) => {
+            JsonGeometry::Rect { width: size.x.to_pt(), height: size.y.to_pt() }
+        }
+        Geometry::Path(p) => JsonGeometry::Path { items: path(p) },
+    }
+}
+
+/// Convert a stroke.
+fn stroke(stroke: Stroke) -> JsonStroke {
+    JsonStroke {
+        paint: paint(stroke.paint),
+        thickness: stroke.thickness.to_pt(),
+        cap: match stroke.line_cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        },
+        join: match stroke.line_join {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        },
+        miter_limit: stroke.miter_limit.0,
+        dash: stroke
+            .dash
+            .array(stroke.thickness)
+            .into_iter()
+            .map(Abs::to_pt)
+            .collect(),
+        dash_phase: stroke.dash_phase.to_pt(),
+    }
+}
+
+/// Convert a path into a list of commands. Each command is an array with an
+/// SVG operator (`M`, `L`, `C`, or `Z`) followed by the coordinates of its
+/// points.
+fn path(path: &Path) -> Vec<Vec<Value>> {
+    path.0
+        .iter()
+        .map(|item| {
+            let (op, points) = match item {
+                PathItem::MoveTo(p) => ("M", vec![*p]),
+                PathItem::LineTo(p) => ("L", vec![*p]),
+                PathItem::CubicTo(a, b, c) => ("C", vec![*a, *b, *c]),
+                PathItem::ClosePath => ("Z", vec![]),
+            };
+            std::iter::once(Value::from(op))
+                .chain(
+                    points
+                        .into_iter()
+                        .flat_map(|p| [p.x.to_pt(), p.y.to_pt()])
+                        .map(Value::from),
+                )
+                .collect()
+        })
+        .collect()
+}
+
+/// The image formats in the order in which they are preferred by browsers,
+/// which display vector graphics sharply and all raster formats natively.
+const PREFERRED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Vector(VectorFormat::Svg),
+    ImageFormat::Raster(RasterFormat::Png),
+    ImageFormat::Raster(RasterFormat::Jpg),
+    ImageFormat::Raster(RasterFormat::Gif),
+];
+
+/// Encode a raster image with color effects as a PNG with the effects applied.
+fn encode_effects(image: &Image) -> Option<Vec<u8>> {
+    if image.effects().is_none() {
+        return None;
+    }
+
+    let decoded = image.decode().ok()?;
+    let DecodedImage::Raster(dynamic, _) = decoded.as_ref() else { return None };
+    let mut data = Cursor::new(vec![]);
+    dynamic.write_to(&mut data, image::ImageFormat::Png).ok()?;
+    Some(data.into_inner())
+}
+
+/// Convert a paint.
+fn paint(paint: Paint) -> JsonPaint {
+    match paint {
+        Paint::Solid(c) => JsonPaint::Color(color(c)),
+        Paint::Gradient(gradient) => {
+            let (kind, angle) = match gradient.kind {
+                GradientKind::Linear(angle) => ("linear", Some(angle.to_deg())),
+                GradientKind::Radial => ("radial", None),
+            };
+            JsonPaint::Gradient {
+                gradient: kind,
+                angle,
+                from: color(gradient.from),
+                to: color(gradient.to),
+            }
+        }
+        Paint::Pattern(pattern) => JsonPaint::Pattern {
+            pattern: match pattern.kind {
+                PatternKind::Lines => "lines",
+                PatternKind::Grid => "grid",
+                PatternKind::Dots => "dots",
+            },
+            angle: pattern.angle.to_deg(),
+            spacing: pattern.spacing.to_pt(),
+            thickness: pattern.thickness.to_pt(),
+            color: color(pattern.color),
+        },
+    }
+}
+
+/// Convert a color.
+fn color(color: Color) -> JsonColor {
+    let f = |c: u8| c as f64 / 255.0;
+    let (space, components) = match color {
+        Color::Luma(c) => ("luma", vec![f(c.0)]),
+        Color::Rgba(c) => ("rgba", vec![f(c.r), f(c.g), f(c.b), f(c.a)]),
+        Color::Cmyk(c) => ("cmyk", vec![f(c.c), f(c.m), f(c.y), f(c.k)]),
+    };
+    JsonColor { space, components }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::Point;
+
+    #[test]
+    fn test_json() {
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(50.0)));
+        let url = Destination::Url("a\"b\n".into());
+        let size = Size::new(Abs::inf(), Abs::pt(10.0));
+        frame.push(Point::zero(), FrameItem::Meta(Meta::Link(url), size));
+        let document = Document { pages: vec![frame], ..Default::default() };
+
+        // Strings are escaped and numbers that JSON can't represent are
+        // written as `null`.
+        let output: Value = serde_json::from_str(&json(&document)).unwrap();
+        let link = &output["pages"][0]["items"][0];
+        assert_eq!(output["version"], JSON_VERSION);
+        assert_eq!(output["pages"][0]["width"], 100.0);
+        assert_eq!(link["kind"], "link");
+        assert_eq!(link["url"], "a\"b\n");
+        assert_eq!(link["width"], Value::Null);
+        assert_eq!(link["height"], 10.0);
+        assert!(link.get("page").is_none());
+    }
+}
@@ -0,0 +1,868 @@
+//! Tessellation into triangle meshes.
+
+use std::f64::consts::PI;
+
+use ecow::eco_format;
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+use super::render;
+use crate::diag::StrResult;
+use crate::doc::{Frame, FrameItem, GroupItem, TextItem};
+use crate::geom::{
+    Abs, Color, Geometry, LineCap, LineJoin, Paint, PathItem, Point, Ratio, RgbaColor,
+    Shape, Size, Stroke, Transform,
+};
+use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+
+/// Tessellate a frame into triangle meshes.
+///
+/// All geometry, including glyph outlines, is flattened into polygons that
+/// deviate at most `tolerance` points from the exact curves. This allows
+/// hardware-accelerated renderers to draw a page without having to deal with
+/// curves, strokes, or fonts. Gradients and patterns are approximated by a
+/// single color and glyphs that only exist as bitmaps or SVGs are drawn from
+/// their outlines, if any.
+///
+/// All coordinates are in points, relative to the top-left corner of the
+/// frame.
+pub fn tessellate(frame: &Frame, tolerance: f64) -> Mesh {
+    tessellate_with_fallback(frame, tolerance, Fallback::Warn).unwrap()
+}
+
+/// Tessellate a frame into triangle meshes, choosing what happens with
+/// content that meshes can't represent exactly.
+///
+/// Fails if such content is encountered with [`Fallback::Error`].
+pub fn tessellate_with_fallback(
+    frame: &Frame,
+    tolerance: f64,
+    fallback: Fallback,
+) -> StrResult<Mesh> {
+    let mut mesh = Mesh {
+        size: [frame.width().to_f32(), frame.height().to_f32()],
+        primitives: vec![],
+        clips: vec![],
+    };
+
+    let mut ctx = MeshContext {
+        mesh: &mut mesh,
+        clips: vec![],
+        tolerance,
+        fallback,
+    };
+    ctx.tessellate_frame(Transform::identity(), frame)?;
+    Ok(mesh)
+}
+
+/// What to do with content that meshes can't represent exactly: gradients,
+/// patterns, and bitmap or SVG glyphs.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Fallback {
+    /// Fail with an error.
+    Error,
+    /// Log a warning and approximate the content. Gradients and patterns
+    /// become a single color and glyphs are drawn from their outlines, if
+    /// any.
+    #[default]
+    Warn,
+    /// Draw the content into an image with the given resolution in dots per
+    /// inch and add that as a textured quad.
+    Rasterize { dpi: u16 },
+}
+
+/// A frame tessellated into triangle meshes.
+#[derive(Debug, Clone)]
+pub struct Mesh {
+    /// The width and height of the frame.
+    pub size: [f32; 2],
+    /// The primitives to draw, from back to front.
+    pub primitives: Vec<Primitive>,
+    /// Clipping quads, referenced by the primitives. A quad's corners are in
+    /// drawing order.
+    pub clips: Vec<[[f32; 2]; 4]>,
+}
+
+/// Something to draw in a mesh.
+#[derive(Debug, Clone)]
+pub enum Primitive {
+    /// Triangles filled with a single color.
+    Triangles(Batch),
+    /// A textured quad.
+    Image(ImageQuad),
+}
+
+/// A batch of triangles filled with a single color.
+#[derive(Debug, Clone)]
+pub struct Batch {
+    /// The color as straight-alpha RGBA in the range from zero to one.
+    pub color: [f32; 4],
+    /// The vertices of the triangles.
+    pub vertices: Vec<[f32; 2]>,
+    /// Indices into the vertices, three per triangle.
+    pub indices: Vec<u32>,
+    /// Whether the triangles may overlap.
+    ///
+    /// If this is `true`, the batch must be drawn with stencil-then-cover:
+    /// Each triangle increments the stencil value of the pixels it covers if
+    /// its vertices are in clockwise order (as seen with the y-axis pointing
+    /// down) and decrements it otherwise. Then, the batch's bounds are painted
+    /// wherever the stencil value is non-zero. If this is `false`, the
+    /// triangles can be painted directly.
+    pub stencil: bool,
+    /// The minimum and maximum corner of the triangles' bounding box.
+    pub bounds: [[f32; 2]; 2],
+    /// Indices of the clipping quads that restrict where the batch is painted.
+    pub clips: Vec<usize>,
+}
+
+/// The image formats in the order in which they are preferred for textures.
+const PREFERRED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Raster(RasterFormat::Png),
+    ImageFormat::Raster(RasterFormat::Jpg),
+    ImageFormat::Raster(RasterFormat::Gif),
+    ImageFormat::Vector(VectorFormat::Svg),
+];
+
+/// An image drawn into a quad.
+#[derive(Debug, Clone)]
+pub struct ImageQuad {
+    /// The image to draw.
+    pub image: Image,
+    /// The corners of the quad, starting with the one where the image's
+    /// top-left corner ends up and continuing in clockwise order.
+    pub corners: [[f32; 2]; 4],
+    /// Indices of the clipping quads that restrict where the image is painted.
+    pub clips: Vec<usize>,
+}
+
+/// Context for tessellation.
+struct MeshContext<'a> {
+    mesh: &'a mut Mesh,
+    clips: Vec<usize>,
+    tolerance: f64,
+    fallback: Fallback,
+}
+
+impl MeshContext<'_> {
+    /// Tessellate a frame whose coordinate system is mapped into the mesh's by
+    /// `ts`.
+    fn tessellate_frame(&mut self, ts: Transform, frame: &Frame) -> StrResult<()> {
+        for (pos, item) in frame.items() {
+            let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+            if let Some(what) = unrepresentable(item) {
+                if self.fall_back(ts, item, what)? {
+                    continue;
+                }
+            }
+
+            match item {
+                FrameItem::Group(group) => self.tessellate_group(ts, group)?,
+                FrameItem::Text(text) => self.tessellate_text(ts, text),
+                FrameItem::Shape(shape, _) => self.tessellate_shape(ts, shape),
+                FrameItem::Image(image, size, _) => {
+                    self.tessellate_image(ts, image, *size)
+                }
+                FrameItem::Meta(..) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tessellate a group frame with optional transform and clipping.
+    fn tessellate_group(&mut self, ts: Transform, group: &GroupItem) -> StrResult<()> {
+        let ts = ts.pre_concat(group.transform);
+        if !group.clips {
+            return self.tessellate_frame(ts, &group.frame);
+        }
+
+        // Clipping quads can't represent clip paths, so those are approximated
+        // by the frame's bounds.
+        let corners = rect_corners(group.frame.size());
+        self.mesh.clips.push(corners.map(|p| to_f32(p.transform(ts))));
+        self.clips.push(self.mesh.clips.len() - 1);
+        let result = self.tessellate_frame(ts, &group.frame);
+        self.clips.pop();
+        result
+    }
+
+    /// Deal with an item containing `what` the mesh can't represent.
+    ///
+    /// Returns whether the item was taken care of. Otherwise, it should be
+    /// tessellated as an approximation.
+    fn fall_back(
+        &mut self,
+        ts: Transform,
+        item: &FrameItem,
+        what: &str,
+    ) -> StrResult<bool> {
+        match self.fallback {
+            Fallback::Error => Err(eco_format!("meshes cannot represent {what}")),
+            Fallback::Warn => {
+                log::warn!("approximating {what} in mesh");
+                Ok(false)
+            }
+            Fallback::Rasterize { dpi } => {
+                let (min, size) = item_bbox(item);
+                let mut frame = Frame::new(size);
+                frame.push(-min, item.clone());
+
+                let transparent = Color::Rgba(RgbaColor::new(0, 0, 0, 0));
+                let pixmap = render(&frame, dpi as f32 / 72.0, transparent);
+                let png = pixmap.encode_png().map_err(|_| "failed to rasterize item")?;
+                let image =
+                    Image::new(png.into(), ImageFormat::Raster(RasterFormat::Png))?;
+                let ts = ts.pre_concat(Transform::translate(min.x, min.y));
+                self.tessellate_image(ts, &image, size);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Tessellate the outlines of a text run.
+    fn tessellate_text(&mut self, ts: Transform, text: &TextItem) {
+        let mut batch = BatchBuilder::new(true);
+        let scale = text.size.to_pt() / text.font.units_per_em();
+
+        let mut x = Abs::zero();
+        for glyph in &text.glyphs {
+            let offset = x + glyph.x_offset.at(text.size);
+            x += glyph.x_advance.at(text.size);
+
+            // Flip vertically because the font design coordinate system is
+            // Y-up.
+            let ts = ts
+                .pre_concat(Transform::translate(offset, Abs::zero()))
+                .pre_concat(Transform::scale(Ratio::new(scale), Ratio::new(-scale)));
+
+            let mut flattener = Flattener::new(self.tolerance / max_scale(ts));
+            if text
+                .font
+                .ttf()
+                .outline_glyph(GlyphId(glyph.id), &mut flattener)
+                .is_some()
+            {
+                for contour in &flattener.contours {
+                    batch.fill(ts, contour);
+                }
+            }
+        }
+
+        self.push_batch(batch, text.fill);
+    }
+
+    /// Tessellate the fill and stroke of a geometrical shape.
+    fn tessellate_shape(&mut self, ts: Transform, shape: &Shape) {
+        let mut flattener = Flattener::new(self.tolerance / max_scale(ts));
+        match &shape.geometry {
+            Geometry::Line(target) => {
+                flattener.move_to_point(Point::zero());
+                flattener.line_to_point(*target);
+            }
+            Geometry::Rect(size) => {
+                let [first, rest @ ..] = rect_corners(*size);
+                flattener.move_to_point(first);
+                for point in rest {
+                    flattener.line_to_point(point);
+                }
+                flattener.close();
+            }
+            Geometry::Path(path) => {
+                for item in &path.0 {
+                    match *item {
+                        PathItem::MoveTo(p) => flattener.move_to_point(p),
+                        PathItem::LineTo(p) => flattener.line_to_point(p),
+                        PathItem::CubicTo(a, b, c) => flattener.cubic_to_points(a, b, c),
+                        PathItem::ClosePath => flattener.close(),
+                    }
+                }
+            }
+        }
+
+        if let Some(paint) = shape.fill {
+            // Rectangles are convex, so their triangles never overlap.
+            let stencil = !matches!(shape.geometry, Geometry::Rect(_));
+            let mut batch = BatchBuilder::new(stencil);
+            for contour in &flattener.contours {
+                batch.fill(ts, contour);
+            }
+            self.push_batch(batch, paint);
+        }
+
+        if let Some(stroke) = shape.stroke {
+            let mut batch = BatchBuilder::new(true);
+            for contour in &flattener.contours {
+                batch.stroke(ts, contour, stroke, self.tolerance / max_scale(ts));
+            }
+            self.push_batch(batch, stroke.paint);
+        }
+    }
+
+    /// Add an image as a quad.
+    ///
+    /// Raster representations are preferred since they can be uploaded as
+    /// textures directly.
+    fn tessellate_image(&mut self, ts: Transform, image: &Image, size: Size) {
+        self.mesh.primitives.push(Primitive::Image(ImageQuad {
+            image: image.select(&PREFERRED_FORMATS).clone(),
+            corners: rect_corners(size).map(|p| to_f32(p.transform(ts))),
+            clips: self.clips.clone(),
+        }));
+    }
+
+    /// Add a batch to the mesh if it isn't empty.
+    fn push_batch(&mut self, batch: BatchBuilder, paint: Paint) {
+        if batch.indices.is_empty() {
+            return;
+        }
+
+        // Batches have a single color, so gradients are approximated by
+        // their middle and patterns by their color.
+        let color = match paint {
+            Paint::Solid(color) => color,
+            Paint::Gradient(gradient) => gradient.sample(0.5),
+            Paint::Pattern(pattern) => pattern.color,
+        };
+
+        let c = color.to_rgba();
+        let mut min = [f32::INFINITY; 2];
+        let mut max = [f32::NEG_INFINITY; 2];
+        for v in &batch.vertices {
+            min = [min[0].min(v[0]), min[1].min(v[1])];
+            max = [max[0].max(v[0]), max[1].max(v[1])];
+        }
+
+        self.mesh.primitives.push(Primitive::Triangles(Batch {
+            color: [c.r, c.g, c.b, c.a].map(|v| v as f32 / 255.0),
+            vertices: batch.vertices,
+            indices: batch.indices,
+            stencil: batch.stencil,
+            bounds: [min, max],
+            clips: self.clips.clone(),
+        }));
+    }
+}
+
+/// What about an item, if anything, a mesh can't represent exactly.
+fn unrepresentable(item: &FrameItem) -> Option<&'static str> {
+    let paint = |paint: &Paint| match paint {
+        Paint::Solid(_) => None,
+        Paint::Gradient(_) => Some("gradients"),
+        Paint::Pattern(_) => Some("patterns"),
+    };
+
+    match item {
+        FrameItem::Text(text) => paint(&text.fill).or_else(|| {
+            let ttf = text.font.ttf();
+            text.glyphs
+                .iter()
+                .map(|glyph| GlyphId(glyph.id))
+                .any(|id| {
+                    ttf.glyph_svg_image(id).is_some()
+                        || ttf.glyph_raster_image(id, u16::MAX).is_some()
+                })
+                .then_some("bitmap and SVG glyphs")
+        }),
+        FrameItem::Shape(shape, _) => shape
+            .fill
+            .as_ref()
+            .and_then(paint)
+            .or_else(|| shape.stroke.as_ref().and_then(|stroke| paint(&stroke.paint))),
+        _ => None,
+    }
+}
+
+/// The bounding box of a text run or shape, given by its top-left corner and
+/// its size.
+fn item_bbox(item: &FrameItem) -> (Point, Size) {
+    match item {
+        FrameItem::Text(text) => {
+            let bbox = text.font.ttf().global_bounding_box();
+            let at = |units: i16| text.font.to_em(units).at(text.size);
+            let min = Point::new(at(bbox.x_min).min(Abs::zero()), -at(bbox.y_max));
+            let max = Point::new(text.width() + at(bbox.x_max), -at(bbox.y_min));
+            (min, (max - min).to_size())
+        }
+        FrameItem::Shape(shape, _) => {
+            let (min, size) = shape.geometry.bbox();
+            let pad = shape.stroke.map_or(Abs::zero(), |stroke| stroke.thickness);
+            (min - Point::splat(pad), size + Size::splat(2.0 * pad))
+        }
+        FrameItem::Image(_, size, _) => (Point::zero(), *size),
+        FrameItem::Group(group) => (Point::zero(), group.frame.size()),
+        FrameItem::Meta(_, size) => (Point::zero(), *size),
+    }
+}
+
+/// Builds the triangles of a batch.
+struct BatchBuilder {
+    vertices: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+    stencil: bool,
+}
+
+impl BatchBuilder {
+    fn new(stencil: bool) -> Self {
+        Self { vertices: vec![], indices: vec![], stencil }
+    }
+
+    /// Add a vertex and return its index.
+    fn vertex(&mut self, point: Point) -> u32 {
+        self.vertices.push(to_f32(point));
+        (self.vertices.len() - 1) as u32
+    }
+
+    /// Add the triangle fan of a filled contour. Combined with the
+    /// stencil-then-cover approach, this fills the contour with the non-zero
+    /// rule.
+    fn fill(&mut self, ts: Transform, contour: &Contour) {
+        if contour.points.len() < 3 {
+            return;
+        }
+
+        let indices: Vec<_> =
+            contour.points.iter().map(|p| self.vertex(p.transform(ts))).collect();
+        for pair in indices[1..].windows(2) {
+            self.indices.extend([indices[0], pair[0], pair[1]]);
+        }
+    }
+
+    /// Add a triangle that always counts positively towards the stencil
+    /// value, so that overlapping parts of a stroke don't cancel out.
+    fn triangle(&mut self, ts: Transform, a: Point, b: Point, c: Point) {
+        let [a, b, c] = [a, b, c].map(|p| p.transform(ts));
+        let (b, c) = if cross(b - a, c - a) < 0.0 { (c, b) } else { (b, c) };
+        let indices = [self.vertex(a), self.vertex(b), self.vertex(c)];
+        self.indices.extend(indices);
+    }
+
+    /// Add a convex polygon as a triangle fan.
+    fn polygon(&mut self, ts: Transform, points: &[Point]) {
+        for pair in points[1..].windows(2) {
+            self.triangle(ts, points[0], pair[0], pair[1]);
+        }
+    }
+
+    /// Add a circle approximated by a polygon.
+    fn circle(&mut self, ts: Transform, center: Point, radius: f64, tolerance: f64) {
+        let n = if tolerance < radius {
+            (PI / (1.0 - tolerance / radius).acos()).ceil() as usize
+        } else {
+            0
+        }
+        .clamp(8, 128);
+
+        let points: Vec<_> = (0..n)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / n as f64;
+                center + point(angle.cos() * radius, angle.sin() * radius)
+            })
+            .collect();
+
+        self.polygon(ts, &points);
+    }
+
+    /// Add the triangles of a stroked contour.
+    fn stroke(
+        &mut self,
+        ts: Transform,
+        contour: &Contour,
+        stroke: Stroke,
+        tolerance: f64,
+    ) {
+        let half = stroke.thickness.to_pt() / 2.0;
+        if half <= 0.0 {
+            return;
+        }
+
+        let mut points = contour.points.clone();
+        points.dedup();
+        if contour.closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        let dash = stroke.dash.array(stroke.thickness);
+        if dash.is_empty() {
+            self.polyline(ts, &points, contour.closed, stroke, tolerance);
+            return;
+        }
+
+        if contour.closed {
+            points.push(points[0]);
+        }
+
+        for piece in split_dashes(&points, &dash, stroke.dash_phase) {
+            self.polyline(ts, &piece, false, stroke, tolerance);
+        }
+    }
+
+    /// Add the triangles of a stroked polyline without repeated points.
+    fn polyline(
+        &mut self,
+        ts: Transform,
+        points: &[Point],
+        closed: bool,
+        stroke: Stroke,
+        tolerance: f64,
+    ) {
+        let half = stroke.thickness.to_pt() / 2.0;
+
+        // A single point is only visible through its caps.
+        if points.len() == 1 {
+            let p = points[0];
+            match stroke.line_cap {
+                LineCap::Butt => {}
+                LineCap::Round => self.circle(ts, p, half, tolerance),
+                LineCap::Square => {
+                    let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                        .map(|(x, y)| p + point(x * half, y * half));
+                    self.polygon(ts, &corners);
+                }
+            }
+            return;
+        }
+
+        let normal = |a: Point, b: Point| {
+            let d = b - a;
+            let len = length(d);
+            point(-d.y.to_pt() / len * half, d.x.to_pt() / len * half)
+        };
+
+        // The segments.
+        let count = if closed { points.len() } else { points.len() - 1 };
+        for i in 0..count {
+            let a = points[i];
+            let b = points[(i + 1) % points.len()];
+            let n = normal(a, b);
+            self.polygon(ts, &[a + n, b + n, b - n, a - n]);
+        }
+
+        // The joins.
+        let joins = if closed { 0..points.len() } else { 1..points.len() - 1 };
+        for i in joins {
+            let prev = points[(i + points.len() - 1) % points.len()];
+            let v = points[i];
+            let next = points[(i + 1) % points.len()];
+            if stroke.line_join == LineJoin::Round {
+                self.circle(ts, v, half, tolerance);
+                continue;
+            }
+
+            let n1 = normal(prev, v);
+            let n2 = normal(v, next);
+            let turn = dot(n1, next - v);
+            if turn == 0.0 {
+                continue;
+            }
+
+            // The outer side is the one the path turns away from.
+            let s = if turn > 0.0 { -1.0 } else { 1.0 };
+            let (n1, n2) = (n1 * s, n2 * s);
+            self.triangle(ts, v, v + n1, v + n2);
+
+            let m = n1 + n2;
+            let projection = dot(m, n1);
+            if stroke.line_join == LineJoin::Miter && projection > 0.0 {
+                let tip = m * (half * half / projection);
+                if length(tip) / half <= stroke.miter_limit.0 {
+                    self.triangle(ts, v + n1, v + tip, v + n2);
+                }
+            }
+        }
+
+        if closed {
+            return;
+        }
+
+        // The caps.
+        let ends = [
+            (points[0], points[1]),
+            (points[points.len() - 1], points[points.len() - 2]),
+        ];
+        for (end, inner) in ends {
+            match stroke.line_cap {
+                LineCap::Butt => {}
+                LineCap::Round => self.circle(ts, end, half, tolerance),
+                LineCap::Square => {
+                    let d = end - inner;
+                    let out = d * (half / length(d));
+                    let n = normal(inner, end);
+                    self.polygon(ts, &[end + n, end + n + out, end - n + out, end - n]);
+                }
+            }
+        }
+    }
+}
+
+/// Split a polyline into the pieces that are visible with a dash pattern.
+fn split_dashes(points: &[Point], dash: &[Abs], phase: Abs) -> Vec<Vec<Point>> {
+    let total: f64 = dash.iter().map(|len| len.to_pt()).sum();
+    if total <= 0.0 {
+        return vec![points.to_vec()];
+    }
+
+    let mut pieces = vec![];
+    let mut current = vec![points[0]];
+    let mut index = 0;
+    let mut left = dash[0].to_pt();
+
+    // Skip the part of the pattern before the phase.
+    let mut skip = phase.to_pt().rem_euclid(total);
+    while skip >= left {
+        skip -= left;
+        index = (index + 1) % dash.len();
+        left = dash[index].to_pt();
+    }
+    left -= skip;
+
+    for pair in points.windows(2) {
+        let (mut a, b) = (pair[0], pair[1]);
+        let mut remaining = length(b - a);
+        while remaining > left {
+            a = a + (b - a) * (left / remaining);
+            remaining -= left;
+            if index % 2 == 0 {
+                current.push(a);
+                pieces.push(std::mem::take(&mut current));
+            } else {
+                current = vec![a];
+            }
+            index = (index + 1) % dash.len();
+            left = dash[index].to_pt();
+        }
+
+        left -= remaining;
+        if index % 2 == 0 {
+            current.push(b);
+        }
+    }
+
+    if index % 2 == 0 && current.len() > 1 {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// A polygonal contour.
+struct Contour {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+/// Flattens paths and glyph outlines into polygonal contours.
+struct Flattener {
+    contours: Vec<Contour>,
+    tolerance: f64,
+}
+
+impl Flattener {
+    fn new(tolerance: f64) -> Self {
+        Self { contours: vec![], tolerance }
+    }
+
+    /// The current point, which is the start of the last contour if it was
+    /// closed.
+    fn current(&self) -> Point {
+        self.contours
+            .last()
+            .and_then(|contour| {
+                if contour.closed {
+                    contour.points.first()
+                } else {
+                    contour.points.last()
+                }
+            })
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn move_to_point(&mut self, p: Point) {
+        self.contours.push(Contour { points: vec![p], closed: false });
+    }
+
+    fn line_to_point(&mut self, p: Point) {
+        match self.contours.last_mut() {
+            Some(contour) if !contour.closed => contour.points.push(p),
+            _ => {
+                let start = self.current();
+                self.contours.push(Contour { points: vec![start, p], closed: false });
+            }
+        }
+    }
+
+    fn cubic_to_points(&mut self, p1: Point, p2: Point, p3: Point) {
+        let p0 = self.current();
+        let dd = length(p0 - p1 * 2.0 + p2).max(length(p1 - p2 * 2.0 + p3));
+        let n = ((0.75 * dd / self.tolerance).sqrt().ceil() as usize).clamp(1, 100);
+        for i in 1..=n {
+            let t = i as f64 / n as f64;
+            let mt = 1.0 - t;
+            let p = p0 * (mt * mt * mt)
+                + p1 * (3.0 * mt * mt * t)
+                + p2 * (3.0 * mt * t * t)
+                + p3 * (t * t * t);
+            self.line_to_point(p);
+        }
+    }
+}
+
+impl OutlineBuilder for Flattener {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.move_to_point(point(x as f64, y as f64));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.line_to_point(point(x as f64, y as f64));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.current();
+        let p1 = point(x1 as f64, y1 as f64);
+        let p = point(x as f64, y as f64);
+        self.cubic_to_points(p0 + (p1 - p0) * (2.0 / 3.0), p + (p1 - p) * (2.0 / 3.0), p);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.cubic_to_points(
+            point(x1 as f64, y1 as f64),
+            point(x2 as f64, y2 as f64),
+            point(x as f64, y as f64),
+        );
+    }
+
+    fn close(&mut self) {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.closed = true;
+        }
+    }
+}
+
+/// The corners of a rectangle at the origin, in clockwise order.
+fn rect_corners(size: Size) -> [Point; 4] {
+    [Point::zero(), Point::with_x(size.x), size.to_point(), Point::with_y(size.y)]
+}
+
+/// The maximum factor by which a transform scales lengths.
+fn max_scale(ts: Transform) -> f64 {
+    let x = ts.sx.get().hypot(ts.ky.get());
+    let y = ts.kx.get().hypot(ts.sy.get());
+    x.max(y).max(f64::EPSILON)
+}
+
+fn point(x: f64, y: f64) -> Point {
+    Point::new(Abs::pt(x), Abs::pt(y))
+}
+
+fn to_f32(p: Point) -> [f32; 2] {
+    [p.x.to_f32(), p.y.to_f32()]
+}
+
+fn length(p: Point) -> f64 {
+    p.x.to_pt().hypot(p.y.to_pt())
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.x.to_pt() * b.x.to_pt() + a.y.to_pt() * b.y.to_pt()
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.x.to_pt() * b.y.to_pt() - a.y.to_pt() * b.x.to_pt()
+}
+
+/// Additional methods for [`Abs`].
+trait AbsExt {
+    /// Convert to a number of points as f32.
+    fn to_f32(self) -> f32;
+}
+
+impl AbsExt for Abs {
+    fn to_f32(self) -> f32 {
+        self.to_pt() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::{DashPattern, Gradient, GradientKind};
+
+    #[test]
+    fn test_tessellate_rect() {
+        let stroke = Stroke { thickness: Abs::pt(2.0), ..Stroke::default() };
+        let shape = Shape {
+            geometry: Geometry::Rect(Size::splat(Abs::pt(10.0))),
+            fill: Some(Color::BLACK.into()),
+            stroke: Some(stroke),
+        };
+
+        let mut frame = Frame::new(Size::splat(Abs::pt(20.0)));
+        frame.push(
+            point(5.0, 5.0),
+            FrameItem::Shape(shape, crate::syntax::Span::detached()),
+        );
+
+        let mesh = tessellate(&frame, 0.1);
+        let [Primitive::Triangles(fill), Primitive::Triangles(stroke)] =
+            &mesh.primitives[..]
+        else {
+            panic!("expected fill and stroke batches");
+        };
+
+        assert!(!fill.stencil);
+        assert_eq!(fill.indices.len(), 6);
+        assert_eq!(fill.bounds, [[5.0, 5.0], [15.0, 15.0]]);
+        assert_eq!(fill.color, [0.0, 0.0, 0.0, 1.0]);
+        assert!(stroke.stencil);
+        assert_eq!(stroke.bounds, [[4.0, 4.0], [16.0, 16.0]]);
+    }
+
+    #[test]
+    fn test_tessellate_fallback() {
+        let gradient = Gradient {
+            kind: GradientKind::Radial,
+            from: Color::BLACK,
+            to: Color::WHITE,
+        };
+
+        let shape =
+            Geometry::Rect(Size::splat(Abs::pt(10.0))).filled(Paint::Gradient(gradient));
+        let mut frame = Frame::new(Size::splat(Abs::pt(20.0)));
+        frame.push(
+            point(5.0, 5.0),
+            FrameItem::Shape(shape, crate::syntax::Span::detached()),
+        );
+
+        assert!(tessellate_with_fallback(&frame, 0.1, Fallback::Error).is_err());
+
+        let mesh = tessellate(&frame, 0.1);
+        let [Primitive::Triangles(batch)] = &mesh.primitives[..] else {
+            panic!("expected an approximated batch");
+        };
+        assert_eq!(batch.bounds, [[5.0, 5.0], [15.0, 15.0]]);
+
+        let fallback = Fallback::Rasterize { dpi: 72 };
+        let mesh = tessellate_with_fallback(&frame, 0.1, fallback).unwrap();
+        let [Primitive::Image(quad)] = &mesh.primitives[..] else {
+            panic!("expected a rasterized quad");
+        };
+        assert_eq!(quad.image.width(), 10);
+        assert_eq!(quad.corners[0], [5.0, 5.0]);
+    }
+
+    #[test]
+    fn test_split_dashes() {
+        let points = [point(0.0, 0.0), point(10.0, 0.0)];
+        let dash = DashPattern::Dashed.array(Abs::pt(1.0));
+        let xs = |phase| -> Vec<Vec<f64>> {
+            split_dashes(&points, &dash, Abs::pt(phase))
+                .iter()
+                .map(|piece| {
+                    piece.iter().map(|p| (p.x.to_pt() * 1e6).round() / 1e6).collect()
+                })
+                .collect()
+        };
+        assert_eq!(xs(0.0), [[0.0, 3.0], [6.0, 9.0]]);
+        assert_eq!(xs(4.0), [[2.0, 5.0], [8.0, 10.0]]);
+    }
+}
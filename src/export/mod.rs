@@ -1,7 +1,18 @@
 //! Exporting into external formats.
 
+mod html;
+mod json;
+mod mesh;
 mod pdf;
 mod render;
 
-pub use self::pdf::pdf;
+pub use self::html::html;
+pub use self::json::{json, JSON_VERSION};
+pub use self::mesh::{
+    tessellate, tessellate_with_fallback, Batch, Fallback, ImageQuad, Mesh, Primitive,
+};
+pub use self::pdf::{
+    pdf, Compression, Grayscale, OutputIntent, PageExtras, PageHook, PdfCache,
+    PdfExporter, PdfOptions, PdfStandard, Rasterize,
+};
 pub use self::render::render;
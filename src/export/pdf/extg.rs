@@ -0,0 +1,32 @@
+use pdf_writer::{Finish, Name};
+
+use super::{PdfContext, RefExt};
+
+/// An external graphics state with the opacities of fills and strokes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ExtGState {
+    /// The opacity of strokes, from 0 (transparent) to 255 (opaque).
+    pub stroke_opacity: u8,
+    /// The opacity of fills, from 0 (transparent) to 255 (opaque).
+    pub fill_opacity: u8,
+}
+
+impl Default for ExtGState {
+    fn default() -> Self {
+        Self { stroke_opacity: 255, fill_opacity: 255 }
+    }
+}
+
+/// Write all used external graphics states.
+pub fn write_external_graphics_states(ctx: &mut PdfContext) {
+    for ext_gs in ctx.ext_gs_map.items() {
+        let gs_ref = ctx.alloc.bump();
+        ctx.ext_gs_refs.push(gs_ref);
+
+        let mut gs = ctx.writer.indirect(gs_ref).dict();
+        gs.pair(Name(b"Type"), Name(b"ExtGState"));
+        gs.pair(Name(b"CA"), ext_gs.stroke_opacity as f32 / 255.0);
+        gs.pair(Name(b"ca"), ext_gs.fill_opacity as f32 / 255.0);
+        gs.finish();
+    }
+}
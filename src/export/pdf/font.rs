@@ -1,21 +1,190 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
 
 use ecow::eco_format;
-use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo};
 use pdf_writer::{Filter, Finish, Name, Rect, Str};
 use ttf_parser::{name_id, GlyphId, Tag};
 
-use super::{deflate, EmExt, PdfContext, RefExt};
-use crate::util::SliceExt;
+use super::{deflate, Compression, EmExt, PdfContext, RefExt};
+use crate::doc::{Frame, FrameItem};
+use crate::font::Font;
+use crate::util::{hash128, SliceExt};
+
+/// Subset the fonts used in the frames ahead of page construction and find
+/// the glyphs that don't survive subsetting.
+///
+/// Such glyphs would be corrupt in the embedded font, so pages draw them as
+/// images rasterized from the original font instead.
+pub fn subset_fonts(ctx: &mut PdfContext, frames: &[Frame]) {
+    // Outlined text doesn't need any fonts.
+    if ctx.options.outline_text && !ctx.options.text_layer {
+        return;
+    }
+
+    let mut used = HashMap::new();
+    for frame in frames {
+        collect_glyphs(frame, &mut used);
+    }
+
+    for (font, glyphs) in used {
+        let glyphs: Vec<_> = glyphs.into_iter().collect();
+        let subsetted = subset(&font, &glyphs);
+
+        // Embed the whole font if the subset is unreadable.
+        let Some(face) = subsetted
+            .as_deref()
+            .and_then(|data| ttf_parser::Face::parse(data, 0).ok())
+        else {
+            ctx.subsets.insert(font, None);
+            continue;
+        };
+
+        for &g in &glyphs {
+            let id = GlyphId(g);
+            if font.ttf().glyph_bounding_box(id).is_some()
+                && face.glyph_bounding_box(id).is_none()
+            {
+                log::warn!(
+                    "glyph {} of font {} could not be embedded and is rasterized",
+                    g,
+                    font.info().family,
+                );
+                ctx.broken_glyphs.entry(font.clone()).or_default().insert(g);
+            }
+        }
+
+        ctx.subsets.insert(font, subsetted);
+    }
+
+    // Pages draw broken glyphs as images, which also shifts the numbers of the
+    // images after them. Since a change on any page can break glyphs that
+    // earlier pages use, these must not reuse their cached streams then.
+    let mut broken: Vec<u128> = ctx
+        .broken_glyphs
+        .iter()
+        .map(|(font, glyphs)| {
+            hash128(&(font, glyphs.iter().copied().collect::<BTreeSet<_>>()))
+        })
+        .collect();
+    broken.sort();
+    ctx.page_key = hash128(&(ctx.page_key, broken));
+}
+
+/// Subset a font to the glyphs, leaving out its layout tables.
+fn subset(font: &Font, glyphs: &[u16]) -> Option<Vec<u8>> {
+    let profile = subsetter::Profile::pdf(glyphs);
+    let subsetted = subsetter::subset(font.data(), font.index(), profile).ok()?;
+    strip_layout_tables(&subsetted)
+}
+
+/// Tables that only serve text layout.
+///
+/// The glyphs in a PDF are already shaped and positioned, so viewers never
+/// read these. Their lookups and glyph classes refer to glyphs that subsetting
+/// removed, which makes them invalid in a subset.
+const LAYOUT_TABLES: [&[u8; 4]; 6] =
+    [b"BASE", b"GDEF", b"GPOS", b"GSUB", b"JSTF", b"MATH"];
+
+/// Rebuild a font without its layout tables.
+///
+/// The remaining tables keep their order and the checksums are computed
+/// anew. Returns `None` if the table directory is malformed.
+fn strip_layout_tables(data: &[u8]) -> Option<Vec<u8>> {
+    let read = |at: usize| -> Option<usize> {
+        let bytes = data.get(at..at + 4)?;
+        Some(u32::from_be_bytes(bytes.try_into().ok()?) as usize)
+    };
+
+    let count = usize::from(u16::from_be_bytes(data.get(4..6)?.try_into().ok()?));
+    let mut tables = vec![];
+    for i in 0..count {
+        let record = 12 + 16 * i;
+        let tag: [u8; 4] = data.get(record..record + 4)?.try_into().ok()?;
+        let (offset, len) = (read(record + 8)?, read(record + 12)?);
+        if !LAYOUT_TABLES.contains(&&tag) {
+            tables.push((tag, data.get(offset..offset.checked_add(len)?)?));
+        }
+    }
+
+    // Lay out the tables, each padded to four bytes, after the directory.
+    let start = 12 + 16 * tables.len();
+    let mut body = vec![];
+    let mut records = vec![];
+    for (tag, table) in &tables {
+        records.push((*tag, body.len(), table.len()));
+        body.extend(*table);
+        body.resize((body.len() + 3) / 4 * 4, 0);
+    }
+
+    // The head table's checksum adjustment is zeroed while the checksums
+    // are computed.
+    let head = records.iter().find(|(tag, ..)| tag == b"head").map(|&(_, at, _)| at);
+    if let Some(head) = head {
+        body.get_mut(head + 8..head + 12)?.fill(0);
+    }
+
+    let num = tables.len() as u16;
+    let entry_selector = 15u16.saturating_sub(num.leading_zeros() as u16);
+    let search_range = 16u16 << entry_selector;
+    let mut font = data.get(..4)?.to_vec();
+    font.extend(num.to_be_bytes());
+    font.extend(search_range.to_be_bytes());
+    font.extend(entry_selector.to_be_bytes());
+    font.extend((16 * num).saturating_sub(search_range).to_be_bytes());
+    for (tag, at, len) in records {
+        font.extend(tag);
+        font.extend(checksum(&body[at..at + len]).to_be_bytes());
+        font.extend(((start + at) as u32).to_be_bytes());
+        font.extend((len as u32).to_be_bytes());
+    }
+
+    font.extend(body);
+
+    // The adjustment makes the whole font sum up to a fixed value.
+    if let Some(head) = head {
+        let adjustment = 0xB1B0AFBAu32.wrapping_sub(checksum(&font));
+        font[start + head + 8..start + head + 12]
+            .copy_from_slice(&adjustment.to_be_bytes());
+    }
+
+    Some(font)
+}
+
+/// The sum of the big-endian 32-bit words of a table, padded with zeros.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4)
+        .map(|chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_be_bytes(word)
+        })
+        .fold(0, u32::wrapping_add)
+}
+
+/// Collect the glyphs used in a frame by font.
+fn collect_glyphs(frame: &Frame, used: &mut HashMap<Font, BTreeSet<u16>>) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => collect_glyphs(&group.frame, used),
+            FrameItem::Text(text) => used
+                .entry(text.font.clone())
+                .or_default()
+                .extend(text.glyphs.iter().map(|g| g.id)),
+            _ => {}
+        }
+    }
+}
 
 /// Embed all used fonts into the PDF.
 pub fn write_fonts(ctx: &mut PdfContext) {
+    let mut programs = HashMap::new();
+    let mut cmaps = HashMap::new();
+    let mut tags = HashSet::new();
     for font in ctx.font_map.items() {
         let type0_ref = ctx.alloc.bump();
         let cid_ref = ctx.alloc.bump();
         let descriptor_ref = ctx.alloc.bump();
-        let cmap_ref = ctx.alloc.bump();
-        let data_ref = ctx.alloc.bump();
         ctx.font_refs.push(type0_ref);
 
         let glyphs = &ctx.glyph_sets[font];
@@ -26,15 +195,42 @@ pub fn write_fonts(ctx: &mut PdfContext) {
             .find_name(name_id::POST_SCRIPT_NAME)
             .unwrap_or_else(|| "unknown".to_string());
 
-        let base_font = eco_format!("ABCDEF+{}", postscript_name);
+        let tag = subset_tag(font, glyphs, &mut tags);
+        let base_font = eco_format!("{}+{}", tag, postscript_name);
         let base_font = Name(base_font.as_bytes());
-        let cmap_name = Name(b"Custom");
         let system_info = SystemInfo {
             registry: Str(b"Adobe"),
             ordering: Str(b"Identity"),
             supplement: 0,
         };
 
+        // Compute a reverse mapping from glyphs to unicode. Faces with the
+        // same mapping for the used glyphs share a single character map.
+        let mut mapping = BTreeMap::new();
+        for subtable in ttf.tables().cmap.into_iter().flat_map(|table| table.subtables) {
+            if subtable.is_unicode() {
+                subtable.codepoints(|n| {
+                    if let Some(c) = std::char::from_u32(n) {
+                        if let Some(GlyphId(g)) = ttf.glyph_index(c) {
+                            if glyphs.contains(&g) {
+                                mapping.insert(g, c);
+                            }
+                        }
+                    }
+                });
+            }
+        }
+
+        let compression = ctx.options.compression;
+        let cmap = match compression {
+            Compression::None => unicode_cmap(&mapping, 2),
+            _ => deflate(&unicode_cmap(&mapping, 2), compression.level()),
+        };
+        let key = hash128(&cmap);
+        let existing_cmap = cmaps.get(&key).copied();
+        let cmap_ref = existing_cmap.unwrap_or_else(|| ctx.alloc.bump());
+        cmaps.insert(key, cmap_ref);
+
         // Write the base font object referencing the CID font.
         ctx.writer
             .type0_font(type0_ref)
@@ -54,6 +250,41 @@ pub fn write_fonts(ctx: &mut PdfContext) {
             None => CidFontType::Type2,
         };
 
+        // Subset the font's bytes, unless that already happened ahead of
+        // page construction.
+        let data = font.data();
+        let subsetted = ctx.subsets.remove(font).unwrap_or_else(|| {
+            let glyphs: Vec<_> = glyphs.iter().copied().collect();
+            subset(font, &glyphs)
+        });
+
+        // Subsetting CFF outlines relies on the subsetter, which drops the
+        // charstrings of unused glyphs. CID-keyed fonts are then embedded as
+        // just their CFF table, which is all a PDF viewer needs to draw the
+        // glyphs. Name-keyed CFF tables select glyphs by name rather than by
+        // CID and CFF2 tables can't be embedded on their own, so such fonts are
+        // embedded as OpenType fonts. The subsetter doesn't support CFF2, so
+        // these are embedded whole.
+        let data = subsetted.as_deref().unwrap_or(data);
+        let index = if subsetted.is_some() { 0 } else { font.index() };
+        let cff = match subtype {
+            CidFontType::Type0 => ttf_parser::RawFace::parse(data, index)
+                .ok()
+                .and_then(|face| face.table(Tag::from_bytes(b"CFF ")))
+                .filter(|cff| is_cid_keyed(cff)),
+            CidFontType::Type2 => None,
+        };
+
+        // Compress the font's bytes. Fonts whose embedded programs end up
+        // identical share a single stream.
+        let bare_cff = cff.is_some();
+        let data = cff.unwrap_or(data);
+        let data = deflate(data, compression.level());
+        let key = hash128(&data);
+        let existing = programs.get(&key).copied();
+        let data_ref = existing.unwrap_or_else(|| ctx.alloc.bump());
+        programs.insert(key, data_ref);
+
         // Write the CID font referencing the font descriptor.
         let mut cid = ctx.writer.cid_font(cid_ref);
         cid.subtype(subtype);
@@ -70,8 +301,8 @@ pub fn write_fonts(ctx: &mut PdfContext) {
         let num_glyphs = ttf.number_of_glyphs();
         let mut widths = vec![0.0; num_glyphs as usize];
         for &g in glyphs {
-            let x = ttf.glyph_hor_advance(GlyphId(g)).unwrap_or(0);
-            widths[g as usize] = font.to_em(x).to_font_units();
+            let x = font.advance(g).unwrap_or_default();
+            widths[g as usize] = x.to_font_units();
         }
 
         // Write all non-zero glyph widths.
@@ -129,56 +360,377 @@ pub fn write_fonts(ctx: &mut PdfContext) {
 
         font_descriptor.finish();
 
-        // Compute a reverse mapping from glyphs to unicode.
-        let cmap = {
-            let mut mapping = BTreeMap::new();
-            for subtable in
-                ttf.tables().cmap.into_iter().flat_map(|table| table.subtables)
-            {
-                if subtable.is_unicode() {
-                    subtable.codepoints(|n| {
-                        if let Some(c) = std::char::from_u32(n) {
-                            if let Some(GlyphId(g)) = ttf.glyph_index(c) {
-                                if glyphs.contains(&g) {
-                                    mapping.insert(g, c);
-                                }
-                            }
-                        }
-                    });
-                }
-            }
-
-            let mut cmap = UnicodeCmap::new(cmap_name, system_info);
-            for (g, c) in mapping {
-                cmap.pair(g, c);
-            }
-            cmap
-        };
-
         // Write the /ToUnicode character map, which maps glyph ids back to
         // unicode codepoints to enable copying out of the PDF.
-        ctx.writer
-            .cmap(cmap_ref, &deflate(&cmap.finish()))
-            .filter(Filter::FlateDecode);
+        if existing_cmap.is_none() {
+            let mut stream = ctx.writer.cmap(cmap_ref, &cmap);
+            if compression != Compression::None {
+                stream.filter(Filter::FlateDecode);
+            }
+        }
 
-        // Subset and write the font's bytes.
-        let data = font.data();
-        let subsetted = {
-            let glyphs: Vec<_> = glyphs.iter().copied().collect();
-            let profile = subsetter::Profile::pdf(&glyphs);
-            subsetter::subset(data, font.index(), profile)
-        };
+        if existing.is_some() {
+            continue;
+        }
 
-        // Compress and write the font's bytes.
-        let data = subsetted.as_deref().unwrap_or(data);
-        let data = deflate(data);
         let mut stream = ctx.writer.stream(data_ref, &data);
         stream.filter(Filter::FlateDecode);
 
         if subtype == CidFontType::Type0 {
-            stream.pair(Name(b"Subtype"), Name(b"CIDFontType0C"));
+            let format = if bare_cff { &b"CIDFontType0C"[..] } else { b"OpenType" };
+            stream.pair(Name(b"Subtype"), Name(format));
         }
 
         stream.finish();
     }
 }
+
+/// Derive the six-letter tag that prefixes the name of a font subset from the
+/// font and its glyphs.
+///
+/// The tag is stable across exports of the same subset. Should two subsets
+/// hash to the same tag, the later one is rehashed until it is unique within
+/// the document.
+fn subset_tag(font: &Font, glyphs: &HashSet<u16>, used: &mut HashSet<[u8; 6]>) -> String {
+    let mut glyphs: Vec<_> = glyphs.iter().copied().collect();
+    glyphs.sort_unstable();
+
+    let mut hash = hash128(&(font, glyphs));
+    loop {
+        let mut tag = [0; 6];
+        let mut rest = hash;
+        for letter in &mut tag {
+            *letter = b'A' + (rest % 26) as u8;
+            rest /= 26;
+        }
+
+        if used.insert(tag) {
+            return tag.iter().map(|&b| b as char).collect();
+        }
+
+        hash = hash128(&hash);
+    }
+}
+
+/// Create a /ToUnicode character map from character codes with the given
+/// number of bytes to characters.
+///
+/// Runs of consecutive glyphs that map to consecutive characters, which are
+/// common in CJK fonts, are written as a single range instead of one entry
+/// per glyph.
+pub(super) fn unicode_cmap(mapping: &BTreeMap<u16, char>, bytes: usize) -> Vec<u8> {
+    // Group the mapping into runs of first glyph, last glyph and first
+    // character. Ranges may only vary in the last byte of both the glyph id
+    // and the character's UTF-16 encoding.
+    let mut runs: Vec<(u16, u16, char)> = vec![];
+    for (&g, &c) in mapping {
+        if let Some((first, last, start)) = runs.last_mut() {
+            let (c, start) = (c as u32, *start as u32);
+            if g == *last + 1
+                && g >> 8 == *first >> 8
+                && c == start + u32::from(g - *first)
+                && c <= 0xFFFF
+                && c >> 8 == start >> 8
+            {
+                *last = g;
+                continue;
+            }
+        }
+        runs.push((g, g, c));
+    }
+
+    let (ranges, pairs): (Vec<_>, Vec<_>) =
+        runs.into_iter().partition(|(first, last, _)| first != last);
+
+    let digits = 2 * bytes;
+    let mut buf = String::from(CMAP_PROLOGUE);
+    writeln!(buf, "1 begincodespacerange").unwrap();
+    writeln!(buf, "<{:0digits$X}> <{:0digits$X}>", 0, (1u32 << (8 * bytes)) - 1).unwrap();
+    buf.push_str("endcodespacerange\n");
+
+    for chunk in pairs.chunks(100) {
+        writeln!(buf, "{} beginbfchar", chunk.len()).unwrap();
+        for &(g, _, c) in chunk {
+            writeln!(buf, "<{g:0digits$X}> <{}>", utf16_hex(c)).unwrap();
+        }
+        buf.push_str("endbfchar\n");
+    }
+
+    for chunk in ranges.chunks(100) {
+        writeln!(buf, "{} beginbfrange", chunk.len()).unwrap();
+        for &(first, last, c) in chunk {
+            writeln!(buf, "<{first:0digits$X}> <{last:0digits$X}> <{}>", utf16_hex(c))
+                .unwrap();
+        }
+        buf.push_str("endbfrange\n");
+    }
+
+    buf.push_str(CMAP_EPILOGUE);
+    buf.into_bytes()
+}
+
+/// Create a /ToUnicode character map from single-byte character codes to
+/// the possibly multi-character text they stand for.
+///
+/// Codes without text are left unmapped.
+pub(super) fn cluster_cmap(mapping: &BTreeMap<u8, &str>) -> Vec<u8> {
+    let pairs: Vec<_> = mapping.iter().filter(|(_, text)| !text.is_empty()).collect();
+
+    let mut buf = String::from(CMAP_PROLOGUE);
+    buf.push_str("1 begincodespacerange\n<00> <FF>\nendcodespacerange\n");
+
+    for chunk in pairs.chunks(100) {
+        writeln!(buf, "{} beginbfchar", chunk.len()).unwrap();
+        for (code, text) in chunk {
+            let hex: String =
+                text.encode_utf16().map(|unit| format!("{unit:04X}")).collect();
+            writeln!(buf, "<{code:02X}> <{hex}>").unwrap();
+        }
+        buf.push_str("endbfchar\n");
+    }
+
+    buf.push_str(CMAP_EPILOGUE);
+    buf.into_bytes()
+}
+
+/// Encode a character as hexadecimal UTF-16.
+fn utf16_hex(c: char) -> String {
+    c.encode_utf16(&mut [0; 2])
+        .iter()
+        .map(|unit| format!("{unit:04X}"))
+        .collect()
+}
+
+/// The start of a /ToUnicode character map, up to its code space.
+const CMAP_PROLOGUE: &str = "\
+%!PS-Adobe-3.0 Resource-CMap
+%%DocumentNeededResources: ProcSet (CIDInit)
+%%IncludeResource: ProcSet (CIDInit)
+/CIDInit /ProcSet findresource begin
+12 dict begin
+begincmap
+/CIDSystemInfo 3 dict dup begin
+/Registry (Adobe) def
+/Ordering (Identity) def
+/Supplement 0 def
+end def
+/CMapName /Custom def
+/CMapVersion 1 def
+/CMapType 2 def
+";
+
+/// The end of a /ToUnicode character map, after its mappings.
+const CMAP_EPILOGUE: &str = "\
+endcmap
+CMapName currentdict /CMap defineresource pop
+end
+end
+";
+
+/// Whether a CFF table holds a CID-keyed font.
+///
+/// The top dictionary of such a font starts with the operator that names its
+/// character collection.
+fn is_cid_keyed(cff: &[u8]) -> bool {
+    first_cff_operator(cff) == Some((12, 30))
+}
+
+/// Find the first operator in the top dictionary of a CFF table.
+fn first_cff_operator(data: &[u8]) -> Option<(u8, u8)> {
+    let read_u16 = |at: usize| {
+        data.get(at..at + 2)
+            .map(|b| usize::from(u16::from_be_bytes([b[0], b[1]])))
+    };
+    let read_offset = |at: usize, size: usize| {
+        let bytes = data.get(at..at + size)?;
+        Some(bytes.iter().fold(0, |acc, &b| (acc << 8) | usize::from(b)))
+    };
+
+    // Skip the header and the name INDEX, which is followed by the top
+    // dictionary INDEX. The offsets in an INDEX start at one.
+    let start = usize::from(*data.get(2)?);
+    let mut top = start + 2;
+    let count = read_u16(start)?;
+    if count > 0 {
+        let size = usize::from(*data.get(start + 2)?);
+        let last = start + 3 + count * size;
+        top = last + size + read_offset(last, size)?.checked_sub(1)?;
+    }
+
+    let size = usize::from(*data.get(top + 2)?);
+    let count = read_u16(top)?;
+    if count == 0 {
+        return None;
+    }
+
+    let first = read_offset(top + 3, size)?.checked_sub(1)?;
+    let mut i = top + 3 + (count + 1) * size + first;
+    loop {
+        match *data.get(i)? {
+            12 => return Some((12, *data.get(i + 1)?)),
+            b @ 0..=21 => return Some((b, 0)),
+            28 => i += 3,
+            29 => i += 5,
+            30 => {
+                // A real number is made up of nibbles up to a terminating one.
+                i += 1;
+                while data.get(i).map(|&b| b & 0x0f != 0x0f && b >> 4 != 0x0f)? {
+                    i += 1;
+                }
+                i += 1;
+            }
+            32..=246 => i += 1,
+            247..=254 => i += 2,
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{export_pages, plex, tables, test_font, text_page};
+    use super::*;
+    use crate::export::PdfOptions;
+
+    /// The CFF table of a font.
+    fn cff(font: &Font) -> &[u8] {
+        font.ttf().raw_face().table(Tag::from_bytes(b"CFF ")).unwrap()
+    }
+
+    #[test]
+    fn test_cid_keyed_cff() {
+        let name_keyed =
+            test_font(include_bytes!("../../../assets/fonts/NewCM10-Regular.otf"));
+        let cid_keyed =
+            test_font(include_bytes!("../../../assets/fonts/NotoSerifCJKsc-Regular.otf"));
+        assert!(!is_cid_keyed(cff(&name_keyed)));
+        assert!(is_cid_keyed(cff(&cid_keyed)));
+        assert!(!is_cid_keyed(&[]));
+    }
+
+    #[test]
+    fn test_subset_tag() {
+        let font = plex();
+        let tag = |glyphs: &[u16], used: &mut HashSet<[u8; 6]>| {
+            subset_tag(&font, &glyphs.iter().copied().collect(), used)
+        };
+
+        // Tags are six uppercase letters that only depend on the subset.
+        let first = tag(&[3, 1, 2], &mut HashSet::new());
+        assert_eq!(first.len(), 6);
+        assert!(first.bytes().all(|b| b.is_ascii_uppercase()));
+        assert_eq!(tag(&[1, 2, 3], &mut HashSet::new()), first);
+        assert_ne!(tag(&[1, 2, 4], &mut HashSet::new()), first);
+
+        // Tags that are already taken are rehashed.
+        let mut used = HashSet::new();
+        assert_eq!(tag(&[1, 2, 3], &mut used), first);
+        let second = tag(&[1, 2, 3], &mut used);
+        assert_ne!(second, first);
+        assert_eq!(second.len(), 6);
+        assert_eq!(used.len(), 2);
+    }
+
+    #[test]
+    fn test_unicode_cmap() {
+        let mapping = BTreeMap::from([
+            (0x10, 'A'),
+            (0x11, 'B'),
+            (0x12, 'C'),
+            (0x13, 'Z'),
+            (0x20, '\u{1F600}'),
+            (0x30, '\u{FF}'),
+            (0x31, '\u{100}'),
+            (0xFF, 'a'),
+            (0x100, 'b'),
+        ]);
+
+        // Only the run of consecutive letters becomes a range. Runs may not
+        // cross into the next high byte of the glyph id or character.
+        let cmap = String::from_utf8(unicode_cmap(&mapping, 2)).unwrap();
+        assert!(cmap.contains("<0000> <FFFF>\nendcodespacerange\n"));
+        assert!(cmap.contains(
+            "6 beginbfchar\n\
+             <0013> <005A>\n\
+             <0020> <D83DDE00>\n\
+             <0030> <00FF>\n\
+             <0031> <0100>\n\
+             <00FF> <0061>\n\
+             <0100> <0062>\n\
+             endbfchar\n\
+             1 beginbfrange\n\
+             <0010> <0012> <0041>\n\
+             endbfrange\n"
+        ));
+
+        // Single-byte codes have a smaller code space.
+        let cmap = unicode_cmap(&BTreeMap::from([(0, 'A')]), 1);
+        let cmap = String::from_utf8(cmap).unwrap();
+        assert!(cmap.contains("<00> <FF>\n"));
+        assert!(cmap.contains("1 beginbfchar\n<00> <0041>\nendbfchar\n"));
+
+        // Sections hold at most 100 entries.
+        let mapping = (0..150).map(|g| (g, 'A')).collect();
+        let cmap = String::from_utf8(unicode_cmap(&mapping, 2)).unwrap();
+        assert!(cmap.contains("100 beginbfchar\n"));
+        assert!(cmap.contains("50 beginbfchar\n"));
+        assert!(!cmap.contains("beginbfrange"));
+    }
+
+    #[test]
+    fn test_subset_without_layout_tables() {
+        let font = plex();
+        let original: Vec<_> =
+            tables(font.data()).into_iter().map(|(tag, _)| tag).collect();
+        assert!(original.contains(b"GSUB"));
+        assert!(original.contains(b"GPOS"));
+
+        let pdf = export_pages(vec![text_page(&font, "office")], PdfOptions::default());
+        let descriptor = pdf.of_type("FontDescriptor").next().unwrap().dict();
+        let program = pdf.get(descriptor.get("FontFile2").unwrap()).decoded();
+
+        // The glyphs in the PDF are already shaped, so the subset has the
+        // tables of the subsetter's output except for the layout tables.
+        let mut glyphs: Vec<_> = "ofice"
+            .chars()
+            .map(|c| font.ttf().glyph_index(c).unwrap().0)
+            .collect();
+        glyphs.sort();
+        let profile = subsetter::Profile::pdf(&glyphs);
+        let full = subsetter::subset(font.data(), 0, profile).unwrap();
+        let expected: Vec<_> = tables(&full)
+            .into_iter()
+            .filter(|(tag, _)| !LAYOUT_TABLES.contains(&tag))
+            .collect();
+        let subset = tables(&program);
+        assert_eq!(subset.len(), expected.len());
+        assert!(subset.iter().any(|(tag, _)| tag == b"glyf"));
+
+        // The tables are copied as they are, except for the head table's
+        // checksum adjustment, which makes the whole font's checksum valid.
+        for ((tag, table), (expected_tag, expected_table)) in subset.iter().zip(&expected)
+        {
+            assert_eq!(tag, expected_tag);
+            if tag != b"head" {
+                assert_eq!(table, expected_table);
+            }
+        }
+        assert_eq!(checksum(&program), 0xB1B0AFBA);
+        assert!(ttf_parser::Face::parse(&program, 0).is_ok());
+    }
+
+    #[test]
+    fn test_strip_layout_tables() {
+        let font = plex();
+        let stripped = strip_layout_tables(font.data()).unwrap();
+        let tags: Vec<_> = tables(&stripped).into_iter().map(|(tag, _)| tag).collect();
+        assert!(tables(font.data()).iter().any(|(tag, _)| tag == b"GDEF"));
+        assert!(!tags.contains(b"GDEF"));
+        assert_eq!(tags.len(), tables(font.data()).len() - 3);
+        assert_eq!(checksum(&stripped), 0xB1B0AFBA);
+
+        // Malformed directories are rejected.
+        assert_eq!(strip_layout_tables(&font.data()[..20]), None);
+        assert_eq!(strip_layout_tables(&[]), None);
+    }
+}
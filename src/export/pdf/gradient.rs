@@ -0,0 +1,93 @@
+use pdf_writer::{Finish, Name};
+
+use super::{AbsExt, PdfContext, RefExt};
+use crate::geom::{Color, Gradient, GradientKind, Transform};
+
+/// A gradient placed on a page.
+///
+/// PDF patterns are positioned relative to the page instead of the current
+/// transformation matrix, so each placement of a gradient needs its own
+/// pattern.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfGradient {
+    /// The gradient itself.
+    pub gradient: Gradient,
+    /// Maps the unit square to the painted bounding box on the page.
+    pub transform: Transform,
+}
+
+/// Write all used gradients as shading patterns.
+pub fn write_gradients(ctx: &mut PdfContext) {
+    for pdf_gradient in ctx.gradient_map.items() {
+        let pattern_ref = ctx.alloc.bump();
+        ctx.gradient_refs.push(pattern_ref);
+
+        let PdfGradient { gradient, transform } = pdf_gradient;
+        let Transform { sx, ky, kx, sy, tx, ty } = *transform;
+
+        let mut pattern = ctx.writer.indirect(pattern_ref).dict();
+        pattern.pair(Name(b"Type"), Name(b"Pattern"));
+        pattern.pair(Name(b"PatternType"), 2);
+        pattern.insert(Name(b"Matrix")).array().items([
+            sx.get() as f32,
+            ky.get() as f32,
+            kx.get() as f32,
+            sy.get() as f32,
+            tx.to_f32(),
+            ty.to_f32(),
+        ]);
+
+        // Gradients between two CMYK colors are interpolated in CMYK so that
+        // they stay in the device color space for print.
+        let cmyk =
+            matches!((gradient.from, gradient.to), (Color::Cmyk(_), Color::Cmyk(_)));
+        let mut shading = pattern.insert(Name(b"Shading")).dict();
+        shading.pair(
+            Name(b"ColorSpace"),
+            Name(if cmyk { b"DeviceCMYK".as_slice() } else { b"DeviceRGB" }),
+        );
+        match gradient.kind {
+            GradientKind::Linear(angle) => {
+                let [(x0, y0), (x1, y1)] = Gradient::line(angle);
+                shading.pair(Name(b"ShadingType"), 2);
+                shading
+                    .insert(Name(b"Coords"))
+                    .array()
+                    .items([x0, y0, x1, y1].map(|v| v as f32));
+            }
+            GradientKind::Radial => {
+                shading.pair(Name(b"ShadingType"), 3);
+                shading
+                    .insert(Name(b"Coords"))
+                    .array()
+                    .items([0.5, 0.5, 0.0, 0.5, 0.5, 0.5]);
+            }
+        }
+
+        // The alpha channel is dropped as shadings have no notion of it.
+        let mut function = shading.insert(Name(b"Function")).dict();
+        function.pair(Name(b"FunctionType"), 2);
+        function.insert(Name(b"Domain")).array().items([0.0, 1.0]);
+        let [c0, c1] = [gradient.from, gradient.to].map(|c| components(c, cmyk));
+        function.insert(Name(b"C0")).array().items(c0);
+        function.insert(Name(b"C1")).array().items(c1);
+        function.pair(Name(b"N"), 1.0);
+        function.finish();
+
+        shading.insert(Name(b"Extend")).array().items([true, true]);
+        shading.finish();
+        pattern.finish();
+    }
+}
+
+/// The RGB or CMYK components of a color between zero and one.
+fn components(color: Color, cmyk: bool) -> Vec<f32> {
+    let f = |v: u8| v as f32 / 255.0;
+    match color {
+        Color::Cmyk(c) if cmyk => vec![f(c.c), f(c.m), f(c.y), f(c.k)],
+        _ => {
+            let c = color.to_rgba();
+            vec![f(c.r), f(c.g), f(c.b)]
+        }
+    }
+}
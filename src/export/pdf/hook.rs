@@ -0,0 +1,164 @@
+use ecow::{eco_format, EcoString};
+use pdf_writer::{Content, PdfWriter, Ref};
+
+use super::RefExt;
+use crate::doc::Frame;
+use crate::geom::Size;
+
+/// A callback that can add raw content and objects to each exported page.
+///
+/// This is an escape hatch for PDF features the exporter doesn't model yet.
+/// Hooks work directly with [`pdf_writer`] primitives and nothing they write
+/// is validated, so they can easily produce broken files.
+///
+/// # Stability
+/// The resource names, object numbers, and graphics state the exporter uses
+/// for its own content are implementation details that may change in any
+/// release. A hook should only rely on what [`PageExtras`] provides. The
+/// version of `pdf-writer` it must be written against can change, too.
+pub trait PageHook {
+    /// Called once for each page, after the page's own content was encoded.
+    fn page(&mut self, page: &mut PageExtras);
+}
+
+impl<F> PageHook for F
+where
+    F: FnMut(&mut PageExtras),
+{
+    fn page(&mut self, page: &mut PageExtras) {
+        self(page)
+    }
+}
+
+/// Access to a page during export, handed to a [`PageHook`].
+pub struct PageExtras<'a> {
+    pub(super) index: usize,
+    pub(super) frame: &'a Frame,
+    pub(super) writer: &'a mut PdfWriter,
+    pub(super) alloc: &'a mut Ref,
+    pub(super) content: Content,
+    pub(super) x_objects: &'a mut Vec<Ref>,
+    pub(super) ext_gs: &'a mut Vec<Ref>,
+    pub(super) annotations: Vec<Ref>,
+}
+
+impl PageExtras<'_> {
+    /// The zero-based index of the page.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The laid out page.
+    pub fn frame(&self) -> &Frame {
+        self.frame
+    }
+
+    /// The size of the page.
+    pub fn size(&self) -> Size {
+        self.frame.size()
+    }
+
+    /// Content that is drawn on top of the page.
+    ///
+    /// It starts in PDF's default coordinate system, with the origin at the
+    /// bottom-left of the page and the y-axis pointing upwards, and with a
+    /// fresh graphics state.
+    pub fn content(&mut self) -> &mut Content {
+        &mut self.content
+    }
+
+    /// The writer for extra indirect objects. Their ids must come from
+    /// [`alloc`](Self::alloc).
+    pub fn writer(&mut self) -> &mut PdfWriter {
+        self.writer
+    }
+
+    /// Allocate an id for an indirect object.
+    pub fn alloc(&mut self) -> Ref {
+        self.alloc.bump()
+    }
+
+    /// Make an external object available to all pages' content and return
+    /// the name under which it can be drawn.
+    pub fn x_object(&mut self, id: Ref) -> EcoString {
+        self.x_objects.push(id);
+        eco_format!("HkIm{}", self.x_objects.len() - 1)
+    }
+
+    /// Make an external graphics state available to all pages' content and
+    /// return the name under which it can be set.
+    pub fn ext_g_state(&mut self, id: Ref) -> EcoString {
+        self.ext_gs.push(id);
+        eco_format!("HkGs{}", self.ext_gs.len() - 1)
+    }
+
+    /// Add an annotation dictionary written to the given id to the page.
+    pub fn annotation(&mut self, id: Ref) {
+        self.annotations.push(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pdf_writer::{Finish, Name, Rect};
+
+    use super::*;
+    use crate::doc::Document;
+    use crate::export::{Compression, PdfExporter, PdfOptions};
+    use crate::geom::Abs;
+
+    #[test]
+    fn test_page_hook() {
+        let page = Frame::new(Size::splat(Abs::pt(100.0)));
+        let document = Document {
+            pages: vec![page.clone(), page],
+            ..Default::default()
+        };
+        let options = PdfOptions {
+            compression: Compression::None,
+            ..Default::default()
+        };
+
+        // Draw a square, a form and a note on the first page only.
+        let mut indices = vec![];
+        let mut hook = |extras: &mut PageExtras| {
+            indices.push(extras.index());
+            if extras.index() > 0 {
+                return;
+            }
+
+            let form = extras.alloc();
+            extras
+                .writer()
+                .form_xobject(form, b"")
+                .bbox(Rect::new(0.0, 0.0, 1.0, 1.0));
+            let name = extras.x_object(form);
+            let note = extras.alloc();
+            let mut annotation = extras.writer().indirect(note).dict();
+            annotation.pair(Name(b"Subtype"), Name(b"Text"));
+            annotation.finish();
+            extras.annotation(note);
+            extras.content().rect(0.0, 0.0, 10.0, 10.0).fill_nonzero();
+            extras.content().x_object(Name(name.as_bytes()));
+        };
+
+        let data = PdfExporter::new()
+            .with_options(options)
+            .with_hook(&mut hook)
+            .export(&document);
+        let pdf = String::from_utf8_lossy(&data);
+        assert_eq!(indices, [0, 1]);
+
+        // The page's own content is wrapped into a save and restore pair,
+        // followed by the hook's content.
+        assert_eq!(pdf.matches("/Contents [").count(), 1);
+        assert_eq!(pdf.matches("stream\nq\n").count(), 1);
+        assert!(pdf.contains("stream\nQ\n0 0 10 10 re\nf\n/HkIm0 Do"));
+
+        // The form is a shared resource and the note is on the first page.
+        assert!(pdf.contains("/HkIm0 "));
+        assert_eq!(pdf.matches("/Annots [").count(), 2);
+        assert_eq!(pdf.matches("/Annots []").count(), 1);
+        assert_eq!(pdf.matches("/Subtype /Text").count(), 1);
+    }
+}
@@ -1,100 +1,287 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::Cursor;
 
-use image::{DynamicImage, GenericImageView, ImageResult, Rgba};
-use pdf_writer::{Filter, Finish};
+use image::codecs::jpeg::JpegEncoder;
+use image::{
+    ColorType, DynamicImage, GenericImageView, GrayImage, ImageResult, Luma, Rgb, Rgba,
+};
+use pdf_writer::writers::ImageXObject;
+use pdf_writer::{Filter, Finish, Name, Str};
 
-use super::{deflate, PdfContext, RefExt};
-use crate::image::{DecodedImage, RasterFormat};
+use super::{deflate, Grayscale, PdfContext, PdfOptions, RefExt};
+use crate::image::{DecodedImage, Image, ImageFormat, RasterFormat};
+use crate::util::hash128;
 
 /// Embed all used images into the PDF.
 pub fn write_images(ctx: &mut PdfContext) {
+    let mut streams = HashMap::new();
+    let level = ctx.options.compression.level();
     for image in ctx.image_map.items() {
-        let image_ref = ctx.alloc.bump();
-        ctx.image_refs.push(image_ref);
-
         let width = image.width();
         let height = image.height();
 
-        // Add the primary image.
-        // TODO: Error if image could not be encoded.
-        match image.decode().unwrap().as_ref() {
-            DecodedImage::Raster(dynamic, format) => {
-                // TODO: Error if image could not be encoded.
-                let (data, filter, has_color) = encode_image(*format, dynamic).unwrap();
-                let mut image = ctx.writer.image_xobject(image_ref, &data);
-                image.filter(filter);
-                image.width(width as i32);
-                image.height(height as i32);
-                image.bits_per_component(8);
-
-                let space = image.color_space();
-                if has_color {
-                    space.device_rgb();
-                } else {
-                    space.device_gray();
+        // Add the primary image. JPEGs are embedded as they are if nothing
+        // needs to change about their pixels, skipping the lossy and slow
+        // decode and re-encode round trip.
+        let encoded = match original_jpeg(image, &ctx.options) {
+            Some((data, space)) => {
+                (Cow::Borrowed(data), Filter::DctDecode, space, 8, None)
+            }
+            // TODO: Error if image could not be encoded.
+            None => match image.decode().unwrap().as_ref() {
+                DecodedImage::Raster(dynamic, format) => {
+                    // TODO: Error if image could not be encoded.
+                    let gray =
+                        ctx.options.grayscale.map(|weights| to_gray(dynamic, weights));
+                    // Images with an alpha channel stay lossless, even when
+                    // they are converted to gray without it.
+                    let quality =
+                        ctx.options.jpeg_quality.filter(|_| !dynamic.color().has_alpha());
+                    let (data, filter, space, bits) = encode_image(
+                        *format,
+                        gray.as_ref().unwrap_or(dynamic),
+                        quality,
+                        level,
+                    )
+                    .unwrap();
+                    let alpha =
+                        dynamic.color().has_alpha().then(|| encode_alpha(dynamic, level));
+                    (Cow::Owned(data), filter, space, bits, alpha)
                 }
-
-                // Add a second gray-scale image containing the alpha values if
-                // this image has an alpha channel.
-                if dynamic.color().has_alpha() {
-                    let (alpha_data, alpha_filter) = encode_alpha(dynamic);
-                    let mask_ref = ctx.alloc.bump();
-                    image.s_mask(mask_ref);
-                    image.finish();
-
-                    let mut mask = ctx.writer.image_xobject(mask_ref, &alpha_data);
-                    mask.filter(alpha_filter);
-                    mask.width(width as i32);
-                    mask.height(height as i32);
-                    mask.color_space().device_gray();
-                    mask.bits_per_component(8);
+                DecodedImage::Svg(svg) => {
+                    let image_ref = ctx.alloc.bump();
+                    ctx.image_refs.push(image_ref);
+                    let next_ref = svg2pdf::convert_tree_into(
+                        svg,
+                        svg2pdf::Options::default(),
+                        &mut ctx.writer,
+                        image_ref,
+                    );
+                    ctx.alloc = next_ref;
+                    continue;
                 }
+            },
+        };
+
+        let (data, filter, space, bits, alpha) = encoded;
+
+        // Distinct images that encode to the same streams, like a logo that
+        // was loaded from two files, are only written once.
+        let mask = alpha.as_ref().map(|(data, _)| data);
+        let key = hash128(&(&data, &space, bits, width, height, mask));
+        if let Some(&existing) = streams.get(&key) {
+            ctx.image_refs.push(existing);
+            continue;
+        }
+
+        let image_ref = ctx.alloc.bump();
+        ctx.image_refs.push(image_ref);
+        streams.insert(key, image_ref);
+
+        let mut image = ctx.writer.image_xobject(image_ref, &data);
+        image.filter(filter);
+        image.width(width as i32);
+        image.height(height as i32);
+        image.bits_per_component(bits);
+        if matches!(filter, Filter::FlateDecode) {
+            let colors = if space == Space::Rgb { 3 } else { 1 };
+            write_predictor(&mut image, colors, bits, width);
+        }
+
+        match &space {
+            Space::Gray => image.color_space().device_gray(),
+            Space::Rgb => image.color_space().device_rgb(),
+            Space::Indexed(palette) => {
+                image
+                    .insert(Name(b"ColorSpace"))
+                    .array()
+                    .item(Name(b"Indexed"))
+                    .item(Name(b"DeviceRGB"))
+                    .item(palette.len() as i32 / 3 - 1)
+                    .item(Str(palette));
+            }
+        }
+
+        // Add a second gray-scale image containing the alpha values if this
+        // image has an alpha channel.
+        if let Some((alpha_data, alpha_filter)) = alpha {
+            let mask_ref = ctx.alloc.bump();
+            image.s_mask(mask_ref);
+            image.finish();
+
+            let mut mask = ctx.writer.image_xobject(mask_ref, &alpha_data);
+            mask.filter(alpha_filter);
+            mask.width(width as i32);
+            mask.height(height as i32);
+            mask.color_space().device_gray();
+            mask.bits_per_component(8);
+            write_predictor(&mut mask, 1, 8, width);
+        }
+    }
+}
+
+/// The color space of an encoded image.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum Space {
+    /// One gray component per pixel.
+    Gray,
+    /// Red, green, and blue components per pixel.
+    Rgb,
+    /// One index per pixel into a palette of red, green, and blue triples.
+    Indexed(Vec<u8>),
+}
+
+/// Images with at most this many distinct colors are written with a
+/// palette.
+const MAX_PALETTE: usize = 256;
+
+/// The original data of a JPEG and its color space, if it can be embedded
+/// without re-encoding.
+///
+/// This is not the case if its pixels need to be adjusted or if it uses
+/// features that PDF's `DCTDecode` filter doesn't support or that we can't
+/// describe, like CMYK, 12-bit samples, or arithmetic coding.
+fn original_jpeg<'a>(
+    image: &'a Image,
+    options: &PdfOptions,
+) -> Option<(&'a [u8], Space)> {
+    if image.format() != ImageFormat::Raster(RasterFormat::Jpg)
+        || !image.effects().is_none()
+        || options.grayscale.is_some()
+        || options.jpeg_quality.is_some()
+    {
+        return None;
+    }
+
+    let data = image.data().as_slice();
+    match jpeg_components(data)? {
+        1 => Some((data, Space::Gray)),
+        3 => Some((data, Space::Rgb)),
+        _ => None,
+    }
+}
+
+/// Find the number of color components of an 8-bit, Huffman-coded JPEG by
+/// scanning its markers up to the start-of-frame segment.
+fn jpeg_components(data: &[u8]) -> Option<u8> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut i = 2;
+    loop {
+        // Markers may be preceded by any number of fill bytes.
+        if *data.get(i)? != 0xFF {
+            return None;
+        }
+        while *data.get(i)? == 0xFF {
+            i += 1;
+        }
+
+        let marker = data[i];
+        i += 1;
+        if matches!(marker, 0x01 | 0xD0..=0xD7) {
+            continue;
+        }
+
+        let len = u16::from_be_bytes([*data.get(i)?, *data.get(i + 1)?]) as usize;
+        let segment = data.get(i + 2..i + len)?;
+        match marker {
+            // Baseline, extended sequential and progressive frames.
+            0xC0..=0xC2 => {
+                let precision = *segment.first()?;
+                return (precision == 8).then_some(*segment.get(5)?);
             }
-            DecodedImage::Svg(svg) => {
-                let next_ref = svg2pdf::convert_tree_into(
-                    svg,
-                    svg2pdf::Options::default(),
-                    &mut ctx.writer,
-                    image_ref,
-                );
-                ctx.alloc = next_ref;
+            // Lossless, hierarchical and arithmetic-coded frames, or the
+            // start of the scan without any frame header.
+            0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF | 0xD9 | 0xDA => {
+                return None;
             }
+            _ => i += len,
         }
     }
 }
 
-/// Encode an image with a suitable filter and return the data, filter and
-/// whether the image has color.
+/// Encode an image with a suitable filter and return the data, filter, color
+/// space, and the number of bits per component.
 ///
-/// Skips the alpha channel as that's encoded separately.
+/// If a JPEG quality is given, opaque 8-bit RGB and gray images are
+/// re-encoded as JPEGs with that quality, unless they have few enough colors
+/// to be written with a palette or as black and white. Otherwise, raw pixels
+/// are deflated with the given level. Skips the alpha channel as that's
+/// encoded separately.
 fn encode_image(
     format: RasterFormat,
     dynamic: &DynamicImage,
-) -> ImageResult<(Vec<u8>, Filter, bool)> {
+    quality: Option<u8>,
+    level: u8,
+) -> ImageResult<(Vec<u8>, Filter, Space, i32)> {
+    if let Some(quality) = quality {
+        let (lossy, gray) = match dynamic {
+            DynamicImage::ImageLuma8(luma) => {
+                (!luma.as_raw().iter().all(|&v| v == 0 || v == 255), true)
+            }
+            DynamicImage::ImageRgb8(rgb) => (palettize(rgb.as_raw()).is_none(), false),
+            _ => (false, false),
+        };
+
+        if lossy {
+            let data = encode_jpeg(dynamic, gray, quality)?;
+            let space = if gray { Space::Gray } else { Space::Rgb };
+            return Ok((data, Filter::DctDecode, space, 8));
+        }
+    }
+
     Ok(match (format, dynamic) {
         // 8-bit gray JPEG.
         (RasterFormat::Jpg, DynamicImage::ImageLuma8(_)) => {
             let mut data = Cursor::new(vec![]);
             dynamic.write_to(&mut data, image::ImageFormat::Jpeg)?;
-            (data.into_inner(), Filter::DctDecode, false)
+            (data.into_inner(), Filter::DctDecode, Space::Gray, 8)
         }
 
         // 8-bit RGB JPEG (CMYK JPEGs get converted to RGB earlier).
         (RasterFormat::Jpg, DynamicImage::ImageRgb8(_)) => {
             let mut data = Cursor::new(vec![]);
             dynamic.write_to(&mut data, image::ImageFormat::Jpeg)?;
-            (data.into_inner(), Filter::DctDecode, true)
+            (data.into_inner(), Filter::DctDecode, Space::Rgb, 8)
+        }
+
+        // Black-and-white scans, packed into one bit per pixel.
+        (_, DynamicImage::ImageLuma8(luma))
+            if luma.as_raw().iter().all(|&v| v == 0 || v == 255) =>
+        {
+            let bits: Vec<_> = luma.iter().map(|&v| v / 255).collect();
+            let (packed, stride) = pack(&bits, luma.width(), 1);
+            let data = deflate(&predict(&packed, stride, 1), level);
+            (data, Filter::FlateDecode, Space::Gray, 1)
         }
 
-        // TODO: Encode flat streams with PNG-predictor?
+        // 8-bit gray PNG (or a converted image).
+        (_, DynamicImage::ImageLuma8(luma)) => {
+            let data = deflate(&predict(luma.as_raw(), luma.width() as usize, 1), level);
+            (data, Filter::FlateDecode, Space::Gray, 8)
+        }
+
+        // 16-bit gray PNG, with its alpha channel encoded separately.
+        (_, DynamicImage::ImageLuma16(_) | DynamicImage::ImageLumaA16(_)) => {
+            let luma = dynamic.to_luma16();
+            let samples: Vec<_> = luma.iter().flat_map(|v| v.to_be_bytes()).collect();
+            let data = deflate(&predict(&samples, 2 * luma.width() as usize, 2), level);
+            (data, Filter::FlateDecode, Space::Gray, 16)
+        }
 
-        // 8-bit gray PNG.
-        (RasterFormat::Png, DynamicImage::ImageLuma8(luma)) => {
-            let data = deflate(luma.as_raw());
-            (data, Filter::FlateDecode, false)
+        // 16-bit RGB(A) PNG, with its alpha channel encoded separately.
+        (_, DynamicImage::ImageRgb16(_) | DynamicImage::ImageRgba16(_)) => {
+            let rgb = dynamic.to_rgb16();
+            let samples: Vec<_> = rgb.iter().flat_map(|v| v.to_be_bytes()).collect();
+            let data = deflate(&predict(&samples, 6 * rgb.width() as usize, 6), level);
+            (data, Filter::FlateDecode, Space::Rgb, 16)
         }
 
-        // Anything else (including Rgb(a) PNGs).
+        // Anything else (including Rgb(a) PNGs). Images with few colors, like
+        // diagrams and screenshots, are written with a palette.
         (_, buf) => {
             let (width, height) = buf.dimensions();
             let mut pixels = Vec::with_capacity(3 * width as usize * height as usize);
@@ -104,14 +291,473 @@ fn encode_image(
                 pixels.push(b);
             }
 
-            let data = deflate(&pixels);
-            (data, Filter::FlateDecode, true)
+            if let Some((indices, palette)) = palettize(&pixels) {
+                let bits = match palette.len() / 3 {
+                    0..=2 => 1,
+                    3..=4 => 2,
+                    5..=16 => 4,
+                    _ => 8,
+                };
+
+                let (packed, stride) = pack(&indices, width, bits);
+                let data = deflate(&predict(&packed, stride, 1), level);
+                (data, Filter::FlateDecode, Space::Indexed(palette), i32::from(bits))
+            } else {
+                let data = deflate(&predict(&pixels, 3 * width as usize, 3), level);
+                (data, Filter::FlateDecode, Space::Rgb, 8)
+            }
         }
     })
 }
 
+/// Find the palette of RGB pixels and the index of each pixel's color in it.
+///
+/// Returns `None` if there are more than [`MAX_PALETTE`] distinct colors.
+fn palettize(pixels: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut palette = vec![];
+    let mut lookup = HashMap::new();
+    let mut indices = Vec::with_capacity(pixels.len() / 3);
+    for rgb in pixels.chunks_exact(3) {
+        let next = lookup.len();
+        let index = *lookup.entry([rgb[0], rgb[1], rgb[2]]).or_insert(next);
+        if index >= MAX_PALETTE {
+            return None;
+        }
+        if index == next {
+            palette.extend_from_slice(rgb);
+        }
+        indices.push(index as u8);
+    }
+    Some((indices, palette))
+}
+
+/// Pack rows of `width` samples into the given number of bits per sample,
+/// starting each row at a new byte. Returns the packed data and the number
+/// of bytes per row.
+fn pack(samples: &[u8], width: u32, bits: u8) -> (Vec<u8>, usize) {
+    let width = width as usize;
+    let bits = usize::from(bits);
+    let stride = (width * bits + 7) / 8;
+    if width == 0 {
+        return (vec![], stride);
+    }
+
+    let per_byte = 8 / bits;
+    let mut packed = vec![0; stride * (samples.len() / width)];
+    for (i, &sample) in samples.iter().enumerate() {
+        let (y, x) = (i / width, i % width);
+        let shift = 8 - bits * (x % per_byte + 1);
+        packed[y * stride + x / per_byte] |= sample << shift;
+    }
+
+    (packed, stride)
+}
+
+/// Encode an image as a JPEG with a quality from 1 to 100.
+fn encode_jpeg(dynamic: &DynamicImage, gray: bool, quality: u8) -> ImageResult<Vec<u8>> {
+    let mut data = vec![];
+    let mut encoder = JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100));
+    let (width, height) = dynamic.dimensions();
+    if gray {
+        encoder.encode(dynamic.to_luma8().as_raw(), width, height, ColorType::L8)?;
+    } else {
+        encoder.encode(dynamic.to_rgb8().as_raw(), width, height, ColorType::Rgb8)?;
+    }
+    Ok(data)
+}
+
+/// Convert an image to gray, dropping its alpha channel.
+fn to_gray(dynamic: &DynamicImage, grayscale: Grayscale) -> DynamicImage {
+    let rgb = dynamic.to_rgb8();
+    DynamicImage::ImageLuma8(GrayImage::from_fn(rgb.width(), rgb.height(), |x, y| {
+        let Rgb(channels) = *rgb.get_pixel(x, y);
+        Luma([grayscale.luma(channels)])
+    }))
+}
+
 /// Encode an image's alpha channel if present.
-fn encode_alpha(dynamic: &DynamicImage) -> (Vec<u8>, Filter) {
+fn encode_alpha(dynamic: &DynamicImage, level: u8) -> (Vec<u8>, Filter) {
     let pixels: Vec<_> = dynamic.pixels().map(|(_, _, Rgba([_, _, _, a]))| a).collect();
-    (deflate(&pixels), Filter::FlateDecode)
+    let data = deflate(&predict(&pixels, dynamic.width() as usize, 1), level);
+    (data, Filter::FlateDecode)
+}
+
+/// Apply PNG predictors to rows of `stride` bytes with `bpp` bytes per pixel
+/// before they are deflated.
+///
+/// Each row is prefixed with the predictor chosen for it: Up or Paeth,
+/// whichever leaves the smaller residuals. Smooth images compress a lot
+/// better that way.
+fn predict(pixels: &[u8], stride: usize, bpp: usize) -> Vec<u8> {
+    if stride == 0 {
+        return vec![];
+    }
+
+    let mut data = Vec::with_capacity(pixels.len() + pixels.len() / stride);
+    let mut up = vec![0; stride];
+    let mut paeth = vec![0; stride];
+    let zeros = vec![0; stride];
+    let mut prev = zeros.as_slice();
+    for row in pixels.chunks_exact(stride) {
+        for i in 0..stride {
+            let left = if i >= bpp { row[i - bpp] } else { 0 };
+            let corner = if i >= bpp { prev[i - bpp] } else { 0 };
+            up[i] = row[i].wrapping_sub(prev[i]);
+            paeth[i] = row[i].wrapping_sub(paeth_predictor(left, prev[i], corner));
+        }
+
+        // Like libpng, pick the residuals with the smallest sum when they
+        // are read as signed bytes.
+        let cost = |residuals: &[u8]| -> u64 {
+            residuals.iter().map(|&r| u64::from((r as i8).unsigned_abs())).sum()
+        };
+
+        if cost(&up) <= cost(&paeth) {
+            data.push(2);
+            data.extend_from_slice(&up);
+        } else {
+            data.push(4);
+            data.extend_from_slice(&paeth);
+        }
+
+        prev = row;
+    }
+
+    data
+}
+
+/// The PNG Paeth predictor: whichever of the left, upper and upper-left
+/// sample is closest to `left + up - corner`.
+fn paeth_predictor(left: u8, up: u8, corner: u8) -> u8 {
+    let p = i16::from(left) + i16::from(up) - i16::from(corner);
+    let dl = (p - i16::from(left)).abs();
+    let du = (p - i16::from(up)).abs();
+    let dc = (p - i16::from(corner)).abs();
+    if dl <= du && dl <= dc {
+        left
+    } else if du <= dc {
+        up
+    } else {
+        corner
+    }
+}
+
+/// Declare that an image stream's data was run through [`predict`].
+fn write_predictor(image: &mut ImageXObject, colors: i32, bits: i32, width: u32) {
+    image
+        .insert(Name(b"DecodeParms"))
+        .dict()
+        .pair(Name(b"Predictor"), 15)
+        .pair(Name(b"Colors"), colors)
+        .pair(Name(b"BitsPerComponent"), bits)
+        .pair(Name(b"Columns"), width as i32);
+}
+
+#[cfg(test)]
+mod tests {
+    use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+    use image::{ImageBuffer, ImageEncoder, RgbImage, RgbaImage};
+
+    use super::super::tests::{blank, export_pages, Object, Pdf};
+    use super::*;
+    use crate::doc::{Frame, FrameItem};
+    use crate::geom::{Abs, Point, Size};
+    use crate::syntax::Span;
+
+    /// An image with a smooth gradient of more colors than fit into a palette.
+    fn gradient() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, y| {
+            Rgb([(x * 8) as u8, (y * 8) as u8, ((x + y) * 4) as u8])
+        }))
+    }
+
+    /// Encode an image as a PNG whose rows are filtered in the given way.
+    fn png(image: &DynamicImage, filter: FilterType) -> Image {
+        let mut data = vec![];
+        PngEncoder::new_with_quality(&mut data, CompressionType::Default, filter)
+            .write_image(image.as_bytes(), image.width(), image.height(), image.color())
+            .unwrap();
+        Image::new(data.into(), ImageFormat::Raster(RasterFormat::Png)).unwrap()
+    }
+
+    /// A page with the images side by side.
+    fn image_page(images: Vec<Image>) -> Frame {
+        let mut page = blank();
+        for (i, image) in images.into_iter().enumerate() {
+            let pos = Point::with_x(Abs::pt(20.0 * i as f64));
+            let size = Size::splat(Abs::pt(10.0));
+            page.push(pos, FrameItem::Image(image, size, Span::detached()));
+        }
+        page
+    }
+
+    /// The reference to the image resource with the given index.
+    fn image_ref(pdf: &Pdf, index: usize) -> &str {
+        let images = pdf.page_tree().dict("Resources").dict("XObject");
+        images.get(&format!("Im{index}")).unwrap()
+    }
+
+    /// The image object of the image resource with the given index.
+    fn xobject(pdf: &Pdf, index: usize) -> &Object {
+        pdf.get(image_ref(pdf, index))
+    }
+
+    #[test]
+    fn test_dedup() {
+        // Two files with the same pixels share one image object.
+        let image = gradient();
+        let files = vec![png(&image, FilterType::NoFilter), png(&image, FilterType::Sub)];
+        assert_ne!(files[0].data(), files[1].data());
+        let pdf = export_pages(vec![image_page(files)], PdfOptions::default());
+        assert_eq!(pdf.of_type("XObject").count(), 1);
+        assert_eq!(image_ref(&pdf, 0), image_ref(&pdf, 1));
+
+        // Different pixels don't.
+        let inverted = DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, y| {
+            let Rgb([r, g, b]) = *image.as_rgb8().unwrap().get_pixel(x, y);
+            Rgb([255 - r, 255 - g, 255 - b])
+        }));
+        let files = vec![png(&image, FilterType::Sub), png(&inverted, FilterType::Sub)];
+        let pdf = export_pages(vec![image_page(files)], PdfOptions::default());
+        assert_eq!(pdf.of_type("XObject").count(), 2);
+        assert_ne!(image_ref(&pdf, 0), image_ref(&pdf, 1));
+    }
+
+    /// Undo [`predict`] like a PDF reader would.
+    fn unpredict(data: &[u8], stride: usize, bpp: usize) -> Vec<u8> {
+        let mut pixels: Vec<u8> = vec![];
+        for (y, row) in data.chunks_exact(stride + 1).enumerate() {
+            for i in 0..stride {
+                let at = |dy: usize, dx: usize| {
+                    if y >= dy && i >= dx {
+                        pixels[(y - dy) * stride + i - dx]
+                    } else {
+                        0
+                    }
+                };
+                let prediction = match row[0] {
+                    2 => at(1, 0),
+                    4 => paeth_predictor(at(0, bpp), at(1, 0), at(1, bpp)),
+                    filter => panic!("unexpected filter {filter}"),
+                };
+                pixels.push(row[i + 1].wrapping_add(prediction));
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn test_predict() {
+        // The first row is best predicted from the left, the second from
+        // above.
+        let rows = [1, 2, 3, 1, 2, 3];
+        assert_eq!(predict(&rows, 3, 1), [4, 1, 1, 1, 2, 0, 0, 0]);
+        assert_eq!(unpredict(&predict(&rows, 3, 1), 3, 1), rows);
+        assert!(predict(&[], 0, 1).is_empty());
+
+        // The predictors are reversible.
+        let image = gradient();
+        let pixels = image.as_bytes();
+        let predicted = predict(pixels, 3 * 32, 3);
+        assert_eq!(predicted.len(), pixels.len() + 32);
+        assert_eq!(unpredict(&predicted, 3 * 32, 3), pixels);
+
+        // The image stream declares them.
+        let page = image_page(vec![png(&image, FilterType::Sub)]);
+        let pdf = export_pages(vec![page], PdfOptions::default());
+        let parms = xobject(&pdf, 0).dict().dict("DecodeParms");
+        assert_eq!(parms.get("Predictor"), Some("15"));
+        assert_eq!(parms.get("Colors"), Some("3"));
+        assert_eq!(parms.get("Columns"), Some("32"));
+    }
+
+    #[test]
+    fn test_paeth_predictor() {
+        assert_eq!(paeth_predictor(10, 20, 10), 20);
+        assert_eq!(paeth_predictor(20, 10, 10), 20);
+        assert_eq!(paeth_predictor(10, 20, 30), 10);
+        assert_eq!(paeth_predictor(30, 30, 50), 30);
+        assert_eq!(paeth_predictor(50, 10, 40), 10);
+    }
+
+    /// Encode an image losslessly and undo the deflating and predictors.
+    fn encode_raw(image: &DynamicImage) -> (Vec<u8>, Space, i32) {
+        let (data, filter, space, bits) =
+            encode_image(RasterFormat::Png, image, None, 6).unwrap();
+        assert!(matches!(filter, Filter::FlateDecode));
+        let colors = if space == Space::Rgb { 3 } else { 1 };
+        let bpp = (colors * bits as usize + 7) / 8;
+        let stride = (colors * bits as usize * image.width() as usize + 7) / 8;
+        let predicted = miniz_oxide::inflate::decompress_to_vec_zlib(&data).unwrap();
+        (unpredict(&predicted, stride, bpp), space, bits)
+    }
+
+    #[test]
+    fn test_pack() {
+        assert_eq!(pack(&[1, 0, 1, 1, 0, 0, 0, 0, 1], 9, 1), (vec![0xB0, 0x80], 2));
+        assert_eq!(pack(&[1, 2, 3, 3, 2, 1], 3, 2), (vec![0x6C, 0xE4], 1));
+        assert_eq!(pack(&[1, 2, 3], 3, 4), (vec![0x12, 0x30], 2));
+        assert_eq!(pack(&[7, 8], 2, 8), (vec![7, 8], 2));
+        assert_eq!(pack(&[], 0, 1), (vec![], 0));
+    }
+
+    #[test]
+    fn test_depths() {
+        // Black-and-white images are packed into one bit per pixel.
+        let scan = GrayImage::from_fn(10, 2, |x, y| Luma([if x == y { 0 } else { 255 }]));
+        let (data, space, bits) = encode_raw(&DynamicImage::ImageLuma8(scan));
+        assert_eq!((space, bits), (Space::Gray, 1));
+        assert_eq!(data, [0x7F, 0xC0, 0xBF, 0xC0]);
+
+        // Images with a single gray in between keep eight bits.
+        let gray = GrayImage::from_fn(2, 1, |x, _| Luma([x as u8 * 128]));
+        let (data, space, bits) = encode_raw(&DynamicImage::ImageLuma8(gray));
+        assert_eq!((space, bits), (Space::Gray, 8));
+        assert_eq!(data, [0, 128]);
+
+        // Sixteen-bit images keep their precision, in big-endian order.
+        let deep = ImageBuffer::from_fn(2, 1, |x, _| Rgb([0x1234, 0, 0xFFFF - x as u16]));
+        let (data, space, bits) = encode_raw(&DynamicImage::ImageRgb16(deep));
+        assert_eq!((space, bits), (Space::Rgb, 16));
+        assert_eq!(data, [0x12, 0x34, 0, 0, 0xFF, 0xFF, 0x12, 0x34, 0, 0, 0xFF, 0xFE]);
+    }
+
+    #[test]
+    fn test_palettize() {
+        let (indices, palette) = palettize(&[255, 0, 0, 0, 255, 0, 255, 0, 0]).unwrap();
+        assert_eq!(indices, [0, 1, 0]);
+        assert_eq!(palette, [255, 0, 0, 0, 255, 0]);
+
+        // A palette holds at most 256 colors.
+        let colors = |n: u32| -> Vec<u8> {
+            (0..n).flat_map(|i| [(i % 256) as u8, (i / 256) as u8, 0]).collect()
+        };
+        assert_eq!(palettize(&colors(256)).unwrap().1.len(), 3 * 256);
+        assert!(palettize(&colors(257)).is_none());
+    }
+
+    #[test]
+    fn test_indexed() {
+        let colors = [Rgb([255, 0, 0]), Rgb([0, 255, 0]), Rgb([0, 0, 255])];
+        let image = RgbImage::from_fn(4, 1, |x, _| colors[x as usize % 3]);
+        let image = DynamicImage::ImageRgb8(image);
+
+        // Three colors take two bits per pixel.
+        let (data, space, bits) = encode_raw(&image);
+        let palette = vec![255, 0, 0, 0, 255, 0, 0, 0, 255];
+        assert_eq!((space, bits), (Space::Indexed(palette), 2));
+        assert_eq!(data, [0b00_01_10_00]);
+
+        // The palette is written as the color space's lookup table, while
+        // images with many colors stay RGB.
+        let files = vec![png(&image, FilterType::Sub), png(&gradient(), FilterType::Sub)];
+        let pdf = export_pages(vec![image_page(files)], PdfOptions::default());
+        let indexed = xobject(&pdf, 0).dict().get("ColorSpace").unwrap();
+        assert!(indexed.starts_with("[/Indexed /DeviceRGB 2 "));
+        assert_eq!(xobject(&pdf, 1).dict().get("ColorSpace"), Some("/DeviceRGB"));
+    }
+
+    #[test]
+    fn test_jpeg_components() {
+        // The start of a JPEG with a frame of three 8-bit components.
+        let frame = |marker, precision| {
+            let mut data = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00];
+            data.extend([0xFF, 0xFF, marker, 0x00, 0x11, precision, 0, 1, 0, 1, 3]);
+            data.extend([1, 0x11, 0, 2, 0x11, 0, 3, 0x11, 0]);
+            data
+        };
+
+        assert_eq!(jpeg_components(&frame(0xC0, 8)), Some(3));
+        assert_eq!(jpeg_components(&frame(0xC2, 8)), Some(3));
+        assert_eq!(jpeg_components(&frame(0xC1, 12)), None);
+        assert_eq!(jpeg_components(&frame(0xC9, 8)), None);
+        assert_eq!(jpeg_components(&frame(0xC0, 8)[..12]), None);
+        assert_eq!(jpeg_components(b"\x89PNG"), None);
+
+        let gray = encode_jpeg(&gradient(), true, 80).unwrap();
+        assert_eq!(jpeg_components(&gray), Some(1));
+    }
+
+    #[test]
+    fn test_jpeg_pass_through() {
+        let data = encode_jpeg(&gradient(), false, 80).unwrap();
+        let format = ImageFormat::Raster(RasterFormat::Jpg);
+        let image = Image::new(data.clone().into(), format).unwrap();
+        let embedded = |options: PdfOptions| {
+            let pdf = export_pages(vec![image_page(vec![image.clone()])], options);
+            xobject(&pdf, 0).data == data
+        };
+
+        // The JPEG is embedded byte for byte.
+        let pdf = export_pages(vec![image_page(vec![image.clone()])], Default::default());
+        assert_eq!(xobject(&pdf, 0).dict().get("Filter"), Some("/DCTDecode"));
+        assert!(embedded(PdfOptions::default()));
+
+        // Unless its pixels have to change.
+        assert!(!embedded(PdfOptions {
+            grayscale: Some(Grayscale::Rec601),
+            ..Default::default()
+        }));
+        assert!(!embedded(PdfOptions { jpeg_quality: Some(50), ..Default::default() }));
+    }
+
+    #[test]
+    fn test_jpeg_quality() {
+        let image = gradient();
+        let encode = |image: &DynamicImage, quality| {
+            encode_image(RasterFormat::Png, image, Some(quality), 6).unwrap()
+        };
+
+        // Lower qualities result in smaller JPEGs.
+        let (low, filter, space, bits) = encode(&image, 10);
+        let (high, ..) = encode(&image, 95);
+        assert!(matches!(filter, Filter::DctDecode));
+        assert_eq!((space, bits), (Space::Rgb, 8));
+        assert!(low.starts_with(&[0xFF, 0xD8]));
+        assert!(low.len() < high.len());
+
+        // Gray images stay gray.
+        let gray = DynamicImage::ImageLuma8(image.to_luma8());
+        let (_, filter, space, _) = encode(&gray, 50);
+        assert!(matches!(filter, Filter::DctDecode));
+        assert_eq!(space, Space::Gray);
+
+        // Images with few colors, with more than eight bits, or with an alpha
+        // channel stay lossless.
+        let colors = [Rgb([255, 0, 0]), Rgb([0, 255, 0])];
+        let indexed = RgbImage::from_fn(4, 4, |x, _| colors[x as usize % 2]);
+        let scan = GrayImage::from_fn(4, 4, |x, _| Luma([if x == 0 { 0 } else { 255 }]));
+        let lossless = [
+            DynamicImage::ImageRgb8(indexed),
+            DynamicImage::ImageLuma8(scan),
+            DynamicImage::ImageLuma16(image.to_luma16()),
+            DynamicImage::ImageLumaA8(image.to_luma_alpha8()),
+            DynamicImage::ImageRgba8(image.to_rgba8()),
+        ];
+        for image in &lossless {
+            assert!(matches!(encode(image, 50).1, Filter::FlateDecode));
+        }
+
+        // Also when they are converted to gray.
+        let translucent = RgbaImage::from_fn(32, 32, |x, y| {
+            Rgba([(x * 8) as u8, (y * 8) as u8, 0, 128])
+        });
+        let options = PdfOptions {
+            jpeg_quality: Some(50),
+            grayscale: Some(Grayscale::Rec601),
+            ..Default::default()
+        };
+        let translucent = png(&DynamicImage::ImageRgba8(translucent), FilterType::Sub);
+        let pdf = export_pages(vec![image_page(vec![translucent])], options);
+        let dict = xobject(&pdf, 0).dict();
+        assert_eq!(dict.get("Filter"), Some("/FlateDecode"));
+        assert!(dict.get("SMask").is_some());
+    }
+
+    #[test]
+    fn test_grayscale() {
+        let red = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 128]));
+        let gray = to_gray(&DynamicImage::ImageRgba8(red), Grayscale::Rec601);
+        assert_eq!(gray.as_luma8().unwrap().as_raw(), &[76; 4]);
+    }
 }
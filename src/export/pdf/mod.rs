@@ -1,39 +1,452 @@
 //! Exporting into PDF documents.
 
+mod extg;
 mod font;
+mod gradient;
+mod hook;
 mod image;
 mod outline;
 mod page;
+mod pattern;
+mod structure;
+mod type3;
 
 use std::cmp::Eq;
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
 
+use ecow::{eco_format, EcoString};
 use pdf_writer::types::Direction;
-use pdf_writer::{Finish, Name, PdfWriter, Ref, TextStr};
-use xmp_writer::{LangId, RenditionClass, XmpWriter};
+use pdf_writer::writers::Destination;
+use pdf_writer::{Filter, Finish, Name, PdfWriter, Ref, Str, TextStr};
+use xmp_writer::{LangId, Namespace, RenditionClass, XmpWriter};
 
+use self::extg::ExtGState;
+use self::gradient::PdfGradient;
+pub use self::hook::{PageExtras, PageHook};
 use self::outline::HeadingNode;
-use self::page::Page;
-use crate::doc::{Document, Lang};
+use self::page::{Page, PageResources};
+use self::pattern::PdfPattern;
+use self::structure::StructTree;
+use self::type3::Type3Font;
+use crate::doc::{
+    Attachment, Document, Frame, FrameItem, Lang, PageLabel, PageLabelStyle,
+};
 use crate::font::Font;
-use crate::geom::{Abs, Dir, Em};
+use crate::geom::{
+    Abs, CmykColor, Color, Dir, Em, Gradient, LumaColor, Paint, Pattern, RgbaColor,
+};
 use crate::image::Image;
 use crate::model::Introspector;
+use crate::util::{hash128, Buffer};
 
 /// Export a document into a PDF file.
 ///
-/// Returns the raw bytes making up the PDF file.
+/// Returns the raw bytes making up the PDF file. Use a [`PdfExporter`] for
+/// custom settings.
 pub fn pdf(document: &Document) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
+    PdfExporter::new().export(document)
+}
+
+/// Exports documents into PDF files with custom settings.
+#[derive(Default)]
+pub struct PdfExporter<'a> {
+    options: PdfOptions,
+    cache: Option<&'a mut PdfCache>,
+    hook: Option<&'a mut dyn PageHook>,
+}
+
+impl<'a> PdfExporter<'a> {
+    /// Create an exporter with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Export with the given settings.
+    pub fn with_options(mut self, options: PdfOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Reuse the content streams of pages that didn't change since the
+    /// previous export with the same cache.
+    pub fn with_cache(mut self, cache: &'a mut PdfCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Call a hook for each page to add raw content and objects.
+    pub fn with_hook(mut self, hook: &'a mut dyn PageHook) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+
+    /// Export a document.
+    ///
+    /// Returns the raw bytes making up the PDF file.
+    pub fn export(self, document: &'a Document) -> Vec<u8> {
+        let mut buffer = vec![];
+        export(document, self, &mut buffer).expect("failed to export PDF");
+        buffer
+    }
+
+    /// Export a document and write the PDF file into a sink.
+    pub fn write(self, document: &'a Document, mut sink: impl Write) -> io::Result<()> {
+        export(document, self, &mut sink)
+    }
+}
+
+/// Export a document into a PDF file, writing it into an output.
+fn export<'a>(
+    document: &'a Document,
+    exporter: PdfExporter<'a>,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let PdfExporter { options, mut cache, hook } = exporter;
+    let mut ctx = PdfContext::new(document, options);
+    ctx.cache = cache.as_deref_mut().map(std::mem::take);
+    ctx.hook = hook;
+    font::subset_fonts(&mut ctx, &document.pages);
     page::construct_pages(&mut ctx, &document.pages);
     font::write_fonts(&mut ctx);
     image::write_images(&mut ctx);
+    type3::write_type3_fonts(&mut ctx);
+    gradient::write_gradients(&mut ctx);
+    pattern::write_patterns(&mut ctx);
+    extg::write_external_graphics_states(&mut ctx);
+    write_output_profile(&mut ctx);
     page::write_page_tree(&mut ctx);
     write_catalog(&mut ctx);
-    ctx.writer.finish()
+    if let (Some(cache), Some(used)) = (cache, ctx.cache) {
+        *cache = used;
+    }
+
+    // Identify the file by its content so that exports are reproducible. The
+    // identifier's permanent and changing parts are the same, since every
+    // export creates a new file.
+    let id = hash128(&(ctx.page_key, &document.title, &document.author));
+    let id = id.to_be_bytes().to_vec();
+    ctx.writer.set_file_id((id.clone(), id));
+
+    out.write_all(&ctx.writer.finish())?;
+    out.flush()
+}
+
+/// Compressed page content streams that are kept across exports.
+///
+/// A page's stream depends on the resources registered by the pages before
+/// it, so it is only reused if neither the page nor any page before it
+/// changed. It also depends on which glyphs of the document's fonts are drawn
+/// as images, so all streams are encoded anew if that changes.
+///
+/// A reused page isn't walked again. Instead, the resources it used are
+/// registered as they were when it was encoded. Pages of tagged documents are
+/// still walked to build the structure tree, so for them the cache only saves
+/// the compression.
+#[derive(Debug, Default, Clone)]
+pub struct PdfCache {
+    /// The pages of the previous export, in order.
+    pages: Vec<CachedPage>,
+}
+
+/// A page kept across exports.
+#[derive(Debug, Default, Clone)]
+struct CachedPage {
+    /// A hash of the options, the glyphs drawn as images, and all frames up
+    /// to the page.
+    key: u128,
+    /// The page's compressed content stream.
+    data: Vec<u8>,
+    /// What encoding the page registered with the document.
+    resources: PageResources,
+}
+
+/// Settings for PDF export.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct PdfOptions {
+    /// The JPEG quality, from 1 to 100, with which raster images are
+    /// re-encoded.
+    ///
+    /// By default, images that aren't JPEGs already are compressed losslessly.
+    /// If this is set, opaque 8-bit RGB and gray images are encoded as JPEGs
+    /// instead, trading fidelity for smaller files. Images with an alpha
+    /// channel, with more than eight bits per component, or with few enough
+    /// colors for a palette stay lossless.
+    pub jpeg_quality: Option<u8>,
+    /// If set, all colors and raster images are converted to gray with the
+    /// given weights. SVG images are embedded unchanged.
+    pub grayscale: Option<Grayscale>,
+    /// Whether to convert all colors to device CMYK, as many print shops
+    /// require. Raster and SVG images are embedded unchanged.
+    pub cmyk: bool,
+    /// The standard the exported file conforms to.
+    pub standard: PdfStandard,
+    /// A custom output intent describing the device the document is meant
+    /// for. If set, its ICC profile is embedded and also used to interpret
+    /// device colors. PDF/A files otherwise get an sRGB output intent.
+    pub output_intent: Option<OutputIntent>,
+    /// Files to attach in addition to the ones declared in the document, like
+    /// the source file it was compiled from.
+    pub attachments: Vec<Attachment>,
+    /// Whether to draw text as filled glyph outlines instead of embedding
+    /// fonts, as some sign-making and cutting workflows require.
+    pub outline_text: bool,
+    /// Whether to keep an invisible copy of outlined text on top of its
+    /// outlines, so that it stays searchable and selectable. This embeds the
+    /// fonts after all. Has no effect unless `outline_text` is set.
+    pub text_layer: bool,
+    /// Whether to write a structure tree that marks up paragraphs, headings,
+    /// lists, figures, and links, so that screen readers can read the file
+    /// and viewers can reflow it. The elements are only marked up if the
+    /// document was laid out with tagging turned on.
+    pub tagged: bool,
+    /// How strongly to compress the file's streams.
+    pub compression: Compression,
+    /// If set, pages with more elements than the threshold are embedded as a
+    /// single raster image, so that viewers don't choke on them. Links and
+    /// other metadata on such pages are kept.
+    pub rasterize: Option<Rasterize>,
+}
+
+impl PdfOptions {
+    /// Convert a paint into one that is valid for export with these options.
+    fn paint(&self, paint: Paint) -> Paint {
+        let paint = match self.grayscale {
+            Some(grayscale) => grayscale.paint(paint),
+            None => paint,
+        };
+
+        let paint = if self.cmyk { cmyk_paint(paint) } else { paint };
+
+        // PDF/A only allows device CMYK colors with a CMYK output intent.
+        let cmyk_intent =
+            self.output_intent.as_ref().map(OutputIntent::components) == Some(4);
+        if self.standard != PdfStandard::A2b || cmyk_intent {
+            return paint;
+        }
+
+        let rgb = |color: Color| match color {
+            Color::Cmyk(_) => Color::Rgba(color.to_rgba()),
+            _ => color,
+        };
+
+        match paint {
+            Paint::Solid(color) => Paint::Solid(rgb(color)),
+            Paint::Gradient(gradient) => Paint::Gradient(Gradient {
+                from: rgb(gradient.from),
+                to: rgb(gradient.to),
+                ..gradient
+            }),
+            Paint::Pattern(pattern) => {
+                Paint::Pattern(Pattern { color: rgb(pattern.color), ..pattern })
+            }
+        }
+    }
+}
+
+/// Convert the colors of a paint to CMYK.
+fn cmyk_paint(paint: Paint) -> Paint {
+    let cmyk = |color| {
+        Color::Cmyk(match color {
+            Color::Luma(c) => CmykColor::new(0, 0, 0, u8::MAX - c.0),
+            Color::Rgba(c) => c.to_cmyk(),
+            Color::Cmyk(c) => c,
+        })
+    };
+
+    match paint {
+        Paint::Solid(color) => Paint::Solid(cmyk(color)),
+        Paint::Gradient(gradient) => Paint::Gradient(Gradient {
+            from: cmyk(gradient.from),
+            to: cmyk(gradient.to),
+            ..gradient
+        }),
+        Paint::Pattern(pattern) => {
+            Paint::Pattern(Pattern { color: cmyk(pattern.color), ..pattern })
+        }
+    }
+}
+
+/// An output intent with an embedded ICC color profile.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OutputIntent {
+    profile: Buffer,
+    components: u8,
+    condition: EcoString,
+}
+
+impl OutputIntent {
+    /// Create an output intent from a raw ICC profile and an identifier of the
+    /// output condition, e.g. `FOGRA39`.
+    ///
+    /// Fails if the profile is not a gray, RGB, or CMYK ICC profile.
+    pub fn new(profile: Buffer, condition: EcoString) -> Result<Self, &'static str> {
+        if profile.get(36..40) != Some(b"acsp".as_slice()) {
+            return Err("not a valid ICC profile");
+        }
+
+        let components = match profile.get(16..20) {
+            Some(b"GRAY") => 1,
+            Some(b"RGB ") => 3,
+            Some(b"CMYK") => 4,
+            _ => return Err("ICC profile must be for gray, RGB, or CMYK"),
+        };
+
+        Ok(Self { profile, components, condition })
+    }
+
+    /// The number of color components of the profile's color space.
+    pub fn components(&self) -> u8 {
+        self.components
+    }
+
+    /// The name of the default color space that the profile replaces.
+    fn default_space(&self) -> Name<'static> {
+        match self.components {
+            1 => Name(b"DefaultGray"),
+            3 => Name(b"DefaultRGB"),
+            _ => Name(b"DefaultCMYK"),
+        }
+    }
+}
+
+/// A standard that an exported PDF file conforms to.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PdfStandard {
+    /// Plain PDF 1.7.
+    #[default]
+    V1_7,
+    /// PDF/A-2b, for long-term archival. Embeds an sRGB output intent unless
+    /// a custom one is given, declares conformance in the XMP metadata, and
+    /// converts CMYK colors to RGB unless the output intent is for CMYK.
+    A2b,
+}
+
+/// Weights for converting colors to gray.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Grayscale {
+    /// The luma weights from ITU-R BT.601: 29.9% red, 58.7% green, and 11.4%
+    /// blue.
+    Rec601,
+    /// The luma weights from ITU-R BT.709: 21.26% red, 71.52% green, and
+    /// 7.22% blue.
+    Rec709,
+    /// The unweighted average of red, green, and blue.
+    Average,
+}
+
+impl Grayscale {
+    /// The weights of the red, green, and blue channels.
+    pub fn weights(self) -> [f32; 3] {
+        match self {
+            Self::Rec601 => [0.299, 0.587, 0.114],
+            Self::Rec709 => [0.2126, 0.7152, 0.0722],
+            Self::Average => [1.0 / 3.0; 3],
+        }
+    }
+
+    /// Convert red, green, and blue channels into a gray value.
+    pub fn luma(self, [r, g, b]: [u8; 3]) -> u8 {
+        let [wr, wg, wb] = self.weights();
+        let luma = wr * r as f32 + wg * g as f32 + wb * b as f32;
+        luma.round().clamp(0.0, 255.0) as u8
+    }
+
+    /// Convert a paint to gray.
+    pub fn paint(self, paint: Paint) -> Paint {
+        match paint {
+            Paint::Solid(color) => Paint::Solid(self.color(color)),
+            Paint::Gradient(gradient) => Paint::Gradient(Gradient {
+                from: self.color(gradient.from),
+                to: self.color(gradient.to),
+                ..gradient
+            }),
+            Paint::Pattern(pattern) => {
+                Paint::Pattern(Pattern { color: self.color(pattern.color), ..pattern })
+            }
+        }
+    }
+
+    /// Convert a color to gray.
+    pub fn color(self, color: Color) -> Color {
+        match color {
+            Color::Luma(_) => color,
+            _ => {
+                let RgbaColor { r, g, b, .. } = color.to_rgba();
+                Color::Luma(LumaColor(self.luma([r, g, b])))
+            }
+        }
+    }
+}
+
+/// How to compress the streams of a PDF file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Compression {
+    /// Leave page content streams and character maps uncompressed, so that
+    /// their operators can be read with a text editor when debugging. Binary
+    /// data like fonts and images is still compressed with the default level.
+    None,
+    /// Compress all streams with the given DEFLATE level, from 0 (fastest) to
+    /// 10 (smallest).
+    Level(u8),
+}
+
+impl Compression {
+    /// The level used by default.
+    pub const DEFAULT_LEVEL: u8 = 6;
+
+    /// The DEFLATE level for binary data.
+    fn level(self) -> u8 {
+        match self {
+            Self::None => Self::DEFAULT_LEVEL,
+            Self::Level(level) => level.min(10),
+        }
+    }
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self::Level(Self::DEFAULT_LEVEL)
+    }
+}
+
+/// When and how to rasterize pages.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rasterize {
+    /// The number of elements, like shapes, text runs, and images, a page
+    /// must exceed to be rasterized.
+    pub threshold: usize,
+    /// The resolution of the raster image in dots per inch.
+    pub dpi: u16,
+}
+
+impl Rasterize {
+    /// Whether a page is complex enough to be rasterized.
+    pub fn applies(&self, frame: &Frame) -> bool {
+        fn count(frame: &Frame) -> usize {
+            frame
+                .items()
+                .map(|(_, item)| match item {
+                    FrameItem::Group(group) => 1 + count(&group.frame),
+                    FrameItem::Meta(..) => 0,
+                    _ => 1,
+                })
+                .sum()
+        }
+
+        count(frame) > self.threshold
+    }
 }
 
+/// The ICC profile of the sRGB output intent written for PDF/A.
+const SRGB_ICC: &[u8] = include_bytes!("icc/sRGB-v2.icc");
+
+/// The XMP namespace for PDF/A identification.
+const PDFA_ID: (&str, &str, &str) =
+    ("pdfaid", "pdfaid", "http://www.aiim.org/pdfa/ns/id/");
+
 /// Identifies the color space definitions.
 const SRGB: Name<'static> = Name(b"srgb");
 const D65_GRAY: Name<'static> = Name(b"d65gray");
@@ -41,49 +454,125 @@ const D65_GRAY: Name<'static> = Name(b"d65gray");
 /// Context for exporting a whole PDF document.
 pub struct PdfContext<'a> {
     document: &'a Document,
+    options: PdfOptions,
     introspector: Introspector,
     writer: PdfWriter,
     pages: Vec<Page>,
     page_heights: Vec<f32>,
+    page_labels: Vec<PageLabel>,
+    form_fields: Vec<Ref>,
     alloc: Ref,
     page_tree_ref: Ref,
     font_refs: Vec<Ref>,
     image_refs: Vec<Ref>,
+    gradient_refs: Vec<Ref>,
+    pattern_refs: Vec<Ref>,
+    ext_gs_refs: Vec<Ref>,
+    icc_ref: Option<Ref>,
     page_refs: Vec<Ref>,
     font_map: Remapper<Font>,
     image_map: Remapper<Image>,
+    gradient_map: Remapper<PdfGradient>,
+    pattern_map: Remapper<PdfPattern>,
+    ext_gs_map: Remapper<ExtGState>,
     glyph_sets: HashMap<Font, HashSet<u16>>,
+    /// Content streams from previous exports, if they are kept.
+    cache: Option<PdfCache>,
+    /// A hash of the options, the document-wide state that affects how pages
+    /// are encoded, and all frames encoded so far.
+    page_key: u128,
+    /// Font subsets made ahead of page construction. `None` if the whole font
+    /// is embedded because subsetting failed.
+    subsets: HashMap<Font, Option<Vec<u8>>>,
+    /// Glyphs that subsetting broke and that are thus drawn as images.
+    broken_glyphs: HashMap<Font, HashSet<u16>>,
+    /// The Type3 font and character code of each glyph that only exists as
+    /// an SVG document or a bitmap, or `None` if it couldn't be rasterized.
+    type3_glyphs: HashMap<(Font, u16), Option<(usize, u8)>>,
+    /// Type3 fonts that draw such glyphs.
+    type3_fonts: Vec<Type3Font>,
+    type3_refs: Vec<Ref>,
     languages: HashMap<Lang, usize>,
     heading_tree: Vec<HeadingNode>,
+    structure: StructTree,
+    /// The callback for post-processing pages.
+    hook: Option<&'a mut dyn PageHook>,
+    /// External objects and graphics states registered by the hook.
+    hook_x_objects: Vec<Ref>,
+    hook_ext_gs: Vec<Ref>,
+    /// A stream that saves the graphics state before a page's own content,
+    /// so that the hook's content can start from a fresh one.
+    hook_save_ref: Option<Ref>,
 }
 
 impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+    fn new(document: &'a Document, options: PdfOptions) -> Self {
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
+        let page_key = hash128(&options);
         Self {
             document,
+            options,
             introspector: Introspector::new(&document.pages),
             writer: PdfWriter::new(),
             pages: vec![],
             page_heights: vec![],
+            page_labels: vec![],
+            form_fields: vec![],
             alloc,
             page_tree_ref,
             page_refs: vec![],
             font_refs: vec![],
             image_refs: vec![],
+            gradient_refs: vec![],
+            pattern_refs: vec![],
+            ext_gs_refs: vec![],
+            icc_ref: None,
             font_map: Remapper::new(),
             image_map: Remapper::new(),
+            gradient_map: Remapper::new(),
+            pattern_map: Remapper::new(),
+            ext_gs_map: Remapper::new(),
             glyph_sets: HashMap::new(),
+            cache: None,
+            page_key,
+            subsets: HashMap::new(),
+            broken_glyphs: HashMap::new(),
+            type3_glyphs: HashMap::new(),
+            type3_fonts: vec![],
+            type3_refs: vec![],
             languages: HashMap::new(),
             heading_tree: vec![],
+            structure: StructTree::default(),
+            hook: None,
+            hook_x_objects: vec![],
+            hook_ext_gs: vec![],
+            hook_save_ref: None,
         }
     }
 }
 
+/// Write the ICC profile of the output intent.
+fn write_output_profile(ctx: &mut PdfContext) {
+    let (profile, components) = match &ctx.options.output_intent {
+        Some(intent) => (intent.profile.as_slice(), intent.components),
+        None if ctx.options.standard == PdfStandard::A2b => (SRGB_ICC, 3),
+        None => return,
+    };
+
+    let icc_ref = ctx.alloc.bump();
+    let data = deflate(profile, ctx.options.compression.level());
+    let mut icc = ctx.writer.stream(icc_ref, &data);
+    icc.filter(Filter::FlateDecode);
+    icc.pair(Name(b"N"), components as i32);
+    icc.finish();
+    ctx.icc_ref = Some(icc_ref);
+}
+
 /// Write the document catalog.
 fn write_catalog(ctx: &mut PdfContext) {
     // Build the outline tree.
+    ctx.heading_tree = outline::construct_tree(ctx, &ctx.document.bookmarks);
     let outline_root_id = (!ctx.heading_tree.is_empty()).then(|| ctx.alloc.bump());
     let outline_start_ref = ctx.alloc;
     let len = ctx.heading_tree.len();
@@ -102,8 +591,8 @@ fn write_catalog(ctx: &mut PdfContext) {
     if let Some(outline_root_id) = outline_root_id {
         let mut outline_root = ctx.writer.outline(outline_root_id);
         outline_root.first(outline_start_ref);
-        outline_root.last(Ref::new(ctx.alloc.get() - 1));
-        outline_root.count(ctx.heading_tree.len() as i32);
+        outline_root.last(prev_ref.unwrap());
+        outline_root.count(len as i32);
     }
 
     let lang = ctx
@@ -126,11 +615,45 @@ fn write_catalog(ctx: &mut PdfContext) {
         xmp.title([(None, title.as_str())]);
     }
 
+    // The information dictionary and the XMP metadata must agree for PDF/A,
+    // so all authors then go into a single creator entry. Otherwise, each
+    // author gets their own.
+    let pdfa = ctx.options.standard == PdfStandard::A2b;
     let authors = &ctx.document.author;
     if !authors.is_empty() {
-        info.author(TextStr(&authors.join(", ")));
-        xmp.creator(authors.iter().map(|s| s.as_str()));
+        let joined = authors.join(", ");
+        info.author(TextStr(&joined));
+        if pdfa {
+            xmp.creator([joined.as_str()]);
+        } else {
+            xmp.creator(authors.iter().map(|s| s.as_str()));
+        }
     }
+
+    if let Some(subject) = &ctx.document.subject {
+        info.subject(TextStr(subject));
+        xmp.description([(None, subject.as_str())]);
+    }
+
+    let keywords = &ctx.document.keywords;
+    if !keywords.is_empty() {
+        let joined = keywords.join(", ");
+        info.keywords(TextStr(&joined));
+        xmp.subject(keywords.iter().map(|s| s.as_str()));
+        xmp.pdf_keywords(&joined);
+    }
+
+    if let Some(date) = ctx.document.date {
+        let mut pdf_date =
+            pdf_writer::Date::new(date.year).month(date.month).day(date.day);
+        if let Some((hour, minute, second)) = date.time {
+            pdf_date = pdf_date.hour(hour).minute(minute).second(second);
+        }
+        info.creation_date(pdf_date);
+        xmp.element("CreateDate", Namespace::Xmp)
+            .value(date.to_iso().as_str());
+    }
+
     info.creator(TextStr("Typst"));
     info.finish();
     xmp.creator_tool("Typst");
@@ -140,6 +663,20 @@ fn write_catalog(ctx: &mut PdfContext) {
     xmp.rendition_class(RenditionClass::Proof);
     xmp.pdf_version("1.7");
 
+    if pdfa {
+        xmp.element("part", Namespace::Custom(PDFA_ID)).value("2");
+        xmp.element("conformance", Namespace::Custom(PDFA_ID)).value("B");
+    }
+
+    for property in &ctx.document.xmp {
+        let namespace = Namespace::Custom((
+            property.prefix.as_str(),
+            property.prefix.as_str(),
+            property.namespace.as_str(),
+        ));
+        xmp.element(&property.name, namespace).value(property.value.as_str());
+    }
+
     let xmp_buf = xmp.finish(None);
     let meta_ref = ctx.alloc.bump();
     let mut meta_stream = ctx.writer.stream(meta_ref, xmp_buf.as_bytes());
@@ -147,25 +684,228 @@ fn write_catalog(ctx: &mut PdfContext) {
     meta_stream.pair(Name(b"Subtype"), Name(b"XML"));
     meta_stream.finish();
 
+    let struct_tree_ref = structure::write_structure(ctx);
+    let attachments = write_attachments(ctx);
+
     // Write the document catalog.
     let mut catalog = ctx.writer.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
     catalog.viewer_preferences().direction(dir);
     catalog.pair(Name(b"Metadata"), meta_ref);
 
+    if let Some(icc_ref) = ctx.icc_ref {
+        let (condition, info) = match &ctx.options.output_intent {
+            Some(intent) => (intent.condition.as_str(), intent.condition.as_str()),
+            None => ("sRGB", "sRGB IEC61966-2.1"),
+        };
+
+        let mut intents = catalog.insert(Name(b"OutputIntents")).array();
+        let mut intent = intents.push().dict();
+        intent.pair(Name(b"Type"), Name(b"OutputIntent"));
+        intent.pair(Name(b"S"), Name(b"GTS_PDFA1"));
+        intent.pair(Name(b"OutputConditionIdentifier"), TextStr(condition));
+        intent.pair(Name(b"Info"), TextStr(info));
+        intent.pair(Name(b"DestOutputProfile"), icc_ref);
+    }
+
+    if let Some(struct_tree_ref) = struct_tree_ref {
+        catalog.pair(Name(b"StructTreeRoot"), struct_tree_ref);
+        catalog.insert(Name(b"MarkInfo")).dict().pair(Name(b"Marked"), true);
+    }
+
     if let Some(outline_root_id) = outline_root_id {
         catalog.outlines(outline_root_id);
     }
 
+    // Write the page labels if they differ from the physical page numbers.
+    let custom =
+        ctx.page_labels.iter().enumerate().any(|(i, label)| {
+            *label != PageLabel::arabic(NonZeroUsize::new(i + 1).unwrap())
+        });
+
+    if custom {
+        let mut labels = catalog.insert(Name(b"PageLabels")).dict();
+        let mut nums = labels.insert(Name(b"Nums")).array();
+        let mut prev: Option<&PageLabel> = None;
+        for (i, label) in ctx.page_labels.iter().enumerate() {
+            // A new range only starts where the label doesn't just continue
+            // the previous one.
+            let continues = prev.map_or(false, |prev| {
+                prev.style == label.style
+                    && prev.prefix == label.prefix
+                    && prev.number.get() + 1 == label.number.get()
+            });
+
+            prev = Some(label);
+            if continues {
+                continue;
+            }
+
+            nums.item(i as i32);
+            let mut dict = nums.push().dict();
+            if let Some(style) = label.style {
+                dict.pair(
+                    Name(b"S"),
+                    Name(match style {
+                        PageLabelStyle::Arabic => b"D",
+                        PageLabelStyle::LowerRoman => b"r",
+                        PageLabelStyle::UpperRoman => b"R",
+                        PageLabelStyle::LowerAlpha => b"a",
+                        PageLabelStyle::UpperAlpha => b"A",
+                    }),
+                );
+            }
+            if let Some(prefix) = &label.prefix {
+                dict.pair(Name(b"P"), TextStr(prefix));
+            }
+            if label.number.get() != 1 {
+                dict.pair(Name(b"St"), label.number.get() as i32);
+            }
+            dict.finish();
+        }
+        nums.finish();
+        labels.finish();
+    }
+
+    // Write the interactive form. Viewers generate the appearance of text
+    // fields themselves, using Helvetica as the default font.
+    if !ctx.form_fields.is_empty() {
+        let mut form = catalog.insert(Name(b"AcroForm")).dict();
+        form.insert(Name(b"Fields"))
+            .array()
+            .items(ctx.form_fields.iter().copied());
+        form.pair(Name(b"NeedAppearances"), true);
+        form.pair(Name(b"DA"), Str(page::FORM_APPEARANCE));
+        let mut resources = form.insert(Name(b"DR")).dict();
+        let mut fonts = resources.insert(Name(b"Font")).dict();
+        let mut helvetica = fonts.insert(Name(b"Helv")).dict();
+        helvetica.pair(Name(b"Type"), Name(b"Font"));
+        helvetica.pair(Name(b"Subtype"), Name(b"Type1"));
+        helvetica.pair(Name(b"BaseFont"), Name(b"Helvetica"));
+        helvetica.pair(Name(b"Encoding"), Name(b"WinAnsiEncoding"));
+        helvetica.finish();
+        fonts.finish();
+        resources.finish();
+        form.finish();
+    }
+
+    // Write the name tree of the attached files.
+    if !attachments.is_empty() {
+        let mut names = catalog.insert(Name(b"Names")).dict();
+        let mut files = names.insert(Name(b"EmbeddedFiles")).dict();
+        let mut array = files.insert(Name(b"Names")).array();
+        for (name, spec_ref) in &attachments {
+            array.item(Str(name.as_bytes()));
+            array.item(*spec_ref);
+        }
+        array.finish();
+        files.finish();
+        names.finish();
+    }
+
+    // Write the named destinations.
+    if !ctx.document.destinations.is_empty() {
+        let mut dests = catalog.insert(Name(b"Dests")).dict();
+        for (name, loc) in &ctx.document.destinations {
+            let pos = ctx.introspector.position(*loc);
+            let index = pos.page.get() - 1;
+            let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
+            if let Some(&height) = ctx.page_heights.get(index) {
+                dests
+                    .insert(Name(name.as_bytes()))
+                    .start::<Destination>()
+                    .page(ctx.page_refs[index])
+                    .xyz(pos.point.x.to_f32(), height - y.to_f32(), None);
+            }
+        }
+        dests.finish();
+    }
+
     if let Some(lang) = lang {
         catalog.lang(TextStr(lang.as_str()));
     }
+
+    catalog.finish();
 }
 
-/// Compress data with the DEFLATE algorithm.
-fn deflate(data: &[u8]) -> Vec<u8> {
-    const COMPRESSION_LEVEL: u8 = 6;
-    miniz_oxide::deflate::compress_to_vec_zlib(data, COMPRESSION_LEVEL)
+/// Write the attached files and their file specifications.
+///
+/// Returns the file names with the references to their specifications, sorted
+/// by name as required for name trees. Identical files are only attached once,
+/// while different files with the same name get numbered names. PDF/A-2 only
+/// allows attaching other PDF/A files, so nothing is attached in that case.
+fn write_attachments(ctx: &mut PdfContext) -> Vec<(EcoString, Ref)> {
+    if ctx.options.standard == PdfStandard::A2b {
+        return vec![];
+    }
+
+    let mut attachments: Vec<_> = ctx
+        .document
+        .attachments
+        .iter()
+        .chain(&ctx.options.attachments)
+        .collect();
+    attachments.sort_by(|a, b| a.name.cmp(&b.name));
+    attachments.dedup_by(|a, b| a.name == b.name && a.data == b.data);
+
+    let mut names = HashSet::new();
+    let mut refs = vec![];
+    for attachment in attachments {
+        let mut name = attachment.name.clone();
+        for i in 2.. {
+            if names.insert(name.clone()) {
+                break;
+            }
+            name = numbered_name(&attachment.name, i);
+        }
+
+        let file_ref = ctx.alloc.bump();
+        let data = deflate(attachment.data.as_slice(), ctx.options.compression.level());
+        let mut file = ctx.writer.stream(file_ref, &data);
+        file.filter(Filter::FlateDecode);
+        file.pair(Name(b"Type"), Name(b"EmbeddedFile"));
+        if let Some(mime) = &attachment.mime {
+            file.pair(Name(b"Subtype"), Name(mime.as_bytes()));
+        }
+        file.insert(Name(b"Params"))
+            .dict()
+            .pair(Name(b"Size"), attachment.data.len() as i32);
+        file.finish();
+
+        let spec_ref = ctx.alloc.bump();
+        let mut spec = ctx.writer.indirect(spec_ref).dict();
+        spec.pair(Name(b"Type"), Name(b"Filespec"));
+        spec.pair(Name(b"F"), Str(name.as_bytes()));
+        spec.pair(Name(b"UF"), TextStr(&name));
+        if let Some(description) = &attachment.description {
+            spec.pair(Name(b"Desc"), TextStr(description));
+        }
+        let mut ef = spec.insert(Name(b"EF")).dict();
+        ef.pair(Name(b"F"), file_ref);
+        ef.pair(Name(b"UF"), file_ref);
+        ef.finish();
+        spec.finish();
+
+        refs.push((name, spec_ref));
+    }
+
+    refs.sort_by(|a, b| a.0.cmp(&b.0));
+    refs
+}
+
+/// Insert a number in front of a file name's extension.
+fn numbered_name(name: &str, i: usize) -> EcoString {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => {
+            eco_format!("{stem}-{i}.{extension}")
+        }
+        _ => eco_format!("{name}-{i}"),
+    }
+}
+
+/// Compress data with the DEFLATE algorithm at the given level.
+fn deflate(data: &[u8], level: u8) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, level)
 }
 
 /// Assigns new, consecutive PDF-internal indices to items.
@@ -197,6 +937,10 @@ where
         self.to_pdf[&item]
     }
 
+    fn len(&self) -> usize {
+        self.to_items.len()
+    }
+
     fn pdf_indices<'a>(
         &'a self,
         refs: &'a [Ref],
@@ -246,3 +990,914 @@ impl RefExt for Ref {
         prev
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Date, Glyph, Meta, TextItem, XmpProperty};
+    use crate::geom::{Point, Size};
+    use crate::model::{Content, StabilityProvider};
+    use crate::syntax::Span;
+
+    /// Load one of the fonts in the assets.
+    pub(super) fn test_font(data: &'static [u8]) -> Font {
+        Font::new(data.into(), 0).unwrap()
+    }
+
+    /// The font that most tests draw text with.
+    pub(super) fn plex() -> Font {
+        test_font(include_bytes!("../../../assets/fonts/IBMPlexSerif-Regular.ttf"))
+    }
+
+    /// Add tables to a font's data, keeping its existing ones.
+    pub(super) fn add_tables(data: &[u8], extra: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+        let mut tables = tables(data);
+        tables.extend(extra.into_iter().map(|(tag, table)| (*tag, table.as_slice())));
+        tables.sort_by_key(|&(tag, _)| tag);
+
+        // The search hints and checksums are left zeroed, as readers don't
+        // depend on them.
+        let mut font = data[..4].to_vec();
+        font.extend((tables.len() as u16).to_be_bytes());
+        font.extend([0; 6]);
+        let start = 12 + 16 * tables.len();
+        let mut body = vec![];
+        for (tag, table) in &tables {
+            font.extend(tag);
+            font.extend([0; 4]);
+            font.extend(((start + body.len()) as u32).to_be_bytes());
+            font.extend((table.len() as u32).to_be_bytes());
+            body.extend(*table);
+            body.resize((body.len() + 3) / 4 * 4, 0);
+        }
+
+        font.extend(body);
+        font
+    }
+
+    /// The tags and data of the tables in a font's table directory.
+    pub(super) fn tables(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        let read = |at: usize| {
+            u32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+                as usize
+        };
+
+        let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+        (0..count)
+            .map(|i| {
+                let record = 12 + 16 * i;
+                let (offset, len) = (read(record + 8), read(record + 12));
+                let tag =
+                    [data[record], data[record + 1], data[record + 2], data[record + 3]];
+                (tag, &data[offset..offset + len])
+            })
+            .collect()
+    }
+
+    /// An empty page.
+    pub(super) fn blank() -> Frame {
+        Frame::new(Size::splat(Abs::pt(100.0)))
+    }
+
+    /// A run of text with glyphs half an em wide.
+    pub(super) fn text_run(font: &Font, text: &str) -> TextItem {
+        let glyphs = text
+            .char_indices()
+            .map(|(i, c)| Glyph {
+                id: font.ttf().glyph_index(c).unwrap().0,
+                x_advance: Em::new(0.5),
+                x_offset: Em::zero(),
+                c,
+                range: i as u16..(i + c.len_utf8()) as u16,
+                span: Span::detached(),
+                offset: 0,
+            })
+            .collect();
+        TextItem {
+            font: font.clone(),
+            size: Abs::pt(10.0),
+            fill: Paint::Solid(Color::BLACK),
+            lang: Lang::ENGLISH,
+            text: text.into(),
+            glyphs,
+        }
+    }
+
+    /// A page with a line of text.
+    pub(super) fn text_page(font: &Font, text: &str) -> Frame {
+        let mut frame = blank();
+        frame.push(Point::with_y(Abs::pt(20.0)), FrameItem::Text(text_run(font, text)));
+        frame
+    }
+
+    /// Export a document and parse the file.
+    pub(super) fn export(document: &Document, options: PdfOptions) -> Pdf {
+        Pdf::parse(&PdfExporter::new().with_options(options).export(document))
+    }
+
+    /// Export a document made up of the pages and parse the file.
+    pub(super) fn export_pages(pages: Vec<Frame>, options: PdfOptions) -> Pdf {
+        export(&Document { pages, ..Default::default() }, options)
+    }
+
+    /// The numeric operands of all operations with the given operator in a
+    /// content stream.
+    pub(super) fn operands(content: &str, op: &str) -> Vec<Vec<f32>> {
+        content
+            .lines()
+            .filter_map(|line| line.strip_suffix(op)?.strip_suffix(' '))
+            .filter_map(|rest| rest.split(' ').map(|s| s.parse().ok()).collect())
+            .collect()
+    }
+
+    /// The indirect objects and the trailer of an exported file.
+    pub(super) struct Pdf {
+        objects: HashMap<i32, Object>,
+        trailer: String,
+    }
+
+    /// An indirect object: its dictionary or other value, and its stream
+    /// data, if any.
+    pub(super) struct Object {
+        pub(super) value: String,
+        pub(super) data: Vec<u8>,
+    }
+
+    impl Pdf {
+        /// Parse the objects of a file written by the exporter.
+        pub(super) fn parse(bytes: &[u8]) -> Self {
+            let mut objects = HashMap::new();
+            let mut i = bytes.windows(6).position(|w| w == b" 0 obj").unwrap();
+            i = bytes[..i].iter().rposition(|&b| b == b'\n').unwrap() + 1;
+            while !bytes[i..].starts_with(b"xref") {
+                let header = i + bytes[i..].iter().position(|&b| b == b'\n').unwrap();
+                let id = std::str::from_utf8(&bytes[i..header]).unwrap();
+                let id = id.strip_suffix(" 0 obj").unwrap().parse().unwrap();
+                i = header + 1;
+
+                let len = object_len(&bytes[i..]);
+                let value = String::from_utf8_lossy(&bytes[i..i + len]).into_owned();
+                i = skip_space(bytes, i + len);
+
+                let mut data = vec![];
+                if bytes[i..].starts_with(b"stream\n") {
+                    i += 7;
+                    let len: usize = Dict(&value).get("Length").unwrap().parse().unwrap();
+                    data = bytes[i..i + len].to_vec();
+                    i = skip_space(bytes, i + len);
+                    assert!(bytes[i..].starts_with(b"endstream"));
+                    i = skip_space(bytes, i + 9);
+                }
+
+                assert!(bytes[i..].starts_with(b"endobj"));
+                i = skip_space(bytes, i + 6);
+                objects.insert(id, Object { value, data });
+            }
+
+            let i = bytes.windows(7).rposition(|w| w == b"trailer").unwrap();
+            let i = skip_space(bytes, i + 7);
+            let len = object_len(&bytes[i..]);
+            let trailer = String::from_utf8_lossy(&bytes[i..i + len]).into_owned();
+            Self { objects, trailer }
+        }
+
+        /// The trailer dictionary.
+        pub(super) fn trailer(&self) -> Dict<'_> {
+            Dict(&self.trailer)
+        }
+
+        /// The document catalog.
+        pub(super) fn catalog(&self) -> Dict<'_> {
+            self.resolve(self.trailer().get("Root").unwrap())
+        }
+
+        /// The document information dictionary.
+        pub(super) fn info(&self) -> Dict<'_> {
+            self.resolve(self.trailer().get("Info").unwrap())
+        }
+
+        /// The XMP metadata of the document.
+        pub(super) fn metadata(&self) -> String {
+            let metadata = self.get(self.catalog().get("Metadata").unwrap());
+            String::from_utf8(metadata.decoded()).unwrap()
+        }
+
+        /// The root of the page tree.
+        pub(super) fn page_tree(&self) -> Dict<'_> {
+            self.resolve(self.catalog().get("Pages").unwrap())
+        }
+
+        /// The page with the given index.
+        pub(super) fn page(&self, index: usize) -> Dict<'_> {
+            let kids = items(self.page_tree().get("Kids").unwrap());
+            self.resolve(kids[index])
+        }
+
+        /// The decoded content stream of the page with the given index.
+        pub(super) fn content(&self, index: usize) -> String {
+            let content = self.get(self.page(index).get("Contents").unwrap());
+            String::from_utf8(content.decoded()).unwrap()
+        }
+
+        /// The object that a reference like `3 0 R` points to.
+        pub(super) fn get(&self, reference: &str) -> &Object {
+            let id = reference.strip_suffix(" 0 R").unwrap();
+            &self.objects[&id.parse().unwrap()]
+        }
+
+        /// The dictionary that a value is or refers to.
+        pub(super) fn resolve<'a>(&'a self, value: &'a str) -> Dict<'a> {
+            if value.ends_with(" R") {
+                self.get(value).dict()
+            } else {
+                Dict(value)
+            }
+        }
+
+        /// All objects with the given type.
+        pub(super) fn of_type<'a>(
+            &'a self,
+            ty: &'a str,
+        ) -> impl Iterator<Item = &'a Object> {
+            self.objects.values().filter(move |object| {
+                object.dict().get("Type").and_then(|name| name.strip_prefix('/'))
+                    == Some(ty)
+            })
+        }
+    }
+
+    impl Object {
+        /// The object as a dictionary.
+        pub(super) fn dict(&self) -> Dict<'_> {
+            Dict(&self.value)
+        }
+
+        /// The stream data, decompressed if it is deflated.
+        pub(super) fn decoded(&self) -> Vec<u8> {
+            match self.dict().get("Filter") {
+                Some("/FlateDecode") => {
+                    miniz_oxide::inflate::decompress_to_vec_zlib(&self.data).unwrap()
+                }
+                _ => self.data.clone(),
+            }
+        }
+    }
+
+    /// A dictionary as it was written.
+    #[derive(Debug, Copy, Clone)]
+    pub(super) struct Dict<'a>(pub(super) &'a str);
+
+    impl<'a> Dict<'a> {
+        /// The value of a key in this dictionary, but not in nested ones.
+        pub(super) fn get(self, key: &str) -> Option<&'a str> {
+            let inner = self.0.strip_prefix("<<")?.strip_suffix(">>")?;
+            let mut entries = items(inner).into_iter();
+            while let (Some(name), Some(value)) = (entries.next(), entries.next()) {
+                if name.strip_prefix('/') == Some(key) {
+                    return Some(value);
+                }
+            }
+            None
+        }
+
+        /// The dictionary that is the value of a key.
+        pub(super) fn dict(self, key: &str) -> Dict<'a> {
+            Dict(self.get(key).unwrap())
+        }
+    }
+
+    /// The objects in a sequence like the contents of an array, keeping
+    /// references together.
+    pub(super) fn items(text: &str) -> Vec<&str> {
+        let text = text.trim();
+        let text = match text.strip_prefix('[') {
+            Some(inner) => inner.strip_suffix(']').unwrap(),
+            None => text,
+        };
+
+        let bytes = text.as_bytes();
+        let mut items = vec![];
+        let mut i = skip_space(bytes, 0);
+        while i < bytes.len() {
+            let mut end = i + object_len(&bytes[i..]);
+
+            // A reference consists of two numbers and an `R`.
+            let rest = &text[end..];
+            let mut parts = rest.split_whitespace();
+            if let (Some(gen), Some("R")) = (parts.next(), parts.next()) {
+                if text[i..end].parse::<i32>().is_ok() && gen.parse::<i32>().is_ok() {
+                    end += rest.find('R').unwrap() + 1;
+                }
+            }
+
+            items.push(&text[i..end]);
+            i = skip_space(bytes, end);
+        }
+        items
+    }
+
+    /// The length of the object at the start of the bytes. A reference counts
+    /// as three objects.
+    fn object_len(bytes: &[u8]) -> usize {
+        let mut depth = 0;
+        let mut i = 0;
+        loop {
+            match bytes[i] {
+                b'<' if bytes.get(i + 1) == Some(&b'<') => {
+                    depth += 1;
+                    i += 2;
+                }
+                b'>' if bytes.get(i + 1) == Some(&b'>') => {
+                    depth -= 1;
+                    i += 2;
+                }
+                b'[' => {
+                    depth += 1;
+                    i += 1;
+                }
+                b']' => {
+                    depth -= 1;
+                    i += 1;
+                }
+                b'<' => i += bytes[i..].iter().position(|&b| b == b'>').unwrap() + 1,
+                b'(' => {
+                    // Strings may contain balanced and escaped parentheses.
+                    let mut nesting = 0;
+                    loop {
+                        match bytes[i] {
+                            b'\\' => i += 1,
+                            b'(' => nesting += 1,
+                            b')' => nesting -= 1,
+                            _ => {}
+                        }
+                        i += 1;
+                        if nesting == 0 {
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    // Names, numbers, and keywords end at whitespace or a
+                    // delimiter. A name starts with its slash.
+                    i += 1;
+                    while i < bytes.len() && !is_boundary(bytes[i]) {
+                        i += 1;
+                    }
+                }
+            }
+
+            if depth == 0 {
+                return i;
+            }
+
+            i = skip_space(bytes, i);
+        }
+    }
+
+    /// Whether a byte ends a name, number, or keyword.
+    fn is_boundary(byte: u8) -> bool {
+        byte.is_ascii_whitespace() || b"()<>[]{}/%".contains(&byte)
+    }
+
+    /// Skip whitespace starting at an index.
+    fn skip_space(bytes: &[u8], mut i: usize) -> usize {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        i
+    }
+
+    #[test]
+    fn test_named_destination() {
+        let mut provider = StabilityProvider::new();
+        let location = provider.locate(0);
+        let mut content = Content::empty();
+        content.set_location(location);
+        let mut second = blank();
+        second.push(
+            Point::new(Abs::pt(5.0), Abs::pt(30.0)),
+            FrameItem::Meta(Meta::Elem(content), Size::zero()),
+        );
+
+        let document = Document {
+            pages: vec![blank(), second],
+            destinations: vec![("intro".into(), location)],
+            ..Default::default()
+        };
+        let pdf = export(&document, PdfOptions::default());
+
+        // The destination points at the second page, a bit above the element.
+        let kids = items(pdf.page_tree().get("Kids").unwrap());
+        let dest = items(pdf.catalog().dict("Dests").get("intro").unwrap());
+        assert_eq!(dest[..4], [kids[1], "/XYZ", "5", "80"]);
+    }
+
+    #[test]
+    fn test_custom_xmp_property() {
+        let document = Document {
+            pages: vec![blank()],
+            xmp: vec![XmpProperty {
+                namespace: "https://example.com/ns/".into(),
+                prefix: "ex".into(),
+                name: "project".into(),
+                value: "Apollo".into(),
+            }],
+            ..Default::default()
+        };
+        let xmp = export(&document, PdfOptions::default()).metadata();
+        assert!(xmp.contains("xmlns:ex=\"https://example.com/ns/\""));
+        assert!(xmp.contains("<ex:project>Apollo</ex:project>"));
+    }
+
+    #[test]
+    fn test_document_metadata() {
+        let document = Document {
+            pages: vec![blank()],
+            subject: Some("Lecture notes".into()),
+            keywords: vec!["typst".into(), "pdf".into()],
+            date: Some(Date { year: 2023, month: 4, day: 5, time: None }),
+            ..Default::default()
+        };
+        let pdf = export(&document, PdfOptions::default());
+
+        // The information dictionary and the XMP metadata agree.
+        let info = pdf.info();
+        assert_eq!(info.get("Subject"), Some("(Lecture notes)"));
+        assert_eq!(info.get("Keywords"), Some("(typst, pdf)"));
+        assert!(info.get("CreationDate").unwrap().starts_with("(D:20230405"));
+        let xmp = pdf.metadata();
+        assert!(xmp.contains("<pdf:Keywords>typst, pdf</pdf:Keywords>"));
+        assert!(xmp.contains("<rdf:li>typst</rdf:li>"));
+        assert!(xmp.contains("<xmp:CreateDate>2023-04-05</xmp:CreateDate>"));
+    }
+
+    #[test]
+    fn test_output_intent() {
+        let mut profile = vec![0; 128];
+        profile[16..20].copy_from_slice(b"CMYK");
+        profile[36..40].copy_from_slice(b"acsp");
+        let intent = OutputIntent::new(profile.into(), "FOGRA39".into()).unwrap();
+        assert_eq!(intent.components(), 4);
+        assert!(OutputIntent::new(vec![0; 128].into(), "FOGRA39".into()).is_err());
+
+        let document = Document { pages: vec![blank()], ..Default::default() };
+        let options = PdfOptions { output_intent: Some(intent), ..Default::default() };
+        let pdf = export(&document, options.clone());
+
+        // The profile describes the output condition and replaces the device
+        // color space.
+        let intents = items(pdf.catalog().get("OutputIntents").unwrap());
+        assert_eq!(intents.len(), 1);
+        let intent = Dict(intents[0]);
+        assert_eq!(intent.get("S"), Some("/GTS_PDFA1"));
+        assert_eq!(intent.get("OutputConditionIdentifier"), Some("(FOGRA39)"));
+        let profile = intent.get("DestOutputProfile").unwrap();
+        assert_eq!(pdf.resolve(profile).get("N"), Some("4"));
+        let spaces = pdf.page_tree().dict("Resources").dict("ColorSpace");
+        let icc_based = format!("[/ICCBased {profile}]");
+        assert_eq!(spaces.get("DefaultCMYK"), Some(icc_based.as_str()));
+        assert_eq!(spaces.get("DefaultRGB"), None);
+
+        // PDF/A-2b files keep CMYK colors for a CMYK output intent and map
+        // device RGB to sRGB.
+        let options = PdfOptions { standard: PdfStandard::A2b, ..options };
+        let cyan = Paint::Solid(Color::Cmyk(CmykColor::new(255, 0, 0, 0)));
+        assert!(options.paint(cyan) == cyan);
+        let pdf = export(&document, options);
+        let spaces = pdf.page_tree().dict("Resources").dict("ColorSpace");
+        assert!(spaces.get("DefaultCMYK").unwrap().starts_with("[/ICCBased "));
+        assert!(spaces.get("DefaultRGB").is_some());
+    }
+
+    #[test]
+    fn test_page_labels() {
+        let page = |style, prefix: Option<&str>, number| {
+            let label = PageLabel {
+                style: Some(style),
+                prefix: prefix.map(Into::into),
+                number: NonZeroUsize::new(number).unwrap(),
+            };
+            let mut frame = blank();
+            frame.push(
+                Point::zero(),
+                FrameItem::Meta(Meta::PageLabel(label), Size::zero()),
+            );
+            frame
+        };
+
+        // Plain page numbers need no labels.
+        let document = Document {
+            pages: vec![page(PageLabelStyle::Arabic, None, 1)],
+            ..Default::default()
+        };
+        let pdf = export(&document, PdfOptions::default());
+        assert_eq!(pdf.catalog().get("PageLabels"), None);
+
+        // Roman front matter is followed by prefixed arabic numbers. Only the
+        // first page of each range gets an entry.
+        let document = Document {
+            pages: vec![
+                page(PageLabelStyle::LowerRoman, None, 1),
+                page(PageLabelStyle::LowerRoman, None, 2),
+                page(PageLabelStyle::Arabic, Some("A-"), 1),
+            ],
+            ..Default::default()
+        };
+        let pdf = export(&document, PdfOptions::default());
+        let nums = items(pdf.catalog().dict("PageLabels").get("Nums").unwrap());
+        assert_eq!(nums.len(), 4);
+        assert_eq!((nums[0], nums[2]), ("0", "2"));
+        let (roman, arabic) = (Dict(nums[1]), Dict(nums[3]));
+        assert_eq!(roman.get("S"), Some("/r"));
+        assert_eq!(roman.get("P"), None);
+        assert_eq!(arabic.get("S"), Some("/D"));
+        assert_eq!(arabic.get("P"), Some("(A-)"));
+        assert_eq!(arabic.get("St"), None);
+    }
+
+    #[test]
+    fn test_attachments() {
+        let attachment = |name: &str, data: &[u8]| Attachment {
+            name: name.into(),
+            data: data.into(),
+            description: None,
+            mime: None,
+        };
+        let document = Document {
+            pages: vec![blank()],
+            attachments: vec![
+                attachment("notes.txt", b"Hello"),
+                attachment("data.csv", b"a,b"),
+            ],
+            ..Default::default()
+        };
+
+        // An identical attachment in the options is only attached once, while
+        // a different file with the same name gets a numbered name. The name
+        // tree is sorted by name.
+        let options = PdfOptions {
+            attachments: vec![
+                attachment("data.csv", b"a,b"),
+                attachment("notes.txt", b"Bye"),
+            ],
+            ..Default::default()
+        };
+        let pdf = export(&document, options.clone());
+        let tree = pdf.catalog().dict("Names").dict("EmbeddedFiles");
+        let names = items(tree.get("Names").unwrap());
+        assert_eq!(names.len(), 6);
+        assert_eq!(
+            [names[0], names[2], names[4]],
+            ["(data.csv)", "(notes-2.txt)", "(notes.txt)"]
+        );
+        assert_eq!(pdf.of_type("Filespec").count(), 3);
+
+        // Each specification refers to a stream with the file's data.
+        for (spec, name, data) in
+            [(names[3], "(notes-2.txt)", "Bye"), (names[5], "(notes.txt)", "Hello")]
+        {
+            let spec = pdf.resolve(spec);
+            let file = pdf.get(spec.dict("EF").get("F").unwrap());
+            let size = data.len().to_string();
+            assert_eq!(spec.get("UF"), Some(name));
+            assert_eq!(file.dict().dict("Params").get("Size"), Some(size.as_str()));
+            assert_eq!(file.decoded(), data.as_bytes());
+        }
+
+        // PDF/A-2b files can't have attachments.
+        let options = PdfOptions { standard: PdfStandard::A2b, ..options };
+        let pdf = export(&document, options);
+        assert_eq!(pdf.catalog().get("Names"), None);
+        assert_eq!(pdf.of_type("Filespec").count(), 0);
+    }
+
+    #[test]
+    fn test_outline_text() {
+        let document = Document {
+            pages: vec![text_page(&plex(), "Outline")],
+            ..Default::default()
+        };
+        let exported = |outline_text, text_layer| {
+            let options = PdfOptions { outline_text, text_layer, ..Default::default() };
+            export(&document, options)
+        };
+        let embedded = |pdf: &Pdf| {
+            pdf.of_type("FontDescriptor")
+                .any(|descriptor| descriptor.dict().get("FontFile2").is_some())
+        };
+
+        // Regular text is shown with an embedded font.
+        let plain = exported(false, false);
+        let content = plain.content(0);
+        assert!(embedded(&plain));
+        assert!(content.contains(" TJ\n"));
+        assert!(!content.contains(" Tr\n"));
+
+        // Outlined text is filled as paths without any font.
+        let outlined = exported(true, false);
+        let content = outlined.content(0);
+        assert_eq!(outlined.of_type("Font").count(), 0);
+        assert!(!content.contains(" TJ\n"));
+        assert!(content.contains(" c\n"));
+        assert_eq!(content.matches("\nf\n").count(), "Outline".len());
+
+        // With a text layer, the text is also shown invisibly.
+        let layered = exported(true, true);
+        let content = layered.content(0);
+        assert!(embedded(&layered));
+        assert!(content.contains("3 Tr\n"));
+        assert!(content.contains(" TJ\n"));
+        assert!(content.contains(" c\n"));
+    }
+
+    #[test]
+    fn test_clipped_image() {
+        use ::image::codecs::jpeg::JpegEncoder;
+        use ::image::{Rgb, RgbImage};
+
+        use crate::image::{ImageFormat, RasterFormat};
+
+        let mut data = vec![];
+        let pixels = RgbImage::from_pixel(4, 4, Rgb([200, 50, 50]));
+        JpegEncoder::new(&mut data).encode_image(&pixels).unwrap();
+        let format = ImageFormat::Raster(RasterFormat::Jpg);
+        let image = Image::new(data.clone().into(), format).unwrap();
+
+        // An image cut to the ellipse inscribed in it.
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let mut inner = Frame::new(size);
+        inner.push(Point::zero(), FrameItem::Image(image, size, Span::detached()));
+        inner.clip_to(crate::geom::ellipse_path(size));
+        let mut page = blank();
+        page.push_frame(Point::splat(Abs::pt(10.0)), inner);
+        let document = Document { pages: vec![page], ..Default::default() };
+        let pdf = export(&document, PdfOptions::default());
+
+        // The clip path is set before the image is drawn.
+        let content = pdf.content(0);
+        let clip = content.find(" c\nW\nn\n").unwrap();
+        let draw = content.find("/Im0 Do").unwrap();
+        assert!(clip < draw);
+
+        // The clip doesn't touch the pixels: The JPEG is still embedded as is
+        // and without a mask.
+        let xobjects = pdf.page_tree().dict("Resources").dict("XObject");
+        let object = pdf.get(xobjects.get("Im0").unwrap());
+        assert_eq!(object.dict().get("Filter"), Some("/DCTDecode"));
+        assert_eq!(object.dict().get("SMask"), None);
+        assert_eq!(object.data, data);
+    }
+
+    #[test]
+    fn test_compression() {
+        let document = Document {
+            pages: vec![text_page(&plex(), "Compressed")],
+            ..Default::default()
+        };
+        let bytes = |compression| {
+            let options = PdfOptions { compression, ..Default::default() };
+            PdfExporter::new().with_options(options).export(&document)
+        };
+
+        // The page's content stream, the font's character map, and the font
+        // program, with their filters.
+        fn streams(pdf: &Pdf) -> [(Option<&str>, &[u8]); 3] {
+            let font = pdf
+                .of_type("Font")
+                .find(|font| font.dict().get("Subtype") == Some("/Type0"))
+                .unwrap();
+            let cmap = font.dict().get("ToUnicode").unwrap();
+            let descriptor = pdf.of_type("FontDescriptor").next().unwrap().dict();
+            [
+                pdf.get(pdf.page(0).get("Contents").unwrap()),
+                pdf.get(cmap),
+                pdf.get(descriptor.get("FontFile2").unwrap()),
+            ]
+            .map(|stream| (stream.dict().get("Filter"), stream.data.as_slice()))
+        }
+
+        // Uncompressed files have readable content streams and character
+        // maps, but still compress the font.
+        let pdf = Pdf::parse(&bytes(Compression::None));
+        let [(content_filter, content), (cmap_filter, cmap), (font_filter, _)] =
+            streams(&pdf);
+        assert_eq!(content_filter, None);
+        assert!(String::from_utf8_lossy(content).contains("BT\n"));
+        assert_eq!(cmap_filter, None);
+        assert!(String::from_utf8_lossy(cmap).contains("begincmap"));
+        assert_eq!(font_filter, Some("/FlateDecode"));
+
+        // Compressed files don't and higher levels make for smaller files.
+        let fast = bytes(Compression::Level(0));
+        let default = bytes(Compression::default());
+        let best = bytes(Compression::Level(10));
+        let pdf = Pdf::parse(&default);
+        for (filter, _) in streams(&pdf) {
+            assert_eq!(filter, Some("/FlateDecode"));
+        }
+        assert!(fast.len() > default.len());
+        assert!(fast.len() > best.len());
+        assert_eq!(bytes(Compression::Level(11)), best);
+    }
+
+    #[test]
+    fn test_type3_fonts() {
+        // The font only has SVG documents for its emoji.
+        let font =
+            test_font(include_bytes!("../../../assets/fonts/TwitterColorEmoji.ttf"));
+        let document = Document {
+            pages: vec![text_page(&font, "\u{1F600}\u{1F601}\u{1F600}")],
+            ..Default::default()
+        };
+        let pdf = export(&document, PdfOptions::default());
+
+        // Each distinct glyph becomes one character of a single Type3 font,
+        // which draws its image.
+        let fonts: Vec<_> = pdf.of_type("Font").collect();
+        assert_eq!(fonts.len(), 1);
+        let font = fonts[0].dict();
+        assert_eq!(font.get("Subtype"), Some("/Type3"));
+        assert_eq!(font.get("FirstChar"), Some("0"));
+        assert_eq!(font.get("LastChar"), Some("1"));
+        let procs: String = ["g0", "g1"]
+            .map(|name| pdf.get(font.dict("CharProcs").get(name).unwrap()).decoded())
+            .map(|proc| String::from_utf8(proc).unwrap())
+            .concat();
+        assert_eq!(procs.matches(" 0 d0\n").count(), 2);
+        assert_eq!(procs.matches(" cm\n/Im0 Do\n").count(), 1);
+        assert_eq!(procs.matches(" cm\n/Im1 Do\n").count(), 1);
+        let images = font.dict("Resources").dict("XObject");
+        assert!(images.get("Im0").is_some() && images.get("Im1").is_some());
+
+        // Each use of a glyph shows its character and the characters map back
+        // to the emoji.
+        assert_eq!(pdf.content(0).matches("/Ty0 10 Tf\n").count(), 3);
+        let cmap = pdf.get(font.get("ToUnicode").unwrap()).decoded();
+        let cmap = String::from_utf8(cmap).unwrap();
+        assert!(cmap.contains("<00> <D83DDE00>\n<01> <D83DDE01>\n"));
+    }
+
+    #[test]
+    fn test_type3_cluster_to_unicode() {
+        // A thumbs up with a skin tone modifier, shaped into a single glyph.
+        let font =
+            test_font(include_bytes!("../../../assets/fonts/TwitterColorEmoji.ttf"));
+        let mut run = text_run(&font, "\u{1F44D}");
+        run.text = "\u{1F44D}\u{1F3FD}".into();
+        run.glyphs[0].range = 0..8;
+
+        let mut page = blank();
+        page.push(Point::with_y(Abs::pt(20.0)), FrameItem::Text(run));
+        let pdf = export_pages(vec![page], PdfOptions::default());
+
+        // The glyph's code maps back to the whole cluster.
+        let font = pdf.of_type("Font").next().unwrap().dict();
+        let cmap = pdf.get(font.get("ToUnicode").unwrap()).decoded();
+        let cmap = String::from_utf8(cmap).unwrap();
+        assert!(cmap.contains("1 beginbfchar\n<00> <D83DDC4DD83CDFFD>\nendbfchar\n"));
+    }
+
+    #[test]
+    fn test_cache_with_changed_later_page() {
+        let font = plex();
+        let before = Document {
+            pages: vec![text_page(&font, "First"), text_page(&font, "Second")],
+            ..Default::default()
+        };
+        let after = Document {
+            pages: vec![text_page(&font, "First"), text_page(&font, "Changed")],
+            ..Default::default()
+        };
+
+        let mut cache = PdfCache::default();
+        PdfExporter::new().with_cache(&mut cache).export(&before);
+        let (first, second) = (cache.pages[0].data.clone(), cache.pages[1].data.clone());
+
+        // The unchanged page is reused, the changed one is encoded anew, and
+        // the file is the same as without a cache.
+        let data = PdfExporter::new().with_cache(&mut cache).export(&after);
+        assert_eq!(cache.pages[0].data, first);
+        assert_ne!(cache.pages[1].data, second);
+        assert_eq!(data, pdf(&after));
+    }
+
+    #[test]
+    fn test_cache_registers_resources() {
+        let font = plex();
+        let mut first = text_page(&font, "Link");
+        let link = Meta::Link(crate::doc::Destination::Url("https://typst.app".into()));
+        first.push(Point::zero(), FrameItem::Meta(link, Size::splat(Abs::pt(10.0))));
+        let document = Document {
+            pages: vec![first, text_page(&font, "Other")],
+            ..Default::default()
+        };
+
+        // A reused page's font, glyphs, and links end up in the file as if it
+        // was walked, for both plain and tagged documents.
+        for tagged in [false, true] {
+            let options = PdfOptions { tagged, ..Default::default() };
+            let mut cache = PdfCache::default();
+            let before = PdfExporter::new()
+                .with_options(options.clone())
+                .with_cache(&mut cache)
+                .export(&document);
+            let after = PdfExporter::new()
+                .with_options(options.clone())
+                .with_cache(&mut cache)
+                .export(&document);
+            assert_eq!(before, after);
+            assert_eq!(after, PdfExporter::new().with_options(options).export(&document));
+
+            let pdf = Pdf::parse(&after);
+            let annotations = items(pdf.page(0).get("Annots").unwrap());
+            let link = pdf.resolve(annotations[0]);
+            assert_eq!(link.dict("A").get("URI"), Some("(https://typst.app)"));
+        }
+    }
+
+    #[test]
+    fn test_cache_with_broken_glyphs() {
+        let font = plex();
+        let document = Document {
+            pages: vec![text_page(&font, "Text")],
+            ..Default::default()
+        };
+
+        // Glyphs that are drawn as images change the key of all pages.
+        let key = |broken: bool| {
+            let mut ctx = PdfContext::new(&document, PdfOptions::default());
+            if broken {
+                let id = font.ttf().glyph_index('T').unwrap().0;
+                ctx.broken_glyphs.entry(font.clone()).or_default().insert(id);
+            }
+            font::subset_fonts(&mut ctx, &document.pages);
+            ctx.page_key
+        };
+
+        assert_eq!(key(false), key(false));
+        assert_ne!(key(false), key(true));
+    }
+
+    #[test]
+    fn test_cff_subset() {
+        // The font has name-keyed CFF outlines.
+        let font = test_font(include_bytes!("../../../assets/fonts/NewCM10-Regular.otf"));
+        let document = Document {
+            pages: vec![text_page(&font, "Subset")],
+            ..Default::default()
+        };
+        let pdf = export(&document, PdfOptions::default());
+
+        // Find the embedded font program.
+        let descriptor = pdf.of_type("FontDescriptor").next().unwrap().dict();
+        let file = pdf.get(descriptor.get("FontFile3").unwrap());
+        assert_eq!(file.dict().get("Subtype"), Some("/OpenType"));
+        let program = file.decoded();
+
+        // Only the used glyphs keep their outlines and the layout tables are
+        // gone.
+        let outlined = |face: &ttf_parser::Face| {
+            (0..face.number_of_glyphs())
+                .filter(|&g| face.glyph_bounding_box(ttf_parser::GlyphId(g)).is_some())
+                .count()
+        };
+        let face = ttf_parser::Face::parse(&program, 0).unwrap();
+        assert!(face.raw_face().table(ttf_parser::Tag::from_bytes(b"CFF ")).is_some());
+        assert!(outlined(font.ttf()) > 500);
+        assert!(outlined(&face) < 10);
+        assert!(program.len() < font.data().len() / 4);
+        assert!(face.raw_face().table(ttf_parser::Tag::from_bytes(b"GSUB")).is_none());
+    }
+
+    #[test]
+    fn test_pdfa_identification() {
+        let document = Document {
+            pages: vec![blank()],
+            title: Some("Report".into()),
+            author: vec!["Ada".into(), "Grace".into()],
+            ..Default::default()
+        };
+
+        let options = PdfOptions { standard: PdfStandard::A2b, ..Default::default() };
+        let data = PdfExporter::new().with_options(options.clone()).export(&document);
+        let pdf = Pdf::parse(&data);
+
+        // The trailer identifies the file with two strings and the same
+        // document gets the same identifier.
+        assert_eq!(items(pdf.trailer().get("ID").unwrap()).len(), 2);
+        assert_eq!(PdfExporter::new().with_options(options).export(&document), data);
+
+        // The information dictionary and the XMP metadata name the same
+        // author.
+        assert_eq!(pdf.info().get("Author"), Some("(Ada, Grace)"));
+        let xmp = pdf.metadata();
+        assert!(xmp.contains("<rdf:li>Ada, Grace</rdf:li>"));
+        assert!(!xmp.contains("<rdf:li>Ada</rdf:li>"));
+
+        // Without a standard, the XMP metadata lists the authors separately.
+        let xmp = export(&document, PdfOptions::default()).metadata();
+        assert!(xmp.contains("<rdf:li>Ada</rdf:li>"));
+        assert!(xmp.contains("<rdf:li>Grace</rdf:li>"));
+        assert!(!xmp.contains("<rdf:li>Ada, Grace</rdf:li>"));
+    }
+}
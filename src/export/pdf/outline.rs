@@ -2,6 +2,7 @@ use ecow::EcoString;
 use pdf_writer::{Finish, Ref, TextStr};
 
 use super::{AbsExt, PdfContext, RefExt};
+use crate::doc::Bookmark;
 use crate::geom::{Abs, Point};
 
 /// A heading in the outline panel.
@@ -11,15 +12,43 @@ pub struct HeadingNode {
     pub level: usize,
     pub position: Point,
     pub page: Ref,
+    pub closed: bool,
     pub children: Vec<HeadingNode>,
 }
 
+/// Build the outline tree from the document's bookmarks.
+pub fn construct_tree(ctx: &PdfContext, bookmarks: &[Bookmark]) -> Vec<HeadingNode> {
+    let mut tree: Vec<HeadingNode> = vec![];
+    for bookmark in bookmarks {
+        let pos = ctx.introspector.position(bookmark.location);
+        let index = pos.page.get() - 1;
+        let Some(&height) = ctx.page_heights.get(index) else { continue };
+
+        let node = HeadingNode {
+            content: bookmark.title.clone(),
+            level: bookmark.level.get(),
+            position: Point::new(pos.point.x, Abs::pt(height as f64) - pos.point.y),
+            page: ctx.page_refs[index],
+            closed: bookmark.closed,
+            children: vec![],
+        };
+
+        if let Some(last) = tree.last_mut() {
+            if last.try_insert(node.clone(), 1) {
+                continue;
+            }
+        }
+
+        tree.push(node);
+    }
+    tree
+}
+
 impl HeadingNode {
     pub fn len(&self) -> usize {
         1 + self.children.iter().map(Self::len).sum::<usize>()
     }
 
-    #[allow(unused)]
     pub fn try_insert(&mut self, child: Self, level: usize) -> bool {
         if level >= child.level {
             return false;
@@ -62,7 +91,8 @@ pub fn write_outline_item(
         let current_child = Ref::new(id.get() + 1);
         outline.first(current_child);
         outline.last(Ref::new(next_ref.get() - 1));
-        outline.count(-(node.children.len() as i32));
+        let count = node.children.len() as i32;
+        outline.count(if node.closed { -count } else { count });
     }
 
     outline.title(TextStr(&node.content));
@@ -87,3 +117,60 @@ pub fn write_outline_item(
 
     id
 }
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::super::tests::{blank, export};
+    use crate::doc::{Bookmark, Document, FrameItem, Meta};
+    use crate::export::PdfOptions;
+    use crate::geom::{Abs, Point, Size};
+    use crate::model::{Content, StabilityProvider};
+
+    #[test]
+    fn test_outline_nesting() {
+        let mut provider = StabilityProvider::new();
+        let mut frame = blank();
+        let mut bookmarks = vec![];
+        for (i, (title, level)) in
+            [("Intro", 1), ("Details", 2), ("End", 1)].into_iter().enumerate()
+        {
+            let location = provider.locate(i as u128);
+            let mut content = Content::empty();
+            content.set_location(location);
+            frame.push(
+                Point::with_y(Abs::pt(10.0 * i as f64)),
+                FrameItem::Meta(Meta::Elem(content), Size::zero()),
+            );
+            bookmarks.push(Bookmark {
+                title: title.into(),
+                level: NonZeroUsize::new(level).unwrap(),
+                closed: false,
+                location,
+            });
+        }
+
+        let document = Document {
+            pages: vec![frame],
+            bookmarks,
+            ..Default::default()
+        };
+        let pdf = export(&document, PdfOptions::default());
+
+        // The top-level items are counted in the outline and the intro, which
+        // has the details as its child, is open.
+        let outlines = pdf.resolve(pdf.catalog().get("Outlines").unwrap());
+        assert_eq!(outlines.get("Count"), Some("2"));
+        let intro = pdf.resolve(outlines.get("First").unwrap());
+        assert_eq!(intro.get("Title"), Some("(Intro)"));
+        assert_eq!(intro.get("Count"), Some("1"));
+        let details = pdf.resolve(intro.get("First").unwrap());
+        assert_eq!(details.get("Title"), Some("(Details)"));
+        assert_eq!(details.get("Count"), None);
+        let end = pdf.resolve(intro.get("Next").unwrap());
+        assert_eq!(end.get("Title"), Some("(End)"));
+        assert_eq!(end.get("First"), None);
+        assert_eq!(outlines.get("Last"), intro.get("Next"));
+    }
+}
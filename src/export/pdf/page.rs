@@ -1,16 +1,40 @@
-use ecow::eco_format;
-use pdf_writer::types::{ActionType, AnnotationType, ColorSpaceOperand};
-use pdf_writer::writers::ColorSpace;
-use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str};
-
-use super::{deflate, AbsExt, EmExt, PdfContext, RefExt, D65_GRAY, SRGB};
-use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
-use crate::font::Font;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::iter::Peekable;
+use std::num::NonZeroUsize;
+
+use ecow::{eco_format, EcoString};
+use image::{DynamicImage, ImageOutputFormat, Rgba, RgbaImage};
+use pdf_writer::types::{
+    ActionType, AnnotationFlags, AnnotationType, ColorSpaceOperand, LineCapStyle,
+    LineJoinStyle, TextRenderingMode,
+};
+use pdf_writer::writers::{Annotation, ColorSpace};
+use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str, TextStr};
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+use super::extg::ExtGState;
+use super::gradient::PdfGradient;
+use super::hook::PageExtras;
+use super::pattern::PdfPattern;
+use super::structure::StructTree;
+use super::type3;
+use super::{
+    deflate, AbsExt, CachedPage, Compression, EmExt, PdfContext, PdfStandard, RefExt,
+    D65_GRAY, SRGB,
+};
+use crate::doc::{
+    Destination, FormField, FormFieldKind, Frame, FrameItem, Glyph, GroupItem, Lang,
+    Meta, PageLabel, TextItem,
+};
+use crate::font::{ColorLayer, Font};
 use crate::geom::{
-    self, Abs, Color, Em, Geometry, Numeric, Paint, Point, Ratio, Shape, Size, Stroke,
-    Transform,
+    self, Abs, Color, DashPattern, Em, Geometry, Gradient, LineCap, LineJoin, Numeric,
+    Paint, Pattern, Point, Ratio, RgbaColor, Shape, Size, Stroke, Transform,
 };
-use crate::image::Image;
+use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+use crate::util::hash128;
 
 /// Construct page objects.
 pub fn construct_pages(ctx: &mut PdfContext, frames: &[Frame]) {
@@ -24,15 +48,119 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
     let page_ref = ctx.alloc.bump();
     ctx.page_refs.push(page_ref);
     ctx.page_heights.push(frame.height().to_f32());
+    let index = ctx.page_refs.len() - 1;
+
+    // If neither this page nor any before it changed since the last export,
+    // the page is encoded exactly as before, so its compressed content stream
+    // and the resources it uses can be reused.
+    let key = hash128(&(ctx.page_key, frame));
+    ctx.page_key = key;
+    let cached = ctx.cache.as_mut().and_then(|cache| {
+        cache
+            .pages
+            .get_mut(index)
+            .filter(|page| page.key == key)
+            .map(std::mem::take)
+    });
+
+    let compression = ctx.options.compression;
+    let (data, resources) = match cached {
+        // Pages of tagged documents are still walked to build the structure
+        // tree.
+        Some(page) if !ctx.options.tagged => (page.data, page.resources),
+        cached => {
+            let (content, resources) = encode_page(ctx, index, frame);
+            let data = match cached {
+                Some(page) => page.data,
+                None if compression == Compression::None => content,
+                None => deflate(&content, compression.level()),
+            };
+            (data, resources)
+        }
+    };
+
+    resources.register(ctx);
+
+    let bleed = frame
+        .items()
+        .find_map(|(_, item)| match item {
+            FrameItem::Meta(Meta::Bleed(bleed), _) => Some(*bleed),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let rotation = frame
+        .items()
+        .find_map(|(_, item)| match item {
+            FrameItem::Meta(Meta::Rotation(rotation), _) => Some(*rotation),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let label = resources
+        .label
+        .clone()
+        .unwrap_or_else(|| PageLabel::arabic(NonZeroUsize::new(index + 1).unwrap()));
+    ctx.page_labels.push(label);
+
+    // Write the content stream right away, so that the uncompressed content
+    // of all pages is never held in memory at the same time.
+    let content = ctx.alloc.bump();
+    let mut stream = ctx.writer.stream(content, &data);
+    if compression != Compression::None {
+        stream.filter(Filter::FlateDecode);
+    }
+    stream.finish();
+
+    let links = resources.links.clone();
+    let fields = resources.fields.clone();
+    if let Some(cache) = &mut ctx.cache {
+        cache.pages.truncate(index);
+        cache.pages.push(CachedPage { key, data, resources });
+    }
+
+    let (overlay, annotations) = call_hook(ctx, index, frame);
+    let page = Page {
+        size: frame.size(),
+        bleed,
+        rotation,
+        content,
+        overlay,
+        id: page_ref,
+        links,
+        fields,
+        annotations,
+    };
+
+    ctx.pages.push(page);
+}
+
+/// Walk a page and encode it into a content stream.
+///
+/// Returns the stream's uncompressed data and what the page registered with
+/// the document.
+fn encode_page(
+    ctx: &mut PdfContext,
+    index: usize,
+    frame: &Frame,
+) -> (Vec<u8>, PageResources) {
+    let fonts = ctx.font_map.len();
+    let images = ctx.image_map.len();
+    let gradients = ctx.gradient_map.len();
+    let patterns = ctx.pattern_map.len();
+    let ext_gs = ctx.ext_gs_map.len();
 
     let mut ctx = PageContext {
+        page: index,
         parent: ctx,
-        page_ref,
         content: Content::new(),
         state: State::default(),
         saves: vec![],
         bottom: 0.0,
-        links: vec![],
+        resources: PageResources::default(),
+        node: StructTree::ROOT,
+        artifact: false,
+        rasterized: false,
     };
 
     let size = frame.size();
@@ -48,23 +176,159 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
         ty: size.y,
     });
 
+    // Draw overly complex pages as a single image. The frame is still walked
+    // afterwards to pick up its links and other metadata.
+    if let Some(rasterize) = ctx.parent.options.rasterize {
+        if rasterize.applies(frame) {
+            write_raster_page(&mut ctx, frame, rasterize.dpi);
+        }
+    }
+
     // Encode the page into the content stream.
     write_frame(&mut ctx, frame);
 
-    let page = Page {
-        size,
-        content: ctx.content,
-        id: ctx.page_ref,
-        links: ctx.links,
+    let parent = ctx.parent;
+    let mut resources = ctx.resources;
+    resources.fonts = parent.font_map.items().skip(fonts).cloned().collect();
+    resources.images = parent.image_map.items().skip(images).cloned().collect();
+    resources.gradients = parent.gradient_map.items().skip(gradients).cloned().collect();
+    resources.patterns = parent.pattern_map.items().skip(patterns).cloned().collect();
+    resources.ext_gs = parent.ext_gs_map.items().skip(ext_gs).copied().collect();
+    (ctx.content.finish(), resources)
+}
+
+/// What a page registered with the document while it was encoded.
+///
+/// A page whose content stream is reused from a cache isn't walked again, so
+/// it registers the same instead.
+#[derive(Debug, Default, Clone)]
+pub struct PageResources {
+    /// The fonts, images, gradients, patterns, and graphics states that the
+    /// page used before any page preceding it, in order of first use.
+    fonts: Vec<Font>,
+    images: Vec<Image>,
+    gradients: Vec<PdfGradient>,
+    patterns: Vec<PdfPattern>,
+    ext_gs: Vec<ExtGState>,
+    /// Glyphs drawn through Type3 fonts that no page preceding it drew, in
+    /// order of first use.
+    type3: Vec<(Font, u16, EcoString)>,
+    /// The glyphs drawn from each regular font.
+    glyphs: HashMap<Font, HashSet<u16>>,
+    /// How many glyphs of each language the page contains.
+    languages: HashMap<Lang, usize>,
+    /// The label of the page, if the page carries one.
+    label: Option<PageLabel>,
+    links: Vec<Link>,
+    fields: Vec<(FormField, Rect)>,
+}
+
+impl PageResources {
+    /// Register the page's resources with the document.
+    ///
+    /// After walking the page, this only adds its glyphs and languages, since
+    /// the other resources are registered by the walk itself.
+    fn register(&self, ctx: &mut PdfContext) {
+        for font in &self.fonts {
+            ctx.font_map.insert(font.clone());
+        }
+
+        for image in &self.images {
+            ctx.image_map.insert(image.clone());
+        }
+
+        for gradient in &self.gradients {
+            ctx.gradient_map.insert(gradient.clone());
+        }
+
+        for pattern in &self.patterns {
+            ctx.pattern_map.insert(pattern.clone());
+        }
+
+        for &ext_gs in &self.ext_gs {
+            ctx.ext_gs_map.insert(ext_gs);
+        }
+
+        for (font, id, text) in &self.type3 {
+            type3::glyph_code(ctx, font, *id, text);
+        }
+
+        for (font, glyphs) in &self.glyphs {
+            ctx.glyph_sets.entry(font.clone()).or_default().extend(glyphs);
+        }
+
+        for (&lang, &count) in &self.languages {
+            *ctx.languages.entry(lang).or_insert(0) += count;
+        }
+    }
+}
+
+/// Let the hook add content and annotations to a page.
+///
+/// Returns the reference of the content stream with the hook's content, if it
+/// added any, and the annotations it added.
+fn call_hook(
+    ctx: &mut PdfContext,
+    index: usize,
+    frame: &Frame,
+) -> (Option<Ref>, Vec<Ref>) {
+    let Some(hook) = ctx.hook.as_mut() else { return (None, vec![]) };
+
+    let mut extras = PageExtras {
+        index,
+        frame,
+        writer: &mut ctx.writer,
+        alloc: &mut ctx.alloc,
+        content: Content::new(),
+        x_objects: &mut ctx.hook_x_objects,
+        ext_gs: &mut ctx.hook_ext_gs,
+        annotations: vec![],
+    };
+
+    hook.page(&mut extras);
+
+    let PageExtras { content, annotations, .. } = extras;
+
+    let content = content.finish();
+    if content.is_empty() {
+        return (None, annotations);
+    }
+
+    // The page's own content is wrapped into a pair of save and restore
+    // operators, so that the hook starts with a fresh graphics state.
+    let save_ref = match ctx.hook_save_ref {
+        Some(save_ref) => save_ref,
+        None => {
+            let save_ref = ctx.alloc.bump();
+            ctx.writer.stream(save_ref, b"q\n");
+            ctx.hook_save_ref = Some(save_ref);
+            save_ref
+        }
     };
 
-    ctx.parent.pages.push(page);
+    let mut data = b"Q\n".to_vec();
+    data.extend(content);
+
+    let compression = ctx.options.compression;
+    let data = match compression {
+        Compression::None => data,
+        _ => deflate(&data, compression.level()),
+    };
+
+    let overlay = ctx.alloc.bump();
+    let mut stream = ctx.writer.stream(overlay, &data);
+    if compression != Compression::None {
+        stream.filter(Filter::FlateDecode);
+    }
+    stream.finish();
+
+    (Some(overlay), annotations)
 }
 
 /// Write the page tree.
 pub fn write_page_tree(ctx: &mut PdfContext) {
-    for page in std::mem::take(&mut ctx.pages).into_iter() {
-        write_page(ctx, page);
+    for (i, page) in std::mem::take(&mut ctx.pages).into_iter().enumerate() {
+        write_page(ctx, i, page);
     }
 
     let mut pages = ctx.writer.pages(ctx.page_tree_ref);
@@ -76,6 +340,19 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
     let mut spaces = resources.color_spaces();
     spaces.insert(SRGB).start::<ColorSpace>().srgb();
     spaces.insert(D65_GRAY).start::<ColorSpace>().d65_gray();
+    if let (Some(icc_ref), Some(intent)) = (ctx.icc_ref, &ctx.options.output_intent) {
+        spaces
+            .insert(intent.default_space())
+            .array()
+            .item(Name(b"ICCBased"))
+            .item(icc_ref);
+
+        // PDF/A only allows device RGB colors, which images, gradients, and
+        // patterns use, with an RGB output intent or a default color space.
+        if ctx.options.standard == PdfStandard::A2b && intent.components() != 3 {
+            spaces.insert(Name(b"DefaultRGB")).start::<ColorSpace>().srgb();
+        }
+    }
     spaces.finish();
 
     let mut fonts = resources.fonts();
@@ -84,6 +361,11 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
         fonts.pair(Name(name.as_bytes()), font_ref);
     }
 
+    for (i, &font_ref) in ctx.type3_refs.iter().enumerate() {
+        let name = eco_format!("Ty{}", i);
+        fonts.pair(Name(name.as_bytes()), font_ref);
+    }
+
     fonts.finish();
 
     let mut images = resources.x_objects();
@@ -92,82 +374,375 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
         images.pair(Name(name.as_bytes()), image_ref);
     }
 
+    for (i, &x_object_ref) in ctx.hook_x_objects.iter().enumerate() {
+        let name = eco_format!("HkIm{}", i);
+        images.pair(Name(name.as_bytes()), x_object_ref);
+    }
+
     images.finish();
+
+    let mut patterns = resources.insert(Name(b"Pattern")).dict();
+    for (pattern_ref, gr) in ctx.gradient_map.pdf_indices(&ctx.gradient_refs) {
+        let name = eco_format!("Gr{}", gr);
+        patterns.pair(Name(name.as_bytes()), pattern_ref);
+    }
+
+    for (pattern_ref, pa) in ctx.pattern_map.pdf_indices(&ctx.pattern_refs) {
+        let name = eco_format!("Pa{}", pa);
+        patterns.pair(Name(name.as_bytes()), pattern_ref);
+    }
+
+    patterns.finish();
+
+    let mut ext_gs_states = resources.insert(Name(b"ExtGState")).dict();
+    for (gs_ref, gs) in ctx.ext_gs_map.pdf_indices(&ctx.ext_gs_refs) {
+        let name = eco_format!("Gs{}", gs);
+        ext_gs_states.pair(Name(name.as_bytes()), gs_ref);
+    }
+
+    for (i, &gs_ref) in ctx.hook_ext_gs.iter().enumerate() {
+        let name = eco_format!("HkGs{}", i);
+        ext_gs_states.pair(Name(name.as_bytes()), gs_ref);
+    }
+
+    ext_gs_states.finish();
     resources.finish();
     pages.finish();
 }
 
 /// Write a page tree node.
-fn write_page(ctx: &mut PdfContext, page: Page) {
-    let content_id = ctx.alloc.bump();
-
+fn write_page(ctx: &mut PdfContext, i: usize, page: Page) {
+    // Links within the structure tree are written as separate objects, so that
+    // their structure elements can reference them.
+    let links: Vec<_> = merge_links(page.links)
+        .into_iter()
+        .map(|link| {
+            let target = link_target(ctx, &link.dest);
+            let id = link.node.map(|_| ctx.alloc.bump());
+            (link, target, id)
+        })
+        .collect();
+
+    let pdfa = ctx.options.standard == PdfStandard::A2b;
     let mut page_writer = ctx.writer.page(page.id);
     page_writer.parent(ctx.page_tree_ref);
 
     let w = page.size.x.to_f32();
     let h = page.size.y.to_f32();
     page_writer.media_box(Rect::new(0.0, 0.0, w, h));
-    page_writer.contents(content_id);
-
-    let mut annotations = page_writer.annotations();
-    for (dest, rect) in page.links {
-        let mut annotation = annotations.push();
-        annotation.subtype(AnnotationType::Link).rect(rect);
-        annotation.border(0.0, 0.0, 0.0, None);
-
-        let pos = match dest {
-            Destination::Url(uri) => {
-                annotation
-                    .action()
-                    .action_type(ActionType::Uri)
-                    .uri(Str(uri.as_bytes()));
-                continue;
-            }
-            Destination::Position(pos) => pos,
-            Destination::Location(loc) => ctx.introspector.position(loc),
-        };
+    if page.bleed > Abs::zero() {
+        // Viewers clip to the crop box, which would otherwise default to the
+        // media box implicitly. Print workflows expect it spelled out.
+        let b = page.bleed.to_f32();
+        page_writer.crop_box(Rect::new(0.0, 0.0, w, h));
+        page_writer.bleed_box(Rect::new(0.0, 0.0, w, h));
+        page_writer.trim_box(Rect::new(b, b, w - b, h - b));
+    }
+    if page.rotation != 0 {
+        page_writer.pair(Name(b"Rotate"), i32::from(page.rotation));
+    }
+    match (page.overlay, ctx.hook_save_ref) {
+        (Some(overlay), Some(save_ref)) => {
+            page_writer.insert(Name(b"Contents")).array().items([
+                save_ref,
+                page.content,
+                overlay,
+            ]);
+        }
+        _ => {
+            page_writer.contents(page.content);
+        }
+    }
+
+    if ctx.structure.has_content(i) {
+        page_writer.pair(Name(b"StructParents"), i as i32);
+    }
+
+    let field_refs: Vec<_> = page.fields.iter().map(|_| ctx.alloc.bump()).collect();
+    let mut annotations = page_writer.insert(Name(b"Annots")).array();
+    for (link, target, id) in &links {
+        if let Some(id) = *id {
+            annotations.item(id);
+        } else {
+            let annotation = annotations.push().start::<Annotation>();
+            write_link_annotation(annotation, link, target.as_ref(), pdfa);
+        }
+    }
+
+    annotations.items(field_refs.iter().copied());
+    annotations.items(page.annotations);
+    annotations.finish();
+    page_writer.finish();
+
+    for (link, target, id) in &links {
+        if let (Some(id), Some(node)) = (*id, link.node) {
+            // The keys of annotations in the parent tree follow those of the
+            // pages.
+            let key = ctx.page_refs.len() + ctx.structure.annotate(node, i, id);
+            let mut annotation = ctx.writer.indirect(id).start::<Annotation>();
+            annotation.pair(Name(b"StructParent"), key as i32);
+            write_link_annotation(annotation, link, target.as_ref(), pdfa);
+        }
+    }
+
+    for ((field, rect), field_ref) in page.fields.iter().zip(field_refs) {
+        write_form_field(ctx, field_ref, page.id, field, *rect);
+    }
+}
+
+/// Where a link annotation leads.
+enum LinkTarget {
+    /// A web address.
+    Uri(EcoString),
+    /// A position on a page, given by the page and its coordinates in the
+    /// PDF coordinate system.
+    Page(Ref, f32, f32),
+}
+
+/// Resolve the target of a link, if it leads anywhere.
+fn link_target(ctx: &PdfContext, dest: &Destination) -> Option<LinkTarget> {
+    let pos = match dest {
+        Destination::Url(uri) => return Some(LinkTarget::Uri(uri.clone())),
+        Destination::Position(pos) => *pos,
+        Destination::Location(loc) => ctx.introspector.position(*loc),
+    };
+
+    let index = pos.page.get() - 1;
+    let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
+    let height = ctx.page_heights.get(index)?;
+    Some(LinkTarget::Page(
+        ctx.page_refs[index],
+        pos.point.x.to_f32(),
+        height - y.to_f32(),
+    ))
+}
+
+/// Write the entries of a link annotation.
+fn write_link_annotation(
+    mut annotation: Annotation,
+    link: &Link,
+    target: Option<&LinkTarget>,
+    pdfa: bool,
+) {
+    annotation.subtype(AnnotationType::Link).rect(link.rect);
+    annotation.border(0.0, 0.0, 0.0, None);
+    if pdfa {
+        annotation.flags(AnnotationFlags::PRINT);
+    }
 
-        let index = pos.page.get() - 1;
-        let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
-        if let Some(&height) = ctx.page_heights.get(index) {
+    if let Some(quad) = link.quad {
+        annotation.insert(Name(b"QuadPoints")).array().items(quad);
+    }
+
+    match target {
+        Some(LinkTarget::Uri(uri)) => {
+            annotation
+                .action()
+                .action_type(ActionType::Uri)
+                .uri(Str(uri.as_bytes()));
+        }
+        Some(&LinkTarget::Page(page, x, y)) => {
             annotation
                 .action()
                 .action_type(ActionType::GoTo)
                 .destination_direct()
-                .page(ctx.page_refs[index])
-                .xyz(pos.point.x.to_f32(), height - y.to_f32(), None);
+                .page(page)
+                .xyz(x, y, None);
         }
+        None => {}
     }
+}
 
-    annotations.finish();
-    page_writer.finish();
+/// Write a form field as a merged field and widget annotation dictionary.
+fn write_form_field(
+    ctx: &mut PdfContext,
+    field_ref: Ref,
+    page_ref: Ref,
+    field: &FormField,
+    rect: Rect,
+) {
+    ctx.form_fields.push(field_ref);
+
+    // Allocate the appearances of a checkbox's two states up front.
+    let states = matches!(field.kind, FormFieldKind::Checkbox { .. })
+        .then(|| (ctx.alloc.bump(), ctx.alloc.bump()));
+
+    let mut widget = ctx.writer.indirect(field_ref).dict();
+    widget.pair(Name(b"Type"), Name(b"Annot"));
+    widget.pair(Name(b"Subtype"), Name(b"Widget"));
+    widget.pair(Name(b"Rect"), rect);
+    widget.pair(Name(b"P"), page_ref);
+    widget.pair(Name(b"F"), AnnotationFlags::PRINT.bits() as i32);
+    widget.pair(Name(b"T"), TextStr(&field.name));
+    if let Some(tooltip) = &field.tooltip {
+        widget.pair(Name(b"TU"), TextStr(tooltip));
+    }
+
+    match &field.kind {
+        FormFieldKind::Text { value, multiline } => {
+            widget.pair(Name(b"FT"), Name(b"Tx"));
+            widget.pair(Name(b"V"), TextStr(value));
+            widget.pair(Name(b"DA"), Str(FORM_APPEARANCE));
+            if *multiline {
+                widget.pair(Name(b"Ff"), 1 << 12);
+            }
+        }
+        FormFieldKind::Checkbox { checked } => {
+            let state = Name(if *checked { b"Yes" } else { b"Off" });
+            let (on_ref, off_ref) = states.unwrap();
+            widget.pair(Name(b"FT"), Name(b"Btn"));
+            widget.pair(Name(b"V"), state);
+            widget.pair(Name(b"AS"), state);
+            let mut appearances = widget.insert(Name(b"AP")).dict();
+            let mut normal = appearances.insert(Name(b"N")).dict();
+            normal.pair(Name(b"Yes"), on_ref);
+            normal.pair(Name(b"Off"), off_ref);
+            normal.finish();
+            appearances.finish();
+        }
+        FormFieldKind::Signature => {
+            widget.pair(Name(b"FT"), Name(b"Sig"));
+        }
+    }
 
-    let data = page.content.finish();
-    let data = deflate(&data);
-    ctx.writer.stream(content_id, &data).filter(Filter::FlateDecode);
+    widget.finish();
+
+    // A checked box shows a cross, an unchecked one nothing on top of the
+    // page content.
+    if let Some((on_ref, off_ref)) = states {
+        let w = (rect.x2 - rect.x1).abs();
+        let h = (rect.y2 - rect.y1).abs();
+        let bbox = Rect::new(0.0, 0.0, w, h);
+        let inset = 0.2 * w.min(h);
+        let mut cross = Content::new();
+        cross.set_stroke_gray(0.0);
+        cross.set_line_width(0.1 * w.min(h));
+        cross.move_to(inset, inset);
+        cross.line_to(w - inset, h - inset);
+        cross.move_to(inset, h - inset);
+        cross.line_to(w - inset, inset);
+        cross.stroke();
+        ctx.writer.form_xobject(on_ref, &cross.finish()).bbox(bbox);
+        ctx.writer.form_xobject(off_ref, &[]).bbox(bbox);
+    }
 }
 
+/// The default appearance of text in form fields: Helvetica, automatically
+/// sized, in black.
+pub const FORM_APPEARANCE: &[u8] = b"/Helv 0 Tf 0 g";
+
 /// Data for an exported page.
 pub struct Page {
     /// The indirect object id of the page.
     pub id: Ref,
-    /// The page's dimensions.
+    /// The page's dimensions, including its bleed.
     pub size: Size,
-    /// The page's content stream.
-    pub content: Content,
+    /// How far the page extends beyond its trimmed edges on each side.
+    pub bleed: Abs,
+    /// The clockwise rotation in degrees with which viewers display the page.
+    pub rotation: u16,
+    /// The indirect object id of the page's already written content stream.
+    pub content: Ref,
+    /// The indirect object id of the content stream the hook drew on top of
+    /// the page, if any.
+    pub overlay: Option<Ref>,
     /// Links in the PDF coordinate system.
-    pub links: Vec<(Destination, Rect)>,
+    pub links: Vec<Link>,
+    /// Form fields with their regions in the PDF coordinate system.
+    pub fields: Vec<(FormField, Rect)>,
+    /// Annotations added by the hook.
+    pub annotations: Vec<Ref>,
+}
+
+/// A clickable link region in the PDF coordinate system.
+#[derive(Debug, Clone)]
+pub struct Link {
+    /// Where the link points to.
+    pub dest: Destination,
+    /// The axis-aligned bounding box of the region.
+    pub rect: Rect,
+    /// The exact quadrilateral covered by the link if it is not axis-aligned,
+    /// in the order top-left, top-right, bottom-left, bottom-right.
+    pub quad: Option<[f32; 8]>,
+    /// The structure element the link belongs to, if the document is tagged
+    /// and the link isn't part of an artifact.
+    pub node: Option<usize>,
+}
+
+impl Link {
+    /// Whether `other` lies on the same line as this link and directly
+    /// continues it horizontally.
+    fn continued_by(&self, other: &Self) -> bool {
+        if self.dest != other.dest
+            || self.node != other.node
+            || self.quad.is_some()
+            || other.quad.is_some()
+        {
+            return false;
+        }
+
+        let (a, b) = (normalize(self.rect), normalize(other.rect));
+        let overlap = a.y2.min(b.y2) - a.y1.max(b.y1);
+        let height = (a.y2 - a.y1).min(b.y2 - b.y1);
+        let gap = a.x1.max(b.x1) - a.x2.min(b.x2);
+        overlap > 0.5 * height && gap <= LINK_TOLERANCE
+    }
+}
+
+/// How far apart two parts of a link on the same line may be to still be
+/// merged into one annotation.
+const LINK_TOLERANCE: f32 = 1.0;
+
+/// Merge consecutive link regions that belong to the same line into one
+/// region, so that a link wrapped across lines results in exactly one
+/// annotation per line segment.
+fn merge_links(links: Vec<Link>) -> Vec<Link> {
+    let mut merged: Vec<Link> = vec![];
+    for link in links {
+        if let Some(prev) = merged.last_mut() {
+            if prev.continued_by(&link) {
+                let (a, b) = (normalize(prev.rect), normalize(link.rect));
+                prev.rect = Rect::new(
+                    a.x1.min(b.x1),
+                    a.y1.min(b.y1),
+                    a.x2.max(b.x2),
+                    a.y2.max(b.y2),
+                );
+                continue;
+            }
+        }
+        merged.push(link);
+    }
+    merged
+}
+
+/// Ensure that the first corner of a rectangle is its bottom-left one.
+fn normalize(rect: Rect) -> Rect {
+    Rect::new(
+        rect.x1.min(rect.x2),
+        rect.y1.min(rect.y2),
+        rect.x1.max(rect.x2),
+        rect.y1.max(rect.y2),
+    )
 }
 
 /// An exporter for the contents of a single PDF page.
 struct PageContext<'a, 'b> {
     parent: &'a mut PdfContext<'b>,
-    page_ref: Ref,
+    page: usize,
     content: Content,
     state: State,
     saves: Vec<State>,
     bottom: f32,
-    links: Vec<(Destination, Rect)>,
+    /// What the page registers with the document.
+    resources: PageResources,
+    /// The structure element that content currently belongs to.
+    node: usize,
+    /// Whether content is currently within an artifact and thus not part of
+    /// the structure tree.
+    artifact: bool,
+    /// Whether the page's visible content was already drawn as an image.
+    rasterized: bool,
 }
 
 /// A simulated graphics state used to deduplicate graphics state changes and
@@ -180,6 +755,7 @@ struct State {
     fill_space: Option<Name<'static>>,
     stroke: Option<Stroke>,
     stroke_space: Option<Name<'static>>,
+    external_graphics_state: Option<ExtGState>,
 }
 
 impl PageContext<'_, '_> {
@@ -193,6 +769,28 @@ impl PageContext<'_, '_> {
         self.state = self.saves.pop().expect("missing state save");
     }
 
+    /// Write content that belongs to the current structure element as a
+    /// marked-content sequence.
+    fn marked<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        if self.artifact || !self.parent.options.tagged {
+            f(self);
+            return;
+        }
+
+        let structure = &mut self.parent.structure;
+        let mcid = structure.mark(self.page, self.node);
+        let name = structure.name(self.node);
+        self.content
+            .begin_marked_content_with_properties(Name(name.as_bytes()))
+            .properties()
+            .identify(mcid);
+        f(self);
+        self.content.end_marked_content();
+    }
+
     fn transform(&mut self, transform: Transform) {
         let Transform { sx, ky, kx, sy, tx, ty } = transform;
         self.state.transform = self.state.transform.pre_concat(transform);
@@ -215,26 +813,42 @@ impl PageContext<'_, '_> {
         }
     }
 
-    fn set_fill(&mut self, fill: Paint) {
-        if self.state.fill != Some(fill) {
+    fn set_fill(&mut self, fill: Paint, bbox: (Point, Size)) {
+        // Determine the opacity before conversion, which may drop it.
+        let alpha = opacity(fill);
+        let fill = self.parent.options.paint(fill);
+
+        // Gradients and patterns are positioned relative to the painted
+        // bounding box or the current transform, so they must be set anew
+        // each time.
+        if self.state.fill != Some(fill) || !matches!(fill, Paint::Solid(_)) {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = fill;
-            match color {
-                Color::Luma(c) => {
+            match fill {
+                Paint::Solid(Color::Luma(c)) => {
                     self.set_fill_color_space(D65_GRAY);
                     self.content.set_fill_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Paint::Solid(Color::Rgba(c)) => {
                     self.set_fill_color_space(SRGB);
                     self.content.set_fill_color([f(c.r), f(c.g), f(c.b)]);
                 }
-                Color::Cmyk(c) => {
+                Paint::Solid(Color::Cmyk(c)) => {
                     self.reset_fill_color_space();
                     self.content.set_fill_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
                 }
+                Paint::Gradient(gradient) => {
+                    let name = self.gradient(gradient, bbox);
+                    self.set_fill_pattern(&name);
+                }
+                Paint::Pattern(pattern) => {
+                    let name = self.pattern(pattern);
+                    self.set_fill_pattern(&name);
+                }
             }
             self.state.fill = Some(fill);
         }
+
+        self.set_fill_opacity(alpha);
     }
 
     fn set_fill_color_space(&mut self, space: Name<'static>) {
@@ -248,28 +862,97 @@ impl PageContext<'_, '_> {
         self.state.fill_space = None;
     }
 
-    fn set_stroke(&mut self, stroke: Stroke) {
-        if self.state.stroke != Some(stroke) {
+    fn set_fill_opacity(&mut self, opacity: u8) {
+        let current = self.state.external_graphics_state.unwrap_or_default();
+        if current.fill_opacity != opacity {
+            self.set_external_graphics_state(ExtGState {
+                fill_opacity: opacity,
+                ..current
+            });
+        }
+    }
+
+    fn set_fill_pattern(&mut self, name: &str) {
+        self.reset_fill_color_space();
+        self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+        self.content.set_fill_pattern(None, Name(name.as_bytes()));
+    }
+
+    fn set_stroke(&mut self, mut stroke: Stroke, bbox: (Point, Size)) {
+        let alpha = opacity(stroke.paint);
+        stroke.paint = self.parent.options.paint(stroke.paint);
+
+        if self.state.stroke != Some(stroke) || !matches!(stroke.paint, Paint::Solid(_)) {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = stroke.paint;
-            match color {
-                Color::Luma(c) => {
+            match stroke.paint {
+                Paint::Solid(Color::Luma(c)) => {
                     self.set_stroke_color_space(D65_GRAY);
                     self.content.set_stroke_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Paint::Solid(Color::Rgba(c)) => {
                     self.set_stroke_color_space(SRGB);
                     self.content.set_stroke_color([f(c.r), f(c.g), f(c.b)]);
                 }
-                Color::Cmyk(c) => {
+                Paint::Solid(Color::Cmyk(c)) => {
                     self.reset_stroke_color_space();
                     self.content.set_stroke_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
                 }
+                Paint::Gradient(gradient) => {
+                    let name = self.gradient(gradient, bbox);
+                    self.set_stroke_pattern(&name);
+                }
+                Paint::Pattern(pattern) => {
+                    let name = self.pattern(pattern);
+                    self.set_stroke_pattern(&name);
+                }
             }
 
             self.content.set_line_width(stroke.thickness.to_f32());
+
+            // Only write the line style if it differs from the PDF defaults or
+            // the previous stroke.
+            let prev = self.state.stroke.unwrap_or(Stroke {
+                miter_limit: geom::Scalar(10.0),
+                ..Stroke::default()
+            });
+
+            if prev.line_cap != stroke.line_cap {
+                self.content.set_line_cap(match stroke.line_cap {
+                    LineCap::Butt => LineCapStyle::ButtCap,
+                    LineCap::Round => LineCapStyle::RoundCap,
+                    LineCap::Square => LineCapStyle::ProjectingSquareCap,
+                });
+            }
+
+            if prev.line_join != stroke.line_join {
+                self.content.set_line_join(match stroke.line_join {
+                    LineJoin::Miter => LineJoinStyle::MiterJoin,
+                    LineJoin::Round => LineJoinStyle::RoundJoin,
+                    LineJoin::Bevel => LineJoinStyle::BevelJoin,
+                });
+            }
+
+            if prev.miter_limit != stroke.miter_limit {
+                self.content.set_miter_limit(stroke.miter_limit.0 as f32);
+            }
+
+            if prev.dash != stroke.dash
+                || prev.thickness != stroke.thickness
+                || prev.dash_phase != stroke.dash_phase
+            {
+                let array = stroke.dash.array(stroke.thickness);
+                if !array.is_empty() || prev.dash != DashPattern::Solid {
+                    self.content.set_dash_pattern(
+                        array.into_iter().map(Abs::to_f32),
+                        stroke.dash_phase.to_f32(),
+                    );
+                }
+            }
+
             self.state.stroke = Some(stroke);
         }
+
+        self.set_stroke_opacity(alpha);
     }
 
     fn set_stroke_color_space(&mut self, space: Name<'static>) {
@@ -282,63 +965,246 @@ impl PageContext<'_, '_> {
     fn reset_stroke_color_space(&mut self) {
         self.state.stroke_space = None;
     }
+
+    fn set_stroke_opacity(&mut self, opacity: u8) {
+        let current = self.state.external_graphics_state.unwrap_or_default();
+        if current.stroke_opacity != opacity {
+            self.set_external_graphics_state(ExtGState {
+                stroke_opacity: opacity,
+                ..current
+            });
+        }
+    }
+
+    fn set_external_graphics_state(&mut self, ext_gs: ExtGState) {
+        self.parent.ext_gs_map.insert(ext_gs);
+        let name = eco_format!("Gs{}", self.parent.ext_gs_map.map(ext_gs));
+        self.content.set_parameters(Name(name.as_bytes()));
+        self.state.external_graphics_state = Some(ext_gs);
+    }
+
+    fn set_stroke_pattern(&mut self, name: &str) {
+        self.reset_stroke_color_space();
+        self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+        self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+    }
+
+    /// Register a gradient stretched across a bounding box in the current
+    /// coordinate system and return its resource name.
+    fn gradient(&mut self, gradient: Gradient, (pos, size): (Point, Size)) -> EcoString {
+        // Keep the pattern matrix invertible for boxes without extent.
+        let w = size.x.max(Abs::pt(1.0));
+        let h = size.y.max(Abs::pt(1.0));
+        let bbox = Transform::translate(pos.x, pos.y)
+            .pre_concat(Transform::scale(Ratio::new(w.to_pt()), Ratio::new(h.to_pt())));
+
+        let pdf_gradient = PdfGradient {
+            gradient,
+            transform: self.state.transform.pre_concat(bbox),
+        };
+        self.parent.gradient_map.insert(pdf_gradient.clone());
+        eco_format!("Gr{}", self.parent.gradient_map.map(pdf_gradient))
+    }
+
+    /// Register a pattern anchored at the origin of the current coordinate
+    /// system and return its resource name.
+    fn pattern(&mut self, pattern: Pattern) -> EcoString {
+        let pdf_pattern = PdfPattern {
+            pattern,
+            transform: self.state.transform.pre_concat(Transform::rotate(pattern.angle)),
+        };
+        self.parent.pattern_map.insert(pdf_pattern.clone());
+        eco_format!("Pa{}", self.parent.pattern_map.map(pdf_pattern))
+    }
+}
+
+/// The opacity with which a paint is applied through the graphics state.
+///
+/// The alpha channels of gradients and patterns are dropped, so they are
+/// always opaque.
+fn opacity(paint: Paint) -> u8 {
+    match paint {
+        Paint::Solid(Color::Rgba(c)) => c.a,
+        _ => 255,
+    }
 }
 
 /// Encode a frame into the content stream.
 fn write_frame(ctx: &mut PageContext, frame: &Frame) {
-    for &(pos, ref item) in frame.items() {
+    let mut items = frame.items().peekable();
+    while let Some(&(pos, ref item)) = items.next() {
         let x = pos.x.to_f32();
         let y = pos.y.to_f32();
         match item {
             FrameItem::Group(group) => write_group(ctx, pos, group),
-            FrameItem::Text(text) => write_text(ctx, x, y, text),
-            FrameItem::Shape(shape, _) => write_shape(ctx, x, y, shape),
-            FrameItem::Image(image, size, _) => write_image(ctx, x, y, image, *size),
+            FrameItem::Text(_) | FrameItem::Shape(..) | FrameItem::Image(..)
+                if ctx.rasterized => {}
+            FrameItem::Text(text) => {
+                let text = coalesce_text(pos, text, &mut items);
+                ctx.marked(|ctx| write_text(ctx, x, y, &text))
+            }
+            FrameItem::Shape(shape, _) => ctx.marked(|ctx| write_shape(ctx, x, y, shape)),
+            FrameItem::Image(image, size, _) => {
+                ctx.marked(|ctx| write_image(ctx, x, y, image, *size))
+            }
             FrameItem::Meta(meta, size) => match meta {
                 Meta::Link(dest) => write_link(ctx, pos, dest, *size),
                 Meta::Elem(_) => {}
                 Meta::Hide => {}
+                Meta::Artifact => {}
+                Meta::Placed(_) => {}
+                Meta::Bleed(_) => {}
+                Meta::Rotation(_) => {}
+                Meta::Tag(_) => {}
+                Meta::PageLabel(label) => ctx.resources.label = Some(label.clone()),
+                Meta::FormField(field) => write_form_region(ctx, pos, field, *size),
             },
         }
     }
 }
 
+/// Merge the text run at `pos` with directly following runs on the same
+/// baseline that share its font, size, language and solid fill, so that
+/// they end up in a single positioned show operation.
+///
+/// The gaps between the runs become part of the preceding glyph's advance.
+fn coalesce_text<'a>(
+    pos: Point,
+    text: &'a TextItem,
+    items: &mut Peekable<std::slice::Iter<'a, (Point, FrameItem)>>,
+) -> Cow<'a, TextItem> {
+    if text.glyphs.is_empty() || !matches!(text.fill, Paint::Solid(_)) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut merged: Option<TextItem> = None;
+    let mut end = pos.x + text.width();
+    while let Some(&(next_pos, FrameItem::Text(ref next))) = items.peek() {
+        if next_pos.y != pos.y
+            || next.glyphs.is_empty()
+            || next.font != text.font
+            || next.size != text.size
+            || next.lang != text.lang
+            || next.fill != text.fill
+        {
+            break;
+        }
+
+        let merged = merged.get_or_insert_with(|| text.clone());
+        if let Some(last) = merged.glyphs.last_mut() {
+            last.x_advance += Em::from_length(next_pos.x - end, text.size);
+        }
+
+        // The next run's clusters are shifted behind the merged text.
+        let shift = merged.text.len() as u16;
+        merged.text.push_str(&next.text);
+        merged.glyphs.extend(next.glyphs.iter().map(|glyph| Glyph {
+            range: glyph.range.start + shift..glyph.range.end + shift,
+            ..glyph.clone()
+        }));
+        end = next_pos.x + next.width();
+        items.next();
+    }
+
+    merged.map_or(Cow::Borrowed(text), Cow::Owned)
+}
+
 /// Encode a group into the content stream.
 fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
     let translation = Transform::translate(pos.x, pos.y);
 
+    let (node, artifact) = (ctx.node, ctx.artifact);
+    let tagged = ctx.parent.options.tagged;
+    if tagged && group.artifact && !artifact {
+        ctx.content.begin_marked_content(Name(b"Artifact"));
+        ctx.artifact = true;
+    } else if let (Some(tag), true, false) = (&group.tag, tagged, artifact) {
+        ctx.node = ctx.parent.structure.open(node, tag);
+    }
+
     ctx.save_state();
     ctx.transform(translation.pre_concat(group.transform));
 
     if group.clips {
-        let size = group.frame.size();
-        let w = size.x.to_f32();
-        let h = size.y.to_f32();
-        ctx.content.move_to(0.0, 0.0);
-        ctx.content.line_to(w, 0.0);
-        ctx.content.line_to(w, h);
-        ctx.content.line_to(0.0, h);
+        if let Some(path) = &group.clip_path {
+            write_path(&mut ctx.content, 0.0, 0.0, path);
+        } else {
+            let size = group.frame.size();
+            let w = size.x.to_f32();
+            let h = size.y.to_f32();
+            ctx.content.move_to(0.0, 0.0);
+            ctx.content.line_to(w, 0.0);
+            ctx.content.line_to(w, h);
+            ctx.content.line_to(0.0, h);
+        }
         ctx.content.clip_nonzero();
         ctx.content.end_path();
     }
 
     write_frame(ctx, &group.frame);
     ctx.restore_state();
+
+    if ctx.artifact && !artifact {
+        ctx.content.end_marked_content();
+    }
+
+    ctx.node = node;
+    ctx.artifact = artifact;
 }
 
 /// Encode a text run into the content stream.
 fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
-    *ctx.parent.languages.entry(text.lang).or_insert(0) += text.glyphs.len();
-    ctx.parent
-        .glyph_sets
-        .entry(text.font.clone())
-        .or_default()
-        .extend(text.glyphs.iter().map(|g| g.id));
-
-    ctx.set_fill(text.fill);
+    *ctx.resources.languages.entry(text.lang).or_insert(0) += text.glyphs.len();
+
+    let outlined = ctx.parent.options.outline_text;
+    if outlined {
+        write_text_outlines(ctx, x, y, text);
+        if !ctx.parent.options.text_layer {
+            return;
+        }
+    }
+
+    // Glyphs that only exist as SVG documents or bitmaps are drawn through
+    // Type3 fonts. Text made up of nothing else doesn't need its own font.
+    let images: HashSet<u16> = text
+        .glyphs
+        .iter()
+        .map(|g| g.id)
+        .filter(|&id| type3::is_image_glyph(&text.font, id))
+        .collect();
+
+    if text.glyphs.iter().all(|g| images.contains(&g.id)) {
+        if !outlined {
+            let mut cursor = x;
+            for glyph in &text.glyphs {
+                let x = cursor + glyph.x_offset.at(text.size).to_f32();
+                write_type3_glyph(ctx, x, y, text, glyph);
+                cursor += glyph.x_advance.at(text.size).to_f32();
+            }
+        }
+        return;
+    }
+
+    // Glyphs that subsetting broke are drawn as images instead.
+    let broken = ctx.parent.broken_glyphs.get(&text.font).cloned().unwrap_or_default();
+    ctx.resources.glyphs.entry(text.font.clone()).or_default().extend(
+        text.glyphs
+            .iter()
+            .map(|g| g.id)
+            .filter(|id| !broken.contains(id) && !images.contains(id)),
+    );
+
+    let pos = Point::new(Abs::pt(x.into()), Abs::pt(y.into()) - text.size);
+    ctx.set_fill(text.fill, (pos, Size::new(text.width(), text.size)));
     ctx.set_font(&text.font, text.size);
     ctx.content.begin_text();
 
+    // The outlines are already visible, so their text is only for search and
+    // selection.
+    if outlined {
+        ctx.content.set_text_rendering_mode(TextRenderingMode::Invisible);
+    }
+
     // Positiosn the text.
     ctx.content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, y]);
 
@@ -346,9 +1212,35 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
     let mut items = positioned.items();
     let mut adjustment = Em::zero();
     let mut encoded = vec![];
+    let mut cursor = Em::zero();
+    let mut rasterized = vec![];
+    let mut colored = vec![];
+    let mut image_glyphs = vec![];
 
     // Write the glyphs with kerning adjustments.
     for glyph in &text.glyphs {
+        let offset = cursor + glyph.x_offset;
+        let separate = if images.contains(&glyph.id) {
+            image_glyphs.push((offset, glyph));
+            true
+        } else if let Some(layers) = text.font.color_layers(glyph.id) {
+            colored.push((offset, glyph.id, layers));
+            true
+        } else if broken.contains(&glyph.id) {
+            rasterized.push((offset, glyph.id));
+            true
+        } else {
+            false
+        };
+
+        if separate {
+            // Leave room for the glyph, which is drawn separately.
+            adjustment += glyph.x_advance;
+            cursor += glyph.x_advance;
+            continue;
+        }
+
+        cursor += glyph.x_advance;
         adjustment += glyph.x_offset;
 
         if !adjustment.is_zero() {
@@ -377,7 +1269,252 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
 
     items.finish();
     positioned.finish();
+
+    // Color glyphs are filled as shapes, so they are also shown as invisible
+    // text to keep them searchable and selectable.
+    if !colored.is_empty() {
+        if !outlined {
+            ctx.content.set_text_rendering_mode(TextRenderingMode::Invisible);
+        }
+
+        for (offset, id, _) in &colored {
+            let x = x + offset.at(text.size).to_f32();
+            ctx.content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, y]);
+            ctx.content.show(Str(&id.to_be_bytes()));
+        }
+
+        if !outlined {
+            ctx.content.set_text_rendering_mode(TextRenderingMode::Fill);
+        }
+    }
+
+    ctx.content.end_text();
+
+    if !outlined {
+        for (offset, id) in rasterized {
+            write_raster_glyph(ctx, x + offset.at(text.size).to_f32(), y, text, id);
+        }
+
+        for (offset, _, layers) in colored {
+            write_color_glyph(ctx, x + offset.at(text.size).to_f32(), y, text, &layers);
+        }
+
+        for (offset, glyph) in image_glyphs {
+            write_type3_glyph(ctx, x + offset.at(text.size).to_f32(), y, text, glyph);
+        }
+    }
+}
+
+/// Draw a glyph that only exists as an SVG document or a bitmap with a Type3
+/// font, so that it stays searchable.
+fn write_type3_glyph(
+    ctx: &mut PageContext,
+    x: f32,
+    y: f32,
+    text: &TextItem,
+    glyph: &Glyph,
+) {
+    let cluster = text.text.get(glyph.range()).unwrap_or_default();
+    if !ctx.parent.type3_glyphs.contains_key(&(text.font.clone(), glyph.id)) {
+        ctx.resources
+            .type3
+            .push((text.font.clone(), glyph.id, cluster.into()));
+    }
+
+    let Some((index, code)) =
+        type3::glyph_code(ctx.parent, &text.font, glyph.id, cluster)
+    else {
+        return;
+    };
+
+    let name = eco_format!("Ty{}", index);
+    ctx.content.begin_text();
+
+    // Outlined text may have left the text layer invisible.
+    if ctx.parent.options.outline_text {
+        ctx.content.set_text_rendering_mode(TextRenderingMode::Fill);
+    }
+
+    ctx.content.set_font(Name(name.as_bytes()), text.size.to_f32());
+    ctx.content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, y]);
+    ctx.content.show(Str(&[code]));
     ctx.content.end_text();
+
+    // The font was switched behind the state's back.
+    ctx.state.font = None;
+}
+
+/// Draw a color glyph by filling the outlines of its layers in their colors.
+fn write_color_glyph(
+    ctx: &mut PageContext,
+    x: f32,
+    y: f32,
+    text: &TextItem,
+    layers: &[ColorLayer],
+) {
+    let pos = Point::new(Abs::pt(x.into()), Abs::pt(y.into()) - text.size);
+    let scale = text.size.to_f32() / text.font.units_per_em() as f32;
+    for layer in layers {
+        let fill = match layer.color {
+            Some(color) => Paint::Solid(Color::Rgba(color)),
+            None => text.fill,
+        };
+
+        ctx.set_fill(fill, (pos, Size::splat(text.size)));
+        let mut writer = OutlineWriter {
+            content: &mut ctx.content,
+            x,
+            y,
+            scale,
+            last: (0.0, 0.0),
+        };
+
+        let id = GlyphId(layer.glyph);
+        if text.font.ttf().outline_glyph(id, &mut writer).is_some() {
+            ctx.content.fill_nonzero();
+        }
+    }
+}
+
+/// Draw a glyph as an image rasterized from the original font.
+fn write_raster_glyph(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem, id: u16) {
+    // Rasterize at a resolution that stays crisp in print.
+    const PX_PER_PT: f32 = 4.0;
+
+    let Some(glyph) = pixglyph::Glyph::load(text.font.ttf(), GlyphId(id)) else {
+        return;
+    };
+
+    let bitmap = glyph.rasterize(0.0, 0.0, text.size.to_f32() * PX_PER_PT);
+    if bitmap.width == 0 || bitmap.height == 0 {
+        return;
+    }
+
+    let color = match text.fill {
+        Paint::Solid(color) => color,
+        _ => Color::BLACK,
+    }
+    .to_rgba();
+
+    let raster = RgbaImage::from_fn(bitmap.width, bitmap.height, |px, py| {
+        let coverage = bitmap.coverage[(py * bitmap.width + px) as usize];
+        Rgba([color.r, color.g, color.b, coverage])
+    });
+
+    let mut png = vec![];
+    let Ok(()) = DynamicImage::ImageRgba8(raster)
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+    else {
+        return;
+    };
+
+    let format = ImageFormat::Raster(RasterFormat::Png);
+    let Ok(image) = Image::new(png.into(), format) else { return };
+    let size = Size::new(
+        Abs::pt((bitmap.width as f32 / PX_PER_PT).into()),
+        Abs::pt((bitmap.height as f32 / PX_PER_PT).into()),
+    );
+
+    let x = x + bitmap.left as f32 / PX_PER_PT;
+    let y = y + bitmap.top as f32 / PX_PER_PT;
+    write_image(ctx, x, y, &image, size);
+}
+
+/// Encode a text run into the content stream as filled glyph outlines.
+fn write_text_outlines(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
+    let pos = Point::new(Abs::pt(x.into()), Abs::pt(y.into()) - text.size);
+    let scale = text.size.to_f32() / text.font.units_per_em() as f32;
+    let mut cursor = x;
+    let mut fill_set = false;
+    for glyph in &text.glyphs {
+        let x = cursor + glyph.x_offset.at(text.size).to_f32();
+        cursor += glyph.x_advance.at(text.size).to_f32();
+        if type3::is_image_glyph(&text.font, glyph.id) {
+            write_type3_glyph(ctx, x, y, text, glyph);
+            continue;
+        }
+
+        if let Some(layers) = text.font.color_layers(glyph.id) {
+            write_color_glyph(ctx, x, y, text, &layers);
+            fill_set = false;
+            continue;
+        }
+
+        // Color glyphs change the fill, so it is set again after them.
+        if !fill_set {
+            ctx.set_fill(text.fill, (pos, Size::new(text.width(), text.size)));
+            fill_set = true;
+        }
+
+        let mut writer = OutlineWriter {
+            content: &mut ctx.content,
+            x,
+            y,
+            scale,
+            last: (0.0, 0.0),
+        };
+
+        let id = GlyphId(glyph.id);
+        if text.font.ttf().outline_glyph(id, &mut writer).is_some() {
+            ctx.content.fill_nonzero();
+        }
+    }
+}
+
+/// Writes a glyph outline into a content stream, scaled from font units and
+/// flipped because the font design coordinate system is Y-up.
+struct OutlineWriter<'a> {
+    content: &'a mut Content,
+    x: f32,
+    y: f32,
+    scale: f32,
+    last: (f32, f32),
+}
+
+impl OutlineWriter<'_> {
+    /// Transform a point from font units into page coordinates.
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x + x * self.scale, self.y - y * self.scale)
+    }
+}
+
+impl OutlineBuilder for OutlineWriter<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.last = self.point(x, y);
+        self.content.move_to(self.last.0, self.last.1);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.last = self.point(x, y);
+        self.content.line_to(self.last.0, self.last.1);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        // PDF has no quadratic curves, so elevate it to a cubic one.
+        let (x0, y0) = self.last;
+        let (qx, qy) = self.point(x1, y1);
+        let (x3, y3) = self.point(x, y);
+        self.content.cubic_to(
+            x0 + 2.0 / 3.0 * (qx - x0),
+            y0 + 2.0 / 3.0 * (qy - y0),
+            x3 + 2.0 / 3.0 * (qx - x3),
+            y3 + 2.0 / 3.0 * (qy - y3),
+            x3,
+            y3,
+        );
+        self.last = (x3, y3);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        self.last = self.point(x, y);
+        self.content.cubic_to(x1, y1, x2, y2, self.last.0, self.last.1);
+    }
+
+    fn close(&mut self) {
+        self.content.close_path();
+    }
 }
 
 /// Encode a geometrical shape into the content stream.
@@ -386,12 +1523,19 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
         return;
     }
 
+    let (offset, size) = shape.geometry.bbox();
+    let pos = Point::new(Abs::pt(x.into()), Abs::pt(y.into())) + offset;
+
     if let Some(fill) = shape.fill {
-        ctx.set_fill(fill);
+        ctx.set_fill(fill, (pos, size));
     }
 
+    // Gradients on strokes stretch across the stroked area, so that they
+    // also show up across the width of a straight line.
     if let Some(stroke) = shape.stroke {
-        ctx.set_stroke(stroke);
+        let (offset, size) = shape.geometry.stroke_bbox(stroke.thickness);
+        let pos = Point::new(Abs::pt(x.into()), Abs::pt(y.into())) + offset;
+        ctx.set_stroke(stroke, (pos, size));
     }
 
     match shape.geometry {
@@ -409,7 +1553,7 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
             }
         }
         Geometry::Path(ref path) => {
-            write_path(ctx, x, y, path);
+            write_path(&mut ctx.content, x, y, path);
         }
     }
 
@@ -422,16 +1566,16 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
 }
 
 /// Encode a bezier path into the content stream.
-fn write_path(ctx: &mut PageContext, x: f32, y: f32, path: &geom::Path) {
+pub(super) fn write_path(content: &mut Content, x: f32, y: f32, path: &geom::Path) {
     for elem in &path.0 {
         match elem {
             geom::PathItem::MoveTo(p) => {
-                ctx.content.move_to(x + p.x.to_f32(), y + p.y.to_f32())
+                content.move_to(x + p.x.to_f32(), y + p.y.to_f32())
             }
             geom::PathItem::LineTo(p) => {
-                ctx.content.line_to(x + p.x.to_f32(), y + p.y.to_f32())
+                content.line_to(x + p.x.to_f32(), y + p.y.to_f32())
             }
-            geom::PathItem::CubicTo(p1, p2, p3) => ctx.content.cubic_to(
+            geom::PathItem::CubicTo(p1, p2, p3) => content.cubic_to(
                 x + p1.x.to_f32(),
                 y + p1.y.to_f32(),
                 x + p2.x.to_f32(),
@@ -439,13 +1583,24 @@ fn write_path(ctx: &mut PageContext, x: f32, y: f32, path: &geom::Path) {
                 x + p3.x.to_f32(),
                 y + p3.y.to_f32(),
             ),
-            geom::PathItem::ClosePath => ctx.content.close_path(),
+            geom::PathItem::ClosePath => content.close_path(),
         };
     }
 }
 
+/// The image formats in the order in which they are preferred for embedding.
+/// Vector graphics stay sharp at any zoom level and JPEGs are embedded without
+/// re-encoding them.
+const PREFERRED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Vector(VectorFormat::Svg),
+    ImageFormat::Raster(RasterFormat::Jpg),
+    ImageFormat::Raster(RasterFormat::Png),
+    ImageFormat::Raster(RasterFormat::Gif),
+];
+
 /// Encode a vector or raster image into the content stream.
 fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size) {
+    let image = image.select(&PREFERRED_FORMATS);
     ctx.parent.image_map.insert(image.clone());
     let name = eco_format!("Im{}", ctx.parent.image_map.map(image.clone()));
     let w = size.x.to_f32();
@@ -456,25 +1611,66 @@ fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size)
     ctx.content.restore_state();
 }
 
+/// Encode a whole page as a raster image at the given resolution.
+fn write_raster_page(ctx: &mut PageContext, frame: &Frame, dpi: u16) {
+    let transparent = Color::Rgba(RgbaColor::new(0, 0, 0, 0));
+    let pixmap = crate::export::render(frame, dpi as f32 / 72.0, transparent);
+    let Ok(png) = pixmap.encode_png() else { return };
+    let format = ImageFormat::Raster(RasterFormat::Png);
+    let Ok(image) = Image::new(png.into(), format) else { return };
+    ctx.marked(|ctx| write_image(ctx, 0.0, 0.0, &image, frame.size()));
+    ctx.rasterized = true;
+}
+
 /// Save a link for later writing in the annotations dictionary.
 fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size) {
+    let (rect, quad) = region(ctx, pos, size);
+    let node = (ctx.parent.options.tagged && !ctx.artifact).then_some(ctx.node);
+    ctx.resources
+        .links
+        .push(Link { dest: dest.clone(), rect, quad, node });
+}
+
+/// Save a form field for later writing as a widget annotation.
+///
+/// Widgets can't be rotated, so transformed fields cover their bounding box.
+/// PDF/A-2 forbids fields whose appearance is left to the viewer, so no fields
+/// are exported in that case.
+fn write_form_region(ctx: &mut PageContext, pos: Point, field: &FormField, size: Size) {
+    if ctx.parent.options.standard == PdfStandard::A2b {
+        return;
+    }
+
+    let (rect, _) = region(ctx, pos, size);
+    ctx.resources.fields.push((field.clone(), normalize(rect)));
+}
+
+/// Compute the bounding box of a region in the PDF coordinate system and, if
+/// it is rotated or skewed, its exact corners.
+fn region(ctx: &PageContext, pos: Point, size: Size) -> (Rect, Option<[f32; 8]>) {
     let mut min_x = Abs::inf();
     let mut min_y = Abs::inf();
     let mut max_x = -Abs::inf();
     let mut max_y = -Abs::inf();
+    let mut quad = [0.0; 8];
 
-    // Compute the bounding box of the transformed link.
-    for point in [
+    // Compute the bounding box and the corners of the transformed link.
+    for (i, point) in [
         pos,
         pos + Point::with_x(size.x),
         pos + Point::with_y(size.y),
         pos + size.to_point(),
-    ] {
+    ]
+    .into_iter()
+    .enumerate()
+    {
         let t = point.transform(ctx.state.transform);
         min_x.set_min(t.x);
         min_y.set_min(t.y);
         max_x.set_max(t.x);
         max_y.set_max(t.y);
+        quad[2 * i] = t.x.to_f32();
+        quad[2 * i + 1] = t.y.to_f32();
     }
 
     let x1 = min_x.to_f32();
@@ -483,5 +1679,575 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
     let y2 = min_y.to_f32();
     let rect = Rect::new(x1, y1, x2, y2);
 
-    ctx.links.push((dest.clone(), rect));
+    // Only rotated or skewed links need explicit quad points.
+    let Transform { ky, kx, .. } = ctx.state.transform;
+    let quad = (!ky.is_zero() || !kx.is_zero()).then_some(quad);
+
+    (rect, quad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{
+        add_tables, blank, export_pages, items, operands, plex, text_page, text_run, Pdf,
+    };
+    use super::*;
+    use crate::export::{Grayscale, PdfOptions, Rasterize};
+    use crate::geom::{Angle, GradientKind, LumaColor, PatternKind, Scalar};
+    use crate::syntax::Span;
+
+    /// A page with the shapes stacked at its left edge.
+    fn shape_page(shapes: Vec<Shape>) -> Frame {
+        let mut page = blank();
+        for (i, shape) in shapes.into_iter().enumerate() {
+            let pos = Point::new(Abs::pt(10.0), Abs::pt(10.0 * (i + 1) as f64));
+            page.push(pos, FrameItem::Shape(shape, Span::detached()));
+        }
+        page
+    }
+
+    /// A horizontal line with the given stroke.
+    fn line(stroke: Stroke) -> Shape {
+        Geometry::Line(Point::with_x(Abs::pt(50.0))).stroked(stroke)
+    }
+
+    /// A page with a filled 20pt by 10pt frame that is clipped by a function.
+    fn clipped_page(clip: impl FnOnce(&mut Frame)) -> Frame {
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let shape = Geometry::Rect(size).filled(Paint::Solid(Color::BLACK));
+        let mut inner = Frame::new(size);
+        inner.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        clip(&mut inner);
+        let mut page = blank();
+        page.push_frame(Point::splat(Abs::pt(10.0)), inner);
+        page
+    }
+
+    /// A page with a link region of the given size at a position.
+    fn link_page(pos: Point, size: Size, transform: Transform) -> Frame {
+        let mut inner = Frame::new(size);
+        let dest = Destination::Url("https://typst.app".into());
+        inner.push(Point::zero(), FrameItem::Meta(Meta::Link(dest), size));
+        let group = GroupItem { transform, ..GroupItem::new(inner) };
+        let mut page = blank();
+        page.push(pos, FrameItem::Group(group));
+        page
+    }
+
+    #[test]
+    fn test_merge_links() {
+        let link = |url: &str, x1, x2, y1, y2| Link {
+            dest: Destination::Url(url.into()),
+            rect: Rect::new(x1, y1, x2, y2),
+            quad: None,
+            node: None,
+        };
+
+        // Only parts of the same link that continue each other on a line are
+        // merged.
+        let merged = merge_links(vec![
+            link("a", 10.0, 40.0, 80.0, 90.0),
+            link("a", 40.5, 70.0, 80.0, 90.0),
+            link("b", 70.0, 80.0, 80.0, 90.0),
+            link("b", 10.0, 30.0, 60.0, 70.0),
+        ]);
+
+        let rects: Vec<_> = merged
+            .iter()
+            .map(|link| (link.rect.x1, link.rect.y1, link.rect.x2, link.rect.y2))
+            .collect();
+        assert_eq!(
+            rects,
+            [
+                (10.0, 80.0, 70.0, 90.0),
+                (70.0, 80.0, 80.0, 90.0),
+                (10.0, 60.0, 30.0, 70.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coalesce_text() {
+        let font = plex();
+        let run = |text| FrameItem::Text(text_run(&font, text));
+        let items = vec![
+            (Point::new(Abs::pt(10.0), Abs::pt(20.0)), run("ab")),
+            (Point::new(Abs::pt(25.0), Abs::pt(20.0)), run("cd")),
+            (Point::new(Abs::pt(10.0), Abs::pt(40.0)), run("ef")),
+        ];
+
+        // The runs on the first baseline are merged and the gap of 5pt
+        // between them widens the last glyph of the first run.
+        let mut iter = items.iter().peekable();
+        let Some((pos, FrameItem::Text(first))) = iter.next() else { panic!() };
+        let merged = coalesce_text(*pos, first, &mut iter);
+        let text: String = merged.glyphs.iter().map(|g| g.c).collect();
+        let advances: Vec<_> = merged.glyphs.iter().map(|g| g.x_advance).collect();
+        assert_eq!(text, "abcd");
+        assert_eq!(merged.text, "abcd");
+        assert_eq!(merged.glyphs[2].range, 2..3);
+        assert_eq!(advances, [Em::new(0.5), Em::new(1.0), Em::new(0.5), Em::new(0.5)]);
+
+        // The run on the next baseline is left alone.
+        let Some((_, FrameItem::Text(last))) = iter.next() else { panic!() };
+        assert_eq!(last.glyphs[0].c, 'e');
+
+        // In the exported file, the two baselines take two show operations.
+        let mut page = blank();
+        for (pos, item) in items {
+            page.push(pos, item);
+        }
+        let pdf = export_pages(vec![page], PdfOptions::default());
+        assert_eq!(pdf.content(0).matches(" TJ\n").count(), 2);
+    }
+
+    #[test]
+    fn test_color_glyph() {
+        let font = plex();
+        let id = |c| font.ttf().glyph_index(c).unwrap().0;
+        let (a, b, c) = (id('A'), id('B'), id('C'));
+
+        // Color glyph A is drawn as B in red, followed by C in the text's
+        // color.
+        let mut colr = vec![0, 0, 0, 1, 0, 0, 0, 14, 0, 0, 0, 20, 0, 2];
+        colr.extend(a.to_be_bytes());
+        colr.extend([0, 0, 0, 2]);
+        colr.extend(b.to_be_bytes());
+        colr.extend([0, 0]);
+        colr.extend(c.to_be_bytes());
+        colr.extend([0xFF, 0xFF]);
+        let cpal = vec![0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 14, 0, 0, 0, 0, 255, 255];
+        let data = add_tables(font.data(), vec![(b"COLR", colr), (b"CPAL", cpal)]);
+        let colored = Font::new(data.into(), 0).unwrap();
+        assert_eq!(font.color_layers(a), None);
+        assert_eq!(colored.color_layers(a).map(|layers| layers.len()), Some(2));
+
+        let pdf = export_pages(vec![text_page(&colored, "AB")], PdfOptions::default());
+        let content = pdf.content(0);
+        let count =
+            |prefix: &str| content.lines().filter(|l| l.starts_with(prefix)).count();
+
+        // The plain glyph is shown as text and the color glyph as invisible
+        // text, which maps back to its character.
+        assert_eq!(content.matches(" TJ\n").count(), 1);
+        assert_eq!(content.matches(" Tj\n").count(), 1);
+        assert_eq!(content.lines().filter(|&line| line == "3 Tr").count(), 1);
+        let font = pdf
+            .of_type("Font")
+            .find(|font| font.dict().get("Subtype") == Some("/Type0"))
+            .unwrap();
+        let cmap = pdf.get(font.dict().get("ToUnicode").unwrap()).decoded();
+        let cmap = String::from_utf8(cmap).unwrap();
+        assert!(
+            cmap.contains(&format!("<{a:04X}> <0041>\n"))
+                || cmap.contains(&format!("<{a:04X}> <{b:04X}> <0041>\n"))
+        );
+
+        // The layers of the color glyph are filled as outlines, the first in
+        // red and the second in the text's black.
+        assert_eq!(content.lines().filter(|&line| line == "f").count(), 2);
+        assert_eq!(count("1 0 0 sc"), 1);
+        assert_eq!(count("0 0 0 sc"), 2);
+    }
+
+    #[test]
+    fn test_line_style() {
+        let styled = Stroke {
+            thickness: Abs::pt(2.0),
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Bevel,
+            ..Stroke::default()
+        };
+        let plain = Stroke {
+            thickness: Abs::pt(2.0),
+            miter_limit: Scalar(10.0),
+            ..Stroke::default()
+        };
+        let page = shape_page(vec![line(styled), line(styled), line(plain)]);
+        let content = export_pages(vec![page], PdfOptions::default()).content(0);
+
+        // The style is only written where it differs from the PDF defaults or
+        // the previous stroke.
+        assert_eq!(operands(&content, "w"), [vec![2.0], vec![2.0]]);
+        assert_eq!(content.matches("2 w\n1 J\n2 j\n4 M\n").count(), 1);
+        assert_eq!(content.matches("2 w\n0 J\n0 j\n10 M\n").count(), 1);
+    }
+
+    #[test]
+    fn test_dash_pattern() {
+        let solid = Stroke { thickness: Abs::pt(2.0), ..Stroke::default() };
+        let dashed = Stroke { dash: DashPattern::Dashed, ..solid };
+        let page = shape_page(vec![line(solid), line(dashed), line(solid)]);
+        let content = export_pages(vec![page], PdfOptions::default()).content(0);
+
+        // Solid lines only reset the pattern after a dashed one.
+        assert_eq!(content.matches(" d\n").count(), 2);
+        assert!(content.contains("[6 6] 0 d\n"));
+        assert!(content.contains("[] 0 d\n"));
+    }
+
+    #[test]
+    fn test_dash_phase() {
+        let dashed = Stroke {
+            thickness: Abs::pt(2.0),
+            dash: DashPattern::Dashed,
+            dash_phase: Abs::pt(1.5),
+            ..Stroke::default()
+        };
+        let shifted = Stroke { dash_phase: Abs::pt(3.0), ..dashed };
+        let page = shape_page(vec![line(dashed), line(shifted)]);
+        let content = export_pages(vec![page], PdfOptions::default()).content(0);
+
+        // A new phase alone is enough to write the pattern again.
+        assert!(content.contains("[6 6] 1.5 d\n"));
+        assert!(content.contains("[6 6] 3 d\n"));
+    }
+
+    #[test]
+    fn test_grayscale() {
+        let red = Paint::Solid(Color::Rgba(RgbaColor::new(255, 0, 0, 255)));
+        let square = Geometry::Rect(Size::splat(Abs::pt(5.0)));
+        let page = shape_page(vec![
+            square.clone().filled(red),
+            square.stroked(Stroke { paint: red, ..Stroke::default() }),
+        ]);
+        let options = PdfOptions {
+            grayscale: Some(Grayscale::Rec601),
+            ..Default::default()
+        };
+        let content = export_pages(vec![page], options).content(0);
+
+        // Fills and strokes are gray with the weight of the red channel.
+        let luma = 76.0 / 255.0;
+        for op in ["g", "G"] {
+            let operands = operands(&content, op);
+            assert_eq!(operands.len(), 1);
+            assert!((operands[0][0] - luma).abs() < 1e-3);
+        }
+
+        assert!(content.contains("/d65gray cs\n"));
+        assert!(!content.contains("/srgb cs\n") && !content.contains("/srgb CS\n"));
+    }
+
+    #[test]
+    fn test_bleed_boxes() {
+        let mut bleeding = Frame::new(Size::splat(Abs::pt(110.0)));
+        bleeding.push(
+            Point::zero(),
+            FrameItem::Meta(Meta::Bleed(Abs::pt(5.0)), Size::zero()),
+        );
+        let pdf = export_pages(vec![bleeding, blank()], PdfOptions::default());
+
+        // Only the page with bleed has a trim box inset from its media box and
+        // an explicit crop box.
+        let (bleeding, plain) = (pdf.page(0), pdf.page(1));
+        assert_eq!(bleeding.get("MediaBox"), Some("[0 0 110 110]"));
+        assert_eq!(bleeding.get("TrimBox"), Some("[5 5 105 105]"));
+        assert_eq!(bleeding.get("BleedBox"), Some("[0 0 110 110]"));
+        assert_eq!(bleeding.get("CropBox"), Some("[0 0 110 110]"));
+        assert_eq!(plain.get("MediaBox"), Some("[0 0 100 100]"));
+        assert_eq!(plain.get("TrimBox"), None);
+        assert_eq!(plain.get("BleedBox"), None);
+        assert_eq!(plain.get("CropBox"), None);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let mut rotated = blank();
+        rotated.push(Point::zero(), FrameItem::Meta(Meta::Rotation(90), Size::zero()));
+        let pdf = export_pages(vec![rotated, blank()], PdfOptions::default());
+
+        // Only the rotated page has a rotation entry and its media box is
+        // unaffected.
+        assert_eq!(pdf.page(0).get("Rotate"), Some("90"));
+        assert_eq!(pdf.page(1).get("Rotate"), None);
+        for i in 0..2 {
+            assert_eq!(pdf.page(i).get("MediaBox"), Some("[0 0 100 100]"));
+        }
+    }
+
+    #[test]
+    fn test_clip_to_bounds() {
+        let pdf = export_pages(vec![clipped_page(Frame::clip)], PdfOptions::default());
+
+        // The contents are clipped to the frame's bounds.
+        assert!(pdf.content(0).contains("0 0 m\n20 0 l\n20 10 l\n0 10 l\nW\nn\n"));
+    }
+
+    #[test]
+    fn test_clip_to_path() {
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let page = clipped_page(|frame| frame.clip_to(geom::ellipse_path(size)));
+        let content = export_pages(vec![page], PdfOptions::default()).content(0);
+
+        // The contents are clipped to the four curves of the ellipse.
+        assert_eq!(content.matches(" c\n").count(), 4);
+        assert!(content.contains("0 5 m\n"));
+        assert!(content.contains(" 0 5 c\nW\nn\n"));
+    }
+
+    #[test]
+    fn test_gradient() {
+        let red = Color::Rgba(RgbaColor::new(255, 0, 0, 255));
+        let blue = Color::Rgba(RgbaColor::new(0, 0, 255, 255));
+        let linear = Gradient {
+            kind: GradientKind::Linear(Angle::zero()),
+            from: red,
+            to: blue,
+        };
+        let radial = Gradient { kind: GradientKind::Radial, ..linear };
+        let vertical = Gradient {
+            kind: GradientKind::Linear(Angle::deg(90.0)),
+            ..linear
+        };
+        let rect = Geometry::Rect(Size::new(Abs::pt(20.0), Abs::pt(10.0)));
+        let page = shape_page(vec![
+            rect.clone().filled(Paint::Gradient(linear)),
+            rect.stroked(Stroke {
+                paint: Paint::Gradient(radial),
+                ..Stroke::default()
+            }),
+            line(Stroke {
+                paint: Paint::Gradient(vertical),
+                thickness: Abs::pt(4.0),
+                ..Stroke::default()
+            }),
+        ]);
+        let pdf = export_pages(vec![page], PdfOptions::default());
+
+        // Gradients are set through the pattern color space.
+        let content = pdf.content(0);
+        assert!(content.contains("/Pattern cs\n/Gr0 scn\n"));
+        assert!(content.contains("/Pattern CS\n/Gr1 SCN\n"));
+        let patterns = pdf.page_tree().dict("Resources").dict("Pattern");
+        let linear = pdf.resolve(patterns.get("Gr0").unwrap());
+        let radial = pdf.resolve(patterns.get("Gr1").unwrap());
+        assert_eq!(linear.dict("Shading").get("ShadingType"), Some("2"));
+        assert_eq!(radial.dict("Shading").get("ShadingType"), Some("3"));
+
+        // The linear one runs from left to right across the filled rectangle.
+        let shading = linear.dict("Shading");
+        assert_eq!(linear.get("Matrix"), Some("[20 0 0 -10 10 90]"));
+        assert_eq!(shading.get("Coords"), Some("[0 0.5 1 0.5]"));
+        assert_eq!(shading.dict("Function").get("C0"), Some("[1 0 0]"));
+        assert_eq!(shading.dict("Function").get("C1"), Some("[0 0 1]"));
+
+        // Gradients on strokes span the stroked area, which extends half the
+        // thickness beyond the geometry, so that they also fade across a
+        // line.
+        assert_eq!(radial.get("Matrix"), Some("[21 0 0 -11 9.5 80.5]"));
+        let across = pdf.resolve(patterns.get("Gr2").unwrap());
+        assert_eq!(across.get("Matrix"), Some("[54 0 0 -4 8 72]"));
+    }
+
+    #[test]
+    fn test_tiling_pattern() {
+        let pattern = Pattern {
+            kind: PatternKind::Grid,
+            color: Color::Rgba(RgbaColor::new(255, 0, 0, 255)),
+            angle: Angle::zero(),
+            spacing: Abs::pt(4.0),
+            thickness: Abs::pt(1.0),
+        };
+        let rect = Geometry::Rect(Size::new(Abs::pt(20.0), Abs::pt(10.0)));
+        let page = shape_page(vec![rect.filled(Paint::Pattern(pattern))]);
+        let pdf = export_pages(vec![page], PdfOptions::default());
+
+        // The pattern is anchored at the page's top-left corner and its tile
+        // holds two centered, perpendicular lines.
+        assert!(pdf.content(0).contains("/Pattern cs\n/Pa0 scn\n"));
+        let patterns = pdf.page_tree().dict("Resources").dict("Pattern");
+        let tile = pdf.get(patterns.get("Pa0").unwrap());
+        let dict = tile.dict();
+        assert_eq!(dict.get("PatternType"), Some("1"));
+        assert_eq!(dict.get("BBox"), Some("[0 0 4 4]"));
+        assert_eq!(dict.get("XStep"), Some("4"));
+        assert_eq!(dict.get("Matrix"), Some("[1 0 0 -1 0 100]"));
+        let data = String::from_utf8(tile.decoded()).unwrap();
+        assert!(data.contains("1 0 0 rg\n0 1.5 4 1 re\n1.5 0 1 4 re\nf"));
+    }
+
+    #[test]
+    fn test_cmyk() {
+        let red = Paint::Solid(Color::Rgba(RgbaColor::new(255, 0, 0, 255)));
+        let gray = Paint::Solid(Color::Luma(LumaColor(51)));
+        let gradient = Paint::Gradient(Gradient {
+            kind: GradientKind::Radial,
+            from: Color::WHITE,
+            to: Color::BLACK,
+        });
+        let square = Geometry::Rect(Size::splat(Abs::pt(5.0)));
+        let page = shape_page(vec![
+            square.clone().filled(red),
+            square.clone().filled(gradient),
+            square.stroked(Stroke { paint: gray, ..Stroke::default() }),
+        ]);
+        let space = |pdf: &Pdf| {
+            let patterns = pdf.page_tree().dict("Resources").dict("Pattern");
+            let pattern = pdf.resolve(patterns.get("Gr0").unwrap());
+            pattern.dict("Shading").get("ColorSpace").unwrap().to_string()
+        };
+
+        // Colors are written with the device CMYK operators.
+        let options = PdfOptions { cmyk: true, ..Default::default() };
+        let pdf = export_pages(vec![page.clone()], options.clone());
+        let content = pdf.content(0);
+        assert_eq!(operands(&content, "k"), [vec![0.0, 1.0, 1.0, 0.0]]);
+        assert_eq!(operands(&content, "K"), [vec![0.0, 0.0, 0.0, 0.8]]);
+        assert!(!content.contains("/srgb cs\n") && !content.contains("/d65gray CS\n"));
+        assert_eq!(space(&pdf), "/DeviceCMYK");
+
+        // PDF/A-2b files stay in RGB, including their gradients.
+        let options = PdfOptions { standard: PdfStandard::A2b, ..options };
+        let pdf = export_pages(vec![page], options);
+        let content = pdf.content(0);
+        assert!(operands(&content, "k").is_empty());
+        assert!(content.contains("/srgb cs\n"));
+        assert_eq!(space(&pdf), "/DeviceRGB");
+    }
+
+    #[test]
+    fn test_opacity() {
+        let translucent = Paint::Solid(Color::Rgba(RgbaColor::new(255, 0, 0, 128)));
+        let square = Geometry::Rect(Size::splat(Abs::pt(5.0)));
+        let page = shape_page(vec![
+            square.clone().filled(translucent),
+            square.filled(Paint::Solid(Color::BLACK)),
+        ]);
+        let pdf = export_pages(vec![page], PdfOptions::default());
+
+        // The opacity is set through a graphics state and reset afterwards.
+        let content = pdf.content(0);
+        assert!(content.find("/Gs0 gs\n").unwrap() < content.find("/Gs1 gs\n").unwrap());
+        assert_eq!(pdf.of_type("ExtGState").count(), 2);
+
+        let states = pdf.page_tree().dict("Resources").dict("ExtGState");
+        let opacity = |name: &str, key: &str| -> f32 {
+            pdf.resolve(states.get(name).unwrap())
+                .get(key)
+                .unwrap()
+                .parse()
+                .unwrap()
+        };
+        assert!((opacity("Gs0", "ca") - 128.0 / 255.0).abs() < 1e-3);
+        assert_eq!(opacity("Gs0", "CA"), 1.0);
+        assert_eq!(opacity("Gs1", "ca"), 1.0);
+        assert_eq!(opacity("Gs1", "CA"), 1.0);
+    }
+
+    #[test]
+    fn test_form_fields() {
+        let field =
+            |name: &str, kind| FormField { name: name.into(), kind, tooltip: None };
+        let text = FormFieldKind::Text { value: "Ada".into(), multiline: true };
+        let checkbox = FormFieldKind::Checkbox { checked: true };
+        let mut page = blank();
+        page.push(
+            Point::splat(Abs::pt(10.0)),
+            FrameItem::Meta(
+                Meta::FormField(field("name", text)),
+                Size::new(Abs::pt(50.0), Abs::pt(10.0)),
+            ),
+        );
+        page.push(
+            Point::new(Abs::pt(10.0), Abs::pt(30.0)),
+            FrameItem::Meta(
+                Meta::FormField(field("agree", checkbox)),
+                Size::splat(Abs::pt(10.0)),
+            ),
+        );
+
+        let pdf = export_pages(vec![page.clone()], PdfOptions::default());
+        let form = pdf.catalog().dict("AcroForm");
+        assert_eq!(form.get("NeedAppearances"), Some("true"));
+        let annots = items(pdf.page(0).get("Annots").unwrap());
+        let widgets: Vec<_> = annots.iter().map(|&annot| pdf.resolve(annot)).collect();
+        assert_eq!(widgets.len(), 2);
+        assert!(widgets.iter().all(|widget| widget.get("Subtype") == Some("/Widget")));
+
+        // The text field covers its region in the PDF coordinate system.
+        let text = widgets[0];
+        assert_eq!(text.get("Rect"), Some("[10 80 60 90]"));
+        assert_eq!(text.get("FT"), Some("/Tx"));
+        assert_eq!(text.get("V"), Some("(Ada)"));
+        assert_eq!(text.get("Ff"), Some("4096"));
+
+        // The checkbox is checked.
+        let checkbox = widgets[1];
+        assert_eq!(checkbox.get("FT"), Some("/Btn"));
+        assert_eq!(checkbox.get("AS"), Some("/Yes"));
+
+        // PDF/A-2b files have no fields.
+        let options = PdfOptions { standard: PdfStandard::A2b, ..Default::default() };
+        let pdf = export_pages(vec![page], options);
+        assert_eq!(pdf.catalog().get("AcroForm"), None);
+        assert_eq!(items(pdf.page(0).get("Annots").unwrap()).len(), 0);
+    }
+
+    #[test]
+    fn test_rotated_link_quad() {
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let pos = Point::splat(Abs::pt(50.0));
+        let rotated = link_page(pos, size, Transform::rotate(Angle::deg(30.0)));
+        let straight = link_page(pos, size, Transform::identity());
+        let pdf = export_pages(vec![rotated, straight], PdfOptions::default());
+
+        // Only the rotated link needs the exact corners.
+        let link = |i| {
+            let annots = items(pdf.page(i).get("Annots").unwrap());
+            assert_eq!(annots.len(), 1);
+            pdf.resolve(annots[0])
+        };
+        for i in 0..2 {
+            assert_eq!(link(i).get("Subtype"), Some("/Link"));
+            assert_eq!(link(i).dict("A").get("URI"), Some("(https://typst.app)"));
+        }
+        assert!(link(0).get("QuadPoints").is_some());
+        assert_eq!(link(1).get("QuadPoints"), None);
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let mut page = shape_page(vec![
+            line(Stroke::default()),
+            line(Stroke::default()),
+            line(Stroke::default()),
+        ]);
+        let dest = Destination::Url("https://typst.app".into());
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        page.push(Point::zero(), FrameItem::Meta(Meta::Link(dest), size));
+
+        // Metadata doesn't count towards the threshold.
+        let rasterize = |threshold| Rasterize { threshold, dpi: 144 };
+        assert!(rasterize(2).applies(&page));
+        assert!(!rasterize(3).applies(&page));
+        let links = |pdf: &Pdf| items(pdf.page(0).get("Annots").unwrap()).len();
+
+        // A simple page is drawn as usual.
+        let options = PdfOptions {
+            rasterize: Some(rasterize(3)),
+            ..Default::default()
+        };
+        let pdf = export_pages(vec![page.clone()], options);
+        assert_eq!(pdf.content(0).matches(" l\n").count(), 3);
+        assert_eq!(pdf.of_type("XObject").count(), 0);
+        assert_eq!(links(&pdf), 1);
+
+        // A complex page is drawn as an image at the chosen resolution, but
+        // keeps its link.
+        let options = PdfOptions {
+            rasterize: Some(rasterize(2)),
+            ..Default::default()
+        };
+        let pdf = export_pages(vec![page], options);
+        let content = pdf.content(0);
+        assert!(!content.contains(" l\n"));
+        assert!(content.contains("100 0 0 -100 0 100 cm\n"));
+        let images = pdf.page_tree().dict("Resources").dict("XObject");
+        let image = pdf.resolve(images.get("Im0").unwrap());
+        assert_eq!(image.get("Subtype"), Some("/Image"));
+        assert_eq!(image.get("Width"), Some("200"));
+        assert_eq!(image.get("Height"), Some("200"));
+        assert_eq!(links(&pdf), 1);
+    }
 }
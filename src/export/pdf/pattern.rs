@@ -0,0 +1,79 @@
+use pdf_writer::{Content, Finish, Name};
+
+use super::page::write_path;
+use super::{AbsExt, PdfContext, RefExt};
+use crate::geom::{Color, Geometry, Pattern, Transform};
+
+/// A pattern placed on a page.
+///
+/// Like gradients, PDF patterns are positioned relative to the page instead
+/// of the current transformation matrix, so each placement needs its own
+/// pattern.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfPattern {
+    /// The pattern itself.
+    pub pattern: Pattern,
+    /// Maps the pattern's tile space to the page.
+    pub transform: Transform,
+}
+
+/// Write all used patterns as colored tiling patterns.
+pub fn write_patterns(ctx: &mut PdfContext) {
+    for pdf_pattern in ctx.pattern_map.items() {
+        let pattern_ref = ctx.alloc.bump();
+        ctx.pattern_refs.push(pattern_ref);
+
+        let PdfPattern { pattern, transform } = pdf_pattern;
+        let data = encode_tile(pattern);
+        let size = pattern.spacing.to_f32();
+        let Transform { sx, ky, kx, sy, tx, ty } = *transform;
+
+        let mut stream = ctx.writer.stream(pattern_ref, &data);
+        stream.pair(Name(b"Type"), Name(b"Pattern"));
+        stream.pair(Name(b"PatternType"), 1);
+        stream.pair(Name(b"PaintType"), 1);
+        stream.pair(Name(b"TilingType"), 1);
+        stream.insert(Name(b"BBox")).array().items([0.0, 0.0, size, size]);
+        stream.pair(Name(b"XStep"), size);
+        stream.pair(Name(b"YStep"), size);
+        stream.insert(Name(b"Resources")).dict();
+        stream.insert(Name(b"Matrix")).array().items([
+            sx.get() as f32,
+            ky.get() as f32,
+            kx.get() as f32,
+            sy.get() as f32,
+            tx.to_f32(),
+            ty.to_f32(),
+        ]);
+        stream.finish();
+    }
+}
+
+/// Encode the content stream of a pattern's tile.
+fn encode_tile(pattern: &Pattern) -> Vec<u8> {
+    let mut content = Content::new();
+
+    // The tile is written with device colors as it has no resources of its
+    // own. The alpha channel is dropped.
+    let f = |c| c as f32 / 255.0;
+    match pattern.color {
+        Color::Luma(c) => content.set_fill_gray(f(c.0)),
+        Color::Rgba(c) => content.set_fill_rgb(f(c.r), f(c.g), f(c.b)),
+        Color::Cmyk(c) => content.set_fill_cmyk(f(c.c), f(c.m), f(c.y), f(c.k)),
+    };
+
+    for (pos, geometry) in pattern.tile() {
+        let x = pos.x.to_f32();
+        let y = pos.y.to_f32();
+        match geometry {
+            Geometry::Rect(size) => {
+                content.rect(x, y, size.x.to_f32(), size.y.to_f32());
+            }
+            Geometry::Path(path) => write_path(&mut content, x, y, &path),
+            Geometry::Line(_) => {}
+        }
+    }
+
+    content.fill_nonzero();
+    content.finish()
+}
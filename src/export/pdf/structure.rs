@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ecow::{eco_format, EcoString};
+use pdf_writer::{Finish, Name, Ref, TextStr};
+
+use super::{PdfContext, RefExt};
+use crate::doc::{Role, Tag};
+use crate::model::Location;
+
+/// The logical structure of a document, built while writing the pages.
+pub struct StructTree {
+    /// All structure elements. The first one is the document itself.
+    nodes: Vec<StructNode>,
+    /// The structure element of each tagged semantic element.
+    located: HashMap<Location, usize>,
+    /// For each page, the structure element of each marked-content sequence,
+    /// indexed by its marked-content identifier.
+    pages: Vec<Vec<usize>>,
+    /// The structure element of each annotation within the tree, indexed by
+    /// its position among the annotations.
+    annotations: Vec<usize>,
+}
+
+impl Default for StructTree {
+    fn default() -> Self {
+        Self {
+            nodes: vec![StructNode { role: None, order: 0, alt: None, kids: vec![] }],
+            located: HashMap::new(),
+            pages: vec![],
+            annotations: vec![],
+        }
+    }
+}
+
+/// A structure element.
+struct StructNode {
+    /// The element's role. Only the document root has none.
+    role: Option<Role>,
+    /// Where the element is read among its siblings.
+    order: i64,
+    /// A textual alternative for the element.
+    alt: Option<EcoString>,
+    /// The element's kids in document order.
+    kids: Vec<StructKid>,
+}
+
+/// A kid of a structure element.
+enum StructKid {
+    /// Another structure element.
+    Node(usize),
+    /// A marked-content sequence on a page.
+    Content { page: usize, mcid: i32 },
+    /// An annotation on a page.
+    Annotation { page: usize, annot: Ref },
+}
+
+impl StructTree {
+    /// The index of the document root.
+    pub const ROOT: usize = 0;
+
+    /// Open the structure element for a tagged group within `parent`,
+    /// returning its index.
+    ///
+    /// Parts of an element reuse the element's existing structure element.
+    /// Paragraphs directly within headings and links are merged into them as
+    /// these may not contain paragraphs.
+    pub fn open(&mut self, parent: usize, tag: &Tag) -> usize {
+        if let Some(&node) = self.located.get(&tag.location) {
+            return node;
+        }
+
+        if tag.role == Role::Paragraph
+            && matches!(
+                self.nodes[parent].role,
+                Some(Role::Heading(_) | Role::Paragraph | Role::Link)
+            )
+        {
+            return parent;
+        }
+
+        let node = self.nodes.len();
+        self.nodes.push(StructNode {
+            role: Some(tag.role),
+            order: tag.order,
+            alt: tag.alt.clone(),
+            kids: vec![],
+        });
+        self.nodes[parent].kids.push(StructKid::Node(node));
+        self.located.insert(tag.location, node);
+        node
+    }
+
+    /// Allocate a marked-content sequence on a page for a structure element,
+    /// returning its marked-content identifier.
+    pub fn mark(&mut self, page: usize, node: usize) -> i32 {
+        if self.pages.len() <= page {
+            self.pages.resize(page + 1, vec![]);
+        }
+
+        let mcid = self.pages[page].len() as i32;
+        self.pages[page].push(node);
+        self.nodes[node].kids.push(StructKid::Content { page, mcid });
+        mcid
+    }
+
+    /// Add an annotation on a page to a structure element, returning its
+    /// position among the annotations.
+    pub fn annotate(&mut self, node: usize, page: usize, annot: Ref) -> usize {
+        self.nodes[node].kids.push(StructKid::Annotation { page, annot });
+        self.annotations.push(node);
+        self.annotations.len() - 1
+    }
+
+    /// The tag with which a structure element's content is marked.
+    pub fn name(&self, node: usize) -> EcoString {
+        self.nodes[node].role.map_or("Span".into(), role_name)
+    }
+
+    /// Whether a page contains marked content.
+    pub fn has_content(&self, page: usize) -> bool {
+        self.pages.get(page).map_or(false, |marks| !marks.is_empty())
+    }
+
+    /// Whether the tree contains any marked content at all.
+    pub fn is_empty(&self) -> bool {
+        self.pages.iter().all(Vec::is_empty) && self.annotations.is_empty()
+    }
+
+    /// Bring the kids of all structure elements into reading order.
+    ///
+    /// Marked content is read in document order, just like elements without
+    /// an explicit position.
+    fn sort(&mut self) {
+        let orders: Vec<i64> = self.nodes.iter().map(|node| node.order).collect();
+        for node in &mut self.nodes {
+            node.kids.sort_by_key(|kid| match *kid {
+                StructKid::Node(child) => orders[child],
+                StructKid::Content { .. } | StructKid::Annotation { .. } => 0,
+            });
+        }
+    }
+}
+
+/// Write the structure tree, returning the reference of its root.
+pub fn write_structure(ctx: &mut PdfContext) -> Option<Ref> {
+    let mut tree = std::mem::take(&mut ctx.structure);
+    if tree.is_empty() {
+        return None;
+    }
+
+    tree.sort();
+
+    let root_ref = ctx.alloc.bump();
+    let parent_tree_ref = ctx.alloc.bump();
+    let refs: Vec<Ref> = tree.nodes.iter().map(|_| ctx.alloc.bump()).collect();
+
+    // Structure elements reference their parent, so collect those first.
+    let mut parents = vec![root_ref; tree.nodes.len()];
+    for (i, node) in tree.nodes.iter().enumerate() {
+        for kid in &node.kids {
+            if let StructKid::Node(child) = kid {
+                parents[*child] = refs[i];
+            }
+        }
+    }
+
+    for (i, node) in tree.nodes.iter().enumerate() {
+        let mut elem = ctx.writer.indirect(refs[i]).dict();
+        elem.pair(Name(b"Type"), Name(b"StructElem"));
+        let role = node.role.map_or("Document".into(), role_name);
+        elem.pair(Name(b"S"), Name(role.as_bytes()));
+        elem.pair(Name(b"P"), parents[i]);
+        if let Some(alt) = &node.alt {
+            elem.pair(Name(b"Alt"), TextStr(alt));
+        }
+
+        let mut kids = elem.insert(Name(b"K")).array();
+        for kid in &node.kids {
+            match *kid {
+                StructKid::Node(child) => {
+                    kids.item(refs[child]);
+                }
+                StructKid::Content { page, mcid } => {
+                    let mut mcr = kids.push().dict();
+                    mcr.pair(Name(b"Type"), Name(b"MCR"));
+                    mcr.pair(Name(b"Pg"), ctx.page_refs[page]);
+                    mcr.pair(Name(b"MCID"), mcid);
+                }
+                StructKid::Annotation { page, annot } => {
+                    let mut objr = kids.push().dict();
+                    objr.pair(Name(b"Type"), Name(b"OBJR"));
+                    objr.pair(Name(b"Pg"), ctx.page_refs[page]);
+                    objr.pair(Name(b"Obj"), annot);
+                }
+            }
+        }
+    }
+
+    // Map each page's marked-content identifiers back to their elements,
+    // followed by the annotations, whose keys start after those of the pages.
+    let offset = ctx.page_refs.len();
+    let mut parent_tree = ctx.writer.indirect(parent_tree_ref).dict();
+    let mut nums = parent_tree.insert(Name(b"Nums")).array();
+    for (page, marks) in tree.pages.iter().enumerate() {
+        if !marks.is_empty() {
+            nums.item(page as i32);
+            nums.push().array().items(marks.iter().map(|&node| refs[node]));
+        }
+    }
+    for (i, &node) in tree.annotations.iter().enumerate() {
+        nums.item((offset + i) as i32);
+        nums.item(refs[node]);
+    }
+    nums.finish();
+    parent_tree.finish();
+
+    // Map roles without a standard structure type to one.
+    let role_map: BTreeMap<EcoString, &str> = tree
+        .nodes
+        .iter()
+        .filter_map(|node| node.role)
+        .filter_map(|role| Some((role_name(role), standard_role(role)?)))
+        .collect();
+
+    let mut root = ctx.writer.indirect(root_ref).dict();
+    root.pair(Name(b"Type"), Name(b"StructTreeRoot"));
+    root.pair(Name(b"K"), refs[StructTree::ROOT]);
+    root.pair(Name(b"ParentTree"), parent_tree_ref);
+    root.pair(Name(b"ParentTreeNextKey"), (offset + tree.annotations.len()) as i32);
+    if !role_map.is_empty() {
+        let mut map = root.insert(Name(b"RoleMap")).dict();
+        for (name, standard) in &role_map {
+            map.pair(Name(name.as_bytes()), Name(standard.as_bytes()));
+        }
+    }
+    root.finish();
+
+    Some(root_ref)
+}
+
+/// The structure type of a role.
+///
+/// Headings below the sixth level have no standard structure type. They get
+/// their own, which the role map maps to the standard one of the sixth level.
+fn role_name(role: Role) -> EcoString {
+    match role {
+        Role::Paragraph => "P".into(),
+        Role::Heading(level) => eco_format!("H{level}"),
+        Role::List => "L".into(),
+        Role::ListItem => "LI".into(),
+        Role::Figure => "Figure".into(),
+        Role::Link => "Link".into(),
+        Role::Section => "Sect".into(),
+    }
+}
+
+/// The standard structure type to which a role's own one is mapped, if that
+/// isn't standard.
+fn standard_role(role: Role) -> Option<&'static str> {
+    match role {
+        Role::Heading(level) if level.get() > 6 => Some("H6"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::super::tests::{blank, export_pages, items, Dict, Pdf};
+    use super::*;
+    use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta};
+    use crate::export::PdfOptions;
+    use crate::geom::{Abs, Color, Geometry, Paint, Point, Size};
+    use crate::model::StabilityProvider;
+    use crate::syntax::Span;
+
+    /// Export the pages with a structure tree.
+    fn tagged(pages: Vec<Frame>) -> Pdf {
+        export_pages(pages, PdfOptions { tagged: true, ..Default::default() })
+    }
+
+    /// A group with a filled square, marked with the tag.
+    fn square(tag: Tag) -> FrameItem {
+        let size = Size::splat(Abs::pt(10.0));
+        let shape = Geometry::Rect(size).filled(Paint::Solid(Color::BLACK));
+        let mut frame = Frame::new(size);
+        frame.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        FrameItem::Group(GroupItem { tag: Some(tag), ..GroupItem::new(frame) })
+    }
+
+    /// The structure elements among the kids of a structure element.
+    fn kids<'a>(pdf: &'a Pdf, elem: Dict<'a>) -> Vec<Dict<'a>> {
+        items(elem.get("K").unwrap())
+            .into_iter()
+            .filter(|kid| kid.ends_with(" R"))
+            .map(|kid| pdf.resolve(kid))
+            .collect()
+    }
+
+    #[test]
+    fn test_untagged_by_default() {
+        let mut provider = StabilityProvider::new();
+        let mut page = blank();
+        page.push(Point::zero(), square(Tag::new(Role::Paragraph, provider.locate(0))));
+
+        let pdf = export_pages(vec![page], PdfOptions::default());
+        assert_eq!(pdf.catalog().get("StructTreeRoot"), None);
+        assert_eq!(pdf.catalog().get("MarkInfo"), None);
+        assert_eq!(pdf.page(0).get("StructParents"), None);
+    }
+
+    #[test]
+    fn test_reading_order() {
+        let mut provider = StabilityProvider::new();
+        let mut page = blank();
+        for (i, (role, order)) in
+            [(Role::Paragraph, 0), (Role::Section, -1)].into_iter().enumerate()
+        {
+            let tag = Tag::new(role, provider.locate(i as u128)).with_order(order);
+            page.push(Point::with_y(Abs::pt(20.0 * i as f64)), square(tag));
+        }
+
+        let pdf = tagged(vec![page]);
+
+        // The section comes first in the document's kids although it comes
+        // last on the page.
+        let root = pdf.resolve(pdf.catalog().get("StructTreeRoot").unwrap());
+        let document = pdf.resolve(root.get("K").unwrap());
+        assert_eq!(document.get("S"), Some("/Document"));
+        let roles: Vec<_> =
+            kids(&pdf, document).iter().map(|kid| kid.get("S").unwrap()).collect();
+        assert_eq!(roles, ["/Sect", "/P"]);
+    }
+
+    #[test]
+    fn test_links_figures_and_roles() {
+        let mut provider = StabilityProvider::new();
+        let size = Size::splat(Abs::pt(10.0));
+        let shape = Geometry::Rect(size).filled(Paint::Solid(Color::BLACK));
+        let dest = Destination::Url("https://typst.app".into());
+        let level = NonZeroUsize::new(7).unwrap();
+
+        // A linked heading.
+        let mut heading = Frame::new(size);
+        heading.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        heading.push(Point::zero(), FrameItem::Meta(Meta::Link(dest), size));
+        heading.tag(Tag::new(Role::Link, provider.locate(0)));
+        heading.tag(Tag::new(Role::Heading(level), provider.locate(1)));
+
+        let figure =
+            Tag::new(Role::Figure, provider.locate(2)).with_alt(Some("A square".into()));
+
+        let mut page = blank();
+        page.push_frame(Point::zero(), heading);
+        page.push(Point::with_y(Abs::pt(20.0)), square(figure));
+
+        let pdf = tagged(vec![page]);
+        let root = pdf.resolve(pdf.catalog().get("StructTreeRoot").unwrap());
+        let document = pdf.resolve(root.get("K").unwrap());
+        let [heading, figure] = kids(&pdf, document)[..] else { panic!() };
+        let [link] = kids(&pdf, heading)[..] else { panic!() };
+        assert_eq!(link.get("S"), Some("/Link"));
+
+        // The link annotation is a kid of the link's structure element and
+        // refers back to it with a key after the page's one.
+        let annot = items(pdf.page(0).get("Annots").unwrap())[0];
+        let objr = items(link.get("K").unwrap())
+            .into_iter()
+            .map(Dict)
+            .find(|kid| kid.get("Type") == Some("/OBJR"))
+            .unwrap();
+        assert_eq!(objr.get("Obj"), Some(annot));
+        assert_eq!(pdf.resolve(annot).get("StructParent"), Some("1"));
+        assert_eq!(root.get("ParentTreeNextKey"), Some("2"));
+
+        // The figure is described and the custom heading type is mapped.
+        assert_eq!(figure.get("S"), Some("/Figure"));
+        assert_eq!(figure.get("Alt"), Some("(A square)"));
+        assert_eq!(heading.get("S"), Some("/H7"));
+        assert_eq!(root.dict("RoleMap").get("H7"), Some("/H6"));
+    }
+}
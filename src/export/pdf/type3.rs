@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+
+use ecow::{eco_format, EcoString};
+use pdf_writer::{Filter, Finish, Name, Rect};
+use ttf_parser::GlyphId;
+
+use super::font::cluster_cmap;
+use super::{deflate, Compression, EmExt, PdfContext, RefExt};
+use crate::doc::{Frame, FrameItem, Glyph, Lang, TextItem};
+use crate::font::Font;
+use crate::geom::{Abs, Color, Em, Paint, Point, RgbaColor, Size};
+use crate::image::{Image, ImageFormat, RasterFormat};
+use crate::syntax::Span;
+
+/// The font size at which glyphs are rasterized.
+const RASTER_SIZE: f64 = 100.0;
+
+/// The resolution at which glyphs are rasterized.
+const PX_PER_PT: f32 = 2.0;
+
+/// A synthesized Type3 font, which draws glyphs that only exist as SVG
+/// documents or bitmaps as images.
+///
+/// A Type3 font can hold at most 256 glyphs, so a face may need several.
+pub struct Type3Font {
+    font: Font,
+    glyphs: Vec<Type3Glyph>,
+}
+
+/// A glyph in a Type3 font.
+struct Type3Glyph {
+    /// The text the glyph represents.
+    text: EcoString,
+    /// The rasterized glyph.
+    image: Image,
+    /// The advance of the glyph in thousandths of an em.
+    advance: f32,
+    /// Where the image is placed in thousandths of an em, relative to the
+    /// glyph's origin on the baseline.
+    bbox: Rect,
+}
+
+/// Whether a glyph is drawn from an SVG document or a bitmap strike.
+///
+/// Neither can be embedded as part of a regular font, so such glyphs are
+/// drawn through Type3 fonts instead. Like in the renderer, this takes
+/// precedence over the glyph's outline.
+pub fn is_image_glyph(font: &Font, id: u16) -> bool {
+    let ttf = font.ttf();
+    ttf.glyph_svg_image(GlyphId(id)).is_some()
+        || ttf.glyph_raster_image(GlyphId(id), u16::MAX).is_some()
+}
+
+/// Find the Type3 font and character code with which to draw a glyph.
+///
+/// The glyph is rasterized and added to a Type3 font when it is first used,
+/// together with the text of the cluster it is first used for. Returns `None`
+/// if it can't be rasterized.
+pub fn glyph_code(
+    ctx: &mut PdfContext,
+    font: &Font,
+    id: u16,
+    text: &str,
+) -> Option<(usize, u8)> {
+    let key = (font.clone(), id);
+    if let Some(&code) = ctx.type3_glyphs.get(&key) {
+        return code;
+    }
+
+    let code = rasterize(font, id).map(|(image, bbox)| {
+        ctx.image_map.insert(image.clone());
+
+        // Add the glyph to the face's last Type3 font, unless it is full.
+        let index = match ctx.type3_fonts.last() {
+            Some(last) if last.font == *font && last.glyphs.len() < 256 => {
+                ctx.type3_fonts.len() - 1
+            }
+            _ => {
+                ctx.type3_fonts.push(Type3Font { font: font.clone(), glyphs: vec![] });
+                ctx.type3_fonts.len() - 1
+            }
+        };
+
+        let advance = font.advance(id).unwrap_or_default().to_font_units();
+        let glyphs = &mut ctx.type3_fonts[index].glyphs;
+        glyphs.push(Type3Glyph { text: text.into(), image, advance, bbox });
+        (index, (glyphs.len() - 1) as u8)
+    });
+
+    ctx.type3_glyphs.insert(key, code);
+    code
+}
+
+/// Rasterize a glyph within the bounding box of its font.
+///
+/// Returns the image and where it is placed relative to the glyph's origin,
+/// in thousandths of an em.
+fn rasterize(font: &Font, id: u16) -> Option<(Image, Rect)> {
+    let global = font.ttf().global_bounding_box();
+    let x_min = font.to_em(global.x_min);
+    let y_min = font.to_em(global.y_min);
+    let x_max = font.to_em(global.x_max);
+    let y_max = font.to_em(global.y_max);
+    if x_max <= x_min || y_max <= y_min {
+        return None;
+    }
+
+    let size = Abs::pt(RASTER_SIZE);
+    let text = TextItem {
+        font: font.clone(),
+        size,
+        fill: Paint::Solid(Color::BLACK),
+        lang: Lang::ENGLISH,
+        text: EcoString::new(),
+        glyphs: vec![Glyph {
+            id,
+            x_advance: font.advance(id).unwrap_or_default(),
+            x_offset: Em::zero(),
+            c: ' ',
+            range: 0..0,
+            span: Span::detached(),
+            offset: 0,
+        }],
+    };
+
+    let mut frame =
+        Frame::new(Size::new((x_max - x_min).at(size), (y_max - y_min).at(size)));
+    frame.push(Point::new(-x_min.at(size), y_max.at(size)), FrameItem::Text(text));
+
+    let transparent = Color::Rgba(RgbaColor::new(0, 0, 0, 0));
+    let pixmap = crate::export::render(&frame, PX_PER_PT, transparent);
+    let png = pixmap.encode_png().ok()?;
+    let image = Image::new(png.into(), ImageFormat::Raster(RasterFormat::Png)).ok()?;
+    let bbox = Rect::new(
+        x_min.to_font_units(),
+        y_min.to_font_units(),
+        x_max.to_font_units(),
+        y_max.to_font_units(),
+    );
+
+    Some((image, bbox))
+}
+
+/// Embed all synthesized Type3 fonts into the PDF.
+pub fn write_type3_fonts(ctx: &mut PdfContext) {
+    let compression = ctx.options.compression;
+    for type3 in std::mem::take(&mut ctx.type3_fonts) {
+        let font_ref = ctx.alloc.bump();
+        let cmap_ref = ctx.alloc.bump();
+        ctx.type3_refs.push(font_ref);
+
+        // Write a glyph procedure per glyph that draws its image.
+        let mut procs = vec![];
+        let mut images = vec![];
+        for glyph in &type3.glyphs {
+            let index = ctx.image_map.map(glyph.image.clone());
+            let Rect { x1, y1, x2, y2 } = glyph.bbox;
+            let data = format!(
+                "{} 0 d0\nq\n{} 0 0 {} {} {} cm\n/Im{} Do\nQ\n",
+                glyph.advance,
+                x2 - x1,
+                y2 - y1,
+                x1,
+                y1,
+                index,
+            );
+
+            let data = match compression {
+                Compression::None => data.into_bytes(),
+                _ => deflate(data.as_bytes(), compression.level()),
+            };
+
+            let proc_ref = ctx.alloc.bump();
+            let mut stream = ctx.writer.stream(proc_ref, &data);
+            if compression != Compression::None {
+                stream.filter(Filter::FlateDecode);
+            }
+            stream.finish();
+
+            procs.push(proc_ref);
+            images.push((index, ctx.image_refs[index]));
+        }
+
+        // The bounding box of all glyphs.
+        let bbox = type3
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.bbox)
+            .reduce(|a, b| {
+                Rect::new(a.x1.min(b.x1), a.y1.min(b.y1), a.x2.max(b.x2), a.y2.max(b.y2))
+            })
+            .unwrap_or(Rect::new(0.0, 0.0, 0.0, 0.0));
+
+        let mut dict = ctx.writer.indirect(font_ref).dict();
+        dict.pair(Name(b"Type"), Name(b"Font"));
+        dict.pair(Name(b"Subtype"), Name(b"Type3"));
+        dict.pair(Name(b"FontBBox"), bbox);
+        dict.insert(Name(b"FontMatrix"))
+            .array()
+            .items([0.001f32, 0.0, 0.0, 0.001, 0.0, 0.0]);
+
+        let names: Vec<_> =
+            (0..type3.glyphs.len()).map(|i| eco_format!("g{i}")).collect();
+        let mut char_procs = dict.insert(Name(b"CharProcs")).dict();
+        for (name, &proc_ref) in names.iter().zip(&procs) {
+            char_procs.pair(Name(name.as_bytes()), proc_ref);
+        }
+        char_procs.finish();
+
+        let mut encoding = dict.insert(Name(b"Encoding")).dict();
+        encoding.pair(Name(b"Type"), Name(b"Encoding"));
+        let mut differences = encoding.insert(Name(b"Differences")).array();
+        differences.item(0);
+        differences.items(names.iter().map(|name| Name(name.as_bytes())));
+        differences.finish();
+        encoding.finish();
+
+        dict.pair(Name(b"FirstChar"), 0);
+        dict.pair(Name(b"LastChar"), type3.glyphs.len() as i32 - 1);
+        dict.insert(Name(b"Widths"))
+            .array()
+            .items(type3.glyphs.iter().map(|glyph| glyph.advance));
+
+        let mut resources = dict.insert(Name(b"Resources")).dict();
+        let mut x_objects = resources.insert(Name(b"XObject")).dict();
+        for (index, image_ref) in images {
+            let name = eco_format!("Im{}", index);
+            x_objects.pair(Name(name.as_bytes()), image_ref);
+        }
+        x_objects.finish();
+        resources.finish();
+
+        dict.pair(Name(b"ToUnicode"), cmap_ref);
+        dict.finish();
+
+        // Map the character codes back to the clusters the glyphs were first
+        // used for. An image glyph often stands for a whole sequence of
+        // characters, like an emoji with modifiers.
+        let mapping: BTreeMap<u8, &str> = type3
+            .glyphs
+            .iter()
+            .enumerate()
+            .map(|(code, glyph)| (code as u8, glyph.text.as_str()))
+            .collect();
+
+        let cmap = match compression {
+            Compression::None => cluster_cmap(&mapping),
+            _ => deflate(&cluster_cmap(&mapping), compression.level()),
+        };
+
+        let mut stream = ctx.writer.cmap(cmap_ref, &cmap);
+        if compression != Compression::None {
+            stream.filter(Filter::FlateDecode);
+        }
+    }
+}
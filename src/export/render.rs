@@ -11,9 +11,10 @@ use usvg::FitTo;
 
 use crate::doc::{Frame, FrameItem, GroupItem, Meta, TextItem};
 use crate::geom::{
-    self, Abs, Color, Geometry, Paint, PathItem, Shape, Size, Stroke, Transform,
+    self, Abs, Color, Geometry, Gradient, GradientKind, LineCap, LineJoin, Paint,
+    PathItem, Pattern, Point, Shape, Size, Transform,
 };
-use crate::image::{DecodedImage, Image};
+use crate::image::{DecodedImage, Image, ImageFormat, RasterFormat, VectorFormat};
 
 /// Export a frame into a raster image.
 ///
@@ -62,6 +63,13 @@ fn render_frame(
                 Meta::Link(_) => {}
                 Meta::Elem(_) => {}
                 Meta::Hide => {}
+                Meta::Artifact => {}
+                Meta::Placed(_) => {}
+                Meta::Bleed(_) => {}
+                Meta::Rotation(_) => {}
+                Meta::Tag(_) => {}
+                Meta::PageLabel(_) => {}
+                Meta::FormField(_) => {}
             },
         }
     }
@@ -79,13 +87,16 @@ fn render_group(
     let mut mask = mask;
     let mut storage;
     if group.clips {
-        let size = group.frame.size();
-        let w = size.x.to_f32();
-        let h = size.y.to_f32();
-        if let Some(path) = sk::Rect::from_xywh(0.0, 0.0, w, h)
-            .map(sk::PathBuilder::from_rect)
-            .and_then(|path| path.transform(ts))
-        {
+        let path = if let Some(path) = &group.clip_path {
+            convert_path(path)
+        } else {
+            let size = group.frame.size();
+            let w = size.x.to_f32();
+            let h = size.y.to_f32();
+            sk::Rect::from_xywh(0.0, 0.0, w, h).map(sk::PathBuilder::from_rect)
+        };
+
+        if let Some(path) = path.and_then(|path| path.transform(ts)) {
             let result = if let Some(mask) = mask {
                 storage = mask.clone();
                 storage.intersect_path(&path, sk::FillRule::default(), false)
@@ -116,17 +127,30 @@ fn render_text(
     mask: Option<&sk::ClipMask>,
     text: &TextItem,
 ) {
+    let width = text.width().to_f32();
     let mut x = 0.0;
     for glyph in &text.glyphs {
         let id = GlyphId(glyph.id);
         let offset = x + glyph.x_offset.at(text.size).to_f32();
         let ts = ts.pre_translate(offset, 0.0);
+        let advance = glyph.x_advance.at(text.size).to_f32();
+
+        // Gradients are sampled once per glyph at its horizontal center and
+        // patterns are approximated by their color.
+        let fill = match text.fill {
+            Paint::Solid(color) => color,
+            Paint::Gradient(gradient) => {
+                let center = if width > 0.0 { (x + advance / 2.0) / width } else { 0.5 };
+                gradient.sample_at(center as f64, 0.5)
+            }
+            Paint::Pattern(pattern) => pattern.color,
+        };
 
         render_svg_glyph(canvas, ts, mask, text, id)
             .or_else(|| render_bitmap_glyph(canvas, ts, mask, text, id))
-            .or_else(|| render_outline_glyph(canvas, ts, mask, text, id));
+            .or_else(|| render_outline_glyph(canvas, ts, mask, text, fill, id));
 
-        x += glyph.x_advance.at(text.size).to_f32();
+        x += advance;
     }
 }
 
@@ -209,6 +233,7 @@ fn render_outline_glyph(
     ts: sk::Transform,
     mask: Option<&sk::ClipMask>,
     text: &TextItem,
+    fill: Color,
     id: GlyphId,
 ) -> Option<()> {
     let ppem = text.size.to_f32() * ts.sy;
@@ -223,7 +248,7 @@ fn render_outline_glyph(
             builder.0.finish()?
         };
 
-        let paint = text.fill.into();
+        let paint = solid_paint(fill);
         let rule = sk::FillRule::default();
 
         // Flip vertically because font design coordinate
@@ -251,8 +276,7 @@ fn render_outline_glyph(
     let bottom = top + mh;
 
     // Premultiply the text color.
-    let Paint::Solid(color) = text.fill;
-    let c = color.to_rgba();
+    let c = fill.to_rgba();
     let color = sk::ColorU8::from_rgba(c.r, c.g, c.b, 255).premultiply().get();
 
     // Blend the glyph bitmap with the existing pixels on the canvas.
@@ -302,8 +326,10 @@ fn render_shape(
         Geometry::Path(ref path) => convert_path(path)?,
     };
 
+    let bbox = shape.geometry.bbox();
     if let Some(fill) = shape.fill {
-        let mut paint: sk::Paint = fill.into();
+        let mut tile = None;
+        let mut paint = convert_paint(fill, bbox, ts, &mut tile);
         if matches!(shape.geometry, Geometry::Rect(_)) {
             paint.anti_alias = false;
         }
@@ -312,9 +338,31 @@ fn render_shape(
         canvas.fill_path(&path, &paint, rule, ts, mask);
     }
 
-    if let Some(Stroke { paint, thickness }) = shape.stroke {
-        let paint = paint.into();
-        let stroke = sk::Stroke { width: thickness.to_f32(), ..Default::default() };
+    if let Some(stroke) = shape.stroke {
+        let mut tile = None;
+        let bbox = shape.geometry.stroke_bbox(stroke.thickness);
+        let paint = convert_paint(stroke.paint, bbox, ts, &mut tile);
+        let line_cap = match stroke.line_cap {
+            LineCap::Butt => sk::LineCap::Butt,
+            LineCap::Round => sk::LineCap::Round,
+            LineCap::Square => sk::LineCap::Square,
+        };
+        let line_join = match stroke.line_join {
+            LineJoin::Miter => sk::LineJoin::Miter,
+            LineJoin::Round => sk::LineJoin::Round,
+            LineJoin::Bevel => sk::LineJoin::Bevel,
+        };
+        let dash = stroke.dash.array(stroke.thickness);
+        let stroke = sk::Stroke {
+            width: stroke.thickness.to_f32(),
+            line_cap,
+            line_join,
+            miter_limit: stroke.miter_limit.0 as f32,
+            dash: sk::StrokeDash::new(
+                dash.into_iter().map(Abs::to_f32).collect(),
+                stroke.dash_phase.to_f32(),
+            ),
+        };
         canvas.stroke_path(&path, &paint, &stroke, ts, mask);
     }
 
@@ -351,6 +399,9 @@ fn convert_path(path: &geom::Path) -> Option<sk::Path> {
 }
 
 /// Render a raster or SVG image into the canvas.
+///
+/// SVG representations are preferred since they are rendered at exactly the
+/// resolution of the canvas.
 fn render_image(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
@@ -358,6 +409,7 @@ fn render_image(
     image: &Image,
     size: Size,
 ) -> Option<()> {
+    let image = image.select(&PREFERRED_FORMATS);
     let view_width = size.x.to_f32();
     let view_height = size.y.to_f32();
 
@@ -387,6 +439,14 @@ fn render_image(
     Some(())
 }
 
+/// The image formats in the order in which they are preferred for rendering.
+const PREFERRED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Vector(VectorFormat::Svg),
+    ImageFormat::Raster(RasterFormat::Png),
+    ImageFormat::Raster(RasterFormat::Gif),
+    ImageFormat::Raster(RasterFormat::Jpg),
+];
+
 /// Prepare a texture for an image at a scaled size.
 #[comemo::memoize]
 fn scaled_texture(image: &Image, w: u32, h: u32) -> Option<Arc<sk::Pixmap>> {
@@ -428,14 +488,101 @@ impl From<Transform> for sk::Transform {
     }
 }
 
-impl From<Paint> for sk::Paint<'static> {
-    fn from(paint: Paint) -> Self {
-        let mut sk_paint = sk::Paint::default();
-        let Paint::Solid(color) = paint;
-        sk_paint.set_color(color.into());
-        sk_paint.anti_alias = true;
-        sk_paint
+/// Convert a Typst paint into a tiny-skia paint.
+///
+/// Gradients stretch across the given bounding box, specified by its top-left
+/// corner and its size. Patterns are rendered into the `tile` at the
+/// resolution of the transform `ts`.
+fn convert_paint<'a>(
+    paint: Paint,
+    (pos, size): (Point, Size),
+    ts: sk::Transform,
+    tile: &'a mut Option<sk::Pixmap>,
+) -> sk::Paint<'a> {
+    let gradient = match paint {
+        Paint::Solid(color) => return solid_paint(color),
+        Paint::Gradient(gradient) => gradient,
+        Paint::Pattern(pattern) => return pattern_paint(pattern, ts, tile),
+    };
+
+    // Map the unit square to the bounding box, keeping the transform
+    // invertible for boxes without extent.
+    let w = size.x.to_f32().max(1.0);
+    let h = size.y.to_f32().max(1.0);
+    let bbox = sk::Transform::from_row(w, 0.0, 0.0, h, pos.x.to_f32(), pos.y.to_f32());
+    let stops = vec![
+        sk::GradientStop::new(0.0, gradient.from.into()),
+        sk::GradientStop::new(1.0, gradient.to.into()),
+    ];
+
+    let shader = match gradient.kind {
+        GradientKind::Linear(angle) => {
+            let [(x0, y0), (x1, y1)] = Gradient::line(angle);
+            sk::LinearGradient::new(
+                sk::Point::from_xy(x0 as f32, y0 as f32),
+                sk::Point::from_xy(x1 as f32, y1 as f32),
+                stops,
+                sk::SpreadMode::Pad,
+                bbox,
+            )
+        }
+        GradientKind::Radial => {
+            let center = sk::Point::from_xy(0.5, 0.5);
+            sk::RadialGradient::new(center, center, 0.5, stops, sk::SpreadMode::Pad, bbox)
+        }
+    };
+
+    // Fall back to the gradient's middle color for degenerate gradients.
+    let mut sk_paint = solid_paint(gradient.sample(0.5));
+    if let Some(shader) = shader {
+        sk_paint.shader = shader;
+    }
+
+    sk_paint
+}
+
+/// Create a paint that repeats a pattern's tile, which is rendered into
+/// `tile` at the resolution of the transform `ts`.
+fn pattern_paint<'a>(
+    pattern: Pattern,
+    ts: sk::Transform,
+    tile: &'a mut Option<sk::Pixmap>,
+) -> sk::Paint<'a> {
+    let size = pattern.spacing.to_f32();
+    let px = (size * ts.sx.hypot(ts.ky)).ceil().max(1.0);
+    let Some(pixmap) = sk::Pixmap::new(px as u32, px as u32) else {
+        return solid_paint(pattern.color);
+    };
+
+    let pixmap = tile.insert(pixmap);
+    let scale = px / size;
+    let tile_ts = sk::Transform::from_scale(scale, scale);
+    for (pos, geometry) in pattern.tile() {
+        let shape = geometry.filled(Paint::Solid(pattern.color));
+        let ts = tile_ts.pre_translate(pos.x.to_f32(), pos.y.to_f32());
+        render_shape(pixmap, ts, None, &shape);
     }
+
+    let pixmap: &'a sk::Pixmap = pixmap;
+
+    let mut sk_paint = solid_paint(pattern.color);
+    sk_paint.shader = sk::Pattern::new(
+        pixmap.as_ref(),
+        sk::SpreadMode::Repeat,
+        sk::FilterQuality::Bilinear,
+        1.0,
+        sk::Transform::from(Transform::rotate(pattern.angle))
+            .pre_scale(1.0 / scale, 1.0 / scale),
+    );
+    sk_paint
+}
+
+/// Create an anti-aliased paint with a single color.
+fn solid_paint(color: Color) -> sk::Paint<'static> {
+    let mut sk_paint = sk::Paint::default();
+    sk_paint.set_color(color.into());
+    sk_paint.anti_alias = true;
+    sk_paint
 }
 
 impl From<Color> for sk::Color {
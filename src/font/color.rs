@@ -0,0 +1,147 @@
+use std::cmp::Ordering;
+
+use ttf_parser::Tag;
+
+use crate::geom::RgbaColor;
+
+/// A layer of a color glyph, as defined by a font's COLR table.
+///
+/// A color glyph is drawn by filling the outlines of its layers on top of
+/// each other, from the first to the last layer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ColorLayer {
+    /// The glyph whose outline makes up the layer.
+    pub glyph: u16,
+    /// The layer's color from the font's first CPAL palette, or `None` if it
+    /// takes the text's color.
+    pub color: Option<RgbaColor>,
+}
+
+/// Look up the layers of a color glyph in a font's COLR table.
+///
+/// Only version 0 of the table, which consists of plain colored layers, is
+/// supported. Returns `None` if the glyph isn't a color glyph.
+pub(super) fn color_layers(
+    ttf: &ttf_parser::Face,
+    glyph: u16,
+) -> Option<Vec<ColorLayer>> {
+    let colr = ttf.raw_face().table(Tag::from_bytes(b"COLR"))?;
+    let cpal = ttf.raw_face().table(Tag::from_bytes(b"CPAL"));
+    parse_layers(colr, cpal, glyph)
+}
+
+/// Parse the layers of a glyph from raw COLR and CPAL tables.
+fn parse_layers(colr: &[u8], cpal: Option<&[u8]>, glyph: u16) -> Option<Vec<ColorLayer>> {
+    let num_base = read_u16(colr, 2)? as usize;
+    let base_offset = read_u32(colr, 4)? as usize;
+    let layer_offset = read_u32(colr, 8)? as usize;
+    let num_layers = read_u16(colr, 12)? as usize;
+
+    // Binary search the base glyph records, which are sorted by glyph id.
+    let (mut lo, mut hi) = (0, num_base);
+    let record = loop {
+        if lo >= hi {
+            return None;
+        }
+
+        let mid = (lo + hi) / 2;
+        let record = base_offset + 6 * mid;
+        match read_u16(colr, record)?.cmp(&glyph) {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+            Ordering::Equal => break record,
+        }
+    };
+
+    let first = read_u16(colr, record + 2)? as usize;
+    let count = read_u16(colr, record + 4)? as usize;
+    if count == 0 || first + count > num_layers {
+        return None;
+    }
+
+    (first..first + count)
+        .map(|i| {
+            let offset = layer_offset + 4 * i;
+            let glyph = read_u16(colr, offset)?;
+            let index = read_u16(colr, offset + 2)?;
+            let color = match index {
+                0xFFFF => None,
+                _ => Some(palette_color(cpal?, index)?),
+            };
+            Some(ColorLayer { glyph, color })
+        })
+        .collect()
+}
+
+/// Look up a color in the first palette of a CPAL table.
+fn palette_color(cpal: &[u8], index: u16) -> Option<RgbaColor> {
+    let num_entries = read_u16(cpal, 2)?;
+    if index >= num_entries {
+        return None;
+    }
+
+    let records = read_u32(cpal, 8)? as usize;
+    let first = read_u16(cpal, 12)? as usize;
+    let offset = records + 4 * (first + index as usize);
+    let bgra = cpal.get(offset..offset + 4)?;
+    Some(RgbaColor::new(bgra[2], bgra[1], bgra[0], bgra[3]))
+}
+
+/// Read a big-endian `u16` at the given offset.
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Read a big-endian `u32` at the given offset.
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_layers() {
+        // Glyph 4 has two layers, glyph 9 one in the text's color.
+        #[rustfmt::skip]
+        let colr = [
+            0, 0, 0, 2, 0, 0, 0, 14, 0, 0, 0, 26, 0, 3,
+            0, 4, 0, 0, 0, 2,
+            0, 9, 0, 2, 0, 1,
+            0, 5, 0, 1,
+            0, 6, 0, 0,
+            0, 7, 0xFF, 0xFF,
+        ];
+
+        // One palette with two colors in BGRA order.
+        #[rustfmt::skip]
+        let cpal = [
+            0, 0, 0, 2, 0, 1, 0, 2, 0, 0, 0, 14, 0, 0,
+            0, 0, 255, 255,
+            255, 0, 0, 128,
+        ];
+
+        assert_eq!(
+            parse_layers(&colr, Some(&cpal), 4),
+            Some(vec![
+                ColorLayer {
+                    glyph: 5,
+                    color: Some(RgbaColor::new(0, 0, 255, 128))
+                },
+                ColorLayer {
+                    glyph: 6,
+                    color: Some(RgbaColor::new(255, 0, 0, 255))
+                },
+            ])
+        );
+        assert_eq!(
+            parse_layers(&colr, None, 9),
+            Some(vec![ColorLayer { glyph: 7, color: None }])
+        );
+        assert_eq!(parse_layers(&colr, Some(&cpal), 5), None);
+        assert_eq!(parse_layers(&colr[..20], Some(&cpal), 9), None);
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::{self, Display, Formatter};
+
+use ttf_parser::{name_id, Permissions};
+
+use super::{find_name, Font};
+
+/// Licensing information embedded in a font.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FontLicense {
+    /// The license description from the font's naming table.
+    pub description: Option<String>,
+    /// The URL of the license from the font's naming table.
+    pub url: Option<String>,
+    /// Whether and how the font may be embedded into documents.
+    pub embedding: Embedding,
+    /// Whether the font may be subsetted before embedding.
+    pub subsetting: bool,
+}
+
+impl FontLicense {
+    /// Extract the licensing information from a font.
+    pub fn from_font(font: &Font) -> Self {
+        let ttf = font.ttf();
+        Self {
+            description: find_name(ttf, name_id::LICENSE),
+            url: find_name(ttf, name_id::LICENSE_URL),
+            embedding: match ttf.permissions() {
+                Some(Permissions::Installable) | None => Embedding::Installable,
+                Some(Permissions::Restricted) => Embedding::Restricted,
+                Some(Permissions::PreviewAndPrint) => Embedding::PreviewAndPrint,
+                Some(Permissions::Editable) => Embedding::Editable,
+            },
+            subsetting: ttf.is_subsetting_allowed(),
+        }
+    }
+}
+
+/// The embedding permissions of a font, as given by the `fsType` field of its
+/// OS/2 table.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Embedding {
+    /// The font may be embedded and permanently installed.
+    Installable,
+    /// The font must not be embedded.
+    Restricted,
+    /// The font may be embedded for viewing and printing only.
+    PreviewAndPrint,
+    /// The font may be embedded for viewing, printing, and editing.
+    Editable,
+}
+
+impl Embedding {
+    /// Whether the font may be embedded into a PDF file at all.
+    pub fn allows_embedding(self) -> bool {
+        self != Self::Restricted
+    }
+}
+
+impl Display for Embedding {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(match self {
+            Self::Installable => "installable",
+            Self::Restricted => "restricted",
+            Self::PreviewAndPrint => "preview & print",
+            Self::Editable => "editable",
+        })
+    }
+}
@@ -1,15 +1,22 @@
 //! Font handling.
 
 mod book;
+mod color;
+mod license;
+mod shape;
 mod variant;
 
 pub use self::book::*;
+pub use self::color::ColorLayer;
+pub use self::license::*;
+pub use self::shape::ShapedInfo;
 pub use self::variant::*;
 
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+use once_cell::sync::OnceCell;
 use ttf_parser::GlyphId;
 
 use crate::eval::Cast;
@@ -32,6 +39,9 @@ struct Repr {
     info: FontInfo,
     /// The font's metrics.
     metrics: FontMetrics,
+    /// The horizontal advances of all glyphs in font units, built on first
+    /// use.
+    advances: OnceCell<Vec<Option<u16>>>,
     /// The underlying ttf-parser face.
     ttf: ttf_parser::Face<'static>,
     /// The underlying rustybuzz face.
@@ -55,7 +65,15 @@ impl Font {
         let metrics = FontMetrics::from_ttf(&ttf);
         let info = FontInfo::from_ttf(&ttf)?;
 
-        Some(Self(Arc::new(Repr { data, index, info, metrics, ttf, rusty })))
+        Some(Self(Arc::new(Repr {
+            data,
+            index,
+            info,
+            metrics,
+            advances: OnceCell::new(),
+            ttf,
+            rusty,
+        })))
     }
 
     /// Parse all fonts in the given data.
@@ -96,9 +114,16 @@ impl Font {
 
     /// Look up the horizontal advance width of a glyph.
     pub fn advance(&self, glyph: u16) -> Option<Em> {
-        self.0
-            .ttf
-            .glyph_hor_advance(GlyphId(glyph))
+        let advances = self.0.advances.get_or_init(|| {
+            (0..self.0.ttf.number_of_glyphs())
+                .map(|id| self.0.ttf.glyph_hor_advance(GlyphId(id)))
+                .collect()
+        });
+
+        advances
+            .get(usize::from(glyph))
+            .copied()
+            .flatten()
             .map(|units| self.to_em(units))
     }
 
@@ -107,6 +132,16 @@ impl Font {
         find_name(&self.0.ttf, id)
     }
 
+    /// The layers of a color glyph, if the glyph has color layers.
+    pub fn color_layers(&self, glyph: u16) -> Option<Vec<ColorLayer>> {
+        color::color_layers(&self.0.ttf, glyph)
+    }
+
+    /// The font's licensing information.
+    pub fn license(&self) -> FontLicense {
+        FontLicense::from_font(self)
+    }
+
     /// A reference to the underlying `ttf-parser` face.
     pub fn ttf(&self) -> &ttf_parser::Face<'_> {
         // We can't implement Deref because that would leak the
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rustybuzz::{Feature, Tag, UnicodeBuffer};
+
+use super::Font;
+use crate::geom::Dir;
+
+/// A glyph resulting from shaping, measured in font units.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ShapedInfo {
+    /// The glyph's index in the font. Zero if the font doesn't contain a glyph
+    /// for the cluster.
+    pub glyph_id: u32,
+    /// The byte index in the shaped text at which the glyph's cluster starts.
+    pub cluster: u32,
+    /// The advance width of the glyph.
+    pub x_advance: i32,
+    /// The horizontal offset of the glyph.
+    pub x_offset: i32,
+    /// The vertical offset of the glyph.
+    pub y_offset: i32,
+    /// Whether breaking the text before this glyph requires reshaping.
+    pub unsafe_to_break: bool,
+}
+
+impl Font {
+    /// Shape text with this font.
+    ///
+    /// The `lang` is a BCP 47 language tag. Results are memoized by font, text,
+    /// direction, language, and features. Because the results are in font
+    /// units, they don't depend on the font size.
+    pub fn shape(
+        &self,
+        text: &str,
+        dir: Dir,
+        lang: &str,
+        features: &[Feature],
+    ) -> Arc<[ShapedInfo]> {
+        let features: Vec<_> = features
+            .iter()
+            .map(|feature| (feature.tag.0, feature.value, feature.start, feature.end))
+            .collect();
+        shape_impl(self, text, dir, lang, &features)
+    }
+}
+
+/// Shape text with rustybuzz.
+///
+/// The features are given as tag, value, start, and end since rustybuzz's
+/// features aren't hashable.
+#[comemo::memoize]
+fn shape_impl(
+    font: &Font,
+    text: &str,
+    dir: Dir,
+    lang: &str,
+    features: &[(u32, u32, u32, u32)],
+) -> Arc<[ShapedInfo]> {
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    if let Ok(language) = rustybuzz::Language::from_str(lang) {
+        buffer.set_language(language);
+    }
+    buffer.set_direction(match dir {
+        Dir::LTR => rustybuzz::Direction::LeftToRight,
+        Dir::RTL => rustybuzz::Direction::RightToLeft,
+        _ => unimplemented!("vertical text layout"),
+    });
+
+    let features: Vec<_> = features
+        .iter()
+        .map(|&(tag, value, start, end)| Feature { tag: Tag(tag), value, start, end })
+        .collect();
+
+    let buffer = rustybuzz::shape(font.rusty(), &features, buffer);
+    buffer
+        .glyph_infos()
+        .iter()
+        .zip(buffer.glyph_positions())
+        .map(|(info, pos)| ShapedInfo {
+            glyph_id: info.glyph_id,
+            cluster: info.cluster,
+            x_advance: pos.x_advance,
+            x_offset: pos.x_offset,
+            y_offset: pos.y_offset,
+            unsafe_to_break: info.unsafe_to_break(),
+        })
+        .collect()
+}
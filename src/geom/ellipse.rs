@@ -2,6 +2,15 @@ use super::*;
 
 /// Produce a shape that approximates an axis-aligned ellipse.
 pub fn ellipse(size: Size, fill: Option<Paint>, stroke: Option<Stroke>) -> Shape {
+    Shape {
+        geometry: Geometry::Path(ellipse_path(size)),
+        stroke,
+        fill,
+    }
+}
+
+/// Produce a path that approximates an axis-aligned ellipse.
+pub fn ellipse_path(size: Size) -> Path {
     // https://stackoverflow.com/a/2007782
     let z = Abs::zero();
     let rx = size.x / 2.0;
@@ -17,6 +26,5 @@ pub fn ellipse(size: Size, fill: Option<Paint>, stroke: Option<Stroke>) -> Shape
     path.cubic_to(point(mx, -ry), point(rx, -my), point(rx, z));
     path.cubic_to(point(rx, my), point(mx, ry), point(z, ry));
     path.cubic_to(point(-mx, ry), point(-rx, my), point(-rx, z));
-
-    Shape { geometry: Geometry::Path(path), stroke, fill }
+    path
 }
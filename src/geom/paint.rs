@@ -7,6 +7,10 @@ use super::*;
 pub enum Paint {
     /// A solid color.
     Solid(Color),
+    /// A gradient between two colors.
+    Gradient(Gradient),
+    /// A repeating pattern.
+    Pattern(Pattern),
 }
 
 impl<T: Into<Color>> From<T> for Paint {
@@ -19,6 +23,8 @@ impl Debug for Paint {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::Solid(color) => color.fmt(f),
+            Self::Gradient(gradient) => gradient.fmt(f),
+            Self::Pattern(pattern) => pattern.fmt(f),
         }
     }
 }
@@ -26,10 +32,163 @@ impl Debug for Paint {
 cast_from_value! {
     Paint,
     color: Color => Self::Solid(color),
+    gradient: Gradient => Self::Gradient(gradient),
+    pattern: Pattern => Self::Pattern(pattern),
 }
 
 cast_to_value! {
-    Paint::Solid(color): Paint => Value::Color(color)
+    v: Paint => match v {
+        Paint::Solid(color) => Value::Color(color),
+        Paint::Gradient(gradient) => gradient.into(),
+        Paint::Pattern(pattern) => pattern.into(),
+    }
+}
+
+/// A smooth transition between two colors across the bounding box of the
+/// painted shape or text.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Gradient {
+    /// How the colors are distributed.
+    pub kind: GradientKind,
+    /// The color at the start of the gradient.
+    pub from: Color,
+    /// The color at the end of the gradient.
+    pub to: Color,
+}
+
+/// How the colors of a gradient are distributed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GradientKind {
+    /// The colors change along a line through the center of the bounding box.
+    /// At zero degrees, the line goes from left to right and it rotates
+    /// clockwise with the angle.
+    Linear(Angle),
+    /// The colors change from the center of the bounding box towards its
+    /// edges.
+    Radial,
+}
+
+impl Gradient {
+    /// The start and end point of a linear gradient with the given angle,
+    /// relative to a unit square. The line is long enough to cover the whole
+    /// square.
+    pub fn line(angle: Angle) -> [(f64, f64); 2] {
+        let (dx, dy) = (angle.cos(), angle.sin());
+        let half = (dx.abs() + dy.abs()) / 2.0;
+        [(0.5 - dx * half, 0.5 - dy * half), (0.5 + dx * half, 0.5 + dy * half)]
+    }
+
+    /// The color at the given position relative to the bounding box, where
+    /// `(0, 0)` is the top-left and `(1, 1)` the bottom-right corner.
+    pub fn sample_at(&self, x: f64, y: f64) -> Color {
+        let t = match self.kind {
+            GradientKind::Linear(angle) => {
+                let [(x0, y0), (x1, y1)] = Self::line(angle);
+                let (dx, dy) = (x1 - x0, y1 - y0);
+                ((x - x0) * dx + (y - y0) * dy) / (dx * dx + dy * dy)
+            }
+            GradientKind::Radial => 2.0 * (x - 0.5).hypot(y - 0.5),
+        };
+        self.sample(t)
+    }
+
+    /// The color at the given fraction of the way from `from` to `to`.
+    pub fn sample(&self, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let (a, b) = (self.from.to_rgba(), self.to.to_rgba());
+        let mix = |a: u8, b: u8| round_u8(a as f64 + (b as f64 - a as f64) * t);
+        Color::Rgba(RgbaColor::new(
+            mix(a.r, b.r),
+            mix(a.g, b.g),
+            mix(a.b, b.b),
+            mix(a.a, b.a),
+        ))
+    }
+}
+
+impl Debug for Gradient {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("gradient(")?;
+        self.from.fmt(f)?;
+        f.write_str(", ")?;
+        self.to.fmt(f)?;
+        match self.kind {
+            GradientKind::Linear(angle) => write!(f, ", angle: {angle:?})"),
+            GradientKind::Radial => f.write_str(", kind: \"radial\")"),
+        }
+    }
+}
+
+cast_from_value! {
+    Gradient: "gradient",
+}
+
+/// A tile of lines or dots that repeats across the painted shape or text.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Pattern {
+    /// What the tile contains.
+    pub kind: PatternKind,
+    /// The color of the lines or dots.
+    pub color: Color,
+    /// How the whole pattern is rotated, clockwise.
+    pub angle: Angle,
+    /// The side length of the square tile, i.e. the distance between two
+    /// lines or dots.
+    pub spacing: Abs,
+    /// The thickness of the lines or the diameter of the dots.
+    pub thickness: Abs,
+}
+
+/// What the tile of a pattern contains.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PatternKind {
+    /// Parallel lines, as in hatching.
+    Lines,
+    /// Two sets of perpendicular lines, as in cross-hatching.
+    Grid,
+    /// A single dot.
+    Dots,
+}
+
+impl Pattern {
+    /// The filled shapes that make up one tile, each with its position within
+    /// the tile. The tile spans from the origin to `spacing` on both axes.
+    pub fn tile(&self) -> Vec<(Point, Geometry)> {
+        let s = self.spacing;
+        let t = self.thickness.min(s);
+        let offset = (s - t) / 2.0;
+        let horizontal = (Point::with_y(offset), Geometry::Rect(Size::new(s, t)));
+        match self.kind {
+            PatternKind::Lines => vec![horizontal],
+            PatternKind::Grid => {
+                let vertical = (Point::with_x(offset), Geometry::Rect(Size::new(t, s)));
+                vec![horizontal, vertical]
+            }
+            PatternKind::Dots => {
+                let dot = Geometry::Path(ellipse_path(Size::splat(t)));
+                vec![(Point::splat(offset), dot)]
+            }
+        }
+    }
+}
+
+impl Debug for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            PatternKind::Lines => "lines",
+            PatternKind::Grid => "grid",
+            PatternKind::Dots => "dots",
+        };
+        write!(
+            f,
+            "pattern({:?}, kind: {kind:?}, angle: {:?}, spacing: {:?}, thickness: {:?})",
+            self.color, self.angle, self.spacing, self.thickness,
+        )
+    }
+}
+
+cast_from_value! {
+    Pattern: "pattern",
 }
 
 /// A color in a dynamic format.
@@ -98,6 +257,31 @@ impl Color {
             Self::Cmyk(cmyk) => Self::Cmyk(cmyk.negate()),
         }
     }
+
+    /// The relative luminance of this color as defined by WCAG, between 0 for
+    /// black and 1 for white.
+    ///
+    /// Translucent colors are composited onto white first.
+    pub fn luminance(self) -> f64 {
+        let RgbaColor { r, g, b, a } = self.to_rgba();
+        let alpha = a as f64 / 255.0;
+        let [r, g, b] = [r, g, b].map(|c| {
+            let c = (c as f64 * alpha + 255.0 * (1.0 - alpha)) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        });
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// The WCAG contrast ratio between this and another color, between 1 and
+    /// 21.
+    pub fn contrast(self, other: Self) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
 }
 
 impl Debug for Color {
@@ -222,6 +406,20 @@ impl RgbaColor {
             a: self.a,
         }
     }
+
+    /// Convert this color to CMYK with full black generation.
+    ///
+    /// The alpha channel is dropped.
+    pub fn to_cmyk(self) -> CmykColor {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f64 / 255.0);
+        let k = 1.0 - r.max(g).max(b);
+        if k >= 1.0 {
+            return CmykColor::new(0, 0, 0, u8::MAX);
+        }
+
+        let f = |c: f64| round_u8(255.0 * (1.0 - c - k) / (1.0 - k));
+        CmykColor::new(f(r), f(g), f(b), round_u8(255.0 * k))
+    }
 }
 
 impl FromStr for RgbaColor {
@@ -412,4 +610,30 @@ mod tests {
         test("hmmm", "color string contains non-hexadecimal letters");
         test("14B2AH", "color string contains non-hexadecimal letters");
     }
+
+    #[test]
+    fn test_convert_rgba_to_cmyk() {
+        let cmyk = |r, g, b| RgbaColor::new(r, g, b, 255).to_cmyk();
+        assert_eq!(cmyk(0, 0, 0), CmykColor::new(0, 0, 0, 255));
+        assert_eq!(cmyk(255, 255, 255), CmykColor::new(0, 0, 0, 0));
+        assert_eq!(cmyk(255, 0, 0), CmykColor::new(0, 255, 255, 0));
+        assert_eq!(cmyk(0, 128, 255).to_rgba(), RgbaColor::new(0, 128, 255, 255));
+    }
+
+    #[test]
+    fn test_sample_gradient() {
+        let linear = Gradient {
+            kind: GradientKind::Linear(Angle::zero()),
+            from: Color::BLACK,
+            to: Color::WHITE,
+        };
+        let gray = RgbaColor::new(128, 128, 128, 255).into();
+        assert_eq!(linear.sample_at(0.0, 0.3), Color::BLACK);
+        assert_eq!(linear.sample_at(0.5, 0.9), gray);
+        assert_eq!(linear.sample_at(1.0, 0.0), Color::WHITE);
+
+        let radial = Gradient { kind: GradientKind::Radial, ..linear };
+        assert_eq!(radial.sample_at(0.5, 0.5), Color::BLACK);
+        assert_eq!(radial.sample_at(0.5, 1.0), Color::WHITE);
+    }
 }
@@ -51,4 +51,29 @@ impl Path {
     pub fn close_path(&mut self) {
         self.0.push(PathItem::ClosePath);
     }
+
+    /// The smallest axis-aligned box containing all points and control
+    /// points of the path, given by its top-left corner and its size.
+    pub fn bbox(&self) -> (Point, Size) {
+        let mut min = Point::splat(Abs::inf());
+        let mut max = Point::splat(-Abs::inf());
+        for item in &self.0 {
+            let points = match *item {
+                PathItem::MoveTo(p) | PathItem::LineTo(p) => vec![p],
+                PathItem::CubicTo(p1, p2, p3) => vec![p1, p2, p3],
+                PathItem::ClosePath => vec![],
+            };
+
+            for p in points {
+                min = min.min(p);
+                max = max.max(p);
+            }
+        }
+
+        if min.x > max.x {
+            return (Point::zero(), Size::zero());
+        }
+
+        (min, (max - min).to_size())
+    }
 }
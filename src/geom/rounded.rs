@@ -29,6 +29,14 @@ pub fn rounded_rect(
     res
 }
 
+/// Produce the outline of a rounded rectangle as a single path.
+pub fn rounded_rect_path(size: Size, radius: Corners<Abs>) -> Path {
+    match fill_geometry(size, radius) {
+        Geometry::Path(path) => path,
+        _ => Path::rect(size),
+    }
+}
+
 /// Output the shape of the rectangle as a path or primitive rectangle,
 /// depending on whether it is rounded.
 fn fill_geometry(size: Size, radius: Corners<Abs>) -> Geometry {
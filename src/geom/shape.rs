@@ -32,4 +32,25 @@ impl Geometry {
     pub fn stroked(self, stroke: Stroke) -> Shape {
         Shape { geometry: self, fill: None, stroke: Some(stroke) }
     }
+
+    /// The smallest axis-aligned box containing the geometry, given by its
+    /// top-left corner and its size.
+    pub fn bbox(&self) -> (Point, Size) {
+        match self {
+            Self::Line(target) => {
+                let min =
+                    Point::new(target.x.min(Abs::zero()), target.y.min(Abs::zero()));
+                (min, Size::new(target.x.abs(), target.y.abs()))
+            }
+            Self::Rect(size) => (Point::zero(), *size),
+            Self::Path(path) => path.bbox(),
+        }
+    }
+
+    /// The box covered by a stroke of the geometry with the given thickness,
+    /// which extends half of it beyond the geometry on each side.
+    pub fn stroke_bbox(&self, thickness: Abs) -> (Point, Size) {
+        let (pos, size) = self.bbox();
+        (pos - Point::splat(thickness / 2.0), size + Size::splat(thickness))
+    }
 }
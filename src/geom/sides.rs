@@ -186,8 +186,9 @@ where
         matches!(value, Value::Dict(_)) || T::is(value)
     }
 
-    fn cast(mut value: Value) -> StrResult<Self> {
-        if let Value::Dict(dict) = &mut value {
+    fn cast(value: Value) -> StrResult<Self> {
+        if let Value::Dict(dict) = &value {
+            let mut dict = dict.clone();
             let mut take = |key| dict.take(key).ok().map(T::cast).transpose();
 
             let rest = take("rest")?;
@@ -200,9 +201,11 @@ where
                 bottom: take("bottom")?.or(y),
             };
 
-            dict.finish(&["left", "top", "right", "bottom", "x", "y", "rest"])?;
-
-            Ok(sides)
+            match dict.finish(&["left", "top", "right", "bottom", "x", "y", "rest"]) {
+                Ok(()) => Ok(sides),
+                // The dictionary might describe a single value instead.
+                Err(err) => T::cast(value).map(|v| Self::splat(Some(v))).map_err(|_| err),
+            }
         } else if T::is(&value) {
             Ok(Self::splat(Some(T::cast(value)?)))
         } else {
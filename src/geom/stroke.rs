@@ -7,6 +7,16 @@ pub struct Stroke {
     pub paint: Paint,
     /// The stroke's thickness.
     pub thickness: Abs,
+    /// The stroke's line cap.
+    pub line_cap: LineCap,
+    /// The stroke's line join.
+    pub line_join: LineJoin,
+    /// The stroke's miter limit.
+    pub miter_limit: Scalar,
+    /// The stroke's dash pattern.
+    pub dash: DashPattern,
+    /// The offset into the dash pattern at which the stroke starts.
+    pub dash_phase: Abs,
 }
 
 impl Default for Stroke {
@@ -14,21 +24,36 @@ impl Default for Stroke {
         Self {
             paint: Paint::Solid(Color::BLACK),
             thickness: Abs::pt(1.0),
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            miter_limit: Scalar(4.0),
+            dash: DashPattern::Solid,
+            dash_phase: Abs::zero(),
         }
     }
 }
 
 /// A partial stroke representation.
 ///
-/// In this representation, both fields are optional so that you can pass either
-/// just a paint (`red`), just a thickness (`0.1em`) or both (`2pt + red`) where
-/// this is expected.
+/// In this representation, all fields are optional so that you can pass either
+/// just a paint (`red`), just a thickness (`0.1em`), both (`2pt + red`) or a
+/// dictionary with any of the fields where this is expected.
 #[derive(Default, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct PartialStroke<T = Length> {
     /// The stroke's paint.
     pub paint: Smart<Paint>,
     /// The stroke's thickness.
     pub thickness: Smart<T>,
+    /// The stroke's line cap.
+    pub line_cap: Smart<LineCap>,
+    /// The stroke's line join.
+    pub line_join: Smart<LineJoin>,
+    /// The stroke's miter limit.
+    pub miter_limit: Smart<Scalar>,
+    /// The stroke's dash pattern.
+    pub dash: Smart<DashPattern>,
+    /// The offset into the dash pattern at which the stroke starts.
+    pub dash_phase: Smart<T>,
 }
 
 impl PartialStroke<Abs> {
@@ -37,6 +62,11 @@ impl PartialStroke<Abs> {
         Stroke {
             paint: self.paint.unwrap_or(default.paint),
             thickness: self.thickness.unwrap_or(default.thickness),
+            line_cap: self.line_cap.unwrap_or(default.line_cap),
+            line_join: self.line_join.unwrap_or(default.line_join),
+            miter_limit: self.miter_limit.unwrap_or(default.miter_limit),
+            dash: self.dash.unwrap_or(default.dash),
+            dash_phase: self.dash_phase.unwrap_or(default.dash_phase),
         }
     }
 
@@ -48,6 +78,37 @@ impl PartialStroke<Abs> {
 
 impl<T: Debug> Debug for PartialStroke<T> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.line_cap.is_custom()
+            || self.line_join.is_custom()
+            || self.miter_limit.is_custom()
+            || self.dash.is_custom()
+            || self.dash_phase.is_custom()
+        {
+            let mut pieces = vec![];
+            if let Smart::Custom(paint) = self.paint {
+                pieces.push(format!("paint: {paint:?}"));
+            }
+            if let Smart::Custom(thickness) = &self.thickness {
+                pieces.push(format!("thickness: {thickness:?}"));
+            }
+            if let Smart::Custom(cap) = self.line_cap {
+                pieces.push(format!("cap: {:?}", Value::from(cap)));
+            }
+            if let Smart::Custom(join) = self.line_join {
+                pieces.push(format!("join: {:?}", Value::from(join)));
+            }
+            if let Smart::Custom(limit) = self.miter_limit {
+                pieces.push(format!("miter-limit: {:?}", limit.0));
+            }
+            if let Smart::Custom(dash) = self.dash {
+                pieces.push(format!("dash: {:?}", Value::from(dash)));
+            }
+            if let Smart::Custom(phase) = &self.dash_phase {
+                pieces.push(format!("dash-phase: {phase:?}"));
+            }
+            return write!(f, "({})", pieces.join(", "));
+        }
+
         match (self.paint, &self.thickness) {
             (Smart::Custom(paint), Smart::Custom(thickness)) => {
                 write!(f, "{thickness:?} + {paint:?}")
@@ -64,10 +125,47 @@ cast_from_value! {
     thickness: Length => Self {
         paint: Smart::Auto,
         thickness: Smart::Custom(thickness),
+        ..Default::default()
     },
     color: Color => Self {
         paint: Smart::Custom(color.into()),
         thickness: Smart::Auto,
+        ..Default::default()
+    },
+    mut dict: Dict => {
+        fn take<T: Cast>(dict: &mut Dict, key: &str) -> StrResult<Smart<T>> {
+            Ok(dict.take(key).ok().map(Value::cast).transpose()?.unwrap_or(Smart::Auto))
+        }
+
+        let paint = take::<Paint>(&mut dict, "paint")?;
+        let thickness = take::<Length>(&mut dict, "thickness")?;
+        let line_cap = take::<LineCap>(&mut dict, "cap")?;
+        let line_join = take::<LineJoin>(&mut dict, "join")?;
+        let miter_limit = take::<f64>(&mut dict, "miter-limit")?;
+        let dash = take::<DashPattern>(&mut dict, "dash")?;
+        let dash_phase = take::<Length>(&mut dict, "dash-phase")?;
+        if miter_limit.map_or(false, |limit| limit < 1.0) {
+            Err("miter limit must be at least one")?;
+        }
+
+        dict.finish(&[
+            "paint",
+            "thickness",
+            "cap",
+            "join",
+            "miter-limit",
+            "dash",
+            "dash-phase",
+        ])?;
+        Self {
+            paint,
+            thickness,
+            line_cap,
+            line_join,
+            miter_limit: miter_limit.map(Scalar),
+            dash,
+            dash_phase,
+        }
     },
 }
 
@@ -78,17 +176,89 @@ impl Resolve for PartialStroke {
         PartialStroke {
             paint: self.paint,
             thickness: self.thickness.resolve(styles),
+            line_cap: self.line_cap,
+            line_join: self.line_join,
+            miter_limit: self.miter_limit,
+            dash: self.dash,
+            dash_phase: self.dash_phase.resolve(styles),
         }
     }
 }
 
-impl Fold for PartialStroke<Abs> {
+impl<T> Fold for PartialStroke<T> {
     type Output = Self;
 
     fn fold(self, outer: Self::Output) -> Self::Output {
         Self {
             paint: self.paint.or(outer.paint),
             thickness: self.thickness.or(outer.thickness),
+            line_cap: self.line_cap.or(outer.line_cap),
+            line_join: self.line_join.or(outer.line_join),
+            miter_limit: self.miter_limit.or(outer.miter_limit),
+            dash: self.dash.or(outer.dash),
+            dash_phase: self.dash_phase.or(outer.dash_phase),
         }
     }
 }
+
+/// The shape at the end of an open stroke.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LineCap {
+    /// The stroke ends exactly at its end point.
+    Butt,
+    /// The stroke ends in a half circle around its end point.
+    Round,
+    /// The stroke ends in a half square around its end point.
+    Square,
+}
+
+/// The shape at the corners of a stroke.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LineJoin {
+    /// The outer edges of the segments are extended until they meet. Falls
+    /// back to a bevel join if the miter limit is exceeded.
+    Miter,
+    /// The corner is rounded off with a circle.
+    Round,
+    /// The corner is cut off with a straight line.
+    Bevel,
+}
+
+/// A preset pattern of dashes and gaps along a stroke.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum DashPattern {
+    /// A continuous line.
+    Solid,
+    /// Dots as long as the stroke is thick.
+    Dotted,
+    /// Dots with small gaps.
+    DenselyDotted,
+    /// Dots with large gaps.
+    LooselyDotted,
+    /// Dashes three times as long as the stroke is thick.
+    Dashed,
+    /// Dashes with small gaps.
+    DenselyDashed,
+    /// Dashes with large gaps.
+    LooselyDashed,
+    /// Alternating dashes and dots.
+    DashDotted,
+}
+
+impl DashPattern {
+    /// The lengths of the alternating dashes and gaps for a stroke of the
+    /// given thickness. Empty for a solid line.
+    pub fn array(self, thickness: Abs) -> Vec<Abs> {
+        let units: &[f64] = match self {
+            Self::Solid => &[],
+            Self::Dotted => &[1.0, 2.0],
+            Self::DenselyDotted => &[1.0, 1.0],
+            Self::LooselyDotted => &[1.0, 4.0],
+            Self::Dashed => &[3.0, 3.0],
+            Self::DenselyDashed => &[3.0, 2.0],
+            Self::LooselyDashed => &[3.0, 6.0],
+            Self::DashDotted => &[3.0, 2.0, 1.0, 2.0],
+        };
+        units.iter().map(|&unit| unit * thickness).collect()
+    }
+}
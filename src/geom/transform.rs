@@ -47,6 +47,16 @@ impl Transform {
         }
     }
 
+    /// A skew transform that shears by the given horizontal and vertical
+    /// angles.
+    pub fn skew(ax: Angle, ay: Angle) -> Self {
+        Self {
+            ky: Ratio::new(ay.tan()),
+            kx: Ratio::new(ax.tan()),
+            ..Self::identity()
+        }
+    }
+
     /// Whether this is the identity transformation.
     pub fn is_identity(self) -> bool {
         self == Self::identity()
@@ -68,6 +78,25 @@ impl Transform {
     pub fn post_concat(self, next: Self) -> Self {
         next.pre_concat(self)
     }
+
+    /// The inverse transformation, if there is one.
+    pub fn invert(self) -> Option<Self> {
+        let det = (self.sx * self.sy - self.kx * self.ky).get();
+        if det.abs() < 1e-12 {
+            return None;
+        }
+
+        let (sx, ky, kx, sy) =
+            (self.sx.get(), self.ky.get(), self.kx.get(), self.sy.get());
+        Some(Self {
+            sx: Ratio::new(sy / det),
+            ky: Ratio::new(-ky / det),
+            kx: Ratio::new(-kx / det),
+            sy: Ratio::new(sx / det),
+            tx: (kx * self.ty - sy * self.tx) / det,
+            ty: (ky * self.tx - sx * self.ty) / det,
+        })
+    }
 }
 
 impl Default for Transform {
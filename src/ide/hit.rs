@@ -0,0 +1,159 @@
+use std::num::NonZeroUsize;
+
+use crate::doc::{Frame, FrameItem, GroupItem};
+use crate::geom::{Abs, Geometry, PathItem, Point, Shape, Size, Transform};
+use crate::syntax::Span;
+
+/// A frame item found under a point on a page.
+#[derive(Debug, Clone)]
+pub struct Hit<'a> {
+    /// The item that was hit.
+    pub item: &'a FrameItem,
+    /// The span of the source code the item originates from. For text, this is
+    /// the span of the hit glyph. Detached if the item has no span.
+    pub span: Span,
+    /// Transforms from the item's coordinate system, with the item at the
+    /// origin, into the page's coordinate system.
+    pub transform: Transform,
+}
+
+/// Find all items on a page that are under a point, from the foremost to the
+/// backmost.
+///
+/// Returns nothing if the page does not exist.
+pub fn hit_test(frames: &[Frame], page: NonZeroUsize, point: Point) -> Vec<Hit<'_>> {
+    let mut hits = vec![];
+    if let Some(frame) = frames.get(page.get() - 1) {
+        hit_frame(frame, Transform::identity(), point, &mut hits);
+    }
+    hits
+}
+
+/// Collect the hits in a frame whose coordinate system is mapped into the
+/// page's by `ts`.
+fn hit_frame<'a>(frame: &'a Frame, ts: Transform, point: Point, hits: &mut Vec<Hit<'a>>) {
+    let Some(inverse) = ts.invert() else { return };
+    let local = point.transform(inverse);
+
+    for (pos, item) in frame.items().rev() {
+        let item_ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        let rel = local - *pos;
+        let span = match item {
+            FrameItem::Group(group) => {
+                hit_group(group, item_ts, point, hits);
+                continue;
+            }
+            FrameItem::Text(text) => {
+                let mut x = Abs::zero();
+                let mut found = None;
+                for glyph in &text.glyphs {
+                    let width = glyph.x_advance.at(text.size);
+                    let origin = Point::new(x, -text.size);
+                    if contains(origin, Size::new(width, text.size), rel) {
+                        found = Some(glyph.span);
+                        break;
+                    }
+                    x += width;
+                }
+                found
+            }
+            FrameItem::Shape(shape, span) => shape_contains(shape, rel).then_some(*span),
+            FrameItem::Image(_, size, span) => {
+                contains(Point::zero(), *size, rel).then_some(*span)
+            }
+            FrameItem::Meta(_, size) => {
+                contains(Point::zero(), *size, rel).then(Span::detached)
+            }
+        };
+
+        if let Some(span) = span {
+            hits.push(Hit { item, span, transform: item_ts });
+        }
+    }
+}
+
+/// Collect the hits in a group, respecting its transform and clipping.
+fn hit_group<'a>(
+    group: &'a GroupItem,
+    ts: Transform,
+    point: Point,
+    hits: &mut Vec<Hit<'a>>,
+) {
+    let ts = ts.pre_concat(group.transform);
+    if group.clips {
+        let Some(inverse) = ts.invert() else { return };
+        if !contains(Point::zero(), group.frame.size(), point.transform(inverse)) {
+            return;
+        }
+    }
+
+    hit_frame(&group.frame, ts, point, hits);
+}
+
+/// Whether a point is within the bounding box of a shape, including the
+/// shape's stroke.
+fn shape_contains(shape: &Shape, point: Point) -> bool {
+    let (min, max) = match &shape.geometry {
+        Geometry::Line(target) => (target.min(Point::zero()), target.max(Point::zero())),
+        Geometry::Rect(size) => (Point::zero(), size.to_point()),
+        Geometry::Path(path) => {
+            let mut points = path.0.iter().flat_map(|item| match *item {
+                PathItem::MoveTo(p) | PathItem::LineTo(p) => vec![p],
+                PathItem::CubicTo(a, b, c) => vec![a, b, c],
+                PathItem::ClosePath => vec![],
+            });
+            let Some(first) = points.next() else { return false };
+            points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)))
+        }
+    };
+
+    let pad = shape.stroke.map_or(Abs::zero(), |stroke| stroke.thickness / 2.0);
+    let origin = min - Point::splat(pad);
+    let size = (max - min).to_size() + Size::splat(2.0 * pad);
+    contains(origin, size, point)
+}
+
+/// Whether a rectangle with the given size at the given position contains a
+/// point.
+fn contains(pos: Point, size: Size, point: Point) -> bool {
+    pos.x <= point.x
+        && pos.x + size.x >= point.x
+        && pos.y <= point.y
+        && pos.y + size.y >= point.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::{Angle, Color};
+
+    #[test]
+    fn test_hit_transformed_group() {
+        let shape = Geometry::Rect(Size::splat(Abs::pt(10.0))).filled(Color::RED.into());
+        let mut inner = Frame::new(Size::splat(Abs::pt(10.0)));
+        inner.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+
+        let mut group = Frame::new(Size::splat(Abs::pt(10.0)));
+        group.push_frame(Point::zero(), inner);
+        group.transform(Transform::rotate(Angle::deg(90.0)));
+
+        let mut page = Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push_frame(Point::splat(Abs::pt(50.0)), group);
+
+        let pages = [page];
+        let hit = |x, y| {
+            hit_test(
+                &pages,
+                NonZeroUsize::new(1).unwrap(),
+                Point::new(Abs::pt(x), Abs::pt(y)),
+            )
+            .len()
+        };
+
+        // The rotation turns the square around the group's origin.
+        assert_eq!(hit(45.0, 55.0), 1);
+        assert_eq!(hit(55.0, 55.0), 0);
+        assert_eq!(hit(20.0, 20.0), 0);
+        assert!(hit_test(&pages, NonZeroUsize::new(2).unwrap(), Point::zero()).is_empty());
+    }
+}
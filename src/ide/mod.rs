@@ -3,12 +3,14 @@
 mod analyze;
 mod complete;
 mod highlight;
+mod hit;
 mod jump;
 mod tooltip;
 
 pub use self::analyze::analyze_labels;
 pub use self::complete::*;
 pub use self::highlight::*;
+pub use self::hit::*;
 pub use self::jump::*;
 pub use self::tooltip::*;
 
@@ -3,7 +3,11 @@
 use std::io;
 use std::sync::Arc;
 
+use ecow::EcoVec;
+use image::{DynamicImage, Rgba};
+
 use crate::diag::{format_xml_like_error, StrResult};
+use crate::geom::{Ratio, RgbaColor};
 use crate::util::Buffer;
 
 /// A raster or vector image.
@@ -19,6 +23,11 @@ pub struct Image {
     width: u32,
     /// The height in pixels.
     height: u32,
+    /// Color effects to apply when decoding.
+    effects: ImageEffects,
+    /// Other representations of the same image, for exporters that support
+    /// them better.
+    alternates: EcoVec<Image>,
 }
 
 impl Image {
@@ -27,7 +36,33 @@ impl Image {
     /// Extracts the width and height.
     pub fn new(data: Buffer, format: ImageFormat) -> StrResult<Self> {
         let (width, height) = determine_size(&data, format)?;
-        Ok(Self { data, format, width, height })
+        Ok(Self {
+            data,
+            format,
+            width,
+            height,
+            effects: ImageEffects::default(),
+            alternates: EcoVec::new(),
+        })
+    }
+
+    /// Apply color effects to the image's pixels when it is decoded.
+    ///
+    /// The effects also apply to the image's alternates.
+    pub fn with_effects(mut self, effects: ImageEffects) -> Self {
+        self.effects = effects;
+        self.alternates = std::mem::take(&mut self.alternates)
+            .into_iter()
+            .map(|alternate| alternate.with_effects(effects))
+            .collect();
+        self
+    }
+
+    /// Attach other representations of the same image, like a PNG version of
+    /// an SVG, among which exporters can [select](Self::select).
+    pub fn with_alternates(mut self, alternates: impl IntoIterator<Item = Self>) -> Self {
+        self.alternates = alternates.into_iter().collect();
+        self
     }
 
     /// The raw image data.
@@ -50,7 +85,50 @@ impl Image {
         self.height
     }
 
+    /// The color effects applied to the image.
+    pub fn effects(&self) -> ImageEffects {
+        self.effects
+    }
+
+    /// The other representations of the image.
+    pub fn alternates(&self) -> &[Self] {
+        &self.alternates
+    }
+
+    /// Select the representation of the image that suits an exporter best.
+    ///
+    /// Returns the decodable representation whose format comes first in
+    /// `preferred`, trying the image itself before its alternates for equally
+    /// preferred formats. Falls back to the image itself.
+    ///
+    /// The representations are ranked by their format first, so only those up
+    /// to the selected one are decoded.
+    pub fn select(&self, preferred: &[ImageFormat]) -> &Self {
+        if self.alternates.is_empty() {
+            return self;
+        }
+
+        let mut ranked: Vec<_> = std::iter::once(self)
+            .chain(self.alternates.iter())
+            .filter_map(|image| {
+                let rank = preferred.iter().position(|&f| f == image.format)?;
+                Some((rank, image))
+            })
+            .collect();
+
+        // The sort is stable, so the image itself stays ahead of alternates
+        // with the same rank.
+        ranked.sort_by_key(|&(rank, _)| rank);
+        ranked
+            .into_iter()
+            .map(|(_, image)| image)
+            .find(|image| image.decode().is_ok())
+            .unwrap_or(self)
+    }
+
     /// Decode the image.
+    ///
+    /// Color effects only apply to raster images.
     #[comemo::memoize]
     pub fn decode(&self) -> StrResult<Arc<DecodedImage>> {
         Ok(Arc::new(match self.format {
@@ -64,12 +142,84 @@ impl Image {
                 let cursor = io::Cursor::new(&self.data);
                 let reader = image::io::Reader::with_format(cursor, format.into());
                 let dynamic = reader.decode().map_err(format_image_error)?;
-                DecodedImage::Raster(dynamic, format)
+                DecodedImage::Raster(self.effects.apply(dynamic), format)
             }
         }))
     }
 }
 
+/// Color adjustments for the pixels of a raster image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ImageEffects {
+    /// How much to brighten the image, from `-100%` (black) to `100%`
+    /// (white).
+    pub brightness: Ratio,
+    /// How much to scale the image's contrast. `100%` keeps it unchanged.
+    pub contrast: Ratio,
+    /// Whether to convert the image to gray.
+    pub grayscale: bool,
+    /// Colors to map the image's darkest and lightest tones to. Tones in
+    /// between are interpolated. Implies `grayscale`.
+    pub duotone: Option<(RgbaColor, RgbaColor)>,
+}
+
+impl ImageEffects {
+    /// Whether the effects leave an image unchanged.
+    pub fn is_none(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the effects to a decoded raster image.
+    fn apply(&self, dynamic: DynamicImage) -> DynamicImage {
+        if self.is_none() {
+            return dynamic;
+        }
+
+        let brightness = self.brightness.get() as f32;
+        let contrast = self.contrast.get() as f32;
+        let adjust = |c: u8| {
+            let c = (c as f32 / 255.0 - 0.5) * contrast + 0.5 + brightness;
+            c.clamp(0.0, 1.0)
+        };
+
+        let alpha = dynamic.color().has_alpha();
+        let mut rgba = dynamic.into_rgba8();
+        for Rgba([r, g, b, _]) in rgba.pixels_mut() {
+            let [mut fr, mut fg, mut fb] = [*r, *g, *b].map(adjust);
+            if self.grayscale || self.duotone.is_some() {
+                let luma = 0.2126 * fr + 0.7152 * fg + 0.0722 * fb;
+                [fr, fg, fb] = [luma; 3];
+                if let Some((dark, light)) = self.duotone {
+                    let mix =
+                        |d: u8, l: u8| (d as f32 + (l as f32 - d as f32) * luma) / 255.0;
+                    fr = mix(dark.r, light.r);
+                    fg = mix(dark.g, light.g);
+                    fb = mix(dark.b, light.b);
+                }
+            }
+            [*r, *g, *b] = [fr, fg, fb].map(|c| (c * 255.0).round() as u8);
+        }
+
+        let dynamic = DynamicImage::ImageRgba8(rgba);
+        if alpha {
+            dynamic
+        } else {
+            DynamicImage::ImageRgb8(dynamic.into_rgb8())
+        }
+    }
+}
+
+impl Default for ImageEffects {
+    fn default() -> Self {
+        Self {
+            brightness: Ratio::zero(),
+            contrast: Ratio::one(),
+            grayscale: false,
+            duotone: None,
+        }
+    }
+}
+
 /// A raster or vector image format.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ImageFormat {
@@ -171,3 +321,85 @@ fn format_usvg_error(error: usvg::Error) -> String {
         usvg::Error::ParsingFailed(error) => format_xml_like_error("svg", error),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use image::codecs::png::PngEncoder;
+    use image::{ImageEncoder, Rgb, RgbImage};
+
+    use super::*;
+    use crate::doc::{Frame, FrameItem};
+    use crate::geom::{Abs, Color, Point, Size};
+    use crate::syntax::Span;
+
+    /// Apply effects to a single pixel.
+    fn pixel(effects: ImageEffects, rgb: [u8; 3]) -> [u8; 3] {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb(rgb)));
+        effects.apply(image).to_rgb8().get_pixel(0, 0).0
+    }
+
+    /// A red PNG of four by four pixels.
+    fn red_png() -> Vec<u8> {
+        let mut data = vec![];
+        let red = RgbImage::from_pixel(4, 4, Rgb([255, 0, 0]));
+        PngEncoder::new(&mut data)
+            .write_image(red.as_raw(), 4, 4, image::ColorType::Rgb8)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_image_effects() {
+        let none = ImageEffects::default();
+        assert_eq!(pixel(none, [10, 20, 30]), [10, 20, 30]);
+
+        // Brightness shifts tones and contrast scales them around the middle.
+        let brighter = ImageEffects { brightness: Ratio::new(0.2), ..none };
+        assert_eq!(pixel(brighter, [0, 100, 255]), [51, 151, 255]);
+        let contrast = ImageEffects { contrast: Ratio::new(1.5), ..none };
+        assert_eq!(pixel(contrast, [0, 100, 200]), [0, 86, 236]);
+
+        // Gray uses the luma weights and duotone maps it between two colors.
+        let gray = ImageEffects { grayscale: true, ..none };
+        assert_eq!(pixel(gray, [255, 0, 0]), [54, 54, 54]);
+        let blue = RgbaColor::new(0, 0, 255, 255);
+        let yellow = RgbaColor::new(255, 255, 0, 255);
+        let duotone = ImageEffects { duotone: Some((blue, yellow)), ..none };
+        assert_eq!(pixel(duotone, [0, 0, 0]), [0, 0, 255]);
+        assert_eq!(pixel(duotone, [255, 255, 255]), [255, 255, 0]);
+    }
+
+    #[test]
+    fn test_select_alternate() {
+        let data = br#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"/>"#;
+        let svg = ImageFormat::Vector(VectorFormat::Svg);
+        let png = ImageFormat::Raster(RasterFormat::Png);
+        let image = Image::new(data.as_slice().into(), svg)
+            .unwrap()
+            .with_alternates([Image::new(red_png().into(), png).unwrap()]);
+
+        // The representation with the most preferred format wins and an
+        // unknown format falls back to the image itself.
+        assert_eq!(image.select(&[svg, png]).format(), svg);
+        assert_eq!(image.select(&[png, svg]).format(), png);
+        assert_eq!(image.select(&[ImageFormat::Raster(RasterFormat::Gif)]), &image);
+    }
+
+    #[test]
+    fn test_image_effects_when_rendered() {
+        // Effects show up wherever the image is drawn, like in the rendered
+        // output the reference images are made of.
+        let format = ImageFormat::Raster(RasterFormat::Png);
+        let gray = ImageEffects { grayscale: true, ..Default::default() };
+        let image = Image::new(red_png().into(), format).unwrap().with_effects(gray);
+        let size = Size::splat(Abs::pt(10.0));
+        let mut frame = Frame::new(size);
+        frame.push(Point::zero(), FrameItem::Image(image, size, Span::detached()));
+
+        let pixmap = crate::export::render(&frame, 1.0, Color::WHITE);
+        let pixel = pixmap.pixel(5, 5).unwrap();
+        for channel in [pixel.red(), pixel.green(), pixel.blue()] {
+            assert!(channel.abs_diff(54) <= 1);
+        }
+    }
+}
@@ -1463,6 +1463,17 @@ impl FuncCall {
     pub fn args(&self) -> Args {
         self.0.cast_last_match().unwrap_or_default()
     }
+
+    /// The name of the called function if the callee is a plain identifier or
+    /// a field access, e.g. `min` for `calc.min(1, 2)`.
+    pub fn callee_name(&self) -> Option<&str> {
+        let node = match self.callee() {
+            Expr::Ident(_) => self.0.children().next()?,
+            Expr::FieldAccess(_) => self.0.children().next()?.children().last()?,
+            _ => return None,
+        };
+        Some(node.text())
+    }
 }
 
 node! {
@@ -660,6 +660,35 @@ impl<'a> LinkedNode<'a> {
 
         None
     }
+
+    /// An iterator over this node and all its descendants in pre-order.
+    pub fn descendants(&self) -> LinkedDescendants<'a> {
+        LinkedDescendants { stack: vec![], root: Some(self.clone()) }
+    }
+
+    /// Find all descendants (including this node) of the given typed kind.
+    ///
+    /// For instance, `node.query::<ast::Heading>()` yields all headings in
+    /// the subtree together with their positions.
+    pub fn query<T: AstNode>(&self) -> impl Iterator<Item = (Self, T)> {
+        self.descendants()
+            .filter_map(|node| node.cast::<T>().map(|typed| (node, typed)))
+    }
+
+    /// Visit this node and its descendants in pre-order.
+    ///
+    /// The subtree of a node is only entered if the visitor returns `true`
+    /// for it.
+    pub fn visit<F>(&self, f: &mut F)
+    where
+        F: FnMut(&LinkedNode<'a>) -> bool,
+    {
+        if f(self) {
+            for child in self.children() {
+                child.visit(f);
+            }
+        }
+    }
 }
 
 /// Access to parents and siblings.
@@ -850,6 +879,37 @@ impl DoubleEndedIterator for LinkedChildren<'_> {
 
 impl ExactSizeIterator for LinkedChildren<'_> {}
 
+/// A pre-order iterator over a linked node and its descendants.
+pub struct LinkedDescendants<'a> {
+    stack: Vec<LinkedChildren<'a>>,
+    root: Option<LinkedNode<'a>>,
+}
+
+impl<'a> Iterator for LinkedDescendants<'a> {
+    type Item = LinkedNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = match self.root.take() {
+            Some(root) => root,
+            None => loop {
+                let children = self.stack.last_mut()?;
+                match children.next() {
+                    Some(child) => break child,
+                    None => {
+                        self.stack.pop();
+                    }
+                }
+            },
+        };
+
+        if node.children().len() > 0 {
+            self.stack.push(node.children());
+        }
+
+        Some(node)
+    }
+}
+
 /// Result of numbering a node within an interval.
 pub(super) type NumberingResult = Result<(), Unnumberable>;
 
@@ -901,4 +961,61 @@ mod tests {
         assert_eq!(leaf.text(), " ");
         assert_eq!(next.text(), "10");
     }
+
+    #[test]
+    fn test_linked_node_query() {
+        use crate::syntax::ast;
+
+        let source = Source::detached("= A\n#f(1) #g[= B #f(2)]");
+        let root = LinkedNode::new(source.root());
+
+        let headings: Vec<_> = root
+            .query::<ast::Heading>()
+            .map(|(node, heading)| (node.offset(), heading.level().get()))
+            .collect();
+        assert_eq!(headings, [(0, 1), (13, 1)]);
+
+        let calls: Vec<_> = root
+            .query::<ast::FuncCall>()
+            .filter(|(_, call)| call.callee_name() == Some("f"))
+            .map(|(node, _)| node.offset())
+            .collect();
+        assert_eq!(calls, [5, 18]);
+
+        let mut visited = 0;
+        root.visit(&mut |node| {
+            visited += 1;
+            node.kind() != SyntaxKind::FuncCall
+        });
+        assert!(visited < root.descendants().count());
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        use crate::syntax::ast;
+
+        let text = "#let = 1\n= Heading\n#g[*strong*] #{1 +}";
+        let source = Source::detached(text);
+        let root = source.root();
+
+        // The tree still covers all of the text.
+        assert!(root.erroneous());
+        assert_eq!(root.len(), text.len());
+        assert_eq!(root.clone().into_text(), text);
+
+        // All errors are reported at once, at the broken let binding and the
+        // incomplete expression at the end.
+        let errors = root.errors();
+        assert!(errors.len() >= 2);
+        let ranges: Vec<_> =
+            errors.iter().map(|error| source.range(error.span)).collect();
+        assert!(ranges.first().unwrap().end <= "#let = 1".len());
+        assert!(ranges.last().unwrap().start >= text.find("#{").unwrap());
+
+        // The markup between the errors is parsed as usual.
+        let root = LinkedNode::new(root);
+        assert_eq!(root.query::<ast::Heading>().count(), 1);
+        assert_eq!(root.query::<ast::Strong>().count(), 1);
+        assert!(root.query::<ast::Heading>().all(|(node, _)| !node.erroneous()));
+    }
 }
@@ -15,7 +15,7 @@ use typst::diag::{bail, FileError, FileResult};
 use typst::doc::{Document, Frame, FrameItem, Meta};
 use typst::eval::{func, Library, Value};
 use typst::font::{Font, FontBook};
-use typst::geom::{Abs, Color, RgbaColor, Sides, Smart};
+use typst::geom::{Abs, Color, Length, RgbaColor, Sides, Smart};
 use typst::syntax::{Source, SourceId, Span, SyntaxNode};
 use typst::util::{Buffer, PathExt};
 use typst::World;
@@ -157,6 +157,23 @@ fn library() -> Library {
         Value::None
     }
 
+    /// Display: Test Close
+    /// Category: test
+    /// Returns:
+    #[func]
+    fn test_close(
+        lhs: Length,
+        rhs: Length,
+        #[named]
+        #[default(Abs::pt(0.01).into())]
+        tolerance: Length,
+    ) -> Value {
+        if lhs.em != rhs.em || (lhs.abs - rhs.abs).abs() > tolerance.abs {
+            bail!(args.span, "Assertion failed: {:?} is not close to {:?}", lhs, rhs);
+        }
+        Value::None
+    }
+
     /// Display: Print
     /// Category: test
     /// Returns:
@@ -188,6 +205,7 @@ fn library() -> Library {
 
     // Hook up helpers into the global scope.
     lib.global.scope_mut().define("test", test);
+    lib.global.scope_mut().define("test-close", test_close);
     lib.global.scope_mut().define("print", print);
     lib.global
         .scope_mut()
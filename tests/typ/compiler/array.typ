@@ -201,6 +201,38 @@
 // Error: 2-26 cannot order content and content
 #([Hi], [There]).sorted()
 
+---
+// Test sorting by key.
+#test(("cc", "a", "bbb").sorted(key: s => s.len()), ("a", "cc", "bbb"))
+#test((3, 1, 2).sorted(key: x => -x), (3, 2, 1))
+#test(((2, "b"), (1, "a"), (2, "a")).sorted(key: p => p.first()), ((1, "a"), (2, "b"), (2, "a")))
+
+---
+// Error: 2-31 cannot order content and content
+#(1, 2).sorted(key: x => [#x])
+
+---
+// Test the `zip`, `windows`, `chunks`, and `dedup` methods.
+#test((1, 2, 3).zip(("a", "b")), ((1, "a"), (2, "b")))
+#test(().zip((1,)), ())
+#test((1, 2, 3, 4).windows(2), ((1, 2), (2, 3), (3, 4)))
+#test((1, 2).windows(3), ())
+#test((1, 2, 3, 4, 5).chunks(2), ((1, 2), (3, 4), (5,)))
+#test((1, 1, 2, 2, 2, 1, 3).dedup(), (1, 2, 1, 3))
+
+---
+// Error: 2-18 chunk size must be positive
+#(1, 2).chunks(0)
+
+---
+// Test the `group-by` method.
+#let groups = ("apple", "avocado", "banana").group-by(s => s.first())
+#test(groups, (a: ("apple", "avocado"), b: ("banana",)))
+
+---
+// Error: 18-37 expected string, found integer
+#(1, 2).group-by(x => calc.rem(x, 2))
+
 ---
 // Error: 2-18 array index out of bounds (index: -4, len: 3)
 #(1, 2, 3).at(-4)
@@ -78,6 +78,12 @@
 // Error: 3-8 expected identifier or string, found binary expression
 #(a + b: "hey")
 
+---
+// Test the `filter` and `map` methods.
+#let dict = (a: 1, b: 2, c: 3)
+#test(dict.filter((k, v) => calc.odd(v)), (a: 1, c: 3))
+#test(dict.map((k, v) => k + str(v)), ("a1", "b2", "c3"))
+
 ---
 // Error: 3-15 cannot mutate a temporary value
 #((key: "val").other = "some")
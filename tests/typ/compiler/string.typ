@@ -52,6 +52,37 @@
 #test("🏳️‍🌈!".clusters(), ("🏳️‍🌈", "!"))
 #test("🏳️‍🌈!".codepoints(), ("🏳", "\u{fe0f}", "\u{200d}", "🌈", "!"))
 
+---
+// Test lengths and slices measured in other units.
+#test("🏳️‍🌈!".len(unit: "codepoints"), 5)
+#test("🏳️‍🌈!".len(unit: "clusters"), 2)
+#test("Straße".len(unit: "bytes"), 7)
+#test("🏳️‍🌈!".slice(1, unit: "clusters"), "!")
+#test("née!".slice(-2, unit: "clusters"), "e!")
+#test("🏳️‍🌈ab".slice(0, count: 2, unit: "clusters"), "🏳️‍🌈a")
+#test("ae\u{301}".slice(0, 2, unit: "codepoints"), "ae")
+
+---
+// Error: 2-34 string index out of bounds (index: 4, len: 3)
+#"abc".slice(4, unit: "clusters")
+
+---
+// Error: 18-25 expected "bytes", "codepoints", or "clusters"
+#"abc".len(unit: "words")
+
+---
+// Test the `words` method.
+#test("Hello, wörld! It's 3.5 o'clock.".words(), ("Hello", "wörld", "It's", "3.5", "o'clock"))
+#test("  ".words(), ())
+
+---
+// Test locale-aware casing.
+#test("Straße".upper(), "STRASSE")
+#test("İstanbul".lower(lang: "tr"), "istanbul")
+#test("Irmak".lower(lang: "tr"), "ırmak")
+#test("istanbul".upper(lang: "tr"), "İSTANBUL")
+#test("istanbul".upper(), "ISTANBUL")
+
 ---
 // Test the `contains` method.
 #test("abc".contains("b"), true)
@@ -40,6 +40,53 @@
 // Error: 21-26 expected integer or ratio, found boolean
 #rgb(10%, 20%, 30%, false)
 
+---
+// Test gradients.
+#test(type(gradient(red, blue)), "gradient")
+#test(repr(gradient(black, white, angle: 90deg)), "gradient(rgb(\"#000000\"), rgb(\"#ffffff\"), angle: 90deg)")
+#test(repr(gradient(black, white, kind: "radial")), "gradient(rgb(\"#000000\"), rgb(\"#ffffff\"), kind: \"radial\")")
+#test(gradient(red, blue) == gradient(red, blue), true)
+#rect(fill: gradient(aqua, blue), stroke: 2pt + gradient(red, blue, angle: 45deg))
+#text(fill: gradient(red, blue))[Gradient]
+
+---
+// Error: 28-35 expected "linear" or "radial"
+#gradient(red, blue, kind: "conic")
+
+---
+// Test patterns.
+#test(type(pattern(red)), "pattern")
+#test(repr(pattern(black)), "pattern(rgb(\"#000000\"), kind: \"lines\", angle: 45deg, spacing: 4pt, thickness: 1pt)")
+#test(pattern(red, kind: "dots") == pattern(red, kind: "dots"), true)
+#rect(fill: pattern(blue, kind: "grid"), stroke: 3pt + pattern(red, spacing: 2pt))
+#text(fill: pattern(red, kind: "dots"))[Pattern]
+
+---
+// Error: 9-28 pattern lengths must be absolute
+#pattern(red, spacing: 1em)
+
+---
+// Error: 9-28 pattern spacing must be positive
+#pattern(red, spacing: 0pt)
+
+---
+// Test contrast and readable text colors.
+#test(black.contrast(black), 1.0)
+#test(calc.round(black.contrast(white), digits: 2), 21.0)
+#test(white.contrast(black), black.contrast(white))
+#test(calc.round(rgb("#767676").contrast(white), digits: 2), 4.54)
+#test(readable(navy), white)
+#test(readable(yellow), black)
+#test(readable(luma(240)), black)
+#test(readable(gradient(navy, blue)), white)
+#test(readable(pattern(maroon)), white)
+#test(readable(white, candidates: (yellow, navy, black)), black)
+#test(readable(white, candidates: (yellow, navy)), navy)
+
+---
+// Error: 10-31 candidates must not be empty
+#readable(red, candidates: ())
+
 ---
 // Ref: true
 #let envelope = symbol(
@@ -67,5 +114,36 @@
 // Error: 6-8 expected integer, float, label, or string, found content
 #str([])
 
+---
+// Test formatting strings.
+#test(format("{} + {} = {}", 1, 2, 3), "1 + 2 = 3")
+#test(format("{1}{0}{1}", "a", "b"), "bab")
+#test(format("{name} is {age}", name: "Ann", age: 30), "Ann is 30")
+#test(format("{{{}}}", true), "{true}")
+#test(format("[{:5}|{:5}]", "ab", 12), "[ab   |   12]")
+#test(format("[{:*^7}]", "mid"), "[**mid**]")
+#test(format("{:<4}|{:>4}", 7, "x"), "7   |   x")
+#test(format("{:.2}", 3.14159), "3.14")
+#test(format("{:05}", -42), "-0042")
+#test(format("{:*^5}", "e\u{301}👍🏽"), "*e\u{301}👍🏽**")
+#test(format("{:b} {:o} {:x} {:X}", 5, 8, 255, -255), "101 10 ff -FF")
+#test(format("{}", (1, 2)), "(1, 2)")
+
+---
+// Error: 9-12 unclosed placeholder in format string
+#format("{", 1)
+
+---
+// Error: 9-15 missing positional value 1
+#format("{}{}", 1)
+
+---
+// Error: 9-13 invalid format specification `?`
+#format("{:?}", 1)
+
+---
+// Error: 9-15 cannot format string in another base
+#format("{:x}", "a")
+
 ---
 #assert(range(2, 5) == (2, 3, 4))
@@ -6,6 +6,27 @@
 #let data = read("/hello.txt")
 #test(data, "Hello, world!")
 
+---
+// Test reading a range of lines.
+#test(read("/zoo.csv", lines: (2, 3)), "Debby,Rhinoceros,1900kg,390cm\nFluffy,Tiger,115kg,310cm")
+#test(read("/zoo.csv", lines: (4, 4)), "Sleepy,Dolphin,150kg,180cm")
+
+---
+// Test expanding tabs.
+#test(read("/tabs.txt", tab-size: 4), "a   b\n    c\n")
+
+---
+// Error: 37-43 last line must not come before first line
+#let data = read("/zoo.csv", lines: (3, 2))
+
+---
+// Error: 17-44 line range out of bounds (end: 6, len: 4)
+#let data = read("/zoo.csv", lines: (5, 6))
+
+---
+// Error: 17-45 line range out of bounds (end: 10, len: 4)
+#let data = read("/zoo.csv", lines: (4, 10))
+
 ---
 // Error: 18-32 file not found (searched at /missing.txt)
 #let data = read("/missing.txt")
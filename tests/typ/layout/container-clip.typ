@@ -0,0 +1,40 @@
+// Test clipping of boxes and blocks.
+// Ref: false
+
+---
+// Clip overflowing content at the box edges.
+Hello #box(width: 12pt, height: 8pt, clip: true, fill: aqua, rect(width: 30pt, height: 20pt, fill: red)) world.
+
+// Clipping keeps the size of the box.
+#style(styles => {
+  let clipped = box(width: 12pt, height: 8pt, clip: true, rect(width: 30pt, height: 20pt))
+  test(measure(clipped, styles), (width: 12pt, height: 8pt))
+})
+
+---
+// Clip to rounded corners.
+#let rounded = block(
+  width: 60pt,
+  height: 30pt,
+  clip: true,
+  radius: 8pt,
+  fill: eastern,
+  square(size: 60pt, fill: orange),
+)
+
+#rounded
+#style(styles => test(measure(rounded, styles), (width: 60pt, height: 30pt)))
+
+---
+// Clipping across a page break.
+#set page(width: 80pt, height: 60pt, margin: 10pt)
+#block(clip: true, radius: 4pt, stroke: black)[
+  #lorem(12) #box(width: 1pt, height: 1pt) <end>
+] <clipped>
+
+// The block still breaks, so its end isn't clipped away.
+#locate(loc => {
+  let page(label) = query(label, loc).first().location().page()
+  test(page(<clipped>), 1)
+  test(page(<end>) > 1, true)
+})
@@ -3,3 +3,30 @@
 ---
 AB #h(1fr) CD \
 #hide[A]B #h(1fr) C#hide[D]
+
+---
+// Ref: false
+// Test that hidden content can still be introspected.
+#hide[= Hidden <hidden>]
+#hide(collapse: true)[= Collapsed <collapsed>]
+#locate(loc => {
+  test(query(<hidden>, loc).len(), 1)
+  test(query(<collapsed>, loc).len(), 1)
+  test(counter(heading).at(query(<collapsed>, loc).first().location()), (2,))
+})
+
+---
+// Ref: false
+// Test that hidden headings are neither outlined nor bookmarked.
+#hide[= Hidden <hidden>]
+= Visible
+#locate(loc => {
+  let it = query(<hidden>, loc).first()
+  test(it.outlined, false)
+  test(it.bookmarked, false)
+  test(query(heading.where(outlined: true), loc).len(), 1)
+})
+
+---
+// Error: 17-18 expected boolean, found integer
+#hide(collapse: 1)[A]
@@ -0,0 +1,25 @@
+// Test access to the available space.
+// Ref: false
+
+---
+#set page(width: 100pt, height: 200pt, margin: 10pt)
+#layout(size => {
+  test(size.width, 80pt)
+  test(size.height, 180pt)
+})
+#box(width: 30pt, layout(size => test(size.width, 30pt)))
+
+---
+// Test building a component from the available space.
+#set page(width: 120pt, margin: 10pt)
+#let bar(progress) = layout(size => box(width: size.width * progress, height: 4pt, fill: eastern))
+#bar(0.25)
+#bar(1.0)
+
+---
+// Test passing the styles.
+#set text(14pt)
+#layout((size, styles) => {
+  let width = measure([Hi], styles).width
+  test(width < size.width, true)
+})
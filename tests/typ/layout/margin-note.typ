@@ -0,0 +1,33 @@
+// Test margin notes.
+
+---
+#set page(width: 160pt, height: 120pt, margin: (x: 50pt))
+#set marginnote(gap: 5pt)
+#let side = marginnote.with(connector: 0.5pt + gray)
+
+First#side[One <one>] and second#side[Two <two>] on the
+same line. Third#marginnote(side: left)[Three <three>]
+on the left.
+
+// Notes keep their gap to the text and notes on the same line are stacked.
+#style(styles => locate(loc => {
+  let pos(label) = query(label, loc).first().location().position()
+  test(pos(<one>).x, 115pt)
+  test(pos(<two>).x, 115pt)
+  test(pos(<three>).x, 5pt)
+  test-close(pos(<two>).y - pos(<one>).y, measure([One], styles).height + 5pt)
+}))
+
+---
+// Test that notes are laid out with the styles where they appear.
+#set page(width: 160pt, height: 120pt, margin: (x: 50pt))
+#text(size: 20pt)[A#marginnote(gap: 0.5em)[B#box()<b>]]
+
+#style(styles => locate(loc => {
+  let x = query(<b>, loc).first().location().position().x
+  test-close(x, 120pt + measure(text(size: 20pt)[B], styles).width)
+}))
+
+---
+// Error: 19-22 expected either `left` or `right`
+#marginnote(side: top)[A]
@@ -0,0 +1,39 @@
+// Test extending pages into the bleed.
+
+---
+// Ref: false
+#set page(width: 120pt, height: 80pt, margin: 10pt, bleed: 6pt, fill: aqua)
+#place(top, scope: "bleed")[#rect(width: 100%, height: 20pt, fill: navy) <strip>]
+#place(bottom + right, scope: "page")[#square(size: 10pt, fill: red) <corner>]
+#block(width: 100%, height: 10pt, fill: eastern) <body>
+
+// The bleed surrounds the page on all sides.
+#locate(loc => {
+  let pos(label) = query(label, loc).first().location().position()
+  test(pos(<strip>), (page: 1, x: 0pt, y: 0pt))
+  test(pos(<corner>), (page: 1, x: 116pt, y: 76pt))
+  test(pos(<body>), (page: 1, x: 16pt, y: 16pt))
+})
+
+---
+// Ref: false
+// Without a bleed, the bleed area is the page.
+#set page(width: 120pt, height: 80pt)
+#place(scope: "bleed", rect(width: 100%, height: 100%, fill: eastern))
+#place(bottom + right, scope: "bleed")[#square(size: 10pt, fill: red) <corner>]
+#locate(loc => {
+  let pos = query(<corner>, loc).first().location().position()
+  test(pos, (page: 1, x: 110pt, y: 70pt))
+})
+
+---
+// Error: 18-24 expected length, found string
+#set page(bleed: "wide")
+
+---
+// Error: 18-22 bleed must not be negative
+#set page(bleed: -2pt)
+
+---
+// Error: 18-26 bleed must not be negative
+#set page(bleed: 1pt - 1em)
@@ -0,0 +1,19 @@
+// Test page labels for front matter and main matter.
+// Ref: false
+
+---
+#set page(height: 60pt, numbering: "i")
+Preface
+#pagebreak()
+Contents
+
+#set page(numbering: "1")
+#counter(page).update(1)
+Body
+#pagebreak()
+#set page(numbering: "A-1", footer: [])
+Appendix
+#pagebreak()
+#set page(numbering: "*")
+Symbols
+#locate(loc => test(counter(page).at(loc).first(), 5))
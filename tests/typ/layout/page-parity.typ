@@ -0,0 +1,29 @@
+// Test marginals that depend on the physical page number.
+// Ref: false
+
+---
+#set page(
+  width: 100pt,
+  height: 60pt,
+  margin: 10pt,
+  header: n => locate(loc => {
+    test(n, loc.page())
+    [Page #n]
+  }),
+  footer: n => align(if calc.odd(n) { right } else { left })[
+    #square(size: 5pt) <foot>
+  ],
+  background: n => if calc.even(n) {
+    place(left + horizon)[#rect(width: 4pt, height: 20pt, fill: aqua) <stripe>]
+  },
+)
+
+A #pagebreak() B #pagebreak() C
+
+#locate(loc => {
+  let feet = query(<foot>, loc).map(it => it.location().position())
+  test(feet.map(pos => pos.page), (1, 2, 3))
+  test(feet.map(pos => pos.x), (85pt, 10pt, 85pt))
+  let stripes = query(<stripe>, loc).map(it => it.location().page())
+  test(stripes, (2,))
+})
@@ -0,0 +1,12 @@
+// Test page rotation for viewers.
+// Ref: false
+
+---
+#set page(height: 60pt)
+Portrait.
+#page(rotation: 90deg)[Shown in landscape.]
+#page(rotation: -90deg)[Normalized to 270 degrees.]
+
+---
+// Error: 21-26 rotation must be a multiple of 90deg
+#set page(rotation: 45deg)
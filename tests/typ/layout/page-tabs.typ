@@ -0,0 +1,41 @@
+// Test thumb tabs for section navigation.
+
+---
+#set page(height: 80pt, bleed: 4pt, tabs: (height: 12pt, fill: eastern))
+Before the first section.
+#pagebreak()
+= First
+#lorem(10)
+#pagebreak()
+= Second
+#lorem(20)
+
+// The tabs walk the pages on which the sections start.
+#locate(loc => {
+  let starts = query(heading.where(level: 1), loc).map(it => it.location().page())
+  test(starts, (2, 3))
+})
+
+// Unset keys take their defaults.
+#let tabs(..args) = page(tabs: args.named())[].tabs
+#test(tabs(height: 12pt, fill: eastern), (level: 1, width: 6mm, height: 12pt, fill: eastern))
+#test(tabs().fill, luma(40%))
+
+---
+#set page(height: 60pt, tabs: (level: 2, width: 3mm))
+= Chapter
+== Section
+== Another section
+
+// Only headings of the tabs' level start a section.
+#locate(loc => {
+  let starts = query(heading.where(level: 2), loc).map(it => it.location().page())
+  test(starts.len(), 2)
+})
+
+#test(page(tabs: (level: 2, width: 3mm))[].tabs.level, 2)
+
+---
+// Ref: false
+// Error: 17-29 unexpected key "color", valid keys are "level", "width", "height", and "fill"
+#set page(tabs: (color: red))
@@ -0,0 +1,23 @@
+// Test balanced line breaking.
+// Ref: false
+
+---
+#set page(width: 180pt)
+#show heading: set par(linebreaks: "balanced")
+= A heading that is just long enough to leave one word behind
+
+---
+// Test that single lines and mandatory breaks are left alone.
+#set par(linebreaks: "balanced")
+Short. \
+Hard break in a balanced paragraph.
+
+---
+// Test with justification and an overlong word.
+#set page(width: 80pt)
+#set par(linebreaks: "balanced", justify: true)
+Supercalifragilisticexpialidocious and more.
+
+---
+// Error: 22-33 expected "simple", "optimized", "balanced", or auto
+#set par(linebreaks: "equalized")
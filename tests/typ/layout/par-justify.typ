@@ -31,3 +31,50 @@ D E F #linebreak(justify: true)
 // basically empty paragraph.
 #set par(justify: true)
 #""
+
+---
+// Test that glyph expansion scales the text of justified lines up to the
+// given amount, but leaves other lines alone.
+// Ref: false
+#set page(width: 180pt, margin: 0pt)
+#set par(justify: true)
+
+Word#box()<plain> text #linebreak(justify: true)
+
+#par(expansion: 3%)[
+  Word#box()<expanded> text #linebreak(justify: true)
+  Word#box()<last> text
+]
+
+#style(styles => locate(loc => {
+  let x(label) = query(label, loc).first().location().position().x
+  let width = measure([Word], styles).width
+  test-close(x(<plain>), width)
+  test-close(x(<expanded>), 1.03 * width)
+  test-close(x(<last>), width)
+}))
+
+---
+// Test that opening punctuation hangs into the start margin if enabled.
+// Ref: false
+#set page(width: 180pt, margin: 0pt)
+
+“Flush#box()<flush>
+
+#text(overhang-start: true)[“Hanging#box()<hanging>]
+
+#style(styles => locate(loc => {
+  let x(label) = query(label, loc).first().location().position().x
+  let quote = measure([“], styles).width
+  let flush = x(<flush>) - measure([“Flush], styles).width
+  let hanging = x(<hanging>) - measure([“Hanging], styles).width
+  test-close(flush - hanging, 0.6 * quote)
+}))
+
+---
+// Error: 21-24 expected ratio, found length
+#set par(expansion: 2pt)
+
+---
+// Error: 27-32 expected boolean, found string
+#set text(overhang-start: "yes")
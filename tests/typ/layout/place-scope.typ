@@ -0,0 +1,21 @@
+// Test placing content relative to the page.
+
+---
+// Ref: false
+#set page(width: 120pt, height: 100pt, margin: 20pt)
+#block(inset: 5pt, stroke: 1pt)[
+  Nested
+  #place(top + right, scope: "page")[#square(size: 10pt, fill: red) <page>]
+  #place(bottom + left, scope: "body", dx: 5pt)[#square(size: 10pt, fill: blue) <body>]
+]
+
+// The nested block doesn't affect where the content ends up.
+#locate(loc => {
+  let pos(label) = query(label, loc).first().location().position()
+  test(pos(<page>), (page: 1, x: 110pt, y: 0pt))
+  test(pos(<body>), (page: 1, x: 25pt, y: 70pt))
+})
+
+---
+// Error: 15-23 expected "parent", "body", "page", or "bleed"
+#place(scope: "margin")[A]
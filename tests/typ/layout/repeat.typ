@@ -37,3 +37,34 @@ A#box(width: 1fr, repeat(rect(width: 6em, height: 0.7em)))B
 
 #set text(dir: rtl)
 ريجين#box(width: 1fr, repeat(rect(width: 4em, height: 0.7em)))سون
+
+---
+// Ref: false
+// Test gaps and unjustified repeats.
+A#box(width: 1fr, repeat(gap: 4pt)[-])B
+
+#set align(center)
+A#box(width: 1fr, repeat(justify: false, gap: 2pt)[.])B
+
+---
+// Ref: false
+// Test where the first piece ends up. Seven 10pt pieces with 4pt gaps leave
+// 6pt of the 100pt, which justified pieces distribute instead of aligning.
+#set page(width: 100pt, margin: 0pt)
+#let piece = box(width: 10pt, height: 5pt, fill: aqua)
+#align(center, repeat(gap: 4pt)[#piece<justified>])
+#repeat(justify: false, gap: 4pt)[#piece<start>]
+#align(center, repeat(justify: false, gap: 4pt)[#piece<center>])
+#align(right, repeat(justify: false, gap: 4pt)[#piece<end>])
+
+#locate(loc => {
+  let x(label) = query(label, loc).first().location().position().x
+  test(x(<justified>), 0pt)
+  test(x(<start>), 0pt)
+  test(x(<center>), 3pt)
+  test(x(<end>), 6pt)
+})
+
+---
+// Error: 14-20 expected length, found string
+#repeat(gap: "wide")[.]
@@ -0,0 +1,42 @@
+// Test tab stops and leaders.
+// Ref: false
+
+---
+#set page(width: 200pt)
+#set par(tabs: (
+  (pos: 100pt, align: "right", leader: [.]),
+  (pos: 150pt, align: "decimal"),
+  (pos: 180pt, align: "center"),
+))
+
+Pasta #tab() Primi #tab() 12.50 #tab() A \
+Tiramisu #tab() Dolci #tab() 7.0 #tab() B \
+Espresso #tab() Caffè #tab() 2 #tab() C
+
+---
+// Test plain length stops and the default stops beyond them.
+#set par(tabs: (2cm,))
+A #tab() B #tab() C #tab() D
+
+---
+// Test that tabs past the last stop and in justified text work.
+#set par(justify: true, tabs: (1cm,))
+#lorem(5) #tab() #lorem(10)
+
+---
+// Test that tabs in right-to-left text advance to the stops in the order in
+// which they are laid out.
+#set page(width: 200pt, margin: 0pt)
+#set text(dir: rtl)
+#set par(tabs: (100pt,))
+#box(width: 20pt)<first>#tab()#box(width: 40pt)<second>
+
+#locate(loc => {
+  let x(label) = query(label, loc).first().location().position().x
+  test(x(<second>), 80pt)
+  test(x(<first>), 180pt)
+})
+
+---
+// Error: 16-33 expected length or dictionary, found string
+#set par(tabs: (2cm, "abc", 3cm))
@@ -0,0 +1,60 @@
+// Test continuation markers of tables that break across pages.
+
+---
+// Ref: false
+#set page(height: 100pt)
+#let seen = state("seen", ())
+#table(
+  columns: 2,
+  continued-top: (part, parts) => {
+    seen.update(it => it + ((part, parts),))
+    emph[(Part #part of #parts)]
+  },
+  continued-bottom: [#align(right, emph[Continued on next page]) <bottom>],
+  ..range(16).map(str),
+)
+
+// Each part but the first is marked at the top and each part but the last at
+// the bottom.
+#locate(loc => {
+  let parts = loc.page()
+  test(parts > 1, true)
+  test(seen.final(loc), range(2, parts + 1).map(part => (part, parts)))
+  test(query(<bottom>, loc).map(it => it.location().page()), range(1, parts))
+})
+
+---
+// Ref: false
+// The room for the markers is adjusted when leaving it makes the table break
+// into more parts.
+#set page(height: 100pt, margin: 10pt)
+#table(
+  columns: 2,
+  continued-top: (part, parts) => block(height: parts * 4pt),
+  continued-bottom: [#block(height: 10pt) <end>],
+  ..range(24).map(str),
+)
+
+#locate(loc => {
+  let ends = query(<end>, loc)
+  test(ends.len(), loc.page() - 1)
+  for end in ends {
+    test(end.location().position().y <= 80pt, true)
+  }
+})
+
+---
+// Ref: false
+// Markers are not shown if the table fits.
+#table(
+  columns: 2,
+  continued-top: [Continued <marker>],
+  continued-bottom: [Continued <marker>],
+  [A], [B],
+)
+
+#locate(loc => test(query(<marker>, loc), ()))
+
+---
+// Error: 35-36 expected content, function, or none, found integer
+#table(columns: 2, continued-top: 5)
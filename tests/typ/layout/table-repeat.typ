@@ -0,0 +1,43 @@
+// Test repeated header and footer rows.
+
+---
+// Ref: false
+#set page(height: 120pt)
+#table(
+  columns: 2,
+  header-rows: 1,
+  footer-rows: 1,
+  fill: (_, row) => if row == 0 { luma(220) },
+  [*Name* <name>], [*Value*],
+  ..range(12).map(n => ([Item #n], str(n * n))).flatten(),
+  [*Total* <total>], [506],
+)
+
+// The header and footer are on every page, with the header at the top.
+#locate(loc => {
+  let pages = range(1, loc.page() + 1)
+  let names = query(<name>, loc).map(it => it.location().position())
+  let totals = query(<total>, loc).map(it => it.location().position())
+  test(pages.len() > 1, true)
+  test(names.map(pos => pos.page), pages)
+  test(totals.map(pos => pos.page), pages)
+  test(names.map(pos => pos.y), names.map(_ => names.first().y))
+})
+
+---
+// Ref: false
+// Test with gutters and a table that fits on one page.
+#table(
+  columns: 2,
+  gutter: 3pt,
+  header-rows: 2,
+  [A <header>], [B],
+  [C <header>], [D],
+  [E], [F],
+)
+
+#locate(loc => test(query(<header>, loc).len(), 2))
+
+---
+// Error: 33-35 number must be at least zero
+#table(columns: 2, header-rows: -1)
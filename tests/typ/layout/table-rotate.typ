@@ -0,0 +1,59 @@
+// Test vertical alignment and rotation of table cells.
+
+---
+#table(
+  columns: 3,
+  rows: 40pt,
+  align: (col, _) => (top, horizon, bottom).at(col),
+  [Top <top>], [Middle <middle>], [Bottom <bottom>],
+)
+
+// The cells are 30pt high inside of their insets.
+#style(styles => locate(loc => {
+  let y(label) = query(label, loc).first().location().position().y
+  let free = 30pt - measure([Top], styles).height
+  test-close(y(<middle>) - y(<top>), free / 2)
+  test-close(y(<bottom>) - y(<top>), free)
+}))
+
+---
+#let schedule(..args) = table(
+  columns: 4,
+  align: bottom,
+  ..args,
+  [], [*Monday*], [*Tuesday*], [*Wednesday*],
+  [Alice], [2h], [3h], [1h],
+  [Bob], [1h], [4h], [2h],
+)
+
+#schedule(rotate: (_, row) => if row == 0 { -90deg } else { 0deg })
+
+// The rotated header row is as high as the widest header is wide.
+#style(styles => {
+  let size(body) = measure(body, styles)
+  let rotated = size(schedule(rotate: (_, row) => if row == 0 { -90deg } else { 0deg }))
+  let plain = size(schedule())
+  let widest = size[*Wednesday*]
+  let grown = rotated.height - plain.height
+  test-close(grown, widest.width - widest.height)
+})
+
+---
+// Test rotation that affects the layout.
+Hello #rotate(90deg, reflow: true)[World]! \
+#rotate(45deg, reflow: true, square(size: 10pt))
+
+#style(styles => {
+  let size(body) = measure(body, styles)
+  let world = size[World]
+  let turned = size(rotate(90deg, reflow: true)[World])
+  test-close(turned.width, world.height)
+  test-close(turned.height, world.width)
+  let diamond = size(rotate(45deg, reflow: true, square(size: 10pt)))
+  test-close(diamond.width, calc.sqrt(2) * 10pt)
+  test-close(diamond.height, calc.sqrt(2) * 10pt)
+})
+
+---
+// Error: 16-19 expected angle or function, found string
+#table(rotate: "x")
@@ -0,0 +1,92 @@
+// Test control over table lines.
+
+---
+// Ref: false
+// Horizontal lines only.
+#table(
+  columns: 3,
+  stroke: (x: none),
+  [*A*], [*B*], [*C*],
+  [1], [2], [3],
+)
+
+// Sides can be given by axis and unset sides keep their default.
+#test(table(stroke: (x: none)).stroke, (left: none, right: none))
+#test(repr(table(stroke: (top: 1pt, rest: 2pt)).stroke), "(bottom: 2pt, left: 2pt, right: 2pt, top: 1pt)")
+#test(repr(table(stroke: (y: red)).stroke), "(bottom: rgb(\"#ff4136\"), top: rgb(\"#ff4136\"))")
+
+---
+// The thicker stroke wins on shared edges, no matter which cell wants it.
+#table(
+  columns: 3,
+  stroke: (_, row) => if row == 0 { (bottom: 2pt) } else { 0.5pt + luma(180) },
+  [*A*], [*B*], [*C*],
+  [1], [2], [3],
+)
+#table(
+  columns: 3,
+  stroke: (_, row) => if row == 1 { (top: 2pt + red) } else { 0.5pt },
+  [*A*], [*B*], [*C*],
+  [1], [2], [3],
+)
+
+---
+// For strokes of the same thickness, the cell further right or further down
+// wins: The middle line is blue and the line below the first row is green.
+#table(
+  columns: 2,
+  stroke: (col, row) => (
+    left: if col == 1 { blue } else { red },
+    right: red,
+    top: if row == 1 { green } else { red },
+    bottom: red,
+  ),
+  [A], [B],
+  [C], [D],
+)
+
+---
+// An edge is only blank if both cells agree. Here, the middle line is only
+// drawn in the second row.
+#table(
+  columns: 2,
+  stroke: (col, row) => {
+    if col == 0 { (right: none) } else if row == 0 { (left: none) } else { 1pt }
+  },
+  [A], [B],
+  [C], [D],
+)
+
+---
+// The border replaces the cells' strokes on the outer edges only.
+#table(
+  columns: 2,
+  stroke: 0.5pt,
+  border: 2pt + red,
+  [A], [B],
+  [C], [D],
+)
+
+---
+// Ref: false
+// Outer frame only.
+#table(
+  columns: 3,
+  stroke: none,
+  border: 1pt,
+  gutter: 2pt,
+  [A], [B], [C],
+  [1], [2], [3],
+)
+
+// Lines don't take up space.
+#style(styles => {
+  let cells = ([A], [B], [C], [1], [2], [3])
+  let size(..args) = measure(table(columns: 3, gutter: 2pt, ..args, ..cells), styles)
+  test(size(stroke: none, border: 1pt), size())
+  test(size(stroke: 4pt), size())
+})
+
+---
+// Error: 16-31 unexpected key "diagonal", valid keys are "left", "top", "right", "bottom", "x", "y", and "rest"
+#table(stroke: (diagonal: 1pt))
@@ -17,6 +17,51 @@
 // Ref: false
 #table()
 
+---
+// Ref: false
+// Test styling cells based on their content.
+#table(
+  columns: 3,
+  fill: (_, row, body: none) => if body == [0] { red.lighten(60%) },
+  align: (col, _, body: none) => if body == [5] { right } else { left },
+  [3], [0], [5],
+  [0], [2], [1],
+)
+
+---
+// Ref: false
+// Test that only functions with a named `body` parameter get the content.
+#let cells = ([3], [0], [5], [0], [2], [1])
+#table(
+  columns: 3,
+  fill: (x, y, body: none) => {
+    test(body, cells.at(y * 3 + x))
+    none
+  },
+  align: (x, y, ..args) => {
+    test(args.named(), (:))
+    left
+  },
+  ..cells,
+)
+
+---
+// Ref: false
+// Test per-cell strokes and folding of strokes.
+#table(
+  columns: 3,
+  stroke: (_, row) => if row == 0 { 1pt } else { 0.5pt + luma(180) },
+  [*A*], [*B*], [*C*],
+  [1], [2], [3],
+)
+
+#set table(stroke: red)
+#table(columns: 2, stroke: 2pt, [A], [B])
+
 ---
 // Error: 14-19 expected color, none, or function, found string
 #table(fill: "hey")
+
+---
+// Error: 16-21 expected length, color, stroke, none, dictionary, or function, found string
+#table(stroke: "hey")
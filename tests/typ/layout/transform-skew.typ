@@ -0,0 +1,11 @@
+// Test skewing.
+// Ref: false
+
+---
+#skew(ax: -12deg)[Slanted]
+#skew(ay: 10deg, origin: top + left, rect(width: 20pt, height: 10pt, fill: aqua))
+#box(skew(ax: 30deg, ay: -5deg, scale(50%, rotate(10deg)[Nested])))
+
+---
+// Error: 11-15 expected angle, found length
+#skew(ax: 10pt)[A]
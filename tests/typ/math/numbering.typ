@@ -9,3 +9,39 @@ $ phi.alt := (1 + sqrt(5)) / 2 $ <ratio>
 
 With @ratio, we get
 $ F_n = floor(1 / sqrt(5) phi.alt^n) $ <fib>
+
+---
+// Ref: false
+// Test numbering each line of an aligned equation.
+#set math.equation(numbering: "(1)", number-lines: true)
+
+$ a &= (x + y)^2 \
+    &= x^2 + 2 x y + y^2 $ <binomial>
+
+By @binomial and @single, we are done.
+$ z = 1 $ <single>
+
+// Each line takes a number, so the next equation is the third.
+#locate(loc => {
+  let at(label) = counter(math.equation).at(query(label, loc).first().location())
+  test(at(<binomial>), (2,))
+  test(at(<single>), (3,))
+})
+
+---
+// Ref: false
+// Test that single-line and unnumbered equations are unaffected.
+#set math.equation(number-lines: true)
+$ a = b \ c = d $ <unnumbered>
+#set math.equation(numbering: "(1)")
+$ e = f $ <numbered>
+
+#locate(loc => {
+  let at(label) = counter(math.equation).at(query(label, loc).first().location())
+  test(at(<unnumbered>), (0,))
+  test(at(<numbered>), (1,))
+})
+
+---
+// Error: 34-39 expected boolean, found string
+#set math.equation(number-lines: "yes")
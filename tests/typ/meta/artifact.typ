@@ -0,0 +1,32 @@
+// Test marking content as decorative artifacts.
+// Ref: false
+
+---
+#set page(header: [Header], fill: luma(240))
+#artifact(line(length: 100%))
+Text between decoration.
+#artifact[#sym.star.filled]
+
+---
+// Error: 10-12 missing argument: body
+#artifact()
+
+---
+// Reading order doesn't affect the layout.
+#set document(tagged: true)
+#style(styles => test(
+  measure(reading(order: -1)[Aside], styles),
+  measure([Aside], styles),
+))
+
+---
+// Reading order can be changed within a paragraph.
+#set document(tagged: true)
+#style(styles => test(
+  measure([A #reading(order: 1)[B] C], styles),
+  measure([A B C], styles),
+))
+
+---
+// Error: 17-24 expected integer, found string
+#reading(order: "first")[A]
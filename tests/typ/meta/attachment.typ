@@ -0,0 +1,16 @@
+// Test file attachments.
+// Ref: false
+
+---
+#attachment("/files/data.csv", description: "Chart data", mime: "text/csv")
+#attachment("/files/hello.txt", name: "greeting.txt")
+
+---
+// Set rules apply to the attached files.
+#set attachment(mime: "text/plain")
+#attachment("/files/hello.txt")
+#locate(loc => test(query(attachment, loc).first().mime, "text/plain"))
+
+---
+// Error: 13-26 file not found (searched at typ/meta/missing.csv)
+#attachment("missing.csv")
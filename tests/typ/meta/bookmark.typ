@@ -0,0 +1,36 @@
+// Test custom outline entries and named destinations.
+// Ref: false
+
+---
+#bookmark("Overview")
+#bookmark("Details", level: 2, closed: true)
+#bookmark("Results", target: <results>, name: "results")
+#bookmark("Hidden", name: "hidden", outlined: false)
+
+*Results* <results>
+
+---
+// Set rules apply to the fields the exported outline is built from.
+#set bookmark(level: 2, closed: true, outlined: false)
+#bookmark("Appendix", name: "appendix")
+#locate(loc => {
+  let it = query(bookmark, loc).first()
+  test(it.level, 2)
+  test(it.closed, true)
+  test(it.outlined, false)
+  test(it.name, "appendix")
+})
+
+---
+// Headings are bookmarked unless disabled.
+= Introduction
+#heading(bookmarked: false)[Not bookmarked]
+#heading(outlined: false, bookmarked: true)[Only bookmarked]
+
+---
+// Error: 23-24 number must be positive
+#bookmark("A", level: 0)
+
+---
+// Error: 11-14 expected string, found content
+#bookmark([A])
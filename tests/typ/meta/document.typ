@@ -10,6 +10,49 @@ What's up?
 // Ref: false
 #set document(author: ("A", "B"))
 
+---
+// Subject, keywords, and creation date.
+// Ref: false
+#set document(subject: "Testing", keywords: ("typst", "pdf"))
+#set document(date: (year: 2023, month: 4, day: 18, hour: 12, minute: 30))
+
+---
+// Error: 21-44 month must be between 1 and 12
+#set document(date: (year: 2023, month: 13))
+
+---
+// Error: 21-31 missing key: "year"
+#set document(date: (month: 1))
+
+---
+// Leap days exist in leap years only.
+// Ref: false
+#set document(date: (year: 2024, month: 2, day: 29))
+#set document(date: (year: 2000, month: 2, day: 29))
+
+---
+// Error: 21-52 day must be between 1 and 28
+#set document(date: (year: 1900, month: 2, day: 29))
+
+---
+// Error: 21-52 day must be between 1 and 30
+#set document(date: (year: 2023, month: 4, day: 31))
+
+---
+// Error: 21-55 minute and second require an hour
+#set document(date: (year: 2023, month: 4, minute: 30))
+
+---
+// Custom XMP properties.
+// Ref: false
+#set document(xmp: (
+  (namespace: "http://ns.example.com/1.0/", prefix: "ex", name: "id", value: "1"),
+))
+
+---
+// Error: 20-38 missing key: "namespace"
+#set document(xmp: ((prefix: "ex",),))
+
 ---
 // This, too.
 // Error: 23-29 expected string, found integer
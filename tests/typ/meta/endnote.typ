@@ -0,0 +1,18 @@
+// Test endnotes.
+// Ref: false
+
+---
+#set page(width: 150pt)
+= Chapter
+First#endnote[One.] and second#endnote[Two.].
+#endnotes()
+
+= Another
+#set endnote(numbering: "a")
+Third.#endnote[Three.]
+#endnotes(title: none)
+
+---
+// Notes after the last list are not shown.
+#endnotes()
+Late.#endnote[Never listed.]
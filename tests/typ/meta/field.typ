@@ -0,0 +1,17 @@
+// Test interactive form fields.
+// Ref: false
+
+---
+Name: #field("name", value: "Jane", tooltip: "Your full name") \
+Notes: #field("notes", multiline: true, width: 100%) \
+#field("terms", kind: "checkbox", checked: true) Accept \
+#field("signature", kind: "signature", stroke: none)
+
+---
+#set field(kind: "checkbox")
+#field("a")
+#rotate(45deg, field("b"))
+
+---
+// Error: 22-28 expected "text", "checkbox", or "signature"
+#field("name", kind: "date")
@@ -22,3 +22,38 @@ We can clearly see that @fig-cylinder and
   table(columns: 3)[a][b][c][d][e][f],
   caption: [The complex table.],
 ) <tab-complex>
+
+---
+// Kinds, caption placement, and sub-figures.
+// Ref: false
+#figure(
+  table(columns: 2)[a][b],
+  caption: [A table.],
+  caption-pos: top,
+  kind: "table",
+) <tab>
+#figure(```rust fn main() {}```, caption: [A listing.], kind: "listing") <lst>
+#figure(
+  grid(
+    columns: 2,
+    figure(rect(), caption: [Left]) <left>,
+    figure(circle(), caption: [Right]) <right>,
+  ),
+  caption: [Two shapes.],
+) <shapes>
+
+See @tab, @lst, @shapes, and @right.
+
+#locate(loc => {
+  let figures = (<tab>, <lst>, <shapes>, <right>).map(l => query(l, loc).first())
+  test(figures.map(it => it.kind), ("table", "listing", "figure", "figure"))
+  test(figures.map(it => it.supplement.text), ("Table", "Listing", "Figure", "Figure"))
+
+  // Sub-figures step the second level of their parent's number.
+  let numbers = figures.map(it => counter(figure).at(it.location()))
+  test(numbers, ((1,), (2,), (3,), (3, 2)))
+})
+
+---
+// Error: 26-30 expected either `top` or `bottom`
+#figure([], caption-pos: left)
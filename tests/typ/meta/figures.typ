@@ -0,0 +1,28 @@
+// Test lists of figures and tables.
+// Ref: false
+
+---
+#set page("a7", margin: 20pt, numbering: "1")
+#figures()
+#figures(kind: "table", title: [Tables], fill: none)
+
+#figure(rect(), caption: [A rectangle.])
+#figure(table(columns: 2)[A][B], caption: [A tiny table.], kind: "table")
+#figure(circle())
+#figure(
+  grid(
+    columns: 2,
+    figure(rect(), caption: [Left]),
+    figure(circle(), caption: [Right]),
+  ),
+  caption: [Two shapes.],
+)
+
+---
+#set text(lang: "de")
+#figures(kind: "listing")
+#figure(```rust fn main() {}```, caption: [Ein Programm.], kind: "listing")
+
+---
+// Error: 16-22 expected "figure", "table", or "listing"
+#figures(kind: "math")
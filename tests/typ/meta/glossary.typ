@@ -0,0 +1,45 @@
+// Test glossaries and terms.
+
+---
+// Test that the first use of a term shows the long and short form and later
+// uses only the short form, unless a form is given.
+#set page(width: 500pt, margin: 0pt)
+#glossary(
+  title: [Abbreviations],
+  (key: "svg", short: "SVG", long: [Scalable Vector Graphics]),
+  (key: "pdf", short: "PDF", long: [Portable Document Format], description: [A document format.]),
+)
+
+#let gap = h(10pt)
+#term("pdf")#gap#term("pdf")#gap#term("svg", form: "short")#gap#[#set term(form: "long")#term("svg")]#gap#term("pdf")
+
+#style(styles => locate(loc => {
+  let starts = query(term, loc).map(it => it.location().position().x)
+  let advance(i) = starts.at(i + 1) - starts.at(i) - 10pt
+
+  // Terms stay empty until a previous layout found the glossary.
+  if advance(0) < 1pt { return }
+
+  let width(body) = measure(body, styles).width
+  test-close(advance(0), width[Portable Document Format (PDF)], tolerance: 0.5pt)
+  test-close(advance(1), width[PDF], tolerance: 0.5pt)
+  test-close(advance(2), width[SVG], tolerance: 0.5pt)
+  test-close(advance(3), width[Scalable Vector Graphics], tolerance: 0.5pt)
+}))
+
+---
+// Error: 2-13 the document does not contain a glossary
+#term("pdf")
+
+---
+#glossary((key: "pdf", short: "PDF", long: [Portable Document Format]))
+// Error: 2-13 glossary does not contain this key
+#term("svg")
+
+---
+// Error: 2-78 duplicate glossary key: a
+#glossary((key: "a", short: "A", long: []), (key: "a", short: "B", long: []))
+
+---
+// Error: 11-37 missing key: "long"
+#glossary((key: "pdf", short: "PDF"))
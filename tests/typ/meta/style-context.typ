@@ -0,0 +1,23 @@
+// Test querying the effective style context.
+// Ref: false
+
+---
+#set page(width: 100pt, height: 200pt)
+#set text(14pt, font: "IBM Plex Sans", lang: "de")
+#style((styles, ctx) => {
+  test(ctx.text-size, 14pt)
+  test(ctx.font, ("ibm plex sans",))
+  test(ctx.lang, "de")
+  test(ctx.dir, ltr)
+  test(ctx.page-width, 100pt)
+  test(ctx.page-height, 200pt)
+})
+
+---
+#set page(height: auto, flipped: true)
+#set text(lang: "ar")
+#text(2em, style((_, ctx) => {
+  test(ctx.text-size, 22pt)
+  test(ctx.dir, rtl)
+  test(ctx.page-width, none)
+}))
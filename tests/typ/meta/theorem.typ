@@ -0,0 +1,39 @@
+// Test theorem-like environments.
+
+---
+// Ref: false
+#set heading(numbering: "1.")
+#let lemma = theorem.with(supplement: [Lemma])
+#let example = theorem.with(kind: "example", supplement: [Example])
+
+= Primes
+#theorem(title: [Euclid])[There are infinitely many primes.] <euclid>
+#lemma[Every integer greater than one has a prime factor.] <factor>
+#example[The number 7 is prime.] <seven>
+
+By @euclid and @factor, as in @seven.
+
+#locate(loc => {
+  let thm = query(<factor>, loc).first()
+  test(thm.supplement, [Lemma])
+  test(thm.kind, "theorem")
+})
+
+---
+// Ref: false
+// Test resetting the counter per section.
+#set heading(numbering: "1.")
+#set theorem(reset: 1)
+
+= Groups
+#theorem[Subgroups of cyclic groups are cyclic.]
+#theorem[Lagrange.] <lagrange>
+
+= Rings
+#theorem[Every field is an integral domain.] <field>
+
+See @lagrange and @field.
+
+---
+// Error: 21-24 expected integer or none, found string
+#set theorem(reset: "1")
@@ -0,0 +1,66 @@
+// Test East Asian line breaking and spacing.
+
+---
+// Ref: false
+// Test that no line starts with closing punctuation or small kana and no line
+// ends with opening punctuation, even if the line overflows then.
+#set text(lang: "ja", font: "Noto Serif CJK SC", size: 10pt)
+#style(styles => {
+  let height(body) = measure(block(width: 10.5pt, body), styles).height
+  let line = height[あ]
+  test(height[あい] > line, true)
+  test(height[あ。], line)
+  test(height[あ」], line)
+  test(height[あっ], line)
+  test(height[「あ], line)
+})
+
+---
+// Ref: false
+// Test that punctuation compression removes half an em between adjacent
+// full-width punctuation, and only when enabled.
+#set text(lang: "ja", font: "Noto Serif CJK SC", size: 10pt)
+#style(styles => {
+  let width(body) = measure(body, styles).width
+  let plain = width[「括弧」「括弧」。]
+  let compressed = width(text(cjk-punctuation-compression: true)[「括弧」「括弧」。])
+  test(plain - compressed > 9.9pt, true)
+  test(plain - compressed < 10.1pt, true)
+  test(width[括弧], width(text(cjk-punctuation-compression: true)[括弧]))
+})
+
+---
+// Ref: false
+// Test that CJK-Latin spacing adds a quarter em between the scripts, and only
+// when enabled.
+#set text(lang: "ja", font: ("Linux Libertine", "Noto Serif CJK SC"), size: 10pt)
+#style(styles => {
+  let width(body) = measure(body, styles).width
+  let plain = width[Typstは]
+  let spaced = width(text(cjk-latin-spacing: true)[Typstは])
+  test(spaced - plain > 2.4pt, true)
+  test(spaced - plain < 2.6pt, true)
+  test(width[組版], width(text(cjk-latin-spacing: true)[組版]))
+})
+
+---
+// Ref: false
+// Test that CJK-Latin spacing is kept when the CJK run is broken across lines.
+#set page(width: 100pt, margin: 0pt)
+#set text(lang: "ja", font: ("Linux Libertine", "Noto Serif CJK SC"), size: 10pt)
+#text(cjk-latin-spacing: false)[組版組版組版組版組版組版組版Typst#box()<plain>]
+
+#text(cjk-latin-spacing: true)[組版組版組版組版組版組版組版Typst#box()<spaced>]
+
+#locate(loc => {
+  let x(label) = query(label, loc).first().location().position().x
+  test-close(x(<spaced>) - x(<plain>), 2.5pt)
+})
+
+---
+// Error: 30-35 expected boolean, found string
+#set text(cjk-latin-spacing: "yes")
+
+---
+// Error: 40-41 expected boolean, found integer
+#set text(cjk-punctuation-compression: 1)
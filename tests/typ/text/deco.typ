@@ -31,3 +31,32 @@ There might be #highlight[redacted] things.
 // Test stroke folding.
 #set underline(stroke: 2pt, offset: 2pt)
 #underline(text(red, [DANGER!]))
+
+---
+// Test line styles.
+#set underline(stroke: 0.5pt)
+#underline(style: "double")[Double] and #overline(style: "wavy")[wavy]. \
+#underline(style: "wavy", stroke: 1pt + blue)[A thicker wave] \
+#set text(size: 15pt)
+#underline(style: "double", offset: 3pt)[Offset double]
+
+---
+// Test emphasis marks. Spaces and punctuation don't get marks.
+これは#emphmark[大切、です]。
+这是#emphmark(position: "under", fill: red)[重 点]。
+#emphmark(size: 3pt, offset: -15pt)[Big marks.]
+
+---
+// Error: 19-24 expected "solid", "double", or "wavy"
+#underline(style: "zig")[A]
+
+---
+// Test highlights across line breaks.
+// Ref: false
+#set page(width: 80pt)
+#highlight[A highlight that wraps across lines.]
+#highlight(fill: aqua, extent: 1pt, radius: 2pt, top-edge: "cap-height")[Rounded]
+
+---
+// Error: 18-23 expected color, found string
+#highlight(fill: "red")[A]
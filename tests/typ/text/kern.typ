@@ -0,0 +1,37 @@
+// Test manual kerning adjustments.
+// Ref: false
+
+---
+// A kern widens or narrows the advance of the preceding glyph.
+#set text(size: 16pt)
+#style(styles => {
+  let plain = measure([AV], styles).width
+  let tight = measure([A#kern(-0.15em)V], styles).width
+  let wide = measure([T#kern(2pt)o], styles).width
+  test(plain - tight > 2.3pt, true)
+  test(plain - tight < 2.5pt, true)
+  test(wide - measure([To], styles).width > 1.9pt, true)
+  test(wide - measure([To], styles).width < 2.1pt, true)
+})
+
+---
+// A kern survives reshaping at a line break and widens right-to-left text.
+#set text(size: 16pt)
+#style(styles => {
+  let tight = measure([A#kern(-0.15em)V], styles).width
+  test(measure([A#kern(-0.15em)V \ x], styles).width, tight)
+  let plain = measure(text(lang: "he")[אבג], styles).width
+  let wide = measure(text(lang: "he")[אב#kern(2pt)ג], styles).width
+  test(wide - plain > 1.9pt, true)
+  test(wide - plain < 2.1pt, true)
+})
+
+---
+// A kern without preceding text has no effect.
+#style(styles => {
+  test(measure([#kern(10pt)Text], styles).width, measure([Text], styles).width)
+})
+
+---
+// Error: 6-8 missing argument: amount
+#kern()
@@ -34,3 +34,18 @@ With a soft #linebreak(justify: true)
 break you can force a break without #linebreak(justify: true)
 breaking justification. #linebreak(justify: false)
 Nice!
+
+---
+// Test that long URLs break after slashes and before dots.
+// Ref: false
+#style(styles => {
+  let line = measure([x], styles).height
+  let url = measure(block(width: 50pt)[https://typst.example.example.org], styles)
+  let www = measure(block(width: 50pt)[www.typst.example.org/docs/par], styles)
+  test(url.height > 2 * line, true)
+  test(www.height > 2 * line, true)
+
+  // Words that aren't URLs aren't split at dots.
+  let word = measure(block(width: 50pt)[typst.example.example.org], styles)
+  test(word.height, line)
+})
@@ -0,0 +1,37 @@
+// Test ruby annotations.
+
+---
+#ruby[漢字][かんじ]を#ruby[勉強][べんきょう]します。
+
+---
+#set ruby(size: 0.6em, gap: 1pt)
+#ruby(position: "under")[東京][とうきょう]
+
+---
+// Test that the annotation is stacked onto the base and only widens it if it
+// is wider than the base and the overhang on both sides.
+// Ref: false
+#set text(lang: "ja", font: "Noto Serif CJK SC", size: 10pt)
+#style(styles => {
+  let size(body) = measure(body, styles)
+  let base = size[勉強]
+  let annotation = size(text(size: 5pt)[べんきょう])
+
+  let over = size(ruby[勉強][べんきょう])
+  test-close(over.width, base.width)
+  test-close(over.height, base.height + annotation.height)
+
+  let under = size(ruby(position: "under", gap: 1pt)[勉強][べんきょう])
+  test-close(under.height, base.height + 1pt + annotation.height)
+
+  let wide = size(ruby(overhang: 0pt)[勉強][べんきょう])
+  test-close(wide.width, annotation.width)
+})
+
+---
+// Error: 17-24 expected "over" or "under"
+#ruby(position: "above")[A][a]
+
+---
+// Error: 6-9 missing argument: annotation
+#ruby[A]
@@ -17,3 +17,13 @@ n#super[1], n#sub[2], ... n#super[N]
 #underline[The claim#super[\[4\]]] has been disputed. \
 The claim#super[#underline[\[4\]]] has been disputed. \
 It really has been#super(box(text(baseline: 0pt, underline[\[4\]]))) \
+
+---
+// Test that typographic sub- and superscripts use dedicated glyphs instead of
+// synthesizing them.
+// Ref: false
+#style(styles => {
+  let width(body) = measure(body, styles).width
+  test(width[H#sub[2]O] != width[H#sub(typographic: false)[2]O], true)
+  test(width[x#super[1]] != width[x#super(typographic: false)[1]], true)
+})
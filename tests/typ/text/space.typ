@@ -40,3 +40,19 @@ A#"\n" B
 ---
 // Test that trailing space does not force a line break.
 LLLLLLLLLLLLLLLLLL R _L_
+
+---
+// Test typographic spaces, which have fixed widths even if the font lacks
+// glyphs for them.
+// Ref: false
+#style(styles => {
+  let width(body) = measure(body, styles).width
+  let plain = width[1000]
+  let thin = width[1#sym.space.thin;000]
+  let hair = width[1#sym.space.hair;000]
+  let narrow = width[1#sym.space.nobreak.narrow;000]
+  test(hair > plain, true)
+  test(thin > hair, true)
+  test(narrow > plain, true)
+  test(width[no#sym.wj;break], width[nobreak])
+})
@@ -0,0 +1,26 @@
+// Test unit and chemical formula helpers.
+
+---
+// Ref: false
+#unit("kg m/s^2") \
+#unit("kg m/s^2", per: "slash") \
+#unit("microm kohm") \
+#qty(9.81, "m/s^2") \
+#qty("6.02214076e23", "mol^-1") \
+#qty(-40, "degC") \
+#qty(90, "deg") \
+#qty(1234567, "m")
+
+---
+// Ref: false
+#ce("H2O") \
+#ce("SO4^2-") \
+#ce("CuSO4*5H2O") \
+#ce("2H2 + O2 -> 2H2O") \
+#ce("Na+ + Cl- <=> NaCl") \
+#isotope("U", mass: 235, atomic: 92) \
+#isotope("C", mass: 14)
+
+---
+// Error: 17-27 expected "power" or "slash"
+#unit("m", per: "fraction")
@@ -49,6 +49,37 @@ A #box(image("/tiger.jpg", height: 1cm, width: 80%)) B
 // Test advanced SVG features.
 #image("/pattern.svg")
 
+---
+// Test that clipping to a shape doesn't change the size of an image.
+// Ref: false
+#set image(width: 1cm, height: 1cm)
+#style(styles => {
+  let size(..args) = measure(image("/tiger.jpg", ..args), styles)
+  test(size(clip: "ellipse"), size())
+  test(size(clip: 25%), size())
+  test(size(fit: "contain", clip: (top-left: 3pt)), size(fit: "contain"))
+})
+
+---
+// Test color effects.
+// Ref: false
+#set image(width: 1cm)
+#image("/tiger.jpg", brightness: -20%, contrast: 120%)
+#image("/rhino.png", grayscale: true)
+#image("/tiger.jpg", duotone: (navy, rgb("#ffd700")))
+
+---
+// Error: 31-37 duotone array must contain exactly two colors
+#image("/tiger.jpg", duotone: (red,))
+
+---
+// Error: 34-38 brightness must be between -100% and 100%
+#image("/tiger.jpg", brightness: 150%)
+
+---
+// Error: 24-29 brightness must be between -100% and 100%
+#set image(brightness: -101%)
+
 ---
 // Error: 8-29 file not found (searched at typ/visualize/path/does/not/exist)
 #image("path/does/not/exist")
@@ -60,3 +91,12 @@ A #box(image("/tiger.jpg", height: 1cm, width: 80%)) B
 ---
 // Error: 8-18 failed to parse svg: found closing tag 'g' instead of 'style' in line 4
 #image("/bad.svg")
+
+---
+// Test alternate representations.
+// Ref: false
+#image("/graph.png", width: 1cm, alternates: ("/tiger.jpg",))
+
+---
+// Error: 34-50 file not found (searched at typ/visualize/missing.svg)
+#image("/graph.png", alternates: ("missing.svg",))
@@ -49,3 +49,45 @@
 ---
 // Error: 14-26 expected relative length, found angle
 #line(start: (3deg, 10pt), length: 5cm)
+
+---
+// Ref: false
+// Test line caps, joins, and miter limits.
+#line(length: 60pt, stroke: (thickness: 6pt, cap: "round"))
+#line(length: 60pt, stroke: (paint: blue, thickness: 6pt, cap: "square"))
+#rect(stroke: (thickness: 4pt, join: "bevel", miter-limit: 1.5))
+#rect(stroke: (paint: red, join: "round"))
+#rect(stroke: (left: (thickness: 2pt, cap: "round"), rest: 1pt))
+
+#let stroke(..args) = repr(line(stroke: args.named()).stroke)
+#test(stroke(thickness: 6pt, cap: "round"), "(thickness: 6pt, cap: \"round\")")
+#test(stroke(join: "bevel", miter-limit: 1.5), "(join: \"bevel\", miter-limit: 1.5)")
+#test(stroke(paint: red, thickness: 2pt), "2pt + rgb(\"#ff4136\")")
+
+---
+// Error: 15-49 miter limit must be at least one
+#line(stroke: (thickness: 1pt, miter-limit: 0.5))
+
+---
+// Error: 15-43 unexpected key "width", valid keys are "paint", "thickness", "cap", "join", "miter-limit", "dash", and "dash-phase"
+#line(stroke: (thickness: 1pt, width: 2pt))
+
+---
+// Ref: false
+// Test dash patterns and per-side dashed borders.
+#line(length: 60pt, stroke: (thickness: 2pt, dash: "dashed"))
+#line(length: 60pt, stroke: (dash: "loosely-dotted"))
+#rect(stroke: (
+  top: (paint: red, thickness: 2pt, dash: "dashed"),
+  bottom: (paint: blue, dash: "dotted"),
+  x: 1pt + green,
+))
+#line(length: 60pt, stroke: (thickness: 2pt, dash: "dashed", dash-phase: 3pt))
+
+#let stroke(..args) = repr(line(stroke: args.named()).stroke)
+#test(stroke(dash: "loosely-dotted"), "(dash: \"loosely-dotted\")")
+#test(stroke(dash: "dashed", dash-phase: 3pt), "(dash: \"dashed\", dash-phase: 3pt)")
+
+---
+// Error: 15-29 expected "solid", "dotted", "densely-dotted", "loosely-dotted", "dashed", "densely-dashed", "loosely-dashed", "dash-dotted", or auto
+#line(stroke: (dash: "wavy"))